@@ -2,7 +2,56 @@ use std::collections::BTreeMap;
 
 use serde::Serialize;
 
-use crate::util::units::Second;
+use crate::benchmark::timing_result::{CpuFrequency, GpuUsage};
+use crate::options::ThroughputUnit;
+use crate::statistics::ConfidenceInterval;
+use crate::util::units::{Scalar, Second};
+
+/// A percentile of the measured times
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Percentile {
+    /// The percentile between 0 and 100, e.g. 90 for the 90th percentile
+    pub percentile: f64,
+
+    /// The time below which the given percentage of runs falls
+    pub time: Second,
+}
+
+impl Percentile {
+    /// Short name of the percentile, e.g. "p90"
+    pub fn name(&self) -> String {
+        format!("p{}", self.percentile)
+    }
+}
+
+/// A run that was detected as a statistical outlier, as reported with `--show-outliers`
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct OutlierRun {
+    /// Index of the run in `times`, starting at zero
+    pub index: usize,
+
+    /// The measured time of the run
+    pub time: Second,
+
+    /// The modified Z-score of the run, in units of standard deviations
+    pub zscore: Scalar,
+}
+
+/// Throughput of a command, as requested with `--throughput`
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ThroughputStats {
+    /// Whether bytes or items are processed
+    pub unit: ThroughputUnit,
+
+    /// The number of bytes or items that is processed in each run
+    pub amount: Scalar,
+
+    /// Mean of the throughputs (per second) of the individual runs
+    pub mean: Scalar,
+
+    /// Standard deviation of the throughputs of the individual runs
+    pub stddev: Option<Scalar>,
+}
 
 /// Set of values that will be exported.
 // NOTE: `serde` is used for JSON serialization, but not for CSV serialization due to the
@@ -23,6 +72,15 @@ pub struct BenchmarkResult {
     /// The standard deviation of all run times. Not available if only one run has been performed
     pub stddev: Option<Second>,
 
+    /// The standard error of the mean, i.e. the standard deviation divided by the square root
+    /// of the number of runs. Not available if only one run has been performed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sem: Option<Second>,
+
+    /// Bootstrap confidence interval of the mean, only computed with `--bootstrap`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mean_ci: Option<ConfidenceInterval>,
+
     /// The median run time
     pub median: Second,
 
@@ -32,20 +90,178 @@ pub struct BenchmarkResult {
     /// Time spent in kernel mode
     pub system: Second,
 
+    /// Fraction of the wall clock time that was spent on a CPU, i.e. `(user + system) / mean`.
+    /// Values above one are possible for commands that use several cores.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpu_utilization: Option<Scalar>,
+
     /// Minimum of all measured times
     pub min: Second,
 
     /// Maximum of all measured times
     pub max: Second,
 
+    /// Percentiles of all measured times, as requested with `--percentiles`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub percentiles: Vec<Percentile>,
+
+    /// Throughput per second, only computed with `--throughput`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub throughput: Option<ThroughputStats>,
+
+    /// Whether the benchmark was stopped early because it reached the time budget of
+    /// `--max-time`
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub truncated: bool,
+
     /// All run time measurements
     #[serde(skip_serializing_if = "Option::is_none")]
     pub times: Option<Vec<Second>>,
 
+    /// All run time measurements as they were measured, before the shell spawning time or
+    /// the overhead of the timer was subtracted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub times_raw: Option<Vec<Second>>,
+
+    /// Start of each run, in seconds since the Unix epoch
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamps: Option<Vec<f64>>,
+
+    /// The 1-minute load average of the system at the start of each run. Not available if it
+    /// could not be determined on this platform.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub load_average: Option<Vec<f64>>,
+
+    /// Memory of the system that was available for new processes at the start of each run,
+    /// in bytes. Only available on Linux.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub available_memory_byte: Option<Vec<u64>>,
+
+    /// Peak memory usage (maximum resident set size) of each run, in bytes. Not available
+    /// if it could not be measured on this platform.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_usage_byte: Option<Vec<u64>>,
+
+    /// Number of bytes that each run read, including reads from pipes and the page cache. Not
+    /// available if it could not be measured on this platform.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub io_read_byte: Option<Vec<u64>>,
+
+    /// Number of bytes that each run wrote, analogous to `io_read_byte`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub io_write_byte: Option<Vec<u64>>,
+
+    /// Number of voluntary context switches of each run, i.e. how often the command waited,
+    /// e.g. for I/O. Not available if it could not be measured on this platform.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub voluntary_context_switches: Option<Vec<u64>>,
+
+    /// Number of involuntary context switches of each run, i.e. how often the command was
+    /// preempted by the scheduler
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub involuntary_context_switches: Option<Vec<u64>>,
+
+    /// Number of minor page faults of each run, i.e. faults that could be resolved without
+    /// I/O. Not available if it could not be measured on this platform.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub minor_page_faults: Option<Vec<u64>>,
+
+    /// Number of major page faults of each run, i.e. faults that required reading from the
+    /// disk. These are typical for runs with a cold cache.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub major_page_faults: Option<Vec<u64>>,
+
+    /// Peak number of processes and threads that were running at the same time in each run.
+    /// Only available on Linux with `--cgroup`, if the pids controller is available.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub peak_tasks: Option<Vec<u64>>,
+
+    /// Number of processes that each run created, including the benchmarked process itself.
+    /// Only available on Windows, and on Linux with `--cgroup`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_processes: Option<Vec<u64>>,
+
+    /// Values of the hardware performance counters (`--perf-counters`) of each run, by the
+    /// name of the counter. Counters that could not be measured for every run are omitted.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub perf_counters: BTreeMap<String, Vec<u64>>,
+
+    /// Energy consumption of the CPU during each run in joules (`--measure-energy`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub energy_joule: Option<Vec<f64>>,
+
+    /// Frequency of the CPU(s) during each run in MHz (`--cpu-frequency`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpu_frequency_mhz: Option<Vec<CpuFrequency>>,
+
+    /// Utilization and memory usage of the GPU(s) during each run (`--gpu`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gpu_usage: Option<Vec<GpuUsage>>,
+
+    /// Time until the first output of the command in each run (`--time-to-first-output`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_to_first_output: Option<Vec<Second>>,
+
     /// Exit codes of all command invocations
     pub exit_codes: Vec<Option<i32>>,
 
+    /// Signals that terminated the command invocations, if any
+    #[serde(skip_serializing_if = "no_signals")]
+    pub signals: Vec<Option<i32>>,
+
+    /// Whether each run was detected as a statistical outlier, only present if any outliers
+    /// were detected. Outliers are not included in the statistics with `--discard-outliers`.
+    #[serde(skip_serializing_if = "no_outliers")]
+    pub outliers: Vec<bool>,
+
+    /// Details of the runs that were detected as outliers, only computed with `--show-outliers`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub outlier_runs: Vec<OutlierRun>,
+
+    /// Modified Z-score of each run, in units of standard deviations. With the default
+    /// outlier method, runs with an absolute score above the threshold are outliers.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub zscores: Vec<Scalar>,
+
     /// Parameter values for this benchmark
     #[serde(skip_serializing_if = "BTreeMap::is_empty")]
     pub parameters: BTreeMap<String, String>,
 }
+
+fn no_signals(signals: &[Option<i32>]) -> bool {
+    signals.iter().all(Option::is_none)
+}
+
+fn no_outliers(outliers: &[bool]) -> bool {
+    !outliers.contains(&true)
+}
+
+impl BenchmarkResult {
+    /// The run times that the statistics are based on, i.e. without the outliers if they
+    /// are discarded
+    pub fn retained_times(&self, discard_outliers: bool) -> Vec<Second> {
+        let times = self.times.clone().unwrap_or_default();
+        if !discard_outliers || no_outliers(&self.outliers) {
+            return times;
+        }
+
+        times
+            .into_iter()
+            .zip(&self.outliers)
+            .filter(|(_, &outlier)| !outlier)
+            .map(|(t, _)| t)
+            .collect()
+    }
+}
+
+#[test]
+fn test_retained_times() {
+    let result = BenchmarkResult {
+        times: Some(vec![1.0, 5.0, 1.1]),
+        outliers: vec![false, true, false],
+        ..Default::default()
+    };
+
+    assert_eq!(vec![1.0, 5.0, 1.1], result.retained_times(false));
+    assert_eq!(vec![1.0, 1.1], result.retained_times(true));
+}
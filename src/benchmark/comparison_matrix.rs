@@ -0,0 +1,123 @@
+use serde::Serialize;
+
+use super::benchmark_result::BenchmarkResult;
+use crate::options::{Options, SignificanceTest};
+use crate::statistics::{mann_whitney_u_test, welch_t_test};
+use crate::util::units::Scalar;
+
+/// Settings for the significance tests of the comparison matrix (`--comparison-matrix`)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Settings {
+    pub significance_level: Scalar,
+    pub significance_test: SignificanceTest,
+    pub discard_outliers: bool,
+}
+
+impl Settings {
+    pub fn from_options(options: &Options) -> Self {
+        Settings {
+            significance_level: options.significance_level,
+            significance_test: options.significance_test,
+            discard_outliers: options.discard_outliers,
+        }
+    }
+}
+
+/// Relative speeds of all pairs of benchmarks, not only relative to the fastest one
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ComparisonMatrix {
+    /// The commands in the order of the rows and columns
+    pub commands: Vec<String>,
+
+    /// `ratios[i][j]` is the mean time of command `j` divided by the mean time of command
+    /// `i`, i.e. how many times faster command `i` is than command `j`
+    pub ratios: Vec<Vec<Scalar>>,
+
+    /// `p_values[i][j]` is the p-value of the significance test for the difference between
+    /// commands `i` and `j`. Not available on the diagonal and if too few runs were performed.
+    pub p_values: Vec<Vec<Option<Scalar>>>,
+
+    /// The level below which a p-value is significant
+    pub significance_level: Scalar,
+}
+
+impl ComparisonMatrix {
+    pub fn compute(results: &[BenchmarkResult], settings: Settings) -> Self {
+        let test = match settings.significance_test {
+            SignificanceTest::Welch => welch_t_test,
+            SignificanceTest::MannWhitney => mann_whitney_u_test,
+        };
+        let times: Vec<_> = results
+            .iter()
+            .map(|r| r.retained_times(settings.discard_outliers))
+            .collect();
+
+        let ratios = results
+            .iter()
+            .map(|row| results.iter().map(|col| col.mean / row.mean).collect())
+            .collect();
+        let p_values = (0..results.len())
+            .map(|i| {
+                (0..results.len())
+                    .map(|j| {
+                        if i == j {
+                            None
+                        } else {
+                            test(&times[i], &times[j]).map(|t| t.p_value)
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+
+        ComparisonMatrix {
+            commands: results.iter().map(|r| r.command.clone()).collect(),
+            ratios,
+            p_values,
+            significance_level: settings.significance_level,
+        }
+    }
+
+    /// Whether the difference between commands `i` and `j` is statistically significant
+    pub fn is_significant(&self, i: usize, j: usize) -> bool {
+        self.p_values[i][j].is_some_and(|p| p < self.significance_level)
+    }
+}
+
+#[test]
+fn test_comparison_matrix() {
+    use approx::assert_relative_eq;
+
+    let result = |command: &str, times: Vec<f64>| BenchmarkResult {
+        command: command.into(),
+        command_with_unused_parameters: command.into(),
+        mean: statistical::mean(&times),
+        times: Some(times),
+        ..Default::default()
+    };
+    let results = vec![
+        result("a", vec![1.0, 1.1, 0.9, 1.0]),
+        result("b", vec![2.0, 2.1, 1.9, 2.0]),
+        result("c", vec![1.0, 1.2, 0.8, 1.0]),
+    ];
+    let settings = Settings {
+        significance_level: 0.05,
+        significance_test: SignificanceTest::Welch,
+        discard_outliers: false,
+    };
+
+    let matrix = ComparisonMatrix::compute(&results, settings);
+
+    assert_eq!(matrix.commands, ["a", "b", "c"]);
+    assert_relative_eq!(matrix.ratios[0][0], 1.0);
+    assert_relative_eq!(matrix.ratios[0][1], 2.0);
+    assert_relative_eq!(matrix.ratios[1][0], 0.5);
+    assert_relative_eq!(matrix.ratios[2][1], 2.0);
+
+    assert!(matrix.p_values[1][1].is_none());
+    assert_eq!(matrix.p_values[0][1], matrix.p_values[1][0]);
+    assert!(matrix.is_significant(0, 1));
+    assert!(matrix.is_significant(2, 1));
+    assert!(!matrix.is_significant(0, 2));
+    assert!(!matrix.is_significant(0, 0));
+}
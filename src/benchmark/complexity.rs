@@ -0,0 +1,201 @@
+use std::fmt;
+
+use super::benchmark_result::BenchmarkResult;
+use crate::util::units::Scalar;
+
+/// Asymptotic complexity models that the mean times of a parameter scan are fitted against
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Complexity {
+    Constant,
+    Linear,
+    Linearithmic,
+    Quadratic,
+}
+
+impl Complexity {
+    const ALL: [Complexity; 4] = [
+        Complexity::Constant,
+        Complexity::Linear,
+        Complexity::Linearithmic,
+        Complexity::Quadratic,
+    ];
+
+    /// The growth function g(n) of this model
+    fn growth(self, n: Scalar) -> Scalar {
+        match self {
+            Complexity::Constant => 1.0,
+            Complexity::Linear => n,
+            Complexity::Linearithmic => n * n.max(1.0).ln(),
+            Complexity::Quadratic => n * n,
+        }
+    }
+}
+
+impl Complexity {
+    /// The growth function g(n) of this model, as a factor of the coefficient
+    pub fn factor(self) -> &'static str {
+        match self {
+            Complexity::Constant => "",
+            Complexity::Linear => " · n",
+            Complexity::Linearithmic => " · n log n",
+            Complexity::Quadratic => " · n²",
+        }
+    }
+}
+
+impl fmt::Display for Complexity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Complexity::Constant => "O(1)",
+            Complexity::Linear => "O(n)",
+            Complexity::Linearithmic => "O(n log n)",
+            Complexity::Quadratic => "O(n²)",
+        })
+    }
+}
+
+/// A fit of the mean times to `coefficient * g(n)`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ComplexityFit {
+    pub complexity: Complexity,
+    pub coefficient: Scalar,
+
+    /// Coefficient of determination. Since the models do not have an intercept, this is
+    /// always zero for `O(1)` and can be negative for models that fit worse than a constant.
+    pub r_squared: Scalar,
+}
+
+/// Least-squares fit of `ys = c * g(xs)` for the given model
+fn fit_model(complexity: Complexity, xs: &[Scalar], ys: &[Scalar]) -> Option<ComplexityFit> {
+    let gs: Vec<Scalar> = xs.iter().map(|&x| complexity.growth(x)).collect();
+    let sum_gg: Scalar = gs.iter().map(|g| g * g).sum();
+    if sum_gg <= 0.0 {
+        return None;
+    }
+    let coefficient = gs.iter().zip(ys).map(|(g, y)| g * y).sum::<Scalar>() / sum_gg;
+
+    let mean_y = ys.iter().sum::<Scalar>() / ys.len() as Scalar;
+    let ss_tot: Scalar = ys.iter().map(|y| (y - mean_y).powi(2)).sum();
+    let ss_res: Scalar = gs
+        .iter()
+        .zip(ys)
+        .map(|(g, y)| (y - coefficient * g).powi(2))
+        .sum();
+    let r_squared = if ss_tot > 0.0 {
+        1.0 - ss_res / ss_tot
+    } else {
+        0.0
+    };
+
+    Some(ComplexityFit {
+        complexity,
+        coefficient,
+        r_squared,
+    })
+}
+
+/// Fit the given times against all complexity models (without intercept, like Google
+/// Benchmark does) and return the model with the smallest residuals (the simpler one, in case of a tie). Returns `None` if
+/// fewer than three distinct parameter values are given.
+pub fn fit(xs: &[Scalar], ys: &[Scalar]) -> Option<ComplexityFit> {
+    let mut distinct = xs.to_vec();
+    distinct.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    distinct.dedup();
+    if distinct.len() < 3 {
+        return None;
+    }
+
+    Complexity::ALL
+        .iter()
+        .filter_map(|&complexity| fit_model(complexity, xs, ys))
+        .reduce(|best, fit| {
+            if fit.r_squared > best.r_squared {
+                fit
+            } else {
+                best
+            }
+        })
+}
+
+/// Fit the mean times of each command of a parameter scan against the complexity models.
+/// Commands are identified by their name (or command line) before parameter substitution,
+/// together with the value of the scanned parameter. Commands for which no fit can be
+/// computed are omitted.
+pub fn compute<'a>(
+    results: impl IntoIterator<Item = (&'a str, Scalar, &'a BenchmarkResult)>,
+) -> Vec<(&'a str, ComplexityFit)> {
+    let mut scans: Vec<(&str, Vec<Scalar>, Vec<Scalar>)> = vec![];
+    for (command, n, result) in results {
+        match scans.iter_mut().find(|(c, _, _)| *c == command) {
+            Some((_, ns, means)) => {
+                ns.push(n);
+                means.push(result.mean);
+            }
+            None => scans.push((command, vec![n], vec![result.mean])),
+        }
+    }
+
+    scans
+        .iter()
+        .filter_map(|(command, ns, means)| fit(ns, means).map(|fit| (*command, fit)))
+        .collect()
+}
+
+#[test]
+fn test_fit() {
+    use approx::assert_relative_eq;
+
+    let ns = [1.0, 2.0, 4.0, 8.0, 16.0];
+
+    let linear: Vec<_> = ns.iter().map(|n| 0.5 * n).collect();
+    let fit = fit(&ns, &linear).unwrap();
+    assert_eq!(Complexity::Linear, fit.complexity);
+    assert_relative_eq!(0.5, fit.coefficient);
+    assert_relative_eq!(1.0, fit.r_squared);
+
+    let quadratic: Vec<_> = ns.iter().map(|n| 0.01 * n * n + 0.001).collect();
+    assert_eq!(
+        Complexity::Quadratic,
+        self::fit(&ns, &quadratic).unwrap().complexity
+    );
+
+    let linearithmic: Vec<_> = ns.iter().map(|n: &f64| 2.0 * n * n.ln()).collect();
+    assert_eq!(
+        Complexity::Linearithmic,
+        self::fit(&ns, &linearithmic).unwrap().complexity
+    );
+
+    let constant = [1.0, 1.01, 0.99, 1.0, 1.0];
+    assert_eq!(
+        Complexity::Constant,
+        self::fit(&ns, &constant).unwrap().complexity
+    );
+
+    assert!(self::fit(&[1.0, 2.0, 2.0], &[1.0, 2.0, 2.0]).is_none());
+}
+
+#[test]
+fn test_compute() {
+    let result = |mean| BenchmarkResult {
+        mean,
+        ..Default::default()
+    };
+    let results = [result(1.0), result(2.0), result(3.0), result(5.0)];
+    let scans = [
+        ("cmd {n}", 1.0),
+        ("cmd {n}", 2.0),
+        ("cmd {n}", 3.0),
+        ("other", 1.0),
+    ];
+
+    let fits = compute(
+        scans
+            .iter()
+            .zip(&results)
+            .map(|(&(command, n), result)| (command, n, result)),
+    );
+
+    assert_eq!(1, fits.len());
+    assert_eq!("cmd {n}", fits[0].0);
+    assert_eq!(Complexity::Linear, fits[0].1.complexity);
+}
@@ -7,13 +7,14 @@ use crate::options::{
     CmdFailureAction, CommandInputPolicy, CommandOutputPolicy, Options, OutputStyleOption, Shell,
 };
 use crate::output::progress_bar::get_progress_bar;
-use crate::timer::{execute_and_measure, TimerResult};
+use crate::timer::{execute_and_measure, measure_overhead, TimerResult};
 use crate::util::randomized_environment_offset;
 use crate::util::units::Second;
 
-use super::timing_result::TimingResult;
+use super::timing_result::{PerfCounts, TimingResult};
 
 use anyhow::{bail, Context, Result};
+use indicatif::ProgressBar;
 use statistical::mean;
 
 pub trait Executor {
@@ -37,24 +38,58 @@ pub trait Executor {
     fn time_overhead(&self) -> Second;
 }
 
+/// Environment variables that are kept with `--env-clear`, since the commands could not be
+/// found or started without them
+#[cfg(not(windows))]
+const REQUIRED_ENVIRONMENT_VARIABLES: &[&str] = &["PATH"];
+#[cfg(windows)]
+const REQUIRED_ENVIRONMENT_VARIABLES: &[&str] = &["PATH", "PATHEXT", "SystemRoot", "ComSpec"];
+
+/// Set the environment variables of `--env` and `--env-file` for the process that runs the
+/// given command, with its parameters substituted in the values. With `--env-clear`, all
+/// other variables are removed first, except for the required ones.
+pub fn set_environment(
+    process: &mut std::process::Command,
+    command: &Command<'_>,
+    options: &Options,
+) {
+    if options.clear_environment {
+        process.env_clear();
+        for key in REQUIRED_ENVIRONMENT_VARIABLES {
+            if let Some(value) = std::env::var_os(key) {
+                process.env(key, value);
+            }
+        }
+    }
+    for (key, value) in &options.environment {
+        process.env(key, command.replace_parameters_in(value));
+    }
+}
+
 fn run_command_and_measure_common(
     mut command: std::process::Command,
     command_failure_action: CmdFailureAction,
     command_input_policy: &CommandInputPolicy,
     command_output_policy: &CommandOutputPolicy,
-    command_name: &str,
+    benchmark_command: &Command<'_>,
+    options: &Options,
 ) -> Result<TimerResult> {
     let stdin = command_input_policy.get_stdin()?;
     let (stdout, stderr) = command_output_policy.get_stdout_stderr()?;
     command.stdin(stdin).stdout(stdout).stderr(stderr);
 
+    set_environment(&mut command, benchmark_command, options);
     command.env(
         "HYPERFINE_RANDOMIZED_ENVIRONMENT_OFFSET",
         randomized_environment_offset::value(),
     );
 
-    let result = execute_and_measure(command)
-        .with_context(|| format!("Failed to run command '{}'", command_name))?;
+    let result = execute_and_measure(command, options).with_context(|| {
+        format!(
+            "Failed to run command '{}'",
+            benchmark_command.get_command_line()
+        )
+    })?;
 
     if command_failure_action == CmdFailureAction::RaiseError && !result.status.success() {
         bail!(
@@ -72,11 +107,15 @@ fn run_command_and_measure_common(
 
 pub struct RawExecutor<'a> {
     options: &'a Options,
+    timer_overhead: Option<Second>,
 }
 
 impl<'a> RawExecutor<'a> {
     pub fn new(options: &'a Options) -> Self {
-        RawExecutor { options }
+        RawExecutor {
+            options,
+            timer_overhead: None,
+        }
     }
 }
 
@@ -86,30 +125,53 @@ impl<'a> Executor for RawExecutor<'a> {
         command: &Command<'_>,
         command_failure_action: Option<CmdFailureAction>,
     ) -> Result<(TimingResult, ExitStatus)> {
-        let result = run_command_and_measure_common(
+        let mut result = run_command_and_measure_common(
             command.get_command()?,
             command_failure_action.unwrap_or(self.options.command_failure_action),
             &self.options.command_input_policy,
             &self.options.command_output_policy,
-            &command.get_command_line(),
+            command,
+            self.options,
         )?;
 
+        let time_real_raw = result.time_real;
+
+        // Subtract the overhead of the timer
+        if let Some(overhead) = self.timer_overhead {
+            result.time_real = (result.time_real - overhead).max(0.0);
+        }
+
         Ok((
             TimingResult {
                 time_real: result.time_real,
+                time_real_raw,
                 time_user: result.time_user,
                 time_system: result.time_system,
+                memory_usage_byte: result.memory_usage_byte,
+                io: result.io,
+                context_switches: result.context_switches,
+                page_faults: result.page_faults,
+                peak_tasks: result.peak_tasks,
+                total_processes: result.total_processes,
+                perf_counts: result.perf_counts,
+                energy_joule: result.energy_joule,
+                cpu_frequency: result.cpu_frequency,
+                gpu_usage: result.gpu_usage,
+                time_to_first_output: result.time_to_first_output,
             },
             result.status,
         ))
     }
 
+    /// Measure the overhead of the timer. With a shell, this is part of the shell spawning
+    /// time instead.
     fn calibrate(&mut self) -> Result<()> {
+        self.timer_overhead = Some(measure_overhead(self.options)?);
         Ok(())
     }
 
     fn time_overhead(&self) -> Second {
-        0.0
+        self.timer_overhead.unwrap_or(0.0)
     }
 }
 
@@ -117,6 +179,9 @@ pub struct ShellExecutor<'a> {
     options: &'a Options,
     shell: &'a Shell,
     shell_spawning_time: Option<TimingResult>,
+
+    /// Spawning time of the shell along with an empty loop of `--batch` iterations
+    batch_spawning_time: Option<TimingResult>,
 }
 
 impl<'a> ShellExecutor<'a> {
@@ -125,26 +190,50 @@ impl<'a> ShellExecutor<'a> {
             shell,
             options,
             shell_spawning_time: None,
+            batch_spawning_time: None,
         }
     }
-}
 
-impl<'a> Executor for ShellExecutor<'a> {
-    fn run_command_and_measure(
+    /// The command line that is passed to the shell. For a batch, the command is executed in
+    /// a loop, which stops at the first failing iteration.
+    fn shell_command_line(command: &Command<'_>, batch_size: u64) -> String {
+        let command_line = command.get_command_line();
+        if batch_size <= 1 {
+            return command_line;
+        }
+        format!(
+            "__hyperfine_iteration=0\n\
+             while [ \"$__hyperfine_iteration\" -lt {} ]; do\n\
+             {}\n\
+             __hyperfine_status=$?; [ \"$__hyperfine_status\" -eq 0 ] || exit \"$__hyperfine_status\"\n\
+             __hyperfine_iteration=$((__hyperfine_iteration + 1))\n\
+             done",
+            batch_size, command_line
+        )
+    }
+
+    /// Run the command in a batch of the given size, and subtract the spawning time of the
+    /// shell (and of the loop) once they have been calibrated. The times refer to the whole
+    /// batch.
+    fn run_batch_and_measure(
         &self,
         command: &Command<'_>,
         command_failure_action: Option<CmdFailureAction>,
-    ) -> Result<(TimingResult, ExitStatus)> {
+        batch_size: u64,
+    ) -> Result<(TimerResult, Second)> {
         let on_windows_cmd = cfg!(windows) && *self.shell == Shell::Default("cmd.exe");
         let mut command_builder = self.shell.command();
         command_builder.arg(if on_windows_cmd { "/C" } else { "-c" });
+        if let Some(directory) = command.get_working_directory() {
+            command_builder.current_dir(directory);
+        }
 
         // Windows needs special treatment for its behavior on parsing cmd arguments
         if on_windows_cmd {
             #[cfg(windows)]
             command_builder.raw_arg(command.get_command_line());
         } else {
-            command_builder.arg(command.get_command_line());
+            command_builder.arg(Self::shell_command_line(command, batch_size));
         }
 
         let mut result = run_command_and_measure_common(
@@ -152,38 +241,38 @@ impl<'a> Executor for ShellExecutor<'a> {
             command_failure_action.unwrap_or(self.options.command_failure_action),
             &self.options.command_input_policy,
             &self.options.command_output_policy,
-            &command.get_command_line(),
+            command,
+            self.options,
         )?;
 
-        // Subtract shell spawning time
-        if let Some(spawning_time) = self.shell_spawning_time {
+        let time_real_raw = result.time_real;
+
+        // Subtract shell spawning time. This also includes the overhead of the timer, since
+        // it is measured in the same way.
+        let spawning_time = if batch_size > 1 {
+            self.batch_spawning_time
+        } else {
+            self.shell_spawning_time
+        };
+        if let Some(spawning_time) = spawning_time {
             result.time_real = (result.time_real - spawning_time.time_real).max(0.0);
             result.time_user = (result.time_user - spawning_time.time_user).max(0.0);
             result.time_system = (result.time_system - spawning_time.time_system).max(0.0);
+            result.time_to_first_output = result
+                .time_to_first_output
+                .map(|time| (time - spawning_time.time_real).max(0.0));
         }
 
-        Ok((
-            TimingResult {
-                time_real: result.time_real,
-                time_user: result.time_user,
-                time_system: result.time_system,
-            },
-            result.status,
-        ))
+        Ok((result, time_real_raw))
     }
 
-    /// Measure the average shell spawning time
-    fn calibrate(&mut self) -> Result<()> {
+    /// Measure the average spawning time of the shell, with a batch of empty commands
+    fn measure_spawning_time(
+        &self,
+        batch_size: u64,
+        progress_bar: Option<&ProgressBar>,
+    ) -> Result<TimingResult> {
         const COUNT: u64 = 50;
-        let progress_bar = if self.options.output_style != OutputStyleOption::Disabled {
-            Some(get_progress_bar(
-                COUNT,
-                "Measuring shell spawning time",
-                self.options.output_style,
-            ))
-        } else {
-            None
-        };
 
         let mut times_real: Vec<Second> = vec![];
         let mut times_user: Vec<Second> = vec![];
@@ -191,7 +280,7 @@ impl<'a> Executor for ShellExecutor<'a> {
 
         for _ in 0..COUNT {
             // Just run the shell without any command
-            let res = self.run_command_and_measure(&Command::new(None, ""), None);
+            let res = self.run_batch_and_measure(&Command::new(None, ""), None, batch_size);
 
             match res {
                 Err(_) => {
@@ -213,20 +302,95 @@ impl<'a> Executor for ShellExecutor<'a> {
                 }
             }
 
-            if let Some(bar) = progress_bar.as_ref() {
+            if let Some(bar) = progress_bar {
                 bar.inc(1)
             }
         }
 
-        if let Some(bar) = progress_bar.as_ref() {
-            bar.finish_and_clear()
-        }
-
-        self.shell_spawning_time = Some(TimingResult {
+        Ok(TimingResult {
             time_real: mean(&times_real),
+            time_real_raw: mean(&times_real),
             time_user: mean(&times_user),
             time_system: mean(&times_system),
-        });
+            memory_usage_byte: None,
+            io: None,
+            context_switches: None,
+            page_faults: None,
+            peak_tasks: None,
+            total_processes: None,
+            perf_counts: PerfCounts::default(),
+            energy_joule: None,
+            cpu_frequency: None,
+            gpu_usage: None,
+            time_to_first_output: None,
+        })
+    }
+}
+
+impl<'a> Executor for ShellExecutor<'a> {
+    fn run_command_and_measure(
+        &self,
+        command: &Command<'_>,
+        command_failure_action: Option<CmdFailureAction>,
+    ) -> Result<(TimingResult, ExitStatus)> {
+        // Only the benchmarked command is run in batches, the setup, preparation and cleanup
+        // commands are run with an explicit failure action
+        let batch_size = if command_failure_action.is_none() {
+            self.options.batch_size
+        } else {
+            1
+        };
+        let (result, time_real_raw) =
+            self.run_batch_and_measure(command, command_failure_action, batch_size)?;
+
+        // With `--batch`, the times are those of a single execution of the command. The
+        // other measurements refer to the whole batch.
+        let batch_size = batch_size as Second;
+
+        Ok((
+            TimingResult {
+                time_real: result.time_real / batch_size,
+                time_real_raw: time_real_raw / batch_size,
+                time_user: result.time_user / batch_size,
+                time_system: result.time_system / batch_size,
+                memory_usage_byte: result.memory_usage_byte,
+                io: result.io,
+                context_switches: result.context_switches,
+                page_faults: result.page_faults,
+                peak_tasks: result.peak_tasks,
+                total_processes: result.total_processes,
+                perf_counts: result.perf_counts,
+                energy_joule: result.energy_joule,
+                cpu_frequency: result.cpu_frequency,
+                gpu_usage: result.gpu_usage,
+                time_to_first_output: result.time_to_first_output,
+            },
+            result.status,
+        ))
+    }
+
+    /// Measure the average shell spawning time
+    fn calibrate(&mut self) -> Result<()> {
+        let batch_size = self.options.batch_size;
+        let progress_bar = if self.options.output_style != OutputStyleOption::Disabled {
+            Some(get_progress_bar(
+                if batch_size > 1 { 100 } else { 50 },
+                "Measuring shell spawning time",
+                self.options.output_style,
+            ))
+        } else {
+            None
+        };
+
+        self.shell_spawning_time = Some(self.measure_spawning_time(1, progress_bar.as_ref())?);
+        if batch_size > 1 {
+            self.batch_spawning_time =
+                Some(self.measure_spawning_time(batch_size, progress_bar.as_ref())?);
+        }
+
+        if let Some(bar) = progress_bar.as_ref() {
+            bar.finish_and_clear()
+        }
 
         Ok(())
     }
@@ -274,11 +438,25 @@ impl Executor for MockExecutor {
             ExitStatus::from_raw(0)
         };
 
+        let time_real = Self::extract_time(command.get_command_line());
+
         Ok((
             TimingResult {
-                time_real: Self::extract_time(command.get_command_line()),
+                time_real,
+                time_real_raw: time_real,
                 time_user: 0.0,
                 time_system: 0.0,
+                memory_usage_byte: None,
+                io: None,
+                context_switches: None,
+                page_faults: None,
+                peak_tasks: None,
+                total_processes: None,
+                perf_counts: PerfCounts::default(),
+                energy_joule: None,
+                cpu_frequency: None,
+                gpu_usage: None,
+                time_to_first_output: None,
             },
             status,
         ))
@@ -300,3 +478,27 @@ impl Executor for MockExecutor {
 fn test_mock_executor_extract_time() {
     assert_eq!(MockExecutor::extract_time("sleep 0.1"), 0.1);
 }
+
+#[test]
+fn test_measure_overhead_is_non_negative() {
+    let overhead = measure_overhead(&Options::default()).unwrap();
+    assert!(overhead.is_finite() && overhead >= 0.0);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_raw_executor_subtracts_timer_overhead() {
+    let options = Options::default();
+    let mut executor = RawExecutor::new(&options);
+    executor.calibrate().unwrap();
+    assert!(executor.time_overhead() >= 0.0);
+
+    // The corrected time is clamped at zero if the overhead exceeds the measured time
+    executor.timer_overhead = Some(1000.0);
+    let (result, status) = executor
+        .run_command_and_measure(&Command::new(None, "true"), None)
+        .unwrap();
+    assert!(status.success());
+    assert_eq!(result.time_real, 0.0);
+    assert!(result.time_real_raw > 0.0);
+}
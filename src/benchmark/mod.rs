@@ -1,25 +1,51 @@
 pub mod benchmark_result;
+pub mod comparison_matrix;
+pub mod complexity;
 pub mod executor;
+pub mod profiler;
 pub mod relative_speed;
 pub mod scheduler;
 pub mod timing_result;
 
+use std::cell::Cell;
 use std::cmp;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::process::ExitStatus;
+use std::time::SystemTime;
 
-use crate::command::Command;
-use crate::options::{CmdFailureAction, ExecutorKind, Options, OutputStyleOption};
-use crate::outlier_detection::{modified_zscores, OUTLIER_THRESHOLD};
-use crate::output::format::{format_duration, format_duration_unit};
+use crate::command::{CacheState, Command};
+use crate::export::{ExportManager, RunKind, RunResult};
+use crate::options::{
+    CmdFailureAction, DropCaches, ExecutorKind, Options, OutputStyleOption, PerfCounter,
+    Throughput, Trim,
+};
+use crate::outlier_detection::scaled_modified_zscores;
+use crate::output::chart::{histogram, sparkline};
+use crate::output::format::{
+    format_bytes, format_count, format_duration, format_duration_unit, format_throughput,
+};
+use crate::output::outputln;
 use crate::output::progress_bar::get_progress_bar;
 use crate::output::warnings::{OutlierWarningOptions, Warnings};
 use crate::parameter::ParameterNameAndValue;
-use crate::util::exit_code::extract_exit_code;
+use crate::statistics::{
+    bootstrap_mean, linear_regression_slope, mann_kendall_test, mean_ci_half_width, percentile,
+    required_runs, trimmed, two_clusters, winsorized,
+};
+#[cfg(target_os = "linux")]
+use crate::util::background_load::BackgroundLoadMonitor;
+use crate::util::date::unix_timestamp;
+use crate::util::exit_code::{extract_exit_code, extract_signal};
 use crate::util::min_max::{max, min};
-use crate::util::units::Second;
-use benchmark_result::BenchmarkResult;
-use timing_result::TimingResult;
+use crate::util::system_state::SystemState;
+use crate::util::units::{Scalar, Second};
+use benchmark_result::{BenchmarkResult, OutlierRun, Percentile, ThroughputStats};
+use timing_result::{
+    ContextSwitches, CpuFrequency, GpuUsage, IoCounters, PageFaults, PerfCounts, TimingResult,
+};
 
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, bail, Result};
 use colored::*;
 use statistical::{mean, median, standard_deviation};
 
@@ -28,11 +54,82 @@ use self::executor::Executor;
 /// Threshold for warning about fast execution time
 pub const MIN_EXECUTION_TIME: Second = 5e-3;
 
+/// Significance level of the trend test for warning about drifting run times
+const DRIFT_SIGNIFICANCE_LEVEL: f64 = 0.01;
+
+/// Minimum relative change of the run times over a benchmark for warning about drift
+const MIN_RELATIVE_DRIFT: f64 = 0.1;
+
+/// Minimum number of runs for the drift detection
+const MIN_RUNS_FOR_DRIFT_DETECTION: usize = 10;
+
+/// Minimum separation (Ashman's D) of two clusters of run times for warning about a bimodal
+/// distribution. This is well above the separation of the two halves of a normal distribution,
+/// even for small samples.
+const MIN_BIMODAL_SEPARATION: f64 = 6.0;
+
+/// Minimum fraction of the runs in the smaller cluster for warning about a bimodal distribution
+const MIN_BIMODAL_CLUSTER_FRACTION: f64 = 0.15;
+
+/// Minimum number of runs for the bimodality detection
+const MIN_RUNS_FOR_BIMODALITY_DETECTION: usize = 20;
+
+/// Minimum CPU utilization for classifying a command as CPU-bound
+const CPU_BOUND_UTILIZATION: f64 = 0.9;
+
+/// Maximum CPU utilization for classifying a command as I/O-bound (or waiting otherwise)
+const IO_BOUND_UTILIZATION: f64 = 0.5;
+
+/// Minimum rate of involuntary context switches (per second of wall clock time) for warning
+/// that the command competed with other programs for the CPU
+const MAX_INVOLUNTARY_CONTEXT_SWITCH_RATE: f64 = 100.0;
+
+/// Minimum mean number of involuntary context switches per run for that warning, since a few
+/// of them happen when starting any process
+const MIN_INVOLUNTARY_CONTEXT_SWITCHES: f64 = 10.0;
+
+/// Maximum fraction of the CPU capacity of the system that other processes may use during a
+/// benchmark before warning about interference
+const MAX_BACKGROUND_LOAD: f64 = 0.3;
+
+/// Statistical power that is assumed for the power analysis (`--power-analysis`)
+const POWER_ANALYSIS_POWER: f64 = 0.8;
+
+/// Number of runs in each of the two windows that are compared by the automatic warmup
+const AUTO_WARMUP_WINDOW: usize = 3;
+
+/// Maximum relative change of the median run time between two consecutive windows, below
+/// which the automatic warmup considers the run times to be stable
+const AUTO_WARMUP_TOLERANCE: f64 = 0.05;
+
+/// Maximum number of runs of the automatic warmup
+const AUTO_WARMUP_MAX_RUNS: u64 = 50;
+
+/// Whether the run times of the automatic warmup (`--warmup auto`) have stabilized, i.e. the
+/// median of the last runs is close to the median of the runs before
+fn is_warmed_up(times: &[Second]) -> bool {
+    if times.len() < 2 * AUTO_WARMUP_WINDOW {
+        return false;
+    }
+
+    let recent = &times[times.len() - 2 * AUTO_WARMUP_WINDOW..];
+    let (previous, last) = recent.split_at(AUTO_WARMUP_WINDOW);
+    let (previous, last) = (median(previous), median(last));
+
+    previous > 0.0 && ((last - previous) / previous).abs() < AUTO_WARMUP_TOLERANCE
+}
+
+/// Mean of the given per-run counts, e.g. of bytes or context switches
+fn mean_count(counts: &[u64]) -> Scalar {
+    counts.iter().sum::<u64>() as Scalar / counts.len() as Scalar
+}
+
 pub struct Benchmark<'a> {
     number: usize,
     command: &'a Command<'a>,
     options: &'a Options,
     executor: &'a dyn Executor,
+    export_manager: &'a ExportManager,
 }
 
 impl<'a> Benchmark<'a> {
@@ -41,15 +138,68 @@ impl<'a> Benchmark<'a> {
         command: &'a Command<'a>,
         options: &'a Options,
         executor: &'a dyn Executor,
+        export_manager: &'a ExportManager,
     ) -> Self {
         Benchmark {
             number,
             command,
             options,
             executor,
+            export_manager,
         }
     }
 
+    /// Run the command once more under a profiler (`--profile`), after the timing runs.
+    /// Returns the path of the recorded profile.
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
+    fn run_profiler(&self) -> Result<Option<PathBuf>> {
+        let Some(ref dir) = self.options.profile_dir else {
+            return Ok(None);
+        };
+        let mut command = match self.options.executor_kind {
+            ExecutorKind::Shell(ref shell) => {
+                let mut command = shell.command();
+                command.arg("-c").arg(self.command.get_command_line());
+                if let Some(directory) = self.command.get_working_directory() {
+                    command.current_dir(directory);
+                }
+                command
+            }
+            ExecutorKind::Raw => self.command.get_command()?,
+            ExecutorKind::Mock(_) => return Ok(None),
+        };
+        executor::set_environment(&mut command, self.command, self.options);
+
+        let path = profiler::output_path(dir, self.number);
+        profiler::profile(
+            &command,
+            &path,
+            &self.options.command_input_policy,
+            &self.options.command_output_policy,
+        )?;
+        Ok(Some(path))
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+    fn run_profiler(&self) -> Result<Option<PathBuf>> {
+        Ok(None)
+    }
+
+    /// Write dirty pages to the disks before a run, if requested by `--sync-before-run`.
+    /// Nothing is written in debug mode.
+    fn sync_file_systems(&self) -> Result<()> {
+        if !self.options.sync_before_run
+            || matches!(self.options.executor_kind, ExecutorKind::Mock(_))
+        {
+            return Ok(());
+        }
+
+        #[cfg(any(unix, windows))]
+        crate::util::sync::sync_file_systems()?;
+
+        Ok(())
+    }
+
     /// Run setup, cleanup, or preparation commands
     fn run_intermediate_command(
         &self,
@@ -62,16 +212,40 @@ impl<'a> Benchmark<'a> {
             .map_err(|_| anyhow!(error_output))
     }
 
+    /// Number of bytes or items that the command processes in each run (`--throughput`)
+    fn throughput_amount(&self, throughput: &Throughput) -> Result<Scalar> {
+        let parameters = self.command.get_parameters();
+        let amount = match throughput.amount {
+            Some(ref amount) => Command::new_parametrized(None, amount, parameters.iter().cloned())
+                .get_command_line(),
+            None => match parameters.first() {
+                Some((_, value)) => value.to_string(),
+                None => bail!(
+                    "The throughput amount has to be specified ('--throughput bytes=AMOUNT') \
+                     for benchmarks without parameters"
+                ),
+            },
+        };
+
+        match amount.parse::<Scalar>() {
+            Ok(a) if a.is_finite() && a > 0.0 => Ok(a),
+            _ => bail!(
+                "The throughput amount '{}' of the command '{}' is not a positive number",
+                amount,
+                self.command.get_name()
+            ),
+        }
+    }
+
     /// Run the command specified by `--setup`.
     fn run_setup_command(
         &self,
         parameters: impl IntoIterator<Item = ParameterNameAndValue<'a>>,
     ) -> Result<TimingResult> {
-        let command = self
-            .options
-            .setup_command
-            .as_ref()
-            .map(|setup_command| Command::new_parametrized(None, setup_command, parameters));
+        let command = self.options.setup_command.as_ref().map(|setup_command| {
+            Command::new_parametrized(None, setup_command, parameters)
+                .in_working_directory(self.command.get_working_directory_template())
+        });
 
         let error_output = "The setup command terminated with a non-zero exit code. \
                             Append ' || true' to the command if you are sure that this can be ignored.";
@@ -91,7 +265,10 @@ impl<'a> Benchmark<'a> {
             .options
             .cleanup_command
             .as_ref()
-            .map(|cleanup_command| Command::new_parametrized(None, cleanup_command, parameters));
+            .map(|cleanup_command| {
+                Command::new_parametrized(None, cleanup_command, parameters)
+                    .in_working_directory(self.command.get_working_directory_template())
+            });
 
         let error_output = "The cleanup command terminated with a non-zero exit code. \
                             Append ' || true' to the command if you are sure that this can be ignored.";
@@ -102,6 +279,21 @@ impl<'a> Benchmark<'a> {
             .unwrap_or_default())
     }
 
+    /// Drop the file system caches if this is the time given by `--drop-caches`. Nothing is
+    /// dropped in debug mode, which does not run any commands.
+    fn drop_caches(&self, when: DropCaches) -> Result<()> {
+        if self.options.drop_caches != Some(when)
+            || matches!(self.options.executor_kind, ExecutorKind::Mock(_))
+        {
+            return Ok(());
+        }
+
+        #[cfg(any(target_os = "linux", target_os = "macos", windows))]
+        crate::util::drop_caches::drop_caches(false)?;
+
+        Ok(())
+    }
+
     /// Run the command specified by `--prepare`.
     fn run_preparation_command(&self, command: &Command<'_>) -> Result<TimingResult> {
         let error_output = "The preparation command terminated with a non-zero exit code. \
@@ -110,10 +302,48 @@ impl<'a> Benchmark<'a> {
         self.run_intermediate_command(command, error_output)
     }
 
+    /// Time that a run of the benchmarked command took, including the overhead. With
+    /// `--batch`, this is the time of the whole batch.
+    fn batch_time(&self, res: &TimingResult) -> Second {
+        res.time_real * self.options.batch_size as Second + self.executor.time_overhead()
+    }
+
+    /// Whether the benchmark should be continued beyond the initially determined number of
+    /// runs, because the run times are not yet precise enough (`--max-relative-stddev` and
+    /// `--max-ci-width`). The number of runs is still bounded by `--max-runs`.
+    fn needs_more_runs(&self, times: &[Second]) -> bool {
+        let Some(max_runs) = self.options.run_bounds.max else {
+            return false;
+        };
+        if times.len() as u64 >= max_runs {
+            return false;
+        }
+
+        if let Some(max_relative_stddev) = self.options.max_relative_stddev {
+            if times.len() < 2 {
+                return true;
+            }
+            let t_mean = mean(times);
+            if standard_deviation(times, Some(t_mean)) > max_relative_stddev * t_mean {
+                return true;
+            }
+        }
+
+        if let Some(max_ci_width) = self.options.max_ci_width {
+            match mean_ci_half_width(times, self.options.confidence_level) {
+                Some(half_width) if half_width <= max_ci_width * mean(times) => {}
+                _ => return true,
+            }
+        }
+
+        false
+    }
+
     /// Run the benchmark for a single command
     pub fn run(&self) -> Result<BenchmarkResult> {
         if self.options.output_style != OutputStyleOption::Disabled {
-            println!(
+            outputln!(
+                self.options,
                 "{}{}: {}",
                 "Benchmark ".bold(),
                 (self.number + 1).to_string().bold(),
@@ -121,38 +351,131 @@ impl<'a> Benchmark<'a> {
             );
         }
 
+        #[cfg(target_os = "linux")]
+        let background_load_monitor = if matches!(self.options.executor_kind, ExecutorKind::Mock(_))
+        {
+            None
+        } else {
+            BackgroundLoadMonitor::start()
+        };
+
+        // Determine the throughput amount upfront, to fail before running the benchmark
+        let throughput_amount = self
+            .options
+            .throughput
+            .as_ref()
+            .map(|throughput| self.throughput_amount(throughput))
+            .transpose()?;
+
         let mut times_real: Vec<Second> = vec![];
+        let mut times_raw: Vec<Second> = vec![];
         let mut times_user: Vec<Second> = vec![];
         let mut times_system: Vec<Second> = vec![];
+        let mut memory_usage: Vec<Option<u64>> = vec![];
+        let mut io_counters: Vec<Option<IoCounters>> = vec![];
+        let mut context_switches: Vec<Option<ContextSwitches>> = vec![];
+        let mut page_faults: Vec<Option<PageFaults>> = vec![];
+        let mut peak_tasks: Vec<Option<u64>> = vec![];
+        let mut total_processes: Vec<Option<u64>> = vec![];
+        let mut perf_counts: Vec<PerfCounts> = vec![];
+        let mut energy: Vec<Option<f64>> = vec![];
+        let mut cpu_frequencies: Vec<Option<CpuFrequency>> = vec![];
+        let mut gpu_usages: Vec<Option<GpuUsage>> = vec![];
+        let mut first_output_times: Vec<Option<Second>> = vec![];
+        let mut timestamps: Vec<f64> = vec![];
+        let mut system_states: Vec<SystemState> = vec![];
         let mut exit_codes: Vec<Option<i32>> = vec![];
+        let mut signals: Vec<Option<i32>> = vec![];
         let mut all_succeeded = true;
 
-        let preparation_command = self.options.preparation_command.as_ref().map(|values| {
-            let preparation_command = if values.len() == 1 {
-                &values[0]
-            } else {
-                &values[self.number]
+        let command_name = self.command.get_name();
+        let parameters = self
+            .command
+            .get_parameters()
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        let export_run =
+            |kind, run, timestamp, system_state, timing, status: Option<ExitStatus>| {
+                self.export_manager.write_run(&RunResult {
+                    command: &command_name,
+                    parameters: &parameters,
+                    kind,
+                    run,
+                    timestamp,
+                    system_state,
+                    timing,
+                    // Preparation commands are only reported if they succeeded
+                    exit_code: status.map_or(Some(0), extract_exit_code),
+                    signal: status.and_then(extract_signal),
+                })
             };
+
+        let preparation_command = match self.command.get_cache_state() {
+            Some(CacheState::Cold) => self.options.cold_preparation_command.as_ref(),
+            _ => self.options.preparation_command.as_ref().map(|values| {
+                if values.len() == 1 {
+                    &values[0]
+                } else {
+                    &values[self.number]
+                }
+            }),
+        }
+        .map(|preparation_command| {
             Command::new_parametrized(
                 None,
                 preparation_command,
                 self.command.get_parameters().iter().cloned(),
             )
+            .in_working_directory(self.command.get_working_directory_template())
         });
-        let run_preparation_command = || {
-            preparation_command
-                .as_ref()
-                .map(|cmd| self.run_preparation_command(cmd))
-                .transpose()
+        let preparation_runs = Cell::new(0);
+        let run_preparation_command = || -> Result<Option<TimingResult>> {
+            self.drop_caches(DropCaches::BeforeEachRun)?;
+
+            let res = match preparation_command.as_ref() {
+                Some(cmd) => {
+                    let timestamp = unix_timestamp(SystemTime::now());
+                    let system_state = SystemState::now();
+                    let res = self.run_preparation_command(cmd)?;
+                    let run = preparation_runs.replace(preparation_runs.get() + 1);
+                    export_run(
+                        RunKind::Preparation,
+                        run,
+                        timestamp,
+                        system_state,
+                        res,
+                        None,
+                    )?;
+                    Some(res)
+                }
+                None => None,
+            };
+
+            self.sync_file_systems()?;
+            Ok(res)
         };
 
         self.run_setup_command(self.command.get_parameters().iter().cloned())?;
+        self.drop_caches(DropCaches::BeforeEachBenchmark)?;
 
         // Warmup phase
-        if self.options.warmup_count > 0 {
+        let max_warmup_runs = if self.options.auto_warmup {
+            AUTO_WARMUP_MAX_RUNS
+        } else if self.command.get_cache_state() == Some(CacheState::Warm) {
+            // The caches are filled by at least one run
+            cmp::max(self.options.warmup_count, 1)
+        } else {
+            self.options.warmup_count
+        };
+        let mut warmup_times: Vec<Second> = vec![];
+
+        // Time spent on this command, for `--max-time`
+        let mut time_spent: Second = 0.0;
+        if max_warmup_runs > 0 {
             let progress_bar = if self.options.output_style != OutputStyleOption::Disabled {
                 Some(get_progress_bar(
-                    self.options.warmup_count,
+                    max_warmup_runs,
                     "Performing warmup runs",
                     self.options.output_style,
                 ))
@@ -160,12 +483,28 @@ impl<'a> Benchmark<'a> {
                 None
             };
 
-            for _ in 0..self.options.warmup_count {
+            for run in 0..max_warmup_runs {
                 let _ = run_preparation_command()?;
-                let _ = self.executor.run_command_and_measure(self.command, None)?;
+                let timestamp = unix_timestamp(SystemTime::now());
+                let system_state = SystemState::now();
+                let (res, status) = self.executor.run_command_and_measure(self.command, None)?;
+                export_run(
+                    RunKind::Warmup,
+                    run as usize,
+                    timestamp,
+                    system_state,
+                    res,
+                    Some(status),
+                )?;
                 if let Some(bar) = progress_bar.as_ref() {
                     bar.inc(1)
                 }
+
+                warmup_times.push(res.time_real);
+                time_spent += self.batch_time(&res);
+                if self.options.auto_warmup && is_warmed_up(&warmup_times) {
+                    break;
+                }
             }
             if let Some(bar) = progress_bar.as_ref() {
                 bar.finish_and_clear()
@@ -188,13 +527,14 @@ impl<'a> Benchmark<'a> {
             preparation_result.map_or(0.0, |res| res.time_real + self.executor.time_overhead());
 
         // Initial timing run
+        let timestamp = unix_timestamp(SystemTime::now());
+        let system_state = SystemState::now();
         let (res, status) = self.executor.run_command_and_measure(self.command, None)?;
         let success = status.success();
 
         // Determine number of benchmark runs
         let runs_in_min_time = (self.options.min_benchmarking_time
-            / (res.time_real + self.executor.time_overhead() + preparation_overhead))
-            as u64;
+            / (self.batch_time(&res) + preparation_overhead)) as u64;
 
         let count = {
             let min = cmp::max(runs_in_min_time, self.options.run_bounds.min);
@@ -207,13 +547,35 @@ impl<'a> Benchmark<'a> {
                 .unwrap_or(min)
         };
 
-        let count_remaining = count - 1;
-
         // Save the first result
+        time_spent += self.batch_time(&res) + preparation_overhead;
         times_real.push(res.time_real);
+        times_raw.push(res.time_real_raw);
         times_user.push(res.time_user);
         times_system.push(res.time_system);
+        memory_usage.push(res.memory_usage_byte);
+        io_counters.push(res.io);
+        context_switches.push(res.context_switches);
+        page_faults.push(res.page_faults);
+        peak_tasks.push(res.peak_tasks);
+        total_processes.push(res.total_processes);
+        perf_counts.push(res.perf_counts);
+        energy.push(res.energy_joule);
+        cpu_frequencies.push(res.cpu_frequency);
+        gpu_usages.push(res.gpu_usage);
+        first_output_times.push(res.time_to_first_output);
+        timestamps.push(timestamp);
+        system_states.push(system_state);
         exit_codes.push(extract_exit_code(status));
+        signals.push(extract_signal(status));
+        export_run(
+            RunKind::Timing,
+            0,
+            timestamp,
+            system_state,
+            res,
+            Some(status),
+        )?;
 
         all_succeeded = all_succeeded && success;
 
@@ -226,7 +588,22 @@ impl<'a> Benchmark<'a> {
         }
 
         // Gather statistics (perform the actual benchmark)
-        for _ in 0..count_remaining {
+        let mut truncated = false;
+        while (times_real.len() as u64) < count || self.needs_more_runs(&times_real) {
+            if let Some(max_time) = self.options.max_time {
+                if time_spent >= max_time && times_real.len() as u64 >= self.options.run_bounds.min
+                {
+                    truncated = true;
+                    break;
+                }
+            }
+
+            if (times_real.len() as u64) >= count {
+                if let Some(bar) = progress_bar.as_ref() {
+                    bar.set_length(times_real.len() as u64 + 1)
+                }
+            }
+
             run_preparation_command()?;
 
             let msg = {
@@ -238,13 +615,40 @@ impl<'a> Benchmark<'a> {
                 bar.set_message(msg.to_owned())
             }
 
+            let timestamp = unix_timestamp(SystemTime::now());
+            let system_state = SystemState::now();
             let (res, status) = self.executor.run_command_and_measure(self.command, None)?;
             let success = status.success();
 
+            time_spent += self.batch_time(&res) + preparation_overhead;
             times_real.push(res.time_real);
+            times_raw.push(res.time_real_raw);
             times_user.push(res.time_user);
             times_system.push(res.time_system);
+            memory_usage.push(res.memory_usage_byte);
+            io_counters.push(res.io);
+            context_switches.push(res.context_switches);
+            page_faults.push(res.page_faults);
+            peak_tasks.push(res.peak_tasks);
+            total_processes.push(res.total_processes);
+            perf_counts.push(res.perf_counts);
+            energy.push(res.energy_joule);
+            cpu_frequencies.push(res.cpu_frequency);
+            gpu_usages.push(res.gpu_usage);
+            first_output_times.push(res.time_to_first_output);
+            timestamps.push(timestamp);
+            system_states.push(system_state);
             exit_codes.push(extract_exit_code(status));
+            signals.push(extract_signal(status));
+            let run = times_real.len() - 1;
+            export_run(
+                RunKind::Timing,
+                run,
+                timestamp,
+                system_state,
+                res,
+                Some(status),
+            )?;
 
             all_succeeded = all_succeeded && success;
 
@@ -257,33 +661,183 @@ impl<'a> Benchmark<'a> {
             bar.finish_and_clear()
         }
 
+        let profile_path = self.run_profiler()?;
+
+        // Run outlier detection
+        let outliers = self.options.outlier_method.outliers(&times_real);
+        let num_outliers = outliers.iter().filter(|&&o| o).count();
+
+        // Remove the outliers from the statistics, if requested. We always keep at least one run.
+        let discard_outliers =
+            self.options.discard_outliers && num_outliers > 0 && num_outliers < times_real.len();
+        let retained = |xs: &[Second]| -> Vec<Second> {
+            xs.iter()
+                .zip(&outliers)
+                .filter(|(_, &outlier)| !(discard_outliers && outlier))
+                .map(|(&x, _)| x)
+                .collect()
+        };
+        let times_stats = retained(&times_real);
+
+        let zscores = scaled_modified_zscores(&times_real);
+        let outlier_runs: Vec<OutlierRun> = if self.options.show_outliers && num_outliers > 0 {
+            outliers
+                .iter()
+                .enumerate()
+                .filter(|(_, &outlier)| outlier)
+                .map(|(index, _)| OutlierRun {
+                    index,
+                    time: times_real[index],
+                    zscore: zscores[index],
+                })
+                .collect()
+        } else {
+            vec![]
+        };
+
+        // The mean (and the spread around it) can be based on a trimmed or winsorized sample
+        let times_mean = match self.options.trim {
+            Some(Trim::Trimmed(fraction)) => trimmed(&times_stats, fraction),
+            Some(Trim::Winsorized(fraction)) => winsorized(&times_stats, fraction),
+            None => times_stats.clone(),
+        };
+
         // Compute statistical quantities
         let t_num = times_real.len();
-        let t_mean = mean(&times_real);
-        let t_stddev = if times_real.len() > 1 {
-            Some(standard_deviation(&times_real, Some(t_mean)))
+        let t_mean = mean(&times_mean);
+        let t_stddev = if times_mean.len() > 1 {
+            Some(standard_deviation(&times_mean, Some(t_mean)))
+        } else {
+            None
+        };
+        let t_sem = t_stddev.map(|stddev| stddev / (times_mean.len() as Scalar).sqrt());
+        let t_median = median(&times_stats);
+        let t_mean_ci = if self.options.bootstrap {
+            bootstrap_mean(&times_mean, self.options.confidence_level)
         } else {
             None
         };
-        let t_median = median(&times_real);
-        let t_min = min(&times_real);
-        let t_max = max(&times_real);
+        // The memory usage is only available if it could be measured for every run
+        let memory_usage_byte: Option<Vec<u64>> = memory_usage.into_iter().collect();
+        // The same holds for the I/O counters
+        let (io_read_byte, io_write_byte): (Option<Vec<u64>>, Option<Vec<u64>>) =
+            match io_counters.into_iter().collect::<Option<Vec<_>>>() {
+                Some(io) => (
+                    Some(io.iter().map(|c| c.read_bytes).collect()),
+                    Some(io.iter().map(|c| c.written_bytes).collect()),
+                ),
+                None => (None, None),
+            };
+        let (voluntary_context_switches, involuntary_context_switches): (
+            Option<Vec<u64>>,
+            Option<Vec<u64>>,
+        ) = match context_switches.into_iter().collect::<Option<Vec<_>>>() {
+            Some(switches) => (
+                Some(switches.iter().map(|c| c.voluntary).collect()),
+                Some(switches.iter().map(|c| c.involuntary).collect()),
+            ),
+            None => (None, None),
+        };
+        let (minor_page_faults, major_page_faults): (Option<Vec<u64>>, Option<Vec<u64>>) =
+            match page_faults.into_iter().collect::<Option<Vec<_>>>() {
+                Some(faults) => (
+                    Some(faults.iter().map(|p| p.minor).collect()),
+                    Some(faults.iter().map(|p| p.major).collect()),
+                ),
+                None => (None, None),
+            };
+        let peak_tasks: Option<Vec<u64>> = peak_tasks.into_iter().collect();
+        let total_processes: Option<Vec<u64>> = total_processes.into_iter().collect();
+        let energy_joule: Option<Vec<f64>> = energy.into_iter().collect();
+        let cpu_frequency_mhz: Option<Vec<CpuFrequency>> = cpu_frequencies.into_iter().collect();
+        let gpu_usage: Option<Vec<GpuUsage>> = gpu_usages.into_iter().collect();
+        let load_average: Option<Vec<f64>> = system_states.iter().map(|s| s.load_average).collect();
+        let available_memory_byte: Option<Vec<u64>> = system_states
+            .iter()
+            .map(|s| s.available_memory_byte)
+            .collect();
+        // Only reported if the command wrote something to stdout in every run
+        let time_to_first_output: Option<Vec<Second>> = first_output_times.into_iter().collect();
+        let perf_counters: BTreeMap<String, Vec<u64>> = self
+            .options
+            .perf_counters
+            .iter()
+            .filter_map(|&counter| {
+                let values: Option<Vec<u64>> = perf_counts.iter().map(|c| c.get(counter)).collect();
+                Some((counter.name().to_string(), values?))
+            })
+            .collect();
+
+        let t_min = min(&times_stats);
+        let t_max = max(&times_stats);
+        let t_percentiles: Vec<Percentile> = self
+            .options
+            .percentiles
+            .iter()
+            .map(|&p| Percentile {
+                percentile: p,
+                time: percentile(&times_stats, p),
+            })
+            .collect();
+
+        let t_throughput = match (&self.options.throughput, throughput_amount) {
+            (Some(throughput), Some(amount)) => {
+                let rates: Vec<Scalar> = times_stats
+                    .iter()
+                    .filter(|&&t| t > 0.0)
+                    .map(|&t| amount / t)
+                    .collect();
+                (!rates.is_empty()).then(|| ThroughputStats {
+                    unit: throughput.unit,
+                    amount,
+                    mean: mean(&rates),
+                    stddev: (rates.len() > 1).then(|| standard_deviation(&rates, None)),
+                })
+            }
+            _ => None,
+        };
 
-        let user_mean = mean(&times_user);
-        let system_mean = mean(&times_system);
+        let user_mean = mean(&retained(&times_user));
+        let system_mean = mean(&retained(&times_system));
 
         // Formatting and console output
         let (mean_str, time_unit) = format_duration_unit(t_mean, self.options.time_unit);
         let min_str = format_duration(t_min, Some(time_unit));
         let max_str = format_duration(t_max, Some(time_unit));
-        let num_str = format!("{} runs", t_num);
+        let mut num_notes = vec![];
+        if truncated {
+            num_notes.push("stopped after --max-time".to_string());
+        }
+        if self.options.auto_warmup {
+            num_notes.push(format!("after {} warmup runs", warmup_times.len()));
+        }
+        if discard_outliers {
+            num_notes.push(format!("{} outliers discarded", num_outliers));
+        }
+        match self.options.trim {
+            Some(Trim::Trimmed(fraction)) => {
+                num_notes.push(format!("{}% trimmed mean", fraction * 100.0))
+            }
+            Some(Trim::Winsorized(fraction)) => {
+                num_notes.push(format!("{}% winsorized mean", fraction * 100.0))
+            }
+            None => {}
+        }
+        let num_str = if num_notes.is_empty() {
+            format!("{} runs", t_num)
+        } else {
+            format!("{} runs ({})", t_num, num_notes.join(", "))
+        };
+
+        let cpu_utilization = (t_mean > 0.0).then(|| (user_mean + system_mean) / t_mean);
 
         let user_str = format_duration(user_mean, Some(time_unit));
         let system_str = format_duration(system_mean, Some(time_unit));
 
         if self.options.output_style != OutputStyleOption::Disabled {
-            if times_real.len() == 1 {
-                println!(
+            if times_mean.len() == 1 {
+                outputln!(
+                    self.options,
                     "  Time ({} ≡):        {:>8}  {:>8}     [User: {}, System: {}]",
                     "abs".green().bold(),
                     mean_str.green().bold(),
@@ -294,7 +848,8 @@ impl<'a> Benchmark<'a> {
             } else {
                 let stddev_str = format_duration(t_stddev.unwrap(), Some(time_unit));
 
-                println!(
+                outputln!(
+                    self.options,
                     "  Time ({} ± {}):     {:>8} ± {:>8}    [User: {}, System: {}]",
                     "mean".green().bold(),
                     "σ".green(),
@@ -304,7 +859,8 @@ impl<'a> Benchmark<'a> {
                     system_str.blue()
                 );
 
-                println!(
+                outputln!(
+                    self.options,
                     "  Range ({} … {}):   {:>8} … {:>8}    {}",
                     "min".cyan(),
                     "max".purple(),
@@ -313,43 +869,372 @@ impl<'a> Benchmark<'a> {
                     num_str.dimmed()
                 );
             }
+
+            if let Some(ref first_output) = time_to_first_output {
+                outputln!(
+                    self.options,
+                    "  First output:        {:>8}               [Min: {}, Max: {}]",
+                    format_duration(mean(first_output), Some(time_unit)).green(),
+                    format_duration(min(first_output), Some(time_unit)).cyan(),
+                    format_duration(max(first_output), Some(time_unit)).purple()
+                );
+            }
+
+            // Without `--cgroup`, the memory usage includes the pages that the process shared
+            // with hyperfine when it was forked, so small values (below 1-2 MB) are not exact
+            if let Some(ref memory_usage) = memory_usage_byte {
+                let memory_usage: Vec<Scalar> = memory_usage.iter().map(|&m| m as Scalar).collect();
+                outputln!(
+                    self.options,
+                    "  Memory (max RSS):    {:>8}               [Min: {}, Max: {}]",
+                    format_bytes(mean(&memory_usage)).green(),
+                    format_bytes(min(&memory_usage)).cyan(),
+                    format_bytes(max(&memory_usage)).purple()
+                );
+            }
+
+            if let Some(ref energy) = energy_joule {
+                outputln!(
+                    self.options,
+                    "  Energy (CPU):        {:>8}               [Min: {}, Max: {}]",
+                    format!("{:.3} J", mean(energy)).green(),
+                    format!("{:.3} J", min(energy)).cyan(),
+                    format!("{:.3} J", max(energy)).purple()
+                );
+            }
+
+            if let Some(ref frequencies) = cpu_frequency_mhz {
+                let means: Vec<f64> = frequencies.iter().map(|f| f.mean).collect();
+                let mins: Vec<f64> = frequencies.iter().map(|f| f.min).collect();
+                let maxs: Vec<f64> = frequencies.iter().map(|f| f.max).collect();
+                outputln!(
+                    self.options,
+                    "  CPU frequency:       {:>8}               [Min: {}, Max: {}]",
+                    format!("{:.0} MHz", mean(&means)).green(),
+                    format!("{:.0} MHz", min(&mins)).cyan(),
+                    format!("{:.0} MHz", max(&maxs)).purple()
+                );
+            }
+
+            if let Some(ref usages) = gpu_usage {
+                let utilizations: Vec<f64> = usages.iter().map(|u| u.utilization_mean).collect();
+                let memory_peak = usages.iter().map(|u| u.memory_peak_byte).max().unwrap_or(0);
+                outputln!(
+                    self.options,
+                    "  GPU (mean):          {:>8} utilization, {} memory (peak)",
+                    format!("{:.1}%", mean(&utilizations)).green(),
+                    format_bytes(memory_peak as Scalar).green()
+                );
+            }
+
+            if let (true, Some(read), Some(written)) = (
+                self.options.show_resource_usage,
+                &io_read_byte,
+                &io_write_byte,
+            ) {
+                outputln!(
+                    self.options,
+                    "  I/O (mean):          {:>8} read, {} written",
+                    format_bytes(mean_count(read)).green(),
+                    format_bytes(mean_count(written)).green()
+                );
+            }
+
+            if let (true, Some(voluntary), Some(involuntary)) = (
+                self.options.show_resource_usage,
+                &voluntary_context_switches,
+                &involuntary_context_switches,
+            ) {
+                outputln!(
+                    self.options,
+                    "  Context switches:    {:>8} voluntary, {} involuntary",
+                    format!("{:.0}", mean_count(voluntary)).green(),
+                    format!("{:.0}", mean_count(involuntary)).green()
+                );
+            }
+
+            if let (true, Some(minor), Some(major)) = (
+                self.options.show_resource_usage,
+                &minor_page_faults,
+                &major_page_faults,
+            ) {
+                outputln!(
+                    self.options,
+                    "  Page faults:         {:>8} minor, {} major",
+                    format!("{:.0}", mean_count(minor)).green(),
+                    format!("{:.0}", mean_count(major)).green()
+                );
+            }
+
+            for (label, counts) in [
+                ("Tasks (peak):", &peak_tasks),
+                ("Processes:", &total_processes),
+            ] {
+                if let Some(counts) = counts {
+                    outputln!(
+                        self.options,
+                        "  {:<21}{:>8}               [Min: {}, Max: {}]",
+                        label,
+                        format!("{:.1}", mean_count(counts)).green(),
+                        counts.iter().min().unwrap().to_string().cyan(),
+                        counts.iter().max().unwrap().to_string().purple()
+                    );
+                }
+            }
+
+            for (counter, values) in &perf_counters {
+                let ipc = match (
+                    counter.as_str(),
+                    perf_counters.get(PerfCounter::Cycles.name()),
+                ) {
+                    ("instructions", Some(cycles)) if mean_count(cycles) > 0.0 => {
+                        format!("  ({:.2} IPC)", mean_count(values) / mean_count(cycles))
+                    }
+                    _ => String::new(),
+                };
+                outputln!(
+                    self.options,
+                    "  {:<21}{:>8}{}",
+                    format!("{}:", counter),
+                    format_count(mean_count(values)).green(),
+                    ipc.dimmed()
+                );
+            }
+
+            // The CPU times are not measured in debug mode
+            if let (true, false, Some(utilization)) = (
+                self.options.show_resource_usage,
+                matches!(self.options.executor_kind, ExecutorKind::Mock(_)),
+                cpu_utilization,
+            ) {
+                outputln!(
+                    self.options,
+                    "  CPU utilization:     {:>8}{}",
+                    format!("{:.1}%", utilization * 100.0).blue(),
+                    if utilization >= CPU_BOUND_UTILIZATION {
+                        "  (CPU-bound)"
+                    } else if utilization <= IO_BOUND_UTILIZATION {
+                        "  (I/O-bound or waiting)"
+                    } else {
+                        ""
+                    }
+                    .dimmed()
+                );
+            }
+
+            if let (true, Some(sem)) = (self.options.show_sem, t_sem) {
+                outputln!(
+                    self.options,
+                    "  Std. error (SEM):   {:>8}",
+                    format_duration(sem, Some(time_unit)).green()
+                );
+            }
+
+            if let Some(ci) = t_mean_ci {
+                outputln!(
+                    self.options,
+                    "  Mean ({} CI):         [{}, {}]",
+                    format!("{}%", self.options.confidence_level * 100.0).green(),
+                    format_duration(ci.lower, Some(time_unit)).green(),
+                    format_duration(ci.upper, Some(time_unit)).green()
+                );
+            }
+
+            if let Some(ref throughput) = t_throughput {
+                outputln!(
+                    self.options,
+                    "  Throughput:          {}",
+                    format_throughput(throughput.mean, throughput.stddev, throughput.unit).green()
+                );
+            }
+
+            if !t_percentiles.is_empty() {
+                let percentiles_str = t_percentiles
+                    .iter()
+                    .map(|p| {
+                        format!(
+                            "{}: {}",
+                            p.name(),
+                            format_duration(p.time, Some(time_unit)).yellow()
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                outputln!(self.options, "  Percentiles:         {}", percentiles_str);
+            }
+
+            if let (Some(difference), Some(stddev)) = (self.options.power_analysis, t_stddev) {
+                outputln!(
+                    self.options,
+                    "  Power analysis:      ~{} runs needed to detect a {}% difference (α = {}, power = {}%)",
+                    required_runs(
+                        stddev / t_mean,
+                        difference,
+                        self.options.significance_level,
+                        POWER_ANALYSIS_POWER
+                    )
+                    .to_string()
+                    .yellow(),
+                    difference * 100.0,
+                    self.options.significance_level,
+                    POWER_ANALYSIS_POWER * 100.0
+                );
+            }
+
+            if self.options.sparkline && t_num > 1 {
+                outputln!(
+                    self.options,
+                    "  Runs:                {}",
+                    sparkline(&times_real).green()
+                );
+            }
+
+            if self.options.histogram && t_num > 1 {
+                outputln!(
+                    self.options,
+                    "  Histogram:           {} {} {}",
+                    format_duration(min(&times_real), Some(time_unit)).cyan(),
+                    histogram(&times_real).green(),
+                    format_duration(max(&times_real), Some(time_unit)).purple()
+                );
+            }
+
+            if !outlier_runs.is_empty() {
+                outputln!(
+                    self.options,
+                    "  Outliers:            {} of {} runs",
+                    num_outliers.to_string().yellow(),
+                    t_num
+                );
+                for outlier in &outlier_runs {
+                    outputln!(
+                        self.options,
+                        "    run {:>4}:  {:>8}  (modified Z-score: {:.1})",
+                        outlier.index + 1,
+                        format_duration(outlier.time, Some(time_unit)).yellow(),
+                        outlier.zscore
+                    );
+                }
+            }
+
+            if let Some(ref path) = profile_path {
+                outputln!(
+                    self.options,
+                    "  Profile:             {}  (see '{}')",
+                    path.display().to_string().cyan(),
+                    profiler::report_command(path)
+                );
+            }
         }
 
         // Warnings
         let mut warnings = vec![];
 
-        // Check execution time
+        // Check execution time, of the whole batch with `--batch`
         if matches!(self.options.executor_kind, ExecutorKind::Shell(_))
-            && times_real.iter().any(|&t| t < MIN_EXECUTION_TIME)
+            && times_real
+                .iter()
+                .any(|&t| t * (self.options.batch_size as Second) < MIN_EXECUTION_TIME)
         {
             warnings.push(Warnings::FastExecutionTime);
         }
 
+        // Check whether the requested precision has been reached
+        if let Some(max_relative_stddev) = self.options.max_relative_stddev {
+            if let Some(stddev) = t_stddev {
+                if stddev > max_relative_stddev * t_mean {
+                    warnings.push(Warnings::RelativeStddevTooHigh(
+                        stddev / t_mean,
+                        max_relative_stddev,
+                    ));
+                }
+            }
+        }
+
+        if let Some(max_ci_width) = self.options.max_ci_width {
+            if let Some(half_width) =
+                mean_ci_half_width(&times_stats, self.options.confidence_level)
+            {
+                if half_width > max_ci_width * t_mean {
+                    warnings.push(Warnings::ConfidenceIntervalTooWide(
+                        self.options.confidence_level,
+                        half_width / t_mean,
+                        max_ci_width,
+                    ));
+                }
+            }
+        }
+
         // Check program exit codes
         if !all_succeeded {
             warnings.push(Warnings::NonZeroExitCode);
         }
 
-        // Run outlier detection
-        let scores = modified_zscores(&times_real);
+        if self.options.time_to_first_output && time_to_first_output.is_none() {
+            warnings.push(Warnings::NoOutput);
+        }
 
+        // Check whether other programs competed with the command for the CPU
+        if let (true, Some(involuntary)) = (t_mean > 0.0, &involuntary_context_switches) {
+            let switches = mean_count(involuntary);
+            let rate = switches / t_mean;
+            if switches >= MIN_INVOLUNTARY_CONTEXT_SWITCHES
+                && rate > MAX_INVOLUNTARY_CONTEXT_SWITCH_RATE
+            {
+                warnings.push(Warnings::ManyInvoluntaryContextSwitches(rate));
+            }
+        }
+
+        // Check whether other programs kept the system busy during the benchmark
+        #[cfg(target_os = "linux")]
+        if let Some(load) = background_load_monitor.and_then(|monitor| monitor.stop()) {
+            if load > MAX_BACKGROUND_LOAD {
+                warnings.push(Warnings::BackgroundLoad(load));
+            }
+        }
+
+        // Check for a systematic drift of the run times, which outlier detection can not find
+        if times_real.len() >= MIN_RUNS_FOR_DRIFT_DETECTION {
+            if let Some(trend) = mann_kendall_test(&times_real) {
+                let drift = linear_regression_slope(&times_real) * (times_real.len() - 1) as f64
+                    / mean(&times_real);
+                if trend.is_significant(DRIFT_SIGNIFICANCE_LEVEL)
+                    && drift.abs() > MIN_RELATIVE_DRIFT
+                {
+                    warnings.push(Warnings::DriftDetected(drift));
+                }
+            }
+        }
+
+        // Check for two clusters of run times, for which the mean is not representative
+        if times_real.len() >= MIN_RUNS_FOR_BIMODALITY_DETECTION {
+            if let Some(clusters) = two_clusters(&times_real) {
+                let smaller = cmp::min(clusters.lower.1, clusters.upper.1);
+                if clusters.separation > MIN_BIMODAL_SEPARATION
+                    && smaller as f64 >= MIN_BIMODAL_CLUSTER_FRACTION * times_real.len() as f64
+                {
+                    warnings.push(Warnings::BimodalDistribution(
+                        clusters.lower.0,
+                        clusters.upper.0,
+                    ));
+                }
+            }
+        }
+
+        // Warn about outliers, unless they have been removed from the statistics
         let outlier_warning_options = OutlierWarningOptions {
-            warmup_in_use: self.options.warmup_count > 0,
-            prepare_in_use: self
-                .options
-                .preparation_command
-                .as_ref()
-                .map(|v| v.len())
-                .unwrap_or(0)
-                > 0,
+            warmup_in_use: !warmup_times.is_empty(),
+            prepare_in_use: preparation_command.is_some(),
         };
 
-        if scores[0] > OUTLIER_THRESHOLD {
+        if discard_outliers {
+            // The outliers are not included in the statistics, so there is nothing to warn about
+        } else if outliers[0] && times_real[0] > t_median {
             warnings.push(Warnings::SlowInitialRun(
                 times_real[0],
                 outlier_warning_options,
             ));
-        } else if scores.iter().any(|&s| s.abs() > OUTLIER_THRESHOLD) {
+        } else if outliers.contains(&true) {
             warnings.push(Warnings::OutliersDetected(outlier_warning_options));
         }
 
@@ -362,29 +1247,67 @@ impl<'a> Benchmark<'a> {
         }
 
         if self.options.output_style != OutputStyleOption::Disabled {
-            println!(" ");
+            outputln!(self.options, " ");
         }
 
         self.run_cleanup_command(self.command.get_parameters().iter().cloned())?;
 
         Ok(BenchmarkResult {
-            command: self.command.get_name(),
+            command: command_name,
             command_with_unused_parameters: self.command.get_name_with_unused_parameters(),
             mean: t_mean,
             stddev: t_stddev,
+            sem: t_sem,
+            mean_ci: t_mean_ci,
             median: t_median,
             user: user_mean,
             system: system_mean,
+            cpu_utilization,
             min: t_min,
             max: t_max,
+            percentiles: t_percentiles,
+            throughput: t_throughput,
+            truncated,
             times: Some(times_real),
+            times_raw: Some(times_raw),
+            timestamps: Some(timestamps),
+            load_average,
+            available_memory_byte,
+            memory_usage_byte,
+            io_read_byte,
+            io_write_byte,
+            voluntary_context_switches,
+            involuntary_context_switches,
+            minor_page_faults,
+            major_page_faults,
+            peak_tasks,
+            total_processes,
+            perf_counters,
+            energy_joule,
+            cpu_frequency_mhz,
+            gpu_usage,
+            time_to_first_output,
             exit_codes,
-            parameters: self
-                .command
-                .get_parameters()
-                .iter()
-                .map(|(name, value)| (name.to_string(), value.to_string()))
-                .collect(),
+            signals,
+            outliers,
+            outlier_runs,
+            zscores,
+            parameters,
         })
     }
 }
+
+#[test]
+fn test_is_warmed_up() {
+    assert!(!is_warmed_up(&[1.0, 1.0, 1.0, 1.0, 1.0]));
+    assert!(is_warmed_up(&[1.0, 1.0, 1.0, 1.0, 1.0, 1.0]));
+
+    // Caches are filled during the first runs
+    assert!(!is_warmed_up(&[3.0, 2.0, 1.5, 1.2, 1.1, 1.0]));
+    assert!(is_warmed_up(&[
+        3.0, 2.0, 1.5, 1.2, 1.1, 1.0, 1.0, 1.01, 1.0, 1.0
+    ]));
+
+    // A single slow run does not prevent stabilization
+    assert!(is_warmed_up(&[1.0, 1.0, 1.0, 1.0, 5.0, 1.0]));
+}
@@ -0,0 +1,114 @@
+//! Profiling run of a benchmarked command (`--profile`), with 'perf record' on Linux and
+//! 'dtrace' on macOS
+
+#![cfg(any(target_os = "linux", target_os = "macos"))]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use crate::options::{CommandInputPolicy, CommandOutputPolicy};
+
+use anyhow::{bail, Context, Result};
+
+/// Sampling frequency of the profiler, in Hz. An odd number avoids sampling in lockstep with
+/// periodic activity of the command.
+const SAMPLING_FREQUENCY: u32 = 997;
+
+/// The file in the given directory to which the profile of the given benchmark is written
+pub fn output_path(dir: &Path, benchmark_number: usize) -> PathBuf {
+    let extension = if cfg!(target_os = "linux") {
+        "perf.data"
+    } else {
+        "stacks"
+    };
+    dir.join(format!("benchmark-{}.{}", benchmark_number + 1, extension))
+}
+
+/// The command that can be used to look at the given profile
+pub fn report_command(path: &Path) -> String {
+    if cfg!(target_os = "linux") {
+        format!("perf report -i {}", path.display())
+    } else {
+        format!(
+            "inferno-collapse-dtrace {} | inferno-flamegraph > flamegraph.svg",
+            path.display()
+        )
+    }
+}
+
+/// Wrap the given command such that it is run under the profiler, which writes its data to
+/// the given path
+fn profiler_command(command: &Command, path: &Path) -> Command {
+    if cfg!(target_os = "linux") {
+        let mut perf = Command::new("perf");
+        perf.args(["record", "--quiet", "-g"])
+            .arg("--freq")
+            .arg(SAMPLING_FREQUENCY.to_string())
+            .arg("--output")
+            .arg(path)
+            .arg("--")
+            .arg(command.get_program())
+            .args(command.get_args());
+        perf
+    } else {
+        // 'dtrace -c' splits the command line at whitespace, without any quoting
+        let command_line: Vec<String> = std::iter::once(command.get_program())
+            .chain(command.get_args())
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect();
+        let mut dtrace = Command::new("dtrace");
+        dtrace
+            .arg("-q")
+            .args(["-x", "ustackframes=100"])
+            .arg("-n")
+            .arg(format!(
+                "profile-{} /pid == $target/ {{ @[ustack()] = count(); }}",
+                SAMPLING_FREQUENCY
+            ))
+            .arg("-o")
+            .arg(path)
+            .arg("-c")
+            .arg(command_line.join(" "));
+        dtrace
+    }
+}
+
+/// Run the given command once under the profiler, without measuring it
+pub fn profile(
+    command: &Command,
+    path: &Path,
+    input_policy: &CommandInputPolicy,
+    output_policy: &CommandOutputPolicy,
+) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Could not create the directory '{}'", dir.display()))?;
+    }
+
+    let (stdout, stderr) = match output_policy {
+        // The output does not have to be read by hyperfine in this run
+        CommandOutputPolicy::Pipe => (Stdio::null(), Stdio::null()),
+        policy => policy.get_stdout_stderr()?,
+    };
+    let profiler = if cfg!(target_os = "linux") {
+        "perf"
+    } else {
+        "dtrace"
+    };
+
+    let status = profiler_command(command, path)
+        .stdin(input_policy.get_stdin()?)
+        .stdout(stdout)
+        .stderr(stderr)
+        .status()
+        .with_context(|| format!("Could not run '{}'. Is it installed?", profiler))?;
+    if !status.success() {
+        bail!(
+            "The profiling run with '{}' failed. Note that profiling usually requires root \
+             privileges.",
+            profiler
+        );
+    }
+    Ok(())
+}
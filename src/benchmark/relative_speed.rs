@@ -1,7 +1,11 @@
 use std::cmp::Ordering;
 
 use super::benchmark_result::BenchmarkResult;
-use crate::{options::SortOrder, util::units::Scalar};
+use crate::{
+    options::{RelativeSpeedMetric, SortOrder},
+    statistics::geometric_mean,
+    util::units::{Scalar, Second},
+};
 
 #[derive(Debug)]
 pub struct BenchmarkResultWithRelativeSpeed<'a> {
@@ -9,49 +13,82 @@ pub struct BenchmarkResultWithRelativeSpeed<'a> {
     pub relative_speed: Scalar,
     pub relative_speed_stddev: Option<Scalar>,
     pub is_fastest: bool,
+
+    /// Whether this is the benchmark that the others are compared to: the fastest one, or
+    /// the one that was specified with `--reference`
+    pub is_reference: bool,
 }
 
 pub fn compare_mean_time(l: &BenchmarkResult, r: &BenchmarkResult) -> Ordering {
     l.mean.partial_cmp(&r.mean).unwrap_or(Ordering::Equal)
 }
 
-fn fastest_of(results: &[BenchmarkResult]) -> &BenchmarkResult {
+/// The time that is used to compare the given benchmark result to others
+fn metric_time(result: &BenchmarkResult, metric: RelativeSpeedMetric) -> Second {
+    match metric {
+        RelativeSpeedMetric::Mean => result.mean,
+        RelativeSpeedMetric::Median => result.median,
+        RelativeSpeedMetric::Min => result.min,
+    }
+}
+
+fn compare_metric_time(
+    l: &BenchmarkResult,
+    r: &BenchmarkResult,
+    metric: RelativeSpeedMetric,
+) -> Ordering {
+    metric_time(l, metric)
+        .partial_cmp(&metric_time(r, metric))
+        .unwrap_or(Ordering::Equal)
+}
+
+fn fastest_of(results: &[BenchmarkResult], metric: RelativeSpeedMetric) -> &BenchmarkResult {
     results
         .iter()
-        .min_by(|&l, &r| compare_mean_time(l, r))
+        .min_by(|&l, &r| compare_metric_time(l, r, metric))
         .expect("at least one benchmark result")
 }
 
 fn compute_relative_speeds<'a>(
     results: &'a [BenchmarkResult],
-    fastest: &'a BenchmarkResult,
+    reference: &'a BenchmarkResult,
     sort_order: SortOrder,
+    metric: RelativeSpeedMetric,
 ) -> Vec<BenchmarkResultWithRelativeSpeed<'a>> {
+    let fastest = fastest_of(results, metric);
+
     let mut results: Vec<_> = results
         .iter()
         .map(|result| {
             let is_fastest = result == fastest;
+            let is_reference = result == reference;
 
-            if result.mean == 0.0 {
+            if metric_time(result, metric) == 0.0 {
                 return BenchmarkResultWithRelativeSpeed {
                     result,
-                    relative_speed: if is_fastest { 1.0 } else { f64::INFINITY },
+                    relative_speed: if is_reference { 1.0 } else { f64::INFINITY },
                     relative_speed_stddev: None,
                     is_fastest,
+                    is_reference,
                 };
             }
 
-            let ratio = result.mean / fastest.mean;
+            let ratio = metric_time(result, metric) / metric_time(reference, metric);
 
             // https://en.wikipedia.org/wiki/Propagation_of_uncertainty#Example_formulas
-            // Covariance asssumed to be 0, i.e. variables are assumed to be independent
-            let ratio_stddev = match (result.stddev, fastest.stddev) {
-                (Some(result_stddev), Some(fastest_stddev)) => Some(
-                    ratio
-                        * ((result_stddev / result.mean).powi(2)
-                            + (fastest_stddev / fastest.mean).powi(2))
-                        .sqrt(),
-                ),
+            // Covariance asssumed to be 0, i.e. variables are assumed to be independent.
+            // The standard deviation only describes the uncertainty of the mean.
+            let ratio_stddev = match (result.stddev, reference.stddev) {
+                (Some(result_stddev), Some(reference_stddev))
+                    if metric == RelativeSpeedMetric::Mean =>
+                {
+                    Some(
+                        ratio
+                            * ((result_stddev / result.mean).powi(2)
+                                + (reference_stddev / reference.mean).powi(2))
+                            .sqrt(),
+                    )
+                }
                 _ => None,
             };
 
@@ -60,6 +97,7 @@ fn compute_relative_speeds<'a>(
                 relative_speed: ratio,
                 relative_speed_stddev: ratio_stddev,
                 is_fastest,
+                is_reference,
             }
         })
         .collect();
@@ -67,40 +105,111 @@ fn compute_relative_speeds<'a>(
     match sort_order {
         SortOrder::Command => {}
         SortOrder::MeanTime => {
-            results.sort_unstable_by(|r1, r2| compare_mean_time(r1.result, r2.result));
+            results.sort_by(|r1, r2| compare_mean_time(r1.result, r2.result));
+        }
+        SortOrder::MedianTime => {
+            results.sort_by(|r1, r2| {
+                compare_metric_time(r1.result, r2.result, RelativeSpeedMetric::Median)
+            });
+        }
+        SortOrder::RelativeSpeed => {
+            results.sort_by(|r1, r2| compare_metric_time(r1.result, r2.result, metric));
+        }
+        SortOrder::Alphabetical => {
+            results.sort_by(|r1, r2| r1.result.command.cmp(&r2.result.command));
         }
     }
 
     results
 }
 
+/// Compute the relative speeds of all benchmarks, compared by the given metric (mean, median
+/// or minimum time). Returns `None` if the time of the fastest benchmark is zero.
 pub fn compute_with_check(
     results: &[BenchmarkResult],
     sort_order: SortOrder,
-) -> Option<Vec<BenchmarkResultWithRelativeSpeed>> {
-    let fastest = fastest_of(results);
+    metric: RelativeSpeedMetric,
+) -> Option<Vec<BenchmarkResultWithRelativeSpeed<'_>>> {
+    let fastest = fastest_of(results, metric);
+
+    if metric_time(fastest, metric) == 0.0 {
+        return None;
+    }
+
+    Some(compute_relative_speeds(
+        results, fastest, sort_order, metric,
+    ))
+}
+
+/// Like `compute_with_check`, but compares all benchmarks to the one at the given index
+/// instead of the fastest one. Returns `None` if the time of the reference is zero.
+pub fn compute_with_reference(
+    results: &[BenchmarkResult],
+    reference: usize,
+    sort_order: SortOrder,
+    metric: RelativeSpeedMetric,
+) -> Option<Vec<BenchmarkResultWithRelativeSpeed<'_>>> {
+    let reference = &results[reference];
 
-    if fastest.mean == 0.0 {
+    if metric_time(reference, metric) == 0.0 {
         return None;
     }
 
-    Some(compute_relative_speeds(results, fastest, sort_order))
+    Some(compute_relative_speeds(
+        results, reference, sort_order, metric,
+    ))
 }
 
-/// Same as compute_with_check, potentially resulting in relative speeds of infinity
+/// Same as compute_with_check, comparing mean times and potentially resulting in relative
+/// speeds of infinity
 pub fn compute(
     results: &[BenchmarkResult],
     sort_order: SortOrder,
-) -> Vec<BenchmarkResultWithRelativeSpeed> {
-    let fastest = fastest_of(results);
+) -> Vec<BenchmarkResultWithRelativeSpeed<'_>> {
+    let fastest = fastest_of(results, RelativeSpeedMetric::Mean);
+
+    compute_relative_speeds(results, fastest, sort_order, RelativeSpeedMetric::Mean)
+}
+
+/// Compute the geometric mean of the relative speeds of each command across all parameter
+/// values, like SPEC does for a suite of benchmarks. Commands are identified by their name
+/// (or command line) before parameter substitution. The speeds are normalized such that the
+/// fastest command has a relative speed of 1. Returns `None` if any mean time is zero.
+pub fn compute_geometric_mean<'a>(
+    results: impl IntoIterator<Item = (&'a str, &'a BenchmarkResult)>,
+) -> Option<Vec<(&'a str, Scalar)>> {
+    let mut means: Vec<(&str, Vec<Scalar>)> = vec![];
+    for (command, result) in results {
+        if result.mean <= 0.0 {
+            return None;
+        }
+        match means.iter_mut().find(|(c, _)| *c == command) {
+            Some((_, command_means)) => command_means.push(result.mean),
+            None => means.push((command, vec![result.mean])),
+        }
+    }
 
-    compute_relative_speeds(results, fastest, sort_order)
+    // Since all commands are benchmarked for the same parameter values, the ratio of the
+    // geometric means of the times is the geometric mean of the ratios of the times.
+    let geometric_means: Vec<_> = means
+        .iter()
+        .map(|(command, command_means)| (*command, geometric_mean(command_means)))
+        .collect();
+    let fastest = geometric_means
+        .iter()
+        .map(|(_, g)| *g)
+        .fold(f64::INFINITY, f64::min);
+
+    Some(
+        geometric_means
+            .into_iter()
+            .map(|(command, g)| (command, g / fastest))
+            .collect(),
+    )
 }
 
 #[cfg(test)]
 fn create_result(name: &str, mean: Scalar) -> BenchmarkResult {
-    use std::collections::BTreeMap;
-
     BenchmarkResult {
         command: name.into(),
         command_with_unused_parameters: name.into(),
@@ -111,9 +220,7 @@ fn create_result(name: &str, mean: Scalar) -> BenchmarkResult {
         system: 0.0,
         min: mean,
         max: mean,
-        times: None,
-        exit_codes: Vec::new(),
-        parameters: BTreeMap::new(),
+        ..Default::default()
     }
 }
 
@@ -127,18 +234,115 @@ fn test_compute_relative_speed() {
         create_result("cmd3", 5.0),
     ];
 
-    let annotated_results = compute_with_check(&results, SortOrder::Command).unwrap();
+    let annotated_results =
+        compute_with_check(&results, SortOrder::Command, RelativeSpeedMetric::Mean).unwrap();
 
     assert_relative_eq!(1.5, annotated_results[0].relative_speed);
     assert_relative_eq!(1.0, annotated_results[1].relative_speed);
     assert_relative_eq!(2.5, annotated_results[2].relative_speed);
 }
 
+#[test]
+fn test_compute_relative_speed_by_median_and_min() {
+    use approx::assert_relative_eq;
+
+    let mut slow_median = create_result("cmd1", 2.0);
+    slow_median.median = 3.0;
+    slow_median.min = 0.5;
+    let results = vec![slow_median, create_result("cmd2", 2.5)];
+
+    let by_median = compute_with_check(
+        &results,
+        SortOrder::RelativeSpeed,
+        RelativeSpeedMetric::Median,
+    )
+    .unwrap();
+    assert_eq!("cmd2", by_median[0].result.command);
+    assert_relative_eq!(1.2, by_median[1].relative_speed);
+    assert!(by_median[1].relative_speed_stddev.is_none());
+
+    let by_min =
+        compute_with_check(&results, SortOrder::RelativeSpeed, RelativeSpeedMetric::Min).unwrap();
+    assert_eq!("cmd1", by_min[0].result.command);
+    assert_relative_eq!(5.0, by_min[1].relative_speed);
+}
+
+#[test]
+fn test_compute_relative_speed_with_reference() {
+    use approx::assert_relative_eq;
+
+    let results = vec![
+        create_result("cmd1", 3.0),
+        create_result("cmd2", 2.0),
+        create_result("cmd3", 6.0),
+    ];
+
+    let annotated_results =
+        compute_with_reference(&results, 0, SortOrder::Command, RelativeSpeedMetric::Mean).unwrap();
+
+    assert_relative_eq!(1.0, annotated_results[0].relative_speed);
+    assert_relative_eq!(2.0 / 3.0, annotated_results[1].relative_speed);
+    assert_relative_eq!(2.0, annotated_results[2].relative_speed);
+    assert!(annotated_results[0].is_reference);
+    assert!(!annotated_results[0].is_fastest);
+    assert!(annotated_results[1].is_fastest);
+}
+
 #[test]
 fn test_compute_relative_speed_for_zero_times() {
     let results = vec![create_result("cmd1", 1.0), create_result("cmd2", 0.0)];
 
-    let annotated_results = compute_with_check(&results, SortOrder::Command);
+    let annotated_results =
+        compute_with_check(&results, SortOrder::Command, RelativeSpeedMetric::Mean);
 
     assert!(annotated_results.is_none());
 }
+
+#[test]
+fn test_compute_geometric_mean() {
+    use approx::assert_relative_eq;
+
+    let results = vec![
+        create_result("cmd1 1", 1.0),
+        create_result("cmd1 2", 4.0),
+        create_result("cmd2 1", 2.0),
+        create_result("cmd2 2", 2.0),
+        create_result("cmd3 1", 4.0),
+        create_result("cmd3 2", 16.0),
+    ];
+    let commands = ["cmd1", "cmd1", "cmd2", "cmd2", "cmd3", "cmd3"];
+
+    let speeds = compute_geometric_mean(commands.iter().copied().zip(&results)).unwrap();
+
+    assert_eq!(3, speeds.len());
+    assert_eq!("cmd1", speeds[0].0);
+    assert_relative_eq!(1.0, speeds[0].1);
+    assert_relative_eq!(1.0, speeds[1].1);
+    assert_relative_eq!(4.0, speeds[2].1);
+
+    let results = vec![create_result("cmd1", 1.0), create_result("cmd2", 0.0)];
+    assert!(compute_geometric_mean(["cmd1", "cmd2"].iter().copied().zip(&results)).is_none());
+}
+
+#[test]
+fn test_sort_orders() {
+    let mut results = vec![
+        create_result("b", 3.0),
+        create_result("c", 2.0),
+        create_result("a", 5.0),
+    ];
+    results[0].median = 1.0;
+
+    let order = |sort_order| -> Vec<String> {
+        compute(&results, sort_order)
+            .iter()
+            .map(|r| r.result.command.clone())
+            .collect()
+    };
+
+    assert_eq!(vec!["b", "c", "a"], order(SortOrder::Command));
+    assert_eq!(vec!["c", "b", "a"], order(SortOrder::MeanTime));
+    assert_eq!(vec!["b", "c", "a"], order(SortOrder::MedianTime));
+    assert_eq!(vec!["c", "b", "a"], order(SortOrder::RelativeSpeed));
+    assert_eq!(vec!["a", "b", "c"], order(SortOrder::Alphabetical));
+}
@@ -1,15 +1,33 @@
 use colored::*;
 
 use super::benchmark_result::BenchmarkResult;
+use super::comparison_matrix::{self, ComparisonMatrix};
 use super::executor::{Executor, MockExecutor, RawExecutor, ShellExecutor};
-use super::{relative_speed, Benchmark};
+use super::{complexity, relative_speed, Benchmark};
 
 use crate::command::Commands;
 use crate::export::ExportManager;
-use crate::options::{ExecutorKind, Options, OutputStyleOption, SortOrder};
+use crate::options::{
+    ExecutorKind, Options, OutputStyleOption, RelativeSpeedMetric, SignificanceTest,
+};
+use crate::output::warnings::Warnings;
+use crate::output::{format::format_duration, outputln};
+use crate::parameter::ParameterValue;
+use crate::statistics::{
+    bootstrap_probability_smaller, bootstrap_ratio_of_means, fieller_ratio_of_means,
+    mann_whitney_u_test, welch_t_test,
+};
 
 use anyhow::Result;
 
+fn format_p_value(p_value: f64) -> String {
+    if p_value < 0.001 {
+        "p < 0.001".into()
+    } else {
+        format!("p = {:.3}", p_value)
+    }
+}
+
 pub struct Scheduler<'a> {
     commands: &'a Commands<'a>,
     options: &'a Options,
@@ -38,11 +56,16 @@ impl<'a> Scheduler<'a> {
             ExecutorKind::Shell(ref shell) => Box::new(ShellExecutor::new(shell, self.options)),
         };
 
+        if !matches!(self.options.executor_kind, ExecutorKind::Mock(_)) {
+            self.print_system_warnings();
+        }
+
         executor.calibrate()?;
 
         for (number, cmd) in self.commands.iter().enumerate() {
-            self.results
-                .push(Benchmark::new(number, cmd, self.options, &*executor).run()?);
+            self.results.push(
+                Benchmark::new(number, cmd, self.options, &*executor, self.export_manager).run()?,
+            );
 
             // We export results after each individual benchmark, because
             // we would risk losing them if a later benchmark fails.
@@ -56,6 +79,32 @@ impl<'a> Scheduler<'a> {
         Ok(())
     }
 
+    /// Warn about settings of the system that make the results unstable
+    fn print_system_warnings(&self) {
+        #[allow(unused_mut)]
+        let mut warnings: Vec<Warnings> = vec![];
+
+        #[cfg(target_os = "linux")]
+        {
+            use crate::util::cpu_frequency;
+
+            warnings.extend(
+                cpu_frequency::scaling_governors(&self.options.cpu_affinity)
+                    .into_iter()
+                    .filter(|governor| governor != cpu_frequency::PERFORMANCE_GOVERNOR)
+                    .map(Warnings::UnsuitableCpuGovernor),
+            );
+            warnings.extend(cpu_frequency::turbo_boost().map(Warnings::TurboBoostEnabled));
+        }
+
+        for warning in &warnings {
+            eprintln!("{}: {}", "Warning".yellow(), warning);
+        }
+        if !warnings.is_empty() {
+            eprintln!(" ");
+        }
+    }
+
     pub fn print_relative_speed_comparison(&self) {
         if self.options.output_style == OutputStyleOption::Disabled {
             return;
@@ -65,54 +114,121 @@ impl<'a> Scheduler<'a> {
             return;
         }
 
-        if let Some(annotated_results) = relative_speed::compute_with_check(
-            &self.results,
-            self.options.sort_order_speed_comparison,
-        ) {
+        let annotated_results = match self.options.reference_command {
+            Some(_) => relative_speed::compute_with_reference(
+                &self.results,
+                0,
+                self.options.sort_order_speed_comparison,
+                self.options.relative_speed_metric,
+            ),
+            None => relative_speed::compute_with_check(
+                &self.results,
+                self.options.sort_order_speed_comparison,
+                self.options.relative_speed_metric,
+            ),
+        };
+
+        if let Some(annotated_results) = annotated_results {
             match self.options.sort_order_speed_comparison {
-                SortOrder::MeanTime => {
-                    println!("{}", "Summary".bold());
+                order if order.is_by_speed() => {
+                    outputln!(self.options, "{}", "Summary".bold());
 
-                    let fastest = annotated_results.iter().find(|r| r.is_fastest).unwrap();
-                    let others = annotated_results.iter().filter(|r| !r.is_fastest);
+                    let reference = annotated_results.iter().find(|r| r.is_reference).unwrap();
+                    let others = annotated_results.iter().filter(|r| !r.is_reference);
 
-                    println!(
-                        "  {} ran",
-                        fastest.result.command_with_unused_parameters.cyan()
+                    outputln!(
+                        self.options,
+                        "  {} ran{}",
+                        reference.result.command_with_unused_parameters.cyan(),
+                        match self.options.relative_speed_metric {
+                            RelativeSpeedMetric::Mean => "",
+                            RelativeSpeedMetric::Median => " (comparing median times)",
+                            RelativeSpeedMetric::Min => " (comparing minimum times)",
+                        }
                     );
 
                     for item in others {
-                        println!(
-                            "{}{} times faster than {}",
-                            format!("{:8.2}", item.relative_speed).bold().green(),
-                            if let Some(stddev) = item.relative_speed_stddev {
+                        // Only a reference given with --reference can be slower than others
+                        let slower = item.relative_speed < 1.0;
+                        let (speed, stddev) = if slower {
+                            // https://en.wikipedia.org/wiki/Propagation_of_uncertainty#Example_formulas
+                            (
+                                1.0 / item.relative_speed,
+                                item.relative_speed_stddev
+                                    .map(|s| s / item.relative_speed.powi(2)),
+                            )
+                        } else {
+                            (item.relative_speed, item.relative_speed_stddev)
+                        };
+
+                        outputln!(
+                            self.options,
+                            "{}{}{} times {} than {}{}",
+                            format!("{:8.2}", speed).bold().green(),
+                            if let Some(stddev) = stddev {
                                 format!(" ± {}", format!("{:.2}", stddev).green())
                             } else {
                                 "".into()
                             },
-                            &item.result.command_with_unused_parameters.magenta()
+                            self.relative_speed_ci(reference.result, item.result, slower),
+                            if slower { "slower" } else { "faster" },
+                            &item.result.command_with_unused_parameters.magenta(),
+                            self.significance(reference.result, item.result)
                         );
+
+                        if let Some(probability) =
+                            self.probability_faster(reference.result, item.result, slower)
+                        {
+                            outputln!(
+                                self.options,
+                                "{:8}  {} with a probability of {}",
+                                "",
+                                if slower { "slower" } else { "faster" },
+                                format!("{:.1}%", probability * 100.0).green()
+                            );
+                        }
                     }
                 }
-                SortOrder::Command => {
-                    println!("{}", "Relative speed comparison".bold());
+                _ => {
+                    outputln!(self.options, "{}", "Relative speed comparison".bold());
+
+                    let reference = annotated_results.iter().find(|r| r.is_reference).unwrap();
 
-                    for item in annotated_results {
-                        println!(
-                            "  {}{}  {}",
+                    for item in &annotated_results {
+                        outputln!(
+                            self.options,
+                            "  {}{}{}  {}{}",
                             format!("{:10.2}", item.relative_speed).bold().green(),
-                            if item.is_fastest {
+                            if item.is_reference {
                                 "        ".into()
                             } else if let Some(stddev) = item.relative_speed_stddev {
                                 format!(" ± {}", format!("{:5.2}", stddev).green())
                             } else {
                                 "        ".into()
                             },
+                            if item.is_reference {
+                                "".into()
+                            } else {
+                                self.relative_speed_ci(reference.result, item.result, false)
+                            },
                             &item.result.command_with_unused_parameters,
+                            if item.is_reference {
+                                "".into()
+                            } else {
+                                self.significance(reference.result, item.result)
+                            }
                         );
                     }
                 }
             }
+
+            if self.options.geometric_mean {
+                self.print_geometric_mean();
+            }
+
+            if self.options.comparison_matrix {
+                self.print_comparison_matrix();
+            }
         } else {
             eprintln!(
                 "{}: The benchmark comparison could not be computed as some benchmark times are zero. \
@@ -126,6 +242,215 @@ impl<'a> Scheduler<'a> {
         }
     }
 
+    /// Print the geometric mean of the relative speeds of each command across all parameter
+    /// values, requested with `--geometric-mean`
+    fn print_geometric_mean(&self) {
+        let templates = self.commands.iter().map(|cmd| cmd.get_template());
+        let Some(speeds) = relative_speed::compute_geometric_mean(templates.zip(&self.results))
+        else {
+            return;
+        };
+        if speeds.len() < 2 {
+            return;
+        }
+
+        outputln!(self.options, "");
+        outputln!(
+            self.options,
+            "{}",
+            "Geometric mean of relative speeds across all parameter values".bold()
+        );
+        for (template, speed) in speeds {
+            outputln!(
+                self.options,
+                "  {}  {}",
+                format!("{:10.2}", speed).bold().green(),
+                template
+            );
+        }
+    }
+
+    /// Print how many times faster each command is than each other command, requested with
+    /// `--comparison-matrix`
+    fn print_comparison_matrix(&self) {
+        let matrix = ComparisonMatrix::compute(
+            &self.results,
+            comparison_matrix::Settings::from_options(self.options),
+        );
+
+        outputln!(self.options, "");
+        outputln!(
+            self.options,
+            "{} (how many times faster the row is than the column, {} = significant at α = {})",
+            "Comparison matrix".bold(),
+            "*".yellow(),
+            matrix.significance_level
+        );
+        outputln!(
+            self.options,
+            "      {}",
+            (1..=self.results.len())
+                .map(|n| format!("{:>7} ", format!("#{}", n)))
+                .collect::<String>()
+                .trim_end()
+        );
+        for (i, result) in self.results.iter().enumerate() {
+            let cells: String = matrix.ratios[i]
+                .iter()
+                .enumerate()
+                .map(|(j, ratio)| {
+                    if i == j {
+                        format!("{:>7} ", "—")
+                    } else {
+                        format!(
+                            "{}{}",
+                            format!("{:7.2}", ratio).green(),
+                            if matrix.is_significant(i, j) {
+                                "*".yellow()
+                            } else {
+                                " ".normal()
+                            }
+                        )
+                    }
+                })
+                .collect();
+            outputln!(
+                self.options,
+                "  {:<4}{}  {}",
+                format!("#{}", i + 1),
+                cells,
+                result.command_with_unused_parameters
+            );
+        }
+    }
+
+    /// Print the complexity models that fit the mean times of each command of a parameter
+    /// scan best, requested with `--complexity`
+    pub fn print_complexity(&self) {
+        if !self.options.complexity || self.options.output_style == OutputStyleOption::Disabled {
+            return;
+        }
+
+        let scans = self
+            .commands
+            .iter()
+            .zip(&self.results)
+            .filter_map(|(cmd, result)| {
+                cmd.get_parameters()
+                    .iter()
+                    .find_map(|(_, value)| match value {
+                        ParameterValue::Numeric(n) => Some((*n).into()),
+                        ParameterValue::Text(_) => None,
+                    })
+                    .map(|n| (cmd.get_template(), n, result))
+            });
+        let fits = complexity::compute(scans);
+        if fits.is_empty() {
+            return;
+        }
+
+        outputln!(self.options, "");
+        outputln!(self.options, "{}", "Complexity".bold());
+        for (template, fit) in fits {
+            outputln!(
+                self.options,
+                "  {}  {}  (≈ {}{}, R² = {:.3})",
+                format!("{:>10}", fit.complexity.to_string()).bold().green(),
+                template,
+                format_duration(fit.coefficient, self.options.time_unit),
+                fit.complexity.factor(),
+                fit.r_squared
+            );
+        }
+    }
+
+    /// Confidence interval of the relative speed, if enabled with `--bootstrap` (bootstrap
+    /// interval) or `--relative-speed-ci` (Fieller interval). If `inverted` is set, the
+    /// interval for the inverse ratio is returned.
+    fn relative_speed_ci(
+        &self,
+        reference: &BenchmarkResult,
+        other: &BenchmarkResult,
+        inverted: bool,
+    ) -> String {
+        if self.options.relative_speed_metric != RelativeSpeedMetric::Mean {
+            return "".into();
+        }
+        let ratio_ci = if self.options.bootstrap {
+            bootstrap_ratio_of_means
+        } else if self.options.relative_speed_ci {
+            fieller_ratio_of_means
+        } else {
+            return "".into();
+        };
+        let times = |result: &BenchmarkResult| result.retained_times(self.options.discard_outliers);
+        let confidence = self.options.confidence_level;
+
+        let ci = if inverted {
+            ratio_ci(&times(reference), &times(other), confidence)
+        } else {
+            ratio_ci(&times(other), &times(reference), confidence)
+        };
+
+        match ci {
+            Some(ci) => format!(
+                " [{}, {}]",
+                format!("{:.2}", ci.lower).green(),
+                format!("{:.2}", ci.upper).green()
+            ),
+            None => "".into(),
+        }
+    }
+
+    /// Probability that the reference is faster than the other benchmark, if enabled with
+    /// `--probability-faster`. If `slower` is set, the probability that the reference is
+    /// slower is returned.
+    fn probability_faster(
+        &self,
+        reference: &BenchmarkResult,
+        other: &BenchmarkResult,
+        slower: bool,
+    ) -> Option<f64> {
+        if !self.options.probability_faster {
+            return None;
+        }
+        let times = |result: &BenchmarkResult| result.retained_times(self.options.discard_outliers);
+
+        if slower {
+            bootstrap_probability_smaller(&times(other), &times(reference))
+        } else {
+            bootstrap_probability_smaller(&times(reference), &times(other))
+        }
+    }
+
+    /// Annotation for the summary, stating whether the difference between the run times
+    /// of the two benchmarks is statistically significant
+    fn significance(&self, reference: &BenchmarkResult, other: &BenchmarkResult) -> String {
+        let times = |result: &BenchmarkResult| result.retained_times(self.options.discard_outliers);
+        let alpha = self.options.significance_level;
+
+        let test = match self.options.significance_test {
+            SignificanceTest::Welch => welch_t_test,
+            SignificanceTest::MannWhitney => mann_whitney_u_test,
+        };
+
+        match test(&times(reference), &times(other)) {
+            Some(test) if test.is_significant(alpha) => {
+                format!(" ({})", format_p_value(test.p_value))
+                    .dimmed()
+                    .to_string()
+            }
+            Some(test) => format!(
+                " ({}, not significant at α = {})",
+                format_p_value(test.p_value),
+                alpha
+            )
+            .yellow()
+            .to_string(),
+            None => "".into(),
+        }
+    }
+
     pub fn final_export(&self) -> Result<()> {
         self.export_manager
             .write_results(&self.results, self.options.sort_order_exports, false)
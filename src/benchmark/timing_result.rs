@@ -1,14 +1,126 @@
+use serde::Serialize;
+
+use crate::options::PerfCounter;
 use crate::util::units::Second;
 
+/// Number of bytes that a process (including its children) read and wrote
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct IoCounters {
+    pub read_bytes: u64,
+    pub written_bytes: u64,
+}
+
+/// Number of times that a process (including its children) was switched off the CPU
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct ContextSwitches {
+    /// Switches because the process was waiting, e.g. for I/O
+    pub voluntary: u64,
+
+    /// Switches because the process was preempted by the scheduler
+    pub involuntary: u64,
+}
+
+/// Number of page faults of a process (including its children)
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct PageFaults {
+    /// Faults that could be resolved without I/O, e.g. from the page cache
+    pub minor: u64,
+
+    /// Faults that required reading from the disk
+    pub major: u64,
+}
+
+/// Values of the hardware performance counters (`--perf-counters`) of a process
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct PerfCounts([Option<u64>; PerfCounter::ALL.len()]);
+
+impl PerfCounts {
+    pub fn get(&self, counter: PerfCounter) -> Option<u64> {
+        self.0[counter as usize]
+    }
+
+    pub fn set(&mut self, counter: PerfCounter, value: u64) {
+        self.0[counter as usize] = Some(value);
+    }
+
+    /// All counters that have a value
+    pub fn iter(&self) -> impl Iterator<Item = (PerfCounter, u64)> + '_ {
+        PerfCounter::ALL
+            .iter()
+            .filter_map(move |&counter| Some((counter, self.get(counter)?)))
+    }
+}
+
+/// Frequency of the CPU(s) that ran a process, from the samples that were taken during the
+/// run (`--cpu-frequency`), in MHz
+#[derive(Debug, Default, Copy, Clone, PartialEq, Serialize)]
+pub struct CpuFrequency {
+    pub min: f64,
+    pub mean: f64,
+    pub max: f64,
+}
+
+/// Utilization and memory usage of the GPU(s), from the samples that were taken during the
+/// run (`--gpu`). With multiple GPUs, the utilization is the mean of all GPUs and the memory
+/// usage is the sum.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Serialize)]
+pub struct GpuUsage {
+    /// Mean utilization in percent
+    pub utilization_mean: f64,
+
+    /// Maximum utilization in percent
+    pub utilization_max: f64,
+
+    /// Peak memory usage in bytes
+    pub memory_peak_byte: u64,
+}
+
 /// Results from timing a single command
 #[derive(Debug, Default, Copy, Clone)]
 pub struct TimingResult {
     /// Wall clock time
     pub time_real: Second,
 
+    /// Wall clock time as it was measured, before the overhead of the executor (e.g. the
+    /// shell spawning time) was subtracted
+    pub time_real_raw: Second,
+
     /// Time spent in user mode
     pub time_user: Second,
 
     /// Time spent in kernel mode
     pub time_system: Second,
+
+    /// Peak memory usage of the process, in bytes
+    pub memory_usage_byte: Option<u64>,
+
+    /// Bytes read and written by the process
+    pub io: Option<IoCounters>,
+
+    /// Context switches of the process
+    pub context_switches: Option<ContextSwitches>,
+
+    /// Page faults of the process
+    pub page_faults: Option<PageFaults>,
+
+    /// Peak number of processes and threads that ran at the same time (with `--cgroup`)
+    pub peak_tasks: Option<u64>,
+
+    /// Number of processes that were created, including the benchmarked process itself
+    pub total_processes: Option<u64>,
+
+    /// Hardware performance counters of the process, if any were requested
+    pub perf_counts: PerfCounts,
+
+    /// Energy consumption of the CPU during the run in joules (`--measure-energy`)
+    pub energy_joule: Option<f64>,
+
+    /// Frequency of the CPU(s) during the run (`--cpu-frequency`)
+    pub cpu_frequency: Option<CpuFrequency>,
+
+    /// Utilization and memory usage of the GPU(s) during the run (`--gpu`)
+    pub gpu_usage: Option<GpuUsage>,
+
+    /// Time until the first output of the command (`--time-to-first-output`)
+    pub time_to_first_output: Option<Second>,
 }
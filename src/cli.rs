@@ -1,9 +1,24 @@
 use std::ffi::OsString;
 
 use clap::{
-    builder::NonEmptyStringValueParser, crate_version, Arg, ArgAction, ArgMatches, Command,
+    builder::NonEmptyStringValueParser, crate_version, Arg, ArgAction, ArgGroup, ArgMatches,
+    Command,
 };
 
+/// Read a command file (`--command-file`), which is parsed along with the other commands.
+/// This results in the path and the content of the file. The path '-' refers to the
+/// standard input.
+fn read_command_file(path: &str) -> Result<(String, String), String> {
+    let content = if path == "-" {
+        std::io::read_to_string(std::io::stdin())
+    } else {
+        std::fs::read_to_string(path)
+    };
+    content
+        .map(|content| (path.to_string(), content))
+        .map_err(|e| format!("could not read the command file: {}", e))
+}
+
 pub fn get_cli_arguments<'a, I, T>(args: I) -> ArgMatches
 where
     I: IntoIterator<Item = T>,
@@ -29,7 +44,7 @@ fn build_command() -> Command {
                        The latter is only available if the shell is not explicitly disabled via \
                        '--shell=none'. If multiple commands are given, hyperfine will show a \
                        comparison of the respective runtimes.")
-                .required(true)
+                .required_unless_present_any(["export-schema", "command-file"])
                 .action(ArgAction::Append)
                 .value_parser(NonEmptyStringValueParser::new()),
         )
@@ -41,7 +56,10 @@ fn build_command() -> Command {
                 .action(ArgAction::Set)
                 .help(
                     "Perform NUM warmup runs before the actual benchmark. This can be used \
-                     to fill (disk) caches for I/O-heavy programs.",
+                     to fill (disk) caches for I/O-heavy programs. With '--warmup auto', warmup \
+                     runs are performed until the run times are stable, i.e. until the median \
+                     time of the last three runs differs by less than 5% from the median time \
+                     of the three runs before (at most 50 runs).",
                 ),
         )
         .arg(
@@ -60,6 +78,42 @@ fn build_command() -> Command {
                 .value_name("NUM")
                 .help("Perform at most NUM runs for each command. By default, there is no limit."),
         )
+        .arg(
+            Arg::new("max-time")
+                .long("max-time")
+                .action(ArgAction::Set)
+                .value_name("SECONDS")
+                .help("Spend at most SECONDS on benchmarking each command (including warmup and \
+                       preparation runs). Once the time budget is used up, no further runs are \
+                       started, but at least --min-runs runs are always performed. Truncated \
+                       benchmarks are marked in the output and in the JSON export. This is \
+                       useful for parameter scans over commands of very different speed.\n\n  \
+                       Example:  hyperfine --max-time 30 -P n 1 10 'make -j {n}'"),
+        )
+        .arg(
+            Arg::new("max-relative-stddev")
+                .long("max-relative-stddev")
+                .action(ArgAction::Set)
+                .value_name("PCT")
+                .requires("max-runs")
+                .help("Keep benchmarking each command until the relative standard deviation \
+                       (coefficient of variation) of its run times drops below PCT percent, \
+                       instead of stopping after the automatically determined number of runs. \
+                       The number of runs is bounded by --max-runs, which is required.\n\n  \
+                       Example:  hyperfine --max-relative-stddev 2 --max-runs 500 'make'"),
+        )
+        .arg(
+            Arg::new("max-ci-width")
+                .long("max-ci-width")
+                .action(ArgAction::Set)
+                .value_name("PCT")
+                .requires("max-runs")
+                .help("Keep benchmarking each command until the confidence interval of its mean \
+                       time (95% by default, see --confidence) is within ±PCT percent of the \
+                       mean. The number of runs is bounded by --min-runs and --max-runs, which \
+                       is required. This results in a consistent precision for fast and slow \
+                       commands."),
+        )
         .arg(
             Arg::new("runs")
                 .long("runs")
@@ -70,6 +124,19 @@ fn build_command() -> Command {
                 .help("Perform exactly NUM runs for each command. If this option is not specified, \
                        hyperfine automatically determines the number of runs."),
         )
+        .arg(
+            Arg::new("batch")
+                .long("batch")
+                .action(ArgAction::Set)
+                .value_name("N")
+                .help("Execute the command N times in a loop within a single shell invocation \
+                       for every run, and divide the measured times by N. This amortizes the \
+                       time for spawning the shell and the resolution of the timer for commands \
+                       that only take a few milliseconds or less. The loop stops at the first \
+                       failing iteration. Other measurements, like the memory usage, are not \
+                       divided and refer to the whole batch. This requires a POSIX shell, so \
+                       it can not be used with --shell=none or with cmd.exe on Windows."),
+        )
         .arg(
             Arg::new("setup")
                 .long("setup")
@@ -98,6 +165,107 @@ fn build_command() -> Command {
                      be run prior to the corresponding benchmark command.",
                 ),
         )
+        .arg(
+            Arg::new("working-directory")
+                .long("working-directory")
+                .action(ArgAction::Append)
+                .num_args(1)
+                .value_name("DIR")
+                .help(
+                    "Execute the commands in DIR instead of the current directory. This also \
+                     applies to the --setup, --prepare and --cleanup commands of a benchmark. \
+                     The option can be specified once for all commands or multiple times, once \
+                     for each command, e.g. to compare the same tool in two checkouts. In the \
+                     latter case, the --reference command runs in the current directory. DIR can \
+                     contain parameters like '{VAR}'.\n\n  \
+                     Example:  hyperfine -L checkout old,new --working-directory '{checkout}' 'make'",
+                ),
+        )
+        .arg(
+            Arg::new("env")
+                .long("env")
+                .action(ArgAction::Append)
+                .num_args(1)
+                .value_name("KEY=VALUE")
+                .help(
+                    "Set the environment variable KEY to VALUE for all commands, independent of \
+                     the shell. VALUE can contain parameters like '{VAR}'. This can be \
+                     specified multiple times.\n\n  \
+                     Example:  hyperfine -L arenas 1,2,4 --env 'MALLOC_ARENA_MAX={arenas}' './server'",
+                ),
+        )
+        .arg(
+            Arg::new("env-file")
+                .long("env-file")
+                .action(ArgAction::Append)
+                .num_args(1)
+                .value_name("FILE")
+                .help(
+                    "Set the environment variables in FILE for all commands. Every line of FILE \
+                     has the form 'KEY=VALUE', like the argument of --env, which takes \
+                     precedence. Empty lines and lines that start with '#' are ignored. This \
+                     can be specified multiple times.",
+                ),
+        )
+        .arg(
+            Arg::new("env-clear")
+                .long("env-clear")
+                .visible_alias("empty-env")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Run all commands without the environment variables of hyperfine, except \
+                     for PATH (and PATHEXT, SystemRoot and ComSpec on Windows), which are \
+                     required to find and start them. Only the variables of --env and \
+                     --env-file are added. This rules out differences between the \
+                     environments of machines or users in comparisons.",
+                ),
+        )
+        .arg(
+            Arg::new("cold-prepare")
+                .long("cold-prepare")
+                .action(ArgAction::Set)
+                .value_name("CMD")
+                .conflicts_with("prepare")
+                .help(
+                    "Benchmark each command twice: once with cold caches, executing CMD before \
+                     each timing run, and once with warm caches, after at least one warmup run. \
+                     CMD is typically a command that drops the file system caches. Both \
+                     benchmarks are reported side by side, distinguished by the 'cache' \
+                     parameter (cold or warm) in the output and in the exports.\n\n  \
+                     Example:  hyperfine --cold-prepare 'sync; echo 3 | sudo tee /proc/sys/vm/drop_caches' 'grep -R TODO *'",
+                ),
+        )
+        .arg(
+            Arg::new("drop-caches")
+                .long("drop-caches")
+                .action(ArgAction::Set)
+                .value_name("WHEN")
+                .value_parser(["before-each-run", "before-each-benchmark"])
+                .help(
+                    "Drop the file system caches of the operating system, either before each \
+                     run (including the warmup runs) or once before each benchmark, after the \
+                     setup command. On Linux, dirty pages are written to the disk first and \
+                     the page cache, dentries and inodes are dropped via /proc/sys/vm/drop_caches. \
+                     On macOS, 'purge' is used. Both need to be run as root, so they are run \
+                     via 'sudo' if hyperfine is not running as root, which may ask for a \
+                     password once at the start. On Windows, the standby list is emptied, \
+                     which requires administrator privileges.",
+                ),
+        )
+        .arg(
+            Arg::new("sync-before-run")
+                .long("sync-before-run")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Write all dirty pages from the file system caches to the disks before \
+                     each run (including the warmup runs), after the preparation command. \
+                     This makes sure that the writeback of files that were written by a \
+                     previous run or by the preparation command does not slow down the next \
+                     run. The time that this takes is not included in the results. On Unix, \
+                     sync(2) is used. On Windows, the volume of the current directory is \
+                     flushed, which requires administrator privileges.",
+                ),
+        )
         .arg(
             Arg::new("cleanup")
                 .long("cleanup")
@@ -123,7 +291,8 @@ fn build_command() -> Command {
                      string '{VAR}' in each command by the current parameter value.\n\n  \
                      Example:  hyperfine -P threads 1 8 'make -j {threads}'\n\n\
                      This performs benchmarks for 'make -j 1', 'make -j 2', …, 'make -j 8'.\n\n\
-                     To have the value increase following different patterns, use shell arithmetics.\n\n  \
+                     To multiply the value by a factor in every step, use a step like '*2' (see \
+                     --parameter-step-size). For other patterns, use shell arithmetics.\n\n  \
                      Example: hyperfine -P size 0 3 'sleep $((2**{size}))'\n\n\
                      This performs benchmarks with power of 2 increases: 'sleep 1', 'sleep 2', 'sleep 4', …\n\
                      The exact syntax may vary depending on your shell and OS."
@@ -140,7 +309,12 @@ fn build_command() -> Command {
                     "This argument requires --parameter-scan to be specified as well. \
                      Traverse the range MIN..MAX in steps of DELTA.\n\n  \
                      Example:  hyperfine -P delay 0.3 0.7 -D 0.2 'sleep {delay}'\n\n\
-                     This performs benchmarks for 'sleep 0.3', 'sleep 0.5' and 'sleep 0.7'.",
+                     This performs benchmarks for 'sleep 0.3', 'sleep 0.5' and 'sleep 0.7'.\n\n\
+                     With a DELTA like '*2', the values are multiplied by the factor in every \
+                     step instead, for ranges that span orders of magnitude. MIN has to be \
+                     positive.\n\n  \
+                     Example:  hyperfine -P size 1 1048576 -D '*2' 'head -c {size} /dev/zero'\n\n\
+                     This performs benchmarks for the sizes 1, 2, 4, …, 1048576.",
                 ),
         )
         .arg(
@@ -157,9 +331,65 @@ fn build_command() -> Command {
                      .\n\nExample:  hyperfine -L compiler gcc,clang '{compiler} -O2 main.cpp'\n\n\
                      This performs benchmarks for 'gcc -O2 main.cpp' and 'clang -O2 main.cpp'.\n\n\
                      The option can be specified multiple times to run benchmarks for all \
-                     possible parameter combinations.\n"
+                     possible parameter combinations (see --parameter-mode).\n"
                 ),
         )
+        .arg(
+            Arg::new("parameter-file")
+                .long("parameter-file")
+                .action(ArgAction::Append)
+                .value_names(["VAR", "FILE"])
+                .conflicts_with_all(["parameter-scan", "parameter-step-size"])
+                .help(
+                    "Like --parameter-list, but read the values from FILE, which contains one \
+                     value per line. The values can contain commas and spaces. Empty lines are \
+                     ignored. This can be specified multiple times, and combined with \
+                     --parameter-list."
+                ),
+        )
+        .group(
+            ArgGroup::new("parameter-lists")
+                .args(["parameter-list", "parameter-file"])
+                .multiple(true),
+        )
+        .arg(
+            Arg::new("parameter-mode")
+                .long("parameter-mode")
+                .action(ArgAction::Set)
+                .value_name("MODE")
+                .value_parser(["product", "zip"])
+                .default_value("product")
+                .hide_default_value(true)
+                .requires("parameter-lists")
+                .help(
+                    "How the values of several '--parameter-list' and '--parameter-file' \
+                     options are combined:\n  \
+                       * 'product' (default): benchmark all possible combinations of the values.\n  \
+                       * 'zip': benchmark the first values of all lists together, then the second \
+                     values, and so on. All lists need to have the same length.\n\n\
+                     Example:  hyperfine -L file a.txt,b.txt -L size 1k,2M --parameter-mode zip \
+                     'check {file} {size}'\n"
+                ),
+        )
+        .arg(
+            Arg::new("geometric-mean")
+                .long("geometric-mean")
+                .action(ArgAction::SetTrue)
+                .help("For parametrized benchmarks (see --parameter-scan and --parameter-list), \
+                       also report the geometric mean of the relative speeds of each command \
+                       across all parameter values. This answers the question which command \
+                       is faster overall."),
+        )
+        .arg(
+            Arg::new("complexity")
+                .long("complexity")
+                .action(ArgAction::SetTrue)
+                .requires("parameter-scan")
+                .help("For benchmarks with --parameter-scan, fit the mean times of each command \
+                       against the complexity models O(1), O(n), O(n log n) and O(n²), where n \
+                       is the parameter value. The best fitting model is reported together with \
+                       its coefficient of determination (R²)."),
+        )
         .arg(
             Arg::new("shell")
                 .long("shell")
@@ -173,7 +403,11 @@ fn build_command() -> Command {
                        the default shell on this platform. Finally, this can also be set to \
                        \"none\" to disable the shell. In this case, commands will be executed \
                        directly. They can still have arguments, but more complex things like \
-                       \"sleep 0.1; sleep 0.2\" are not possible without a shell.")
+                       \"sleep 0.1; sleep 0.2\" are not possible without a shell. The shell \
+                       spawning time (or, without a shell, the overhead of the timer) is \
+                       measured before the benchmarks and subtracted from all times. The \
+                       uncorrected times are included in the 'times_raw' field of the JSON \
+                       export.")
         )
         .arg(
             Arg::new("no-shell")
@@ -221,6 +455,42 @@ fn build_command() -> Command {
                    * 'mean-time': order benchmarks by mean runtime\n"
             ),
         )
+        .arg(
+            Arg::new("reference")
+                .long("reference")
+                .action(ArgAction::Set)
+                .value_name("CMD")
+                .help("The reference command for the speed comparison summary. It is benchmarked \
+                       first, and all other commands are expressed relative to it instead of \
+                       relative to the fastest command.\n\n  \
+                       Example:  hyperfine --reference './main-build' './feature-build'"),
+        )
+        .arg(
+            Arg::new("relative-speed-metric")
+                .long("relative-speed-metric")
+                .action(ArgAction::Set)
+                .value_name("METRIC")
+                .value_parser(["mean", "median", "min"])
+                .help("Select the time that is used to compare the commands in the speed \
+                       comparison summary: 'mean' (default), 'median' or 'min'. The median is \
+                       more robust for skewed distributions of the run times. Exported tables \
+                       always compare the mean times."),
+        )
+        .arg(
+            Arg::new("sort-by")
+                .long("sort-by")
+                .action(ArgAction::Set)
+                .value_name("KEY")
+                .value_parser(["relative-speed", "mean", "median", "command", "order"])
+                .help("Specify the sort order of the speed comparison summary. This takes precedence \
+                       over --sort, which still controls the exported tables:\n  \
+                         * 'relative-speed': order by relative speed (the default, see also\n    \
+                           --relative-speed-metric)\n  \
+                         * 'mean': order by mean time\n  \
+                         * 'median': order by median time\n  \
+                         * 'command': order alphabetically by command\n  \
+                         * 'order': order benchmarks in the way they were specified\n"),
+        )
         .arg(
             Arg::new("time-unit")
                 .long("time-unit")
@@ -232,6 +502,425 @@ fn build_command() -> Command {
                        If the option is not given, the time unit is determined automatically. \
                        This option affects the standard output as well as all export formats except for CSV and JSON."),
         )
+        .arg(
+            Arg::new("percentiles")
+                .long("percentiles")
+                .action(ArgAction::Set)
+                .value_name("LIST")
+                .value_delimiter(',')
+                .help("Compute the given comma-separated list of percentiles (between 0 and 100) of \
+                       the run times, e.g. '--percentiles 50,90,99'. The percentiles are shown in \
+                       the terminal output and are included in the JSON and CSV exports."),
+        )
+        .arg(
+            Arg::new("trim")
+                .long("trim")
+                .action(ArgAction::Set)
+                .value_name("PCT")
+                .help("Report a trimmed mean instead of the arithmetic mean: the fastest PCT percent \
+                       and the slowest PCT percent of the runs are not taken into account for the \
+                       mean and the standard deviation. This is a standard remedy for benchmarks \
+                       that are affected by rare pauses, e.g. due to garbage collection or the \
+                       scheduler.\n\n  \
+                       Example:  hyperfine --trim 5 'java Main'"),
+        )
+        .arg(
+            Arg::new("winsorize")
+                .long("winsorize")
+                .action(ArgAction::SetTrue)
+                .requires("trim")
+                .help("Together with --trim, report a winsorized mean instead of a trimmed mean: \
+                       the fastest and slowest runs are replaced by the fastest and slowest \
+                       remaining run, respectively, instead of being removed."),
+        )
+        .arg(
+            Arg::new("throughput")
+                .long("throughput")
+                .action(ArgAction::Set)
+                .value_name("UNIT[=AMOUNT]")
+                .help("Additionally report the throughput of each command, computed from the time \
+                       of every run. UNIT is either 'bytes' (reported as kB/s, MB/s, …) or 'items' \
+                       (reported as items/s). AMOUNT is the number of bytes or items that is \
+                       processed in each run. It may contain parameter placeholders. If it is \
+                       omitted, the value of the benchmark parameter is used.\n\n  \
+                       Examples:  hyperfine --throughput bytes=1048576 'gzip -c data.bin'\n             \
+                       hyperfine -P size 1000 4000 -D 1000 --throughput bytes 'head -c {size} /dev/zero | gzip'"),
+        )
+        .arg(
+            Arg::new("histogram")
+                .long("histogram")
+                .action(ArgAction::SetTrue)
+                .help("Show a histogram of the run times after each benchmark, ranging from the \
+                       fastest run (left) to the slowest run (right). This makes it easy to spot \
+                       long tails or multiple peaks in the distribution of the run times."),
+        )
+        .arg(
+            Arg::new("sparkline")
+                .long("sparkline")
+                .action(ArgAction::SetTrue)
+                .help("Show the run times in chronological order as a sparkline after each \
+                       benchmark. This makes it easy to spot trends during the benchmark, for \
+                       example due to caching effects or thermal throttling."),
+        )
+        .arg(
+            Arg::new("confidence")
+                .long("confidence")
+                .action(ArgAction::Set)
+                .value_name("LEVEL")
+                .help("Set the confidence level of all confidence intervals (default: 0.95). This \
+                       applies to the intervals of --bootstrap, --relative-speed-ci and \
+                       --max-ci-width. Unless --significance-level is given, the significance \
+                       tests of the summary use the corresponding level of 1 - LEVEL.\n\n  \
+                       Example:  hyperfine --confidence 0.99 --relative-speed-ci 'cmd1' 'cmd2'"),
+        )
+        .arg(
+            Arg::new("significance-level")
+                .long("significance-level")
+                .action(ArgAction::Set)
+                .value_name("ALPHA")
+                .help("Set the significance level for the comparison of benchmarks (default: 0.05). \
+                       The summary states whether the difference between the fastest command and \
+                       each other command is statistically significant at this level, according \
+                       to the test selected with --significance-test."),
+        )
+        .arg(
+            Arg::new("significance-test")
+                .long("significance-test")
+                .action(ArgAction::Set)
+                .value_name("TEST")
+                .value_parser(["welch", "mann-whitney"])
+                .help("Select the statistical test for the comparison of benchmarks:\n  \
+                         * 'welch' (default): Welch's t-test, which compares the mean times and\n    \
+                           assumes normally distributed run times.\n  \
+                         * 'mann-whitney': Mann-Whitney U test, which does not make any\n    \
+                           assumptions about the distribution of the run times. Use this for\n    \
+                           skewed distributions.\n"),
+        )
+        .arg(
+            Arg::new("power-analysis")
+                .long("power-analysis")
+                .action(ArgAction::Set)
+                .value_name("PCT")
+                .help("After each benchmark, report how many runs per command would be needed \
+                       to detect a difference of PCT percent between the mean times of two \
+                       commands with a statistical power of 80%, at the significance level of \
+                       --significance-level. The estimate is based on the observed spread of \
+                       the run times.\n\n  \
+                       Example:  hyperfine --power-analysis 2 'make'"),
+        )
+        .arg(
+            Arg::new("outlier-method")
+                .long("outlier-method")
+                .action(ArgAction::Set)
+                .value_name("METHOD")
+                .value_parser(["mzscore", "iqr", "grubbs", "none"])
+                .help("Select the method that is used to detect statistical outliers in the run \
+                       times. The threshold of each method can be changed with --outlier-threshold.\n  \
+                         * 'mzscore' (default): runs with a modified Z-score (based on the median\n    \
+                           absolute deviation) of more than THRESHOLD standard deviations.\n    \
+                           Default threshold: 10.\n  \
+                         * 'iqr': runs that are farther than THRESHOLD times the interquartile\n    \
+                           range away from the first or third quartile. Default threshold: 3.\n  \
+                         * 'grubbs': Grubbs' test at a significance level of THRESHOLD, repeated\n    \
+                           until no further outlier is found. Default threshold: 0.05.\n  \
+                         * 'none': disable outlier detection.\n"),
+        )
+        .arg(
+            Arg::new("outlier-threshold")
+                .long("outlier-threshold")
+                .action(ArgAction::Set)
+                .value_name("THRESHOLD")
+                .help("Threshold for the outlier detection method selected with --outlier-method."),
+        )
+        .arg(
+            Arg::new("discard-outliers")
+                .long("discard-outliers")
+                .action(ArgAction::SetTrue)
+                .help("Remove the runs that were detected as statistical outliers (see \
+                       --outlier-method) before computing the statistics and the relative speeds. \
+                       All runs are still included in the exports, marked as outliers in the \
+                       'outliers' field of the JSON export."),
+        )
+        .arg(
+            Arg::new("show-outliers")
+                .long("show-outliers")
+                .action(ArgAction::SetTrue)
+                .help("List the runs that were detected as statistical outliers (see \
+                       --outlier-method), with their run number, time and modified Z-score (in \
+                       units of standard deviations). The list is also included in the \
+                       'outlier_runs' field of the JSON export."),
+        )
+        .arg(
+            Arg::new("priority")
+                .long("priority")
+                .action(ArgAction::Set)
+                .allow_hyphen_values(true)
+                .value_name("LEVEL")
+                .help("Run the benchmarked commands (and the shell that runs them) with the given \
+                       nice level, from -20 (highest priority) to 19 (lowest priority). Negative \
+                       levels usually require root privileges. On Windows, the level is mapped \
+                       to a priority class: 'high' for -10 and below, 'above normal' for -9 to \
+                       -1, 'normal' for 0, 'below normal' for 1 to 9 and 'idle' for 10 and \
+                       above."),
+        )
+        .arg(
+            Arg::new("no-aslr")
+                .long("no-aslr")
+                .action(ArgAction::SetTrue)
+                .help("Disable address space layout randomization (ASLR) for the benchmarked \
+                       commands (and the shell that runs them). Randomized addresses change the \
+                       alignment of code and data in the caches and the TLB from run to run, \
+                       which is a source of noise in micro-benchmarks. Only available on Linux \
+                       (using personality(2))."),
+        )
+        .arg(
+            Arg::new("isolate-network")
+                .long("isolate-network")
+                .action(ArgAction::SetTrue)
+                .help("Run all commands (including the setup, preparation and cleanup commands) \
+                       in a new network namespace that only contains the loopback interface. \
+                       Connections to other hosts fail immediately, such that commands which \
+                       check for updates or send telemetry do not depend on the latency of \
+                       the network, while connections to localhost keep working. Without root \
+                       privileges, an unprivileged user namespace is created along with it. \
+                       Only available on Linux."),
+        )
+        .arg(
+            Arg::new("clock")
+                .long("clock")
+                .action(ArgAction::Set)
+                .value_name("CLOCK")
+                .value_parser(["monotonic", "monotonic-raw", "process-cputime"])
+                .help("The clock that measures the run time of the benchmarked commands:\n  \
+                         * 'monotonic' (default): the monotonic clock of the operating system.\n  \
+                         * 'monotonic-raw': the monotonic clock without the frequency adjustments \
+                           of NTP (CLOCK_MONOTONIC_RAW), which can otherwise slew the times \
+                           during long benchmark sessions. Only available on Linux and macOS.\n  \
+                         * 'process-cputime': the CPU time of the benchmarked command, i.e. the sum \
+                           of its user and system time, instead of the wall clock time.\n\
+                       The time to the first output (--time-to-first-output) is always measured \
+                       with the monotonic clock."),
+        )
+        .arg(
+            Arg::new("cpu")
+                .long("cpu")
+                .action(ArgAction::Set)
+                .value_name("LIST")
+                .help("Pin the benchmarked commands (and the shell that runs them) to the given \
+                       comma-separated list of CPUs or CPU ranges, e.g. '--cpu 2' or \
+                       '--cpu 0,2-3'. This avoids noise from the migration of the process \
+                       between cores. In contrast to wrapping the command in 'taskset', the \
+                       shell spawning time is calibrated with the same affinity. Only available \
+                       on Linux and Windows."),
+        )
+        .arg(
+            Arg::new("hyperfine-cpu")
+                .long("hyperfine-cpu")
+                .action(ArgAction::Set)
+                .value_name("LIST")
+                .help("Pin hyperfine itself to the given list of CPUs (see --cpu), e.g. to keep \
+                       it away from the CPUs of the benchmarked commands. If --cpu is not given, \
+                       the benchmarked commands inherit this affinity. Only available on Linux \
+                       and Windows."),
+        )
+        .arg(
+            Arg::new("cgroup")
+                .long("cgroup")
+                .action(ArgAction::SetTrue)
+                .help("Perform every run in a fresh cgroup (v2), and measure the CPU times and \
+                       the peak memory usage of all processes in the cgroup combined, including \
+                       those that the command did not wait for. Unlike the maximum resident set \
+                       size that is measured otherwise, the peak memory usage does not include \
+                       the memory that a process shares with hyperfine after it was forked \
+                       (about 1-2 MB). The cgroup also measures the peak number of \
+                       processes and threads (tasks) that ran at the same time and the number \
+                       of processes that were created. Processes that are still running when the command exits are killed. The cgroups are \
+                       created below the cgroup of hyperfine, which requires root privileges or \
+                       a delegated cgroup, e.g. 'systemd-run --user --scope -p Delegate=yes \
+                       hyperfine ...'. Only available on Linux."),
+        )
+        .arg(
+            Arg::new("limit-memory")
+                .long("limit-memory")
+                .action(ArgAction::Set)
+                .value_name("SIZE")
+                .help("Limit the memory usage of the cgroup of every run to SIZE bytes, with an \
+                       optional suffix K, M, G or T, e.g. '--limit-memory 512M'. Swapping is \
+                       disabled. This implies --cgroup."),
+        )
+        .arg(
+            Arg::new("limit-cpus")
+                .long("limit-cpus")
+                .action(ArgAction::Set)
+                .value_name("N")
+                .help("Limit the CPU time of the cgroup of every run to N CPUs, e.g. \
+                       '--limit-cpus 2' or '--limit-cpus 0.5'. In contrast to --cpu, the \
+                       processes may still run on all cores. This implies --cgroup."),
+        )
+        .arg(
+            Arg::new("aggregate-children")
+                .long("aggregate-children")
+                .action(ArgAction::SetTrue)
+                .help("Include all descendants of the benchmarked command in the measurements, \
+                       even those that it does not wait for, like background processes or \
+                       daemons. hyperfine waits until all of them have exited, and includes \
+                       their CPU times, context switches and page faults in the results. On \
+                       Windows, the memory usage is the peak of all processes combined, instead \
+                       of the peak of the largest process. Only available on Linux and \
+                       Windows."),
+        )
+        .arg(
+            Arg::new("measure-energy")
+                .long("measure-energy")
+                .action(ArgAction::SetTrue)
+                .help("Measure the energy that the CPU consumes during every run, using the \
+                       Intel RAPL interface (which recent AMD CPUs support as well). Note that \
+                       this is a system-wide measurement that includes the energy of all other \
+                       programs and of idle cores, so it is most meaningful on a quiet system. \
+                       Only available on Linux, and usually requires root privileges."),
+        )
+        .arg(
+            Arg::new("cpu-frequency")
+                .long("cpu-frequency")
+                .action(ArgAction::SetTrue)
+                .help("Sample the frequency of the CPU that runs the benchmarked command (or the \
+                       mean frequency of the CPUs given by --cpu) every 10 ms during every run, \
+                       and report the minimum, mean and maximum frequency. This makes thermal \
+                       throttling and frequency scaling visible. Only available on Linux, and \
+                       not in most virtual machines."),
+        )
+        .arg(
+            Arg::new("gpu")
+                .long("gpu")
+                .action(ArgAction::SetTrue)
+                .help("Sample the utilization and the memory usage of the GPU(s) every 10 ms \
+                       during every run, and report the mean utilization and the peak memory \
+                       usage. This shows whether a command is bound by the CPU or by the GPU. \
+                       These are system-wide measurements that include all other programs that \
+                       use the GPU. Only NVIDIA GPUs are supported (using the NVIDIA Management \
+                       Library of the driver), and only on Linux."),
+        )
+        .arg(
+            Arg::new("perf-counters")
+                .long("perf-counters")
+                .action(ArgAction::Set)
+                .value_name("LIST")
+                .value_delimiter(',')
+                .value_parser([
+                    "cycles",
+                    "instructions",
+                    "cache-misses",
+                    "branch-misses",
+                    "syscalls",
+                ])
+                .help("Measure the given comma-separated list of hardware performance counters \
+                       for every run, e.g. '--perf-counters cycles,instructions'. Like with \
+                       'perf stat', the counters include all threads of the benchmarked process \
+                       and all processes that it spawns (see --no-perf-counters-children), but \
+                       not hyperfine itself. If both 'cycles' and 'instructions' are measured, the number of instructions \
+                       per cycle (IPC) is shown as well. 'syscalls' counts the system calls \
+                       (using the 'raw_syscalls:sys_enter' tracepoint, which usually requires \
+                       root privileges). Counters are often much less noisy than \
+                       wall clock times when comparing near-identical commands. Only available \
+                       on Linux (using perf_event_open(2)) and macOS. Depending on \
+                       /proc/sys/kernel/perf_event_paranoid, only events in user space are \
+                       counted for unprivileged users on Linux. On macOS, only 'cycles' and \
+                       'instructions' are available, and they only include the benchmarked \
+                       process itself, not its children."),
+        )
+        .arg(
+            Arg::new("no-perf-counters-children")
+                .long("no-perf-counters-children")
+                .action(ArgAction::SetTrue)
+                .requires("perf-counters")
+                .help("Exclude the processes that are spawned by the benchmarked command from \
+                       the performance counters (--perf-counters), like 'perf stat \
+                       --no-inherit'. The counters then only include the threads of the \
+                       benchmarked process. Note that this also excludes the commands that a \
+                       shell spawns, e.g. the first command of 'a && b', while most shells \
+                       execute a simple command directly. On macOS, the counters never include \
+                       child processes."),
+        )
+        .arg(
+            Arg::new("profile")
+                .long("profile")
+                .action(ArgAction::Set)
+                .value_name("DIR")
+                .help("After the timing runs, run every benchmarked command once more under a \
+                       sampling profiler and write the profile to DIR/benchmark-N.perf.data (with \
+                       'perf record' on Linux) or DIR/benchmark-N.stacks (with 'dtrace' on \
+                       macOS). The profiling run is not included in the results. A flame graph \
+                       can be created from the profile with the 'inferno' tools, e.g. \
+                       'perf script -i FILE | inferno-collapse-perf | inferno-flamegraph > \
+                       flamegraph.svg'. Profiling usually requires root privileges."),
+        )
+        .arg(
+            Arg::new("show-sem")
+                .long("show-sem")
+                .action(ArgAction::SetTrue)
+                .help("Show the standard error of the mean (SEM) of the run times, i.e. the \
+                       standard deviation divided by the square root of the number of runs. It \
+                       describes the uncertainty of the mean time, rather than the spread of \
+                       the individual runs. The SEM is always included in the JSON export."),
+        )
+        .arg(
+            Arg::new("show-resource-usage")
+                .long("show-resource-usage")
+                .action(ArgAction::SetTrue)
+                .help("Show the resource usage of the runs in the results: the CPU \
+                       utilization, i.e. (user + system) / wall clock time, the number of bytes \
+                       that were read and written, and the number of context switches and page \
+                       faults. It is always included in the JSON export."),
+        )
+        .arg(
+            Arg::new("bootstrap")
+                .long("bootstrap")
+                .action(ArgAction::SetTrue)
+                .help("Compute confidence intervals (95% by default, see --confidence) for the \
+                       mean times and the relative speeds by resampling the measured runs \
+                       (bootstrapping). The intervals are shown in the terminal output and the \
+                       mean intervals are included in the JSON and CSV exports. Unlike the \
+                       standard deviation, they do not assume normally distributed run times."),
+        )
+        .arg(
+            Arg::new("relative-speed-ci")
+                .long("relative-speed-ci")
+                .action(ArgAction::SetTrue)
+                .help("Show confidence intervals (95% by default, see --confidence) for the \
+                       relative speeds in the summary. They are computed with Fieller's theorem, \
+                       which - unlike the '± stddev' notation - accounts for the uncertainty of \
+                       the reference time in the denominator of the ratio. The intervals are \
+                       always included in the template-based exports. See also: --bootstrap"),
+        )
+        .arg(
+            Arg::new("comparison-matrix")
+                .long("comparison-matrix")
+                .action(ArgAction::SetTrue)
+                .help("Show how many times faster each command is than each other command, as a \
+                       matrix below the summary. Significant differences (see \
+                       --significance-level and --significance-test) are marked with '*'. The \
+                       matrix is also included in the 'comparison_matrix' field of the JSON \
+                       export."),
+        )
+        .arg(
+            Arg::new("probability-faster")
+                .long("probability-faster")
+                .action(ArgAction::SetTrue)
+                .help("State the probability that the fastest command (or the one given with \
+                       --reference) is actually faster than each other command in the summary, \
+                       for example 'faster with a probability of 97.3%'. It is estimated by \
+                       resampling the measured runs (bootstrapping) and compares the mean times."),
+        )
+        .arg(
+            Arg::new("export-append")
+                .long("export-append")
+                .action(ArgAction::SetTrue)
+                .requires("export-json")
+                .help("Merge the results into an existing JSON export file (see --export-json) instead \
+                       of overwriting it. Existing results for the same command and parameters are \
+                       replaced, all others are kept. This allows to collect the results of multiple \
+                       hyperfine invocations in a single file."),
+        )
         .arg(
             Arg::new("export-asciidoc")
                 .long("export-asciidoc")
@@ -240,6 +929,37 @@ fn build_command() -> Command {
                 .help("Export the timing summary statistics as an AsciiDoc table to the given FILE. \
                        The output time unit can be changed using the --time-unit option."),
         )
+        .arg(
+            Arg::new("export-bmf")
+                .long("export-bmf")
+                .action(ArgAction::Set)
+                .value_name("FILE")
+                .help("Export the results in the Bencher Metric Format (BMF) to the given FILE, such \
+                       that they can be uploaded to continuous benchmarking services like bencher.dev. \
+                       The mean time of each command is reported as 'latency' (in nanoseconds), \
+                       with bounds of one standard deviation."),
+        )
+        .arg(
+            Arg::new("export-columns")
+                .long("export-columns")
+                .action(ArgAction::Set)
+                .value_name("COLUMNS")
+                .value_delimiter(',')
+                .value_parser(["mean", "stddev", "median", "min", "max", "user", "system", "relative"])
+                .help("Comma-separated list of the columns (besides the command) in the tables of the \
+                       Markdown, AsciiDoc, org-mode and LaTeX exports. Possible values: mean, stddev, \
+                       median, min, max, user, system, relative. The standard deviation is shown next \
+                       to the mean if both are selected. [default: mean,stddev,min,max,relative]"),
+        )
+        .arg(
+            Arg::new("export-criterion")
+                .long("export-criterion")
+                .action(ArgAction::Set)
+                .value_name("DIR")
+                .help("Export the results to the given DIR, using the directory layout of Criterion.rs \
+                       (<benchmark>/new/{benchmark,estimates,sample}.json). This allows tools like \
+                       'critcmp' to be used for comparing the results."),
+        )
         .arg(
             Arg::new("export-csv")
                 .long("export-csv")
@@ -249,13 +969,107 @@ fn build_command() -> Command {
                        the timing results for each individual run, use the JSON export format. \
                        The output time unit is always seconds."),
         )
+        .arg(
+            Arg::new("export-csv-wide")
+                .long("export-csv-wide")
+                .action(ArgAction::Set)
+                .value_name("FILE")
+                .help("Export the timings of individual runs as CSV to the given FILE, with one \
+                       column per command and one row per run. This layout can be used for side \
+                       by side plots in spreadsheets or R without pivoting the data first. The \
+                       output time unit is always seconds."),
+        )
+        .group(
+            ArgGroup::new("csv-exports")
+                .args(["export-csv", "export-csv-wide"])
+                .multiple(true),
+        )
+        .arg(
+            Arg::new("csv-delimiter")
+                .long("csv-delimiter")
+                .action(ArgAction::Set)
+                .value_name("CHAR")
+                .requires("csv-exports")
+                .help("Use the given single-byte CHAR to separate fields in the CSV exports (see \
+                       --export-csv and --export-csv-wide), instead of a comma. Use '\\t' for tabs."),
+        )
+        .arg(
+            Arg::new("export-gnuplot")
+                .long("export-gnuplot")
+                .action(ArgAction::Set)
+                .value_name("FILE")
+                .help("Export a gnuplot script to the given FILE, along with the times of the \
+                       individual runs in a data file with the same name and a '.dat' extension. \
+                       Running 'gnuplot FILE' creates an SVG file with a histogram and a box plot \
+                       for each command. The output time unit can be changed using the \
+                       --time-unit option."),
+        )
+        .arg(
+            Arg::new("export-google-benchmark")
+                .long("export-google-benchmark")
+                .action(ArgAction::Set)
+                .value_name("FILE")
+                .help("Export the results in the JSON format of Google Benchmark to the given FILE, such \
+                       that tools like its 'compare.py' script can be used. Each run is written as a \
+                       repetition of the benchmark, followed by the mean, median and stddev aggregates. \
+                       The output time unit can be changed using the --time-unit option."),
+        )
+        .arg(
+            Arg::new("export-html")
+                .long("export-html")
+                .action(ArgAction::Set)
+                .value_name("FILE")
+                .help("Export a standalone HTML report to the given FILE. The report contains a table \
+                       of the timing summary statistics, box plots of the run time distributions and \
+                       a relative speed comparison. The output time unit can be changed using the \
+                       --time-unit option."),
+        )
+        .arg(
+            Arg::new("export-influxdb")
+                .long("export-influxdb")
+                .action(ArgAction::Set)
+                .value_name("FILE")
+                .help("Export the timing summary statistics in the InfluxDB line protocol to the given \
+                       FILE. Each command is written as a point of the 'hyperfine' measurement, tagged \
+                       with the command and its parameters. The output time unit is always seconds."),
+        )
         .arg(
             Arg::new("export-json")
                 .long("export-json")
                 .action(ArgAction::Set)
                 .value_name("FILE")
                 .help("Export the timing summary statistics and timings of individual runs as JSON to the given FILE. \
-                       The output time unit is always seconds"),
+                       The output time unit is always seconds. Use '-' as FILE to write the results to \
+                       stdout, with the regular output of hyperfine going to stderr. This is supported by \
+                       all text-based export formats. Exports to files ending in '.gz' or '.zst' are \
+                       compressed with gzip or Zstandard, respectively"),
+        )
+        .arg(
+            Arg::new("export-junit")
+                .long("export-junit")
+                .action(ArgAction::Set)
+                .value_name("FILE")
+                .help("Export the results as a JUnit XML test report to the given FILE. Each benchmarked \
+                       command is reported as a test case with its mean time. Use --junit-threshold to \
+                       report slow commands as failures. The output time unit is always seconds."),
+        )
+        .arg(
+            Arg::new("junit-threshold")
+                .long("junit-threshold")
+                .action(ArgAction::Set)
+                .value_name("SECONDS")
+                .requires("export-junit")
+                .help("Report commands with a mean time above SECONDS as failed test cases in the \
+                       JUnit XML export (see --export-junit)."),
+        )
+        .arg(
+            Arg::new("export-latex")
+                .long("export-latex")
+                .action(ArgAction::Set)
+                .value_name("FILE")
+                .help("Export the timing summary statistics as a LaTeX table to the given FILE. \
+                       The table uses the rules of the 'booktabs' package. The output time unit \
+                       can be changed using the --time-unit option."),
         )
         .arg(
             Arg::new("export-markdown")
@@ -265,6 +1079,33 @@ fn build_command() -> Command {
                 .help("Export the timing summary statistics as a Markdown table to the given FILE. \
                        The output time unit can be changed using the --time-unit option."),
         )
+        .arg(
+            Arg::new("export-metadata")
+                .long("export-metadata")
+                .action(ArgAction::SetTrue)
+                .help("Include information about the system (hostname, OS and kernel version, CPU \
+                       model and number of cores) along with the hyperfine version and the full \
+                       command line in the JSON, YAML and MessagePack exports."),
+        )
+        .arg(
+            Arg::new("export-msgpack")
+                .long("export-msgpack")
+                .action(ArgAction::Set)
+                .value_name("FILE")
+                .help("Export the timing summary statistics and timings of individual runs in the \
+                       binary MessagePack format to the given FILE. The schema is the same as for \
+                       the JSON export. The output time unit is always seconds."),
+        )
+        .arg(
+            Arg::new("export-ndjson")
+                .long("export-ndjson")
+                .action(ArgAction::Set)
+                .value_name("FILE")
+                .help("Write one line of JSON for each timing run (command, run, time, user, system, \
+                       exit_code and parameters) to the given FILE, as soon as the run has finished. \
+                       This allows to follow the progress of a benchmark and keeps all finished runs \
+                       if hyperfine is interrupted. The output time unit is always seconds."),
+        )
         .arg(
             Arg::new("export-orgmode")
                 .long("export-orgmode")
@@ -273,6 +1114,103 @@ fn build_command() -> Command {
                 .help("Export the timing summary statistics as an Emacs org-mode table to the given FILE. \
                        The output time unit can be changed using the --time-unit option."),
         )
+        .arg(
+            Arg::new("export-parquet")
+                .long("export-parquet")
+                .action(ArgAction::Set)
+                .value_name("FILE")
+                .help("Export the timings of individual runs in the Apache Parquet format to the \
+                       given FILE, with one row per run (command, run, time, exit_code and one \
+                       'parameter_<name>' column per parameter). The output time unit is always \
+                       seconds."),
+        )
+        .arg(
+            Arg::new("export-prometheus")
+                .long("export-prometheus")
+                .action(ArgAction::Set)
+                .value_name("FILE")
+                .help("Export the timing summary statistics in the Prometheus text exposition format to \
+                       the given FILE. Each statistic is exported as a gauge, labeled with the command \
+                       and its parameters. The file can be read by the textfile collector of the \
+                       Prometheus node exporter or be pushed to a Pushgateway. The output time unit is \
+                       always seconds."),
+        )
+        .arg(
+            Arg::new("export-schema")
+                .long("export-schema")
+                .action(ArgAction::SetTrue)
+                .help("Print the JSON Schema of the JSON export format to stdout and exit. The \
+                       'schema_version' field of the JSON export is incremented whenever fields \
+                       are removed or change their meaning."),
+        )
+        .arg(
+            Arg::new("export-sqlite")
+                .long("export-sqlite")
+                .action(ArgAction::Set)
+                .value_name("FILE")
+                .help("Append the timing summary statistics and timings of individual runs to the SQLite \
+                       database in the given FILE. The database is created if it does not exist yet. \
+                       Each invocation of hyperfine is recorded as a new session. The output time unit \
+                       is always seconds."),
+        )
+        .arg(
+            Arg::new("export-template")
+                .long("export-template")
+                .action(ArgAction::Set)
+                .value_names(["TEMPLATE", "FILE"])
+                .help("Render the results through the Handlebars TEMPLATE file and write the output \
+                       to the given FILE. The template has access to the fields of the JSON export \
+                       within 'results' (along with 'relative_speed' and 'is_fastest' for each \
+                       command) and the time 'unit'. Use '{{format_time mean}}' to format a time in \
+                       this unit. Values are inserted without escaping. The output time unit can be \
+                       changed using the --time-unit option.\n\n  \
+                       Example:  hyperfine --export-template report.hbs report.txt 'sleep 0.1'"),
+        )
+        .arg(
+            Arg::new("export-timeline")
+                .long("export-timeline")
+                .action(ArgAction::Set)
+                .value_name("FILE")
+                .help("Export a standalone HTML page to the given FILE that shows all runs of all \
+                       commands on a shared timeline. Warmup runs and the runs of the --prepare \
+                       command are shown in different colors. This helps to spot interleaving, \
+                       drift and the effect of warmup runs. The time unit of the axis can be \
+                       changed using the --time-unit option."),
+        )
+        .arg(
+            Arg::new("export-tsv")
+                .long("export-tsv")
+                .action(ArgAction::Set)
+                .value_name("FILE")
+                .help("Export the timing summary statistics as TSV (tab separated values) to the \
+                       given FILE. This is the same as the CSV export with a tab delimiter."),
+        )
+        .arg(
+            Arg::new("export-vega")
+                .long("export-vega")
+                .action(ArgAction::Set)
+                .value_name("FILE")
+                .help("Export a Vega-Lite specification with a box plot of the run times of each \
+                       command to the given FILE. The results are embedded in the specification, \
+                       so it can be rendered directly, e.g. with vega-embed. The output time unit \
+                       can be changed using the --time-unit option."),
+        )
+        .arg(
+            Arg::new("export-xml")
+                .long("export-xml")
+                .action(ArgAction::Set)
+                .value_name("FILE")
+                .help("Export the timing summary statistics and timings of individual runs as XML to the given FILE. \
+                       Each result contains the same fields as in the JSON export. The output time unit is always seconds"),
+        )
+        .arg(
+            Arg::new("export-yaml")
+                .long("export-yaml")
+                .action(ArgAction::Set)
+                .value_name("FILE")
+                .help("Export the timing summary statistics and timings of individual runs as YAML to the given FILE. \
+                       The output time unit is always seconds"),
+        )
         .arg(
             Arg::new("show-output")
                 .long("show-output")
@@ -308,6 +1246,16 @@ fn build_command() -> Command {
                        <FILE>:   Write the output to the given file.",
                 ),
         )
+        .arg(
+            Arg::new("time-to-first-output")
+                .long("time-to-first-output")
+                .action(ArgAction::SetTrue)
+                .conflicts_with_all(["show-output", "output"])
+                .help("Measure the time from spawning the command until its first output on \
+                       stdout, in addition to the total time. This is the startup latency that \
+                       users of interactive programs perceive. The output is fed through a \
+                       pipe (see '--output=pipe') and discarded."),
+        )
         .arg(
             Arg::new("input")
                 .long("input")
@@ -332,6 +1280,22 @@ fn build_command() -> Command {
                 .help("Give a meaningful name to a command. This can be specified multiple times \
                        if several commands are benchmarked."),
         )
+        .arg(
+            Arg::new("command-file")
+                .long("command-file")
+                .visible_alias("commands-from")
+                .action(ArgAction::Append)
+                .value_name("FILE")
+                .value_parser(read_command_file)
+                .help("Read the commands to benchmark from FILE, in addition to the commands on \
+                       the command line. Every line contains one command, which can be preceded \
+                       by its name as in 'NAME: COMMAND'. A line that starts with ': ' is a \
+                       command without a name, which can itself contain ': '. Empty lines and \
+                       lines that start with '#' are ignored. The names are used for commands \
+                       without a '--command-name'. With '-', the commands are read from the \
+                       standard input, e.g. from a script that generates them. This can be \
+                       specified multiple times."),
+        )
         // This option is hidden for now, as it is not yet clear yet if we want to 'stabilize' this,
         // see discussion in https://github.com/sharkdp/hyperfine/issues/527
         .arg(
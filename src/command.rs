@@ -1,5 +1,7 @@
 use std::collections::BTreeMap;
 use std::fmt;
+use std::fs;
+use std::path::Path;
 use std::str::FromStr;
 
 use crate::parameter::tokenize::tokenize;
@@ -17,6 +19,26 @@ use clap::{parser::ValuesRef, ArgMatches};
 use anyhow::{bail, Context, Result};
 use rust_decimal::Decimal;
 
+/// Name of the parameter that distinguishes the cold-cache and the warm-cache benchmark of a
+/// command (`--cold-prepare`)
+pub const CACHE_PARAMETER: &str = "cache";
+
+/// Whether a command is benchmarked with cold or with warm caches (`--cold-prepare`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheState {
+    Cold,
+    Warm,
+}
+
+impl CacheState {
+    fn name(self) -> &'static str {
+        match self {
+            CacheState::Cold => "cold",
+            CacheState::Warm => "warm",
+        }
+    }
+}
+
 /// A command that should be benchmarked.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Command<'a> {
@@ -28,6 +50,10 @@ pub struct Command<'a> {
 
     /// Zero or more parameter values.
     parameters: Vec<ParameterNameAndValue<'a>>,
+
+    /// The directory in which the command is executed (without parameter substitution), if
+    /// it is not the current directory
+    working_directory: Option<&'a str>,
 }
 
 impl<'a> Command<'a> {
@@ -36,6 +62,7 @@ impl<'a> Command<'a> {
             name,
             expression,
             parameters: Vec::new(),
+            working_directory: None,
         }
     }
 
@@ -48,6 +75,7 @@ impl<'a> Command<'a> {
             name,
             expression,
             parameters: parameters.into_iter().collect(),
+            working_directory: None,
         }
     }
 
@@ -58,10 +86,15 @@ impl<'a> Command<'a> {
         )
     }
 
+    /// The command name (or command line) without parameter substitution
+    pub fn get_template(&self) -> &'a str {
+        self.name.unwrap_or(self.expression)
+    }
+
     pub fn get_name_with_unused_parameters(&self) -> String {
         let parameters = self
             .get_unused_parameters()
-            .map(|(parameter, value)| format!("{} = {}, ", parameter, value.to_string()))
+            .map(|(parameter, value)| format!("{} = {}, ", parameter, value))
             .collect::<String>();
         let parameters = parameters.trim_end_matches(", ");
         let parameters = if parameters.is_empty() {
@@ -86,6 +119,9 @@ impl<'a> Command<'a> {
         if let Some(program_name) = tokens.next() {
             let mut command_builder = std::process::Command::new(program_name);
             command_builder.args(tokens);
+            if let Some(directory) = self.get_working_directory() {
+                command_builder.current_dir(directory);
+            }
             Ok(command_builder)
         } else {
             bail!("Can not execute empty command")
@@ -96,13 +132,59 @@ impl<'a> Command<'a> {
         &self.parameters
     }
 
+    /// The directory in which the command is executed (`--working-directory`), with the
+    /// parameters substituted
+    pub fn get_working_directory(&self) -> Option<String> {
+        self.working_directory
+            .map(|directory| self.replace_parameters_in(directory))
+    }
+
+    /// The directory in which the command is executed, without parameter substitution. The
+    /// intermediate commands of a benchmark (e.g. `--prepare`) are executed there as well.
+    pub fn get_working_directory_template(&self) -> Option<&'a str> {
+        self.working_directory
+    }
+
+    /// The same command, executed in the given directory
+    pub fn in_working_directory(self, working_directory: Option<&'a str>) -> Command<'a> {
+        Command {
+            working_directory,
+            ..self
+        }
+    }
+
+    /// The same command with an additional parameter
+    fn with_parameter(&self, name: &'a str, value: ParameterValue) -> Command<'a> {
+        let mut command = self.clone();
+        command.parameters.push((name, value));
+        command
+    }
+
+    /// Whether this command is benchmarked with cold or with warm caches, if the benchmark
+    /// has been split by `--cold-prepare`
+    pub fn get_cache_state(&self) -> Option<CacheState> {
+        self.parameters
+            .iter()
+            .rev()
+            .find(|(name, _)| *name == CACHE_PARAMETER)
+            .and_then(|(_, value)| match value {
+                ParameterValue::Text(state) if state == CacheState::Cold.name() => {
+                    Some(CacheState::Cold)
+                }
+                ParameterValue::Text(state) if state == CacheState::Warm.name() => {
+                    Some(CacheState::Warm)
+                }
+                _ => None,
+            })
+    }
+
     pub fn get_unused_parameters(&self) -> impl Iterator<Item = &(&'a str, ParameterValue)> {
         self.parameters
             .iter()
             .filter(move |(parameter, _)| !self.expression.contains(&format!("{{{}}}", parameter)))
     }
 
-    fn replace_parameters_in(&self, original: &str) -> String {
+    pub fn replace_parameters_in(&self, original: &str) -> String {
         let mut result = String::new();
         let mut replacements = BTreeMap::<String, String>::new();
         for (param_name, param_value) in &self.parameters {
@@ -132,16 +214,145 @@ impl<'a> Command<'a> {
     }
 }
 
+/// Parse the content of a command file (`--command-file`) into the names and the commands.
+/// Every line contains a command, optionally preceded by its name as in 'NAME: COMMAND'. A
+/// line that starts with ': ' is a command without a name, which can itself contain ': '.
+/// Empty lines and lines that start with '#' are ignored.
+fn parse_command_file(content: &str) -> Vec<(Option<&str>, &str)> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| match line.split_once(": ") {
+            Some((name, command)) => {
+                let name = name.trim();
+                ((!name.is_empty()).then_some(name), command.trim())
+            }
+            None => (None, line),
+        })
+        .collect()
+}
+
+/// Parse the content of a parameter file (`--parameter-file`), which contains one value per
+/// line. The values are used as they are, including commas and spaces. Empty lines are
+/// ignored.
+fn parse_parameter_file(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(String::from)
+        .collect()
+}
+
 /// A collection of commands that should be benchmarked
 pub struct Commands<'a>(Vec<Command<'a>>);
 
 impl<'a> Commands<'a> {
-    pub fn from_cli_arguments(matches: &'a ArgMatches) -> Result<Commands> {
+    pub fn from_cli_arguments(matches: &'a ArgMatches) -> Result<Commands<'a>> {
+        let mut commands = Self::from_command_arguments(matches)?;
+
+        // Every command is benchmarked with cold caches first, and then with warm caches
+        if matches.get_one::<String>("cold-prepare").is_some() {
+            commands.0 = commands
+                .0
+                .iter()
+                .flat_map(|cmd| {
+                    [CacheState::Cold, CacheState::Warm].map(|state| {
+                        cmd.with_parameter(
+                            CACHE_PARAMETER,
+                            ParameterValue::Text(state.name().into()),
+                        )
+                    })
+                })
+                .collect();
+        }
+
+        // The reference command is always benchmarked first
+        if let Some(reference) = matches.get_one::<String>("reference") {
+            commands.0.insert(0, Command::new(None, reference));
+        }
+
+        if let Some(directories) = matches.get_many::<String>("working-directory") {
+            // A single working directory is used for all commands, including the reference
+            if directories.len() == 1 {
+                let directory = directories.map(|d| d.as_str()).next();
+                for command in commands.0.iter_mut() {
+                    *command = command.clone().in_working_directory(directory);
+                }
+            }
+            for command in &commands.0 {
+                if let Some(directory) = command.get_working_directory() {
+                    if !Path::new(&directory).is_dir() {
+                        bail!(
+                            "The working directory '{}' of the command '{}' does not exist",
+                            directory,
+                            command.get_name()
+                        );
+                    }
+                }
+            }
+        }
+
+        Ok(commands)
+    }
+
+    fn from_command_arguments(matches: &'a ArgMatches) -> Result<Commands<'a>> {
+        let mut file_commands = vec![];
+        for (path, content) in matches
+            .get_many::<(String, String)>("command-file")
+            .unwrap_or_default()
+        {
+            let commands = parse_command_file(content);
+            if commands.is_empty() {
+                bail!("The command file '{}' does not contain any commands", path);
+            }
+            file_commands.extend(commands);
+        }
+
+        // The names from the command files are used for commands without a '--command-name'.
+        // Every command string is used once for each combination of the parameters, which
+        // are varied in the outer loops.
+        let mut commands = Self::from_command_strings(matches, &file_commands)?;
+        let default_names = matches
+            .get_many::<String>("command")
+            .unwrap_or_default()
+            .map(|_| None)
+            .chain(file_commands.iter().map(|(name, _)| *name))
+            .collect::<Vec<_>>();
+        if !default_names.is_empty() {
+            for (command, default_name) in commands.0.iter_mut().zip(default_names.iter().cycle()) {
+                command.name = command.name.or(*default_name);
+            }
+        }
+
+        // Given N times, the working directories belong to the N user-given commands
+        if let Some(directories) = matches.get_many::<String>("working-directory") {
+            let directories: Vec<_> = directories.map(|d| d.as_str()).collect();
+            if directories.len() > 1 {
+                if directories.len() != default_names.len() {
+                    bail!(
+                        "The '--working-directory' option has to be provided just once or N times, \
+                         where N is the number of benchmark commands."
+                    );
+                }
+                for (command, directory) in commands.0.iter_mut().zip(directories.iter().cycle()) {
+                    *command = command.clone().in_working_directory(Some(directory));
+                }
+            }
+        }
+        Ok(commands)
+    }
+
+    fn from_command_strings(
+        matches: &'a ArgMatches,
+        file_commands: &[(Option<&'a str>, &'a str)],
+    ) -> Result<Commands<'a>> {
         let command_names = matches.get_many::<String>("command-name");
         let command_strings = matches
             .get_many::<String>("command")
             .unwrap_or_default()
             .map(|v| v.as_str())
+            .chain(file_commands.iter().map(|(_, command)| *command))
             .collect::<Vec<_>>();
 
         if let Some(args) = matches.get_many::<String>("parameter-scan") {
@@ -154,12 +365,16 @@ impl<'a> Commands<'a> {
                 args,
                 step_size,
             )?))
-        } else if let Some(args) = matches.get_many::<String>("parameter-list") {
+        } else if matches.contains_id("parameter-list") || matches.contains_id("parameter-file") {
             let command_names = command_names.map_or(vec![], |names| {
                 names.map(|v| v.as_str()).collect::<Vec<_>>()
             });
-            let args: Vec<_> = args.map(|v| v.as_str()).collect::<Vec<_>>();
-            let param_names_and_values: Vec<(&str, Vec<String>)> = args
+            let args: Vec<_> = matches
+                .get_many::<String>("parameter-list")
+                .unwrap_or_default()
+                .map(|v| v.as_str())
+                .collect::<Vec<_>>();
+            let mut param_names_and_values: Vec<(&str, Vec<String>)> = args
                 .chunks_exact(2)
                 .map(|pair| {
                     let name = pair[0];
@@ -167,6 +382,23 @@ impl<'a> Commands<'a> {
                     (name, tokenize(list_str))
                 })
                 .collect();
+
+            let file_args: Vec<_> = matches
+                .get_many::<String>("parameter-file")
+                .unwrap_or_default()
+                .map(|v| v.as_str())
+                .collect::<Vec<_>>();
+            for pair in file_args.chunks_exact(2) {
+                let name = pair[0];
+                let path = pair[1];
+                let content = fs::read_to_string(path)
+                    .with_context(|| format!("Could not read the parameter file '{}'", path))?;
+                let values = parse_parameter_file(&content);
+                if values.is_empty() {
+                    bail!("The parameter file '{}' does not contain any values", path);
+                }
+                param_names_and_values.push((name, values));
+            }
             {
                 let duplicates =
                     Self::find_duplicates(param_names_and_values.iter().map(|(name, _)| *name));
@@ -175,6 +407,18 @@ impl<'a> Commands<'a> {
                 }
             }
 
+            if matches
+                .get_one::<String>("parameter-mode")
+                .map(|m| m.as_str())
+                == Some("zip")
+            {
+                return Ok(Self(Self::build_zipped_parameter_list_commands(
+                    command_names,
+                    command_strings,
+                    param_names_and_values,
+                )?));
+            }
+
             let dimensions: Vec<usize> = std::iter::once(command_strings.len())
                 .chain(
                     param_names_and_values
@@ -270,6 +514,58 @@ impl<'a> Commands<'a> {
             .collect()
     }
 
+    /// Benchmarks for `--parameter-mode zip`, where the n-th values of all parameter lists
+    /// are used together
+    fn build_zipped_parameter_list_commands<'b>(
+        command_names: Vec<&'b str>,
+        command_strings: Vec<&'b str>,
+        param_names_and_values: Vec<(&'b str, Vec<String>)>,
+    ) -> Result<Vec<Command<'b>>> {
+        let value_count = param_names_and_values[0].1.len();
+        if let Some((name, values)) = param_names_and_values
+            .iter()
+            .find(|(_, values)| values.len() != value_count)
+        {
+            bail!(
+                "With '--parameter-mode zip', all parameter lists need to have the same length, \
+                 but '{}' has {} values and '{}' has {}",
+                param_names_and_values[0].0,
+                value_count,
+                name,
+                values.len()
+            );
+        }
+
+        // `--command-name` should appear exactly once or exactly B times,
+        // where B is the total number of benchmarks.
+        let param_space_size = value_count * command_strings.len();
+        let command_name_count = command_names.len();
+        if command_name_count > 1 && command_name_count != param_space_size {
+            return Err(OptionsError::UnexpectedCommandNameCount(
+                command_name_count,
+                param_space_size,
+            )
+            .into());
+        }
+
+        let mut i = 0;
+        let mut commands = Vec::with_capacity(param_space_size);
+        for value_index in 0..value_count {
+            for cmd in &command_strings {
+                let name = command_names
+                    .get(i)
+                    .or_else(|| command_names.first())
+                    .copied();
+                let parameters = param_names_and_values.iter().map(|(name, values)| {
+                    (*name, ParameterValue::Text(values[value_index].clone()))
+                });
+                commands.push(Command::new_parametrized(name, cmd, parameters));
+                i += 1;
+            }
+        }
+        Ok(commands)
+    }
+
     fn build_parameter_scan_commands<'b, T: Numeric>(
         param_name: &'b str,
         param_min: T,
@@ -278,7 +574,20 @@ impl<'a> Commands<'a> {
         command_names: Vec<&'b str>,
         command_strings: Vec<&'b str>,
     ) -> Result<Vec<Command<'b>>, ParameterScanError> {
-        let param_range = RangeStep::new(param_min, param_max, step)?;
+        Self::build_parameter_range_commands(
+            param_name,
+            RangeStep::new(param_min, param_max, step)?,
+            command_names,
+            command_strings,
+        )
+    }
+
+    fn build_parameter_range_commands<'b, T: Numeric>(
+        param_name: &'b str,
+        param_range: RangeStep<T>,
+        command_names: Vec<&'b str>,
+        command_strings: Vec<&'b str>,
+    ) -> Result<Vec<Command<'b>>, ParameterScanError> {
         let param_count = param_range.size_hint().1.unwrap();
         let command_name_count = command_names.len();
 
@@ -319,11 +628,37 @@ impl<'a> Commands<'a> {
         let command_names = command_names.map_or(vec![], |names| {
             names.map(|v| v.as_str()).collect::<Vec<_>>()
         });
-        let command_strings = command_strings;
         let param_name = vals.next().unwrap().as_str();
         let param_min = vals.next().unwrap().as_str();
         let param_max = vals.next().unwrap().as_str();
 
+        // A step like '*2' multiplies the values by the factor
+        if let Some(factor) = step.and_then(|step| step.strip_prefix('*')) {
+            if let (Ok(param_min), Ok(param_max), Ok(factor)) = (
+                param_min.parse::<i32>(),
+                param_max.parse::<i32>(),
+                factor.parse::<i32>(),
+            ) {
+                return Self::build_parameter_range_commands(
+                    param_name,
+                    RangeStep::new_geometric(param_min, param_max, factor)?,
+                    command_names,
+                    command_strings,
+                );
+            }
+
+            return Self::build_parameter_range_commands(
+                param_name,
+                RangeStep::new_geometric(
+                    Decimal::from_str(param_min)?,
+                    Decimal::from_str(param_max)?,
+                    Decimal::from_str(factor)?,
+                )?,
+                command_names,
+                command_strings,
+            );
+        }
+
         // attempt to parse as integers
         if let (Ok(param_min), Ok(param_max), Ok(step)) = (
             param_min.parse::<i32>(),
@@ -430,6 +765,37 @@ fn test_build_commands_cross_product() {
     assert_eq!(result, expected);
 }
 
+#[test]
+fn test_build_cold_and_warm_commands() {
+    use crate::cli::get_cli_arguments;
+
+    let matches = get_cli_arguments(vec![
+        "hyperfine",
+        "--cold-prepare",
+        "sync",
+        "echo a",
+        "echo b",
+    ]);
+    let result = Commands::from_cli_arguments(&matches).unwrap().0;
+
+    let names: Vec<_> = result
+        .iter()
+        .map(|cmd| cmd.get_name_with_unused_parameters())
+        .collect();
+    assert_eq!(
+        vec![
+            "echo a (cache = cold)",
+            "echo a (cache = warm)",
+            "echo b (cache = cold)",
+            "echo b (cache = warm)"
+        ],
+        names
+    );
+    assert_eq!(Some(CacheState::Cold), result[0].get_cache_state());
+    assert_eq!(Some(CacheState::Warm), result[1].get_cache_state());
+    assert_eq!(None, Command::new(None, "echo a").get_cache_state());
+}
+
 #[test]
 fn test_build_parameter_list_commands() {
     use crate::cli::get_cli_arguments;
@@ -451,6 +817,46 @@ fn test_build_parameter_list_commands() {
     assert_eq!(commands[1].get_command_line(), "echo 2");
 }
 
+#[test]
+fn test_build_zipped_parameter_list_commands() {
+    use crate::cli::get_cli_arguments;
+
+    let matches = get_cli_arguments(vec![
+        "hyperfine",
+        "check {file} {size}",
+        "--parameter-list",
+        "file",
+        "a.txt,b.txt,c.txt",
+        "--parameter-list",
+        "size",
+        "1k,2M,3G",
+        "--parameter-mode",
+        "zip",
+    ]);
+    let commands = Commands::from_cli_arguments(&matches).unwrap().0;
+    assert_eq!(
+        commands
+            .iter()
+            .map(|c| c.get_command_line())
+            .collect::<Vec<_>>(),
+        vec!["check a.txt 1k", "check b.txt 2M", "check c.txt 3G"]
+    );
+
+    let matches = get_cli_arguments(vec![
+        "hyperfine",
+        "check {file} {size}",
+        "--parameter-list",
+        "file",
+        "a.txt,b.txt",
+        "--parameter-list",
+        "size",
+        "1k",
+        "--parameter-mode",
+        "zip",
+    ]);
+    assert!(Commands::from_cli_arguments(&matches).is_err());
+}
+
 #[test]
 fn test_build_parameter_scan_commands() {
     use crate::cli::get_cli_arguments;
@@ -474,6 +880,36 @@ fn test_build_parameter_scan_commands() {
     assert_eq!(commands[1].get_command_line(), "echo 2");
 }
 
+#[test]
+fn test_build_geometric_parameter_scan_commands() {
+    use crate::cli::get_cli_arguments;
+    let matches = get_cli_arguments(vec![
+        "hyperfine",
+        "head -c {size} /dev/zero",
+        "--parameter-scan",
+        "size",
+        "1",
+        "1024",
+        "--parameter-step-size",
+        "*4",
+    ]);
+    let commands = Commands::from_cli_arguments(&matches).unwrap().0;
+    assert_eq!(
+        commands
+            .iter()
+            .map(|c| c.get_command_line())
+            .collect::<Vec<_>>(),
+        vec![
+            "head -c 1 /dev/zero",
+            "head -c 4 /dev/zero",
+            "head -c 16 /dev/zero",
+            "head -c 64 /dev/zero",
+            "head -c 256 /dev/zero",
+            "head -c 1024 /dev/zero",
+        ]
+    );
+}
+
 #[test]
 fn test_parameter_scan_commands_int() {
     let commands = Commands::build_parameter_scan_commands(
@@ -529,6 +965,36 @@ fn test_parameter_scan_commands_names() {
     assert_eq!(command_names, vec!["name-1", "name-2", "name-3"]);
 }
 
+#[test]
+fn test_parse_command_file() {
+    let content = "\
+        # Comments and empty lines are ignored\n\
+        \n\
+        grep: grep -r TODO src\n\
+        \x20 ripgrep :  rg TODO src \n\
+        : echo 'no name: just output'\n\
+        sleep 0.1\n";
+    assert_eq!(
+        parse_command_file(content),
+        vec![
+            (Some("grep"), "grep -r TODO src"),
+            (Some("ripgrep"), "rg TODO src"),
+            (None, "echo 'no name: just output'"),
+            (None, "sleep 0.1"),
+        ]
+    );
+    assert!(parse_command_file("# nothing\n\n").is_empty());
+}
+
+#[test]
+fn test_parse_parameter_file() {
+    assert_eq!(
+        parse_parameter_file("a, b\n\n  spaces  \r\nlast"),
+        vec!["a, b", "  spaces  ", "last"]
+    );
+    assert!(parse_parameter_file("\n").is_empty());
+}
+
 #[test]
 fn test_get_specified_command_names() {
     let commands = Commands::build_parameter_scan_commands(
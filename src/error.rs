@@ -15,6 +15,10 @@ pub enum ParameterScanError {
     TooLarge,
     #[error("Zero is not a valid parameter step")]
     ZeroStep,
+    #[error("A geometric parameter range has to start at a positive value")]
+    NonPositiveGeometricStart,
+    #[error("The factor of a geometric parameter step has to be larger than 1")]
+    InvalidFactor,
     #[error("A step size is required when the range bounds are floating point numbers. The step size can be specified with the '-D/--parameter-step-size <DELTA>' parameter")]
     StepRequired,
     #[error("'--command-name' has been specified {0} times. It has to appear exactly once, or exactly {1} times (number of benchmarks)")]
@@ -39,6 +43,10 @@ pub enum OptionsError<'a> {
         "Conflicting requirements for the number of runs (empty range, min is larger than max)"
     )]
     EmptyRunsRange,
+    #[error("The argument to '--batch' has to be at least 1")]
+    InvalidBatchSize,
+    #[error("'--batch' runs the command in a loop of a POSIX shell, so it can not be used with '--shell=none' or with 'cmd.exe'")]
+    BatchRequiresShell,
     #[error("Too many --command-name options: Expected {0} at most")]
     TooManyCommandNames(usize),
     #[error("'--command-name' has been specified {0} times. It has to appear exactly once, or exactly {1} times (number of benchmarks)")]
@@ -55,4 +63,74 @@ pub enum OptionsError<'a> {
     UnknownOutputPolicy(String),
     #[error("The file '{0}' specified as '--input' does not exist")]
     StdinDataFileDoesNotExist(String),
+    #[error("The CSV delimiter has to be a single ASCII character (or '\\t' for tabs), got '{0}'")]
+    InvalidCsvDelimiter(String),
+    #[error("Percentiles have to be numbers between 0 and 100, got '{0}'")]
+    InvalidPercentile(String),
+    #[error("The confidence level has to be a number between 0 and 1, got '{0}'")]
+    InvalidConfidenceLevel(String),
+    #[error("The significance level has to be a number between 0 and 1, got '{0}'")]
+    InvalidSignificanceLevel(String),
+    #[error("The outlier threshold has to be a positive number (and smaller than 1 for 'grubbs'), got '{0}'")]
+    InvalidOutlierThreshold(String),
+    #[error("The argument to '--{0}' has to be a positive percentage, got '{1}'")]
+    InvalidPercentage(&'a str, String),
+    #[error("The argument to '--trim' has to be a percentage between 0 and 50, got '{0}'")]
+    InvalidTrim(String),
+    #[error(
+        "The argument to '--throughput' has to be of the form 'bytes=AMOUNT' or 'items=AMOUNT' \
+         with a positive AMOUNT, got '{0}'"
+    )]
+    InvalidThroughput(String),
+    #[error("The argument to '--max-time' has to be a positive number of seconds, got '{0}'")]
+    InvalidMaxTime(f64),
+    #[error(
+        "Hardware performance counters ('--perf-counters') are only supported on Linux and macOS"
+    )]
+    PerfCountersNotSupported,
+    #[error(
+        "The performance counter '{0}' is not supported on this platform. Only 'cycles' and \
+         'instructions' are available on macOS."
+    )]
+    PerfCounterNotSupported(&'static str),
+    #[error("Energy measurements ('--measure-energy') are only supported on Linux")]
+    EnergyMeasurementNotSupported,
+    #[error("Sampling the CPU frequency ('--cpu-frequency') is only supported on Linux")]
+    CpuFrequencyNotSupported,
+    #[error("Sampling the GPU utilization ('--gpu') is only supported on Linux")]
+    GpuSamplingNotSupported,
+    #[error("The argument to '--limit-memory' has to be a positive size in bytes, with an optional suffix K, M, G or T, got '{0}'")]
+    InvalidMemoryLimit(String),
+    #[error("The argument to '--limit-cpus' has to be a positive number of CPUs, got '{0}'")]
+    InvalidCpuLimit(String),
+    #[error("Running commands in cgroups ('--cgroup', '--limit-memory', '--limit-cpus') is only supported on Linux")]
+    CgroupNotSupported,
+    #[error("'--aggregate-children' is only supported on Linux and Windows")]
+    AggregateChildrenNotSupported,
+    #[error("Environment variables have to be of the form 'KEY=VALUE', got '{0}'")]
+    InvalidEnvironmentVariable(String),
+    #[error("Could not read the environment file '{0}': {1}")]
+    EnvironmentFileUnreadable(String, std::io::Error),
+    #[error("Invalid list of CPUs '{0}'. Expected a comma-separated list of CPU numbers or ranges, e.g. '0,2-3'")]
+    InvalidCpuList(String),
+    #[error("Pinning processes to CPUs ('--cpu', '--hyperfine-cpu') is only supported on Linux and Windows")]
+    CpuAffinityNotSupported,
+    #[error("The argument to '--priority' has to be a nice level between -20 and 19, got '{0}'")]
+    InvalidPriority(i32),
+    #[error(
+        "Disabling address space layout randomization ('--no-aslr') is only supported on Linux"
+    )]
+    AslrNotSupported,
+    #[error("Isolating the benchmarks from the network ('--isolate-network') is only supported on Linux")]
+    NetworkIsolationNotSupported,
+    #[error(
+        "Profiling the benchmarked commands ('--profile') is only supported on Linux and macOS"
+    )]
+    ProfilingNotSupported,
+    #[error("The clock 'monotonic-raw' ('--clock') is only supported on Linux and macOS")]
+    MonotonicRawClockNotSupported,
+    #[error("Dropping the file system caches ('--drop-caches') is only supported on Linux, macOS and Windows")]
+    DropCachesNotSupported,
+    #[error("Writing the file system caches to the disks ('--sync-before-run') is only supported on Unix and Windows")]
+    SyncNotSupported,
 }
@@ -1,10 +1,22 @@
-use super::markup::Alignment;
+use super::markup::{Alignment, Columns};
 use crate::export::markup::MarkupExporter;
 
 #[derive(Default)]
-pub struct AsciidocExporter {}
+pub struct AsciidocExporter {
+    columns: Columns,
+}
+
+impl AsciidocExporter {
+    pub fn new(columns: Columns) -> Self {
+        AsciidocExporter { columns }
+    }
+}
 
 impl MarkupExporter for AsciidocExporter {
+    fn columns(&self) -> &Columns {
+        &self.columns
+    }
+
     fn table_header(&self, cell_aligmnents: &[Alignment]) -> String {
         format!(
             "[cols=\"{}\"]\n|===",
@@ -113,6 +125,7 @@ fn test_asciidoc_format_s() {
                 params.insert("bar".into(), "2".into());
                 params
             },
+            ..Default::default()
         },
         BenchmarkResult {
             command: String::from("FOO=1 BAR=7 command | 2"),
@@ -132,6 +145,7 @@ fn test_asciidoc_format_s() {
                 params.insert("bar".into(), "7".into());
                 params
             },
+            ..Default::default()
         },
     ];
 
@@ -188,6 +202,7 @@ fn test_asciidoc_format_ms() {
                 params.insert("bar".into(), "7".into());
                 params
             },
+            ..Default::default()
         },
         BenchmarkResult {
             command: String::from("FOO=1 BAR=2 command | 1"),
@@ -207,6 +222,7 @@ fn test_asciidoc_format_ms() {
                 params.insert("bar".into(), "2".into());
                 params
             },
+            ..Default::default()
         },
     ];
 
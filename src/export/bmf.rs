@@ -0,0 +1,100 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+use serde_json::to_vec_pretty;
+
+use super::Exporter;
+use crate::benchmark::benchmark_result::BenchmarkResult;
+use crate::options::SortOrder;
+use crate::util::units::Unit;
+
+use anyhow::Result;
+
+/// The `latency` measure of the Bencher Metric Format is given in nanoseconds
+const NANOSECONDS: f64 = 1e9;
+
+#[derive(Serialize, Debug, PartialEq)]
+struct Metric {
+    value: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lower_value: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    upper_value: Option<f64>,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+struct Measures {
+    latency: Metric,
+}
+
+/// Exports the results in the Bencher Metric Format (BMF), which can be uploaded to
+/// continuous benchmarking services like bencher.dev. The mean time of each command is
+/// reported as `latency`, with bounds of one standard deviation.
+#[derive(Default)]
+pub struct BmfExporter {}
+
+impl Exporter for BmfExporter {
+    fn serialize(
+        &self,
+        results: &[BenchmarkResult],
+        _unit: Option<Unit>,
+        _sort_order: SortOrder,
+    ) -> Result<Vec<u8>> {
+        let benchmarks: BTreeMap<&str, Measures> = results
+            .iter()
+            .map(|result| {
+                let value = result.mean * NANOSECONDS;
+                let stddev = result.stddev.map(|s| s * NANOSECONDS);
+                let measures = Measures {
+                    latency: Metric {
+                        value,
+                        lower_value: stddev.map(|s| value - s),
+                        upper_value: stddev.map(|s| value + s),
+                    },
+                };
+                (result.command.as_str(), measures)
+            })
+            .collect();
+
+        let mut output = to_vec_pretty(&benchmarks)?;
+        output.push(b'\n');
+
+        Ok(output)
+    }
+}
+
+#[test]
+fn test_bmf() {
+    let exporter = BmfExporter::default();
+
+    let results = vec![
+        BenchmarkResult {
+            command: String::from("sleep 0.5"),
+            mean: 0.5,
+            stddev: Some(0.25),
+            ..Default::default()
+        },
+        BenchmarkResult {
+            command: String::from("sleep 1"),
+            mean: 1.0,
+            ..Default::default()
+        },
+    ];
+
+    let actual = exporter
+        .serialize(&results, None, SortOrder::Command)
+        .unwrap();
+    let actual: serde_json::Value = serde_json::from_slice(&actual).unwrap();
+
+    assert_eq!(
+        actual,
+        serde_json::json!({
+            "sleep 0.5": {
+                "latency": { "value": 5e8, "lower_value": 2.5e8, "upper_value": 7.5e8 }
+            },
+            "sleep 1": {
+                "latency": { "value": 1e9 }
+            },
+        })
+    );
+}
@@ -0,0 +1,244 @@
+use std::fs;
+use std::path::Path;
+
+use serde::Serialize;
+use serde_json::to_vec_pretty;
+
+use super::Exporter;
+use crate::benchmark::benchmark_result::BenchmarkResult;
+use crate::options::SortOrder;
+use crate::util::units::{Second, Unit};
+
+use anyhow::{bail, Context, Result};
+
+/// Criterion reports all times in nanoseconds
+const NANOSECONDS: f64 = 1e9;
+
+/// Name of the baseline that Criterion uses for the most recent measurement
+const BASELINE: &str = "new";
+
+#[derive(Serialize, Debug)]
+struct Benchmark<'a> {
+    group_id: &'a str,
+    function_id: Option<&'a str>,
+    value_str: Option<&'a str>,
+    throughput: Option<()>,
+    full_id: &'a str,
+    directory_name: &'a str,
+    title: &'a str,
+}
+
+#[derive(Serialize, Debug)]
+struct ConfidenceInterval {
+    confidence_level: f64,
+    lower_bound: f64,
+    upper_bound: f64,
+}
+
+#[derive(Serialize, Debug)]
+struct Estimate {
+    confidence_interval: ConfidenceInterval,
+    point_estimate: f64,
+    standard_error: f64,
+}
+
+impl Estimate {
+    /// Create an estimate with a 95% confidence interval, based on a normal distribution
+    fn new(point_estimate: Second, standard_error: Second) -> Self {
+        let point_estimate = point_estimate * NANOSECONDS;
+        let standard_error = standard_error * NANOSECONDS;
+        Estimate {
+            confidence_interval: ConfidenceInterval {
+                confidence_level: 0.95,
+                lower_bound: point_estimate - 1.96 * standard_error,
+                upper_bound: point_estimate + 1.96 * standard_error,
+            },
+            point_estimate,
+            standard_error,
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+struct Estimates {
+    mean: Estimate,
+    median: Estimate,
+    median_abs_dev: Estimate,
+    slope: Option<Estimate>,
+    std_dev: Estimate,
+}
+
+#[derive(Serialize, Debug)]
+struct Sample {
+    sampling_mode: &'static str,
+    iters: Vec<f64>,
+    times: Vec<f64>,
+}
+
+/// Criterion stores each benchmark in a directory whose name is derived from the
+/// benchmark ID, with characters that are problematic in paths replaced.
+fn directory_name(id: &str) -> String {
+    id.chars()
+        .map(|c| match c {
+            '?' | '"' | '/' | '\\' | '*' | '<' | '>' | ':' | '|' | '^' => '_',
+            c if c.is_whitespace() => '_',
+            c => c,
+        })
+        .collect()
+}
+
+fn median_abs_dev(times: &[Second], median: Second) -> Second {
+    let mut deviations: Vec<Second> = times.iter().map(|t| (t - median).abs()).collect();
+    if deviations.is_empty() {
+        return 0.0;
+    }
+    deviations.sort_by(|a, b| a.total_cmp(b));
+    // Scale factor for consistency with the standard deviation of a normal distribution,
+    // as used by Criterion
+    1.4826 * statistical::median(&deviations)
+}
+
+/// Writes the results into a directory with the layout that Criterion.rs uses
+/// (`<benchmark>/new/{benchmark,estimates,sample}.json`), such that tools like
+/// `critcmp` can be used to compare them.
+#[derive(Default)]
+pub struct CriterionExporter {}
+
+impl CriterionExporter {
+    fn write_benchmark(directory: &Path, result: &BenchmarkResult) -> Result<()> {
+        let name = directory_name(&result.command);
+        let directory = directory.join(&name).join(BASELINE);
+        fs::create_dir_all(&directory)
+            .with_context(|| format!("Could not create directory '{}'", directory.display()))?;
+
+        let benchmark = Benchmark {
+            group_id: &result.command,
+            function_id: None,
+            value_str: None,
+            throughput: None,
+            full_id: &result.command,
+            directory_name: &name,
+            title: &result.command,
+        };
+
+        let times = result.times.as_deref().unwrap_or_default();
+        let runs = times.len().max(1) as f64;
+        let stddev = result.stddev.unwrap_or(0.0);
+        let standard_error = stddev / runs.sqrt();
+        let mad = median_abs_dev(times, result.median);
+        let estimates = Estimates {
+            mean: Estimate::new(result.mean, standard_error),
+            // Asymptotic standard error of the median for normally distributed samples
+            median: Estimate::new(result.median, 1.2533 * standard_error),
+            median_abs_dev: Estimate::new(mad, 0.0),
+            slope: None,
+            std_dev: Estimate::new(stddev, 0.0),
+        };
+
+        let sample = Sample {
+            sampling_mode: "Flat",
+            iters: vec![1.0; times.len()],
+            times: times.iter().map(|t| t * NANOSECONDS).collect(),
+        };
+
+        let write = |filename: &str, content: Vec<u8>| {
+            let path = directory.join(filename);
+            fs::write(&path, content)
+                .with_context(|| format!("Failed to export results to '{}'", path.display()))
+        };
+        write("benchmark.json", to_vec_pretty(&benchmark)?)?;
+        write("estimates.json", to_vec_pretty(&estimates)?)?;
+        write("sample.json", to_vec_pretty(&sample)?)?;
+
+        Ok(())
+    }
+}
+
+impl Exporter for CriterionExporter {
+    fn serialize(
+        &self,
+        _results: &[BenchmarkResult],
+        _unit: Option<Unit>,
+        _sort_order: SortOrder,
+    ) -> Result<Vec<u8>> {
+        bail!("The Criterion export can only be written to a directory")
+    }
+
+    fn create_file(&mut self, filename: &str) -> Result<()> {
+        fs::create_dir_all(filename)
+            .with_context(|| format!("Could not create export directory '{}'", filename))
+    }
+
+    fn write_to_file(
+        &self,
+        filename: &str,
+        results: &[BenchmarkResult],
+        _unit: Option<Unit>,
+        _sort_order: SortOrder,
+    ) -> Result<()> {
+        for result in results {
+            Self::write_benchmark(Path::new(filename), result)?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_directory_name() {
+    assert_eq!(directory_name("sleep 0.1"), "sleep_0.1");
+    assert_eq!(directory_name("cat a/b | wc -l"), "cat_a_b___wc_-l");
+}
+
+#[test]
+fn test_criterion_layout() {
+    let tempdir = tempfile::tempdir().unwrap();
+    let directory = tempdir.path().join("criterion");
+    let directory = directory.to_str().unwrap();
+
+    let results = vec![BenchmarkResult {
+        command: String::from("sleep 0.1"),
+        command_with_unused_parameters: String::from("sleep 0.1"),
+        mean: 0.1,
+        stddev: Some(0.01),
+        median: 0.1,
+        user: 0.0,
+        system: 0.0,
+        min: 0.09,
+        max: 0.11,
+        times: Some(vec![0.09, 0.1, 0.11]),
+        exit_codes: vec![Some(0), Some(0), Some(0)],
+        ..Default::default()
+    }];
+
+    let mut exporter = CriterionExporter::default();
+    exporter.create_file(directory).unwrap();
+    exporter
+        .write_to_file(directory, &results, None, SortOrder::Command)
+        .unwrap();
+
+    let read = |filename: &str| -> serde_json::Value {
+        let path = Path::new(directory)
+            .join("sleep_0.1")
+            .join("new")
+            .join(filename);
+        serde_json::from_slice(&fs::read(path).unwrap()).unwrap()
+    };
+
+    let benchmark = read("benchmark.json");
+    assert_eq!(benchmark["full_id"], "sleep 0.1");
+    assert_eq!(benchmark["directory_name"], "sleep_0.1");
+
+    let estimates = read("estimates.json");
+    let mean = estimates["mean"]["point_estimate"].as_f64().unwrap();
+    assert!((mean - 1e8).abs() < 1e-3);
+    assert!(estimates["slope"].is_null());
+
+    let median_abs_dev = estimates["median_abs_dev"]["point_estimate"]
+        .as_f64()
+        .unwrap();
+    assert!((median_abs_dev - 1.4826e7).abs() < 1e-3);
+
+    let sample = read("sample.json");
+    assert_eq!(sample["iters"].as_array().unwrap().len(), 3);
+    assert_eq!(sample["sampling_mode"], "Flat");
+}
@@ -9,8 +9,21 @@ use crate::util::units::Unit;
 
 use anyhow::Result;
 
-#[derive(Default)]
-pub struct CsvExporter {}
+pub struct CsvExporter {
+    delimiter: u8,
+}
+
+impl CsvExporter {
+    pub fn new(delimiter: u8) -> Self {
+        CsvExporter { delimiter }
+    }
+}
+
+impl Default for CsvExporter {
+    fn default() -> Self {
+        CsvExporter::new(b',')
+    }
+}
 
 impl Exporter for CsvExporter {
     fn serialize(
@@ -19,17 +32,35 @@ impl Exporter for CsvExporter {
         _unit: Option<Unit>,
         _sort_order: SortOrder,
     ) -> Result<Vec<u8>> {
-        let mut writer = WriterBuilder::new().from_writer(vec![]);
+        let mut writer = WriterBuilder::new()
+            .delimiter(self.delimiter)
+            .from_writer(vec![]);
 
         {
             let mut headers: Vec<Cow<[u8]>> = [
                 // The list of times and exit codes cannot be exported to the CSV file - omit them.
-                "command", "mean", "stddev", "median", "user", "system", "min", "max",
+                // Only the start of the first run is included as a timestamp.
+                "command",
+                "mean",
+                "stddev",
+                "median",
+                "user",
+                "system",
+                "min",
+                "max",
+                "timestamp",
             ]
             .iter()
             .map(|x| Cow::Borrowed(x.as_bytes()))
             .collect();
             if let Some(res) = results.first() {
+                if res.mean_ci.is_some() {
+                    headers.push(Cow::Borrowed(b"mean_ci_lower"));
+                    headers.push(Cow::Borrowed(b"mean_ci_upper"));
+                }
+                for percentile in &res.percentiles {
+                    headers.push(Cow::Owned(percentile.name().into_bytes()));
+                }
                 for param_name in res.parameters.keys() {
                     headers.push(Cow::Owned(format!("parameter_{}", param_name).into_bytes()));
                 }
@@ -50,6 +81,19 @@ impl Exporter for CsvExporter {
             ] {
                 fields.push(Cow::Owned(f.to_string().into_bytes()))
             }
+            let timestamp = res.timestamps.as_ref().and_then(|t| t.first());
+            fields.push(Cow::Owned(
+                timestamp
+                    .map_or(String::new(), |t| t.to_string())
+                    .into_bytes(),
+            ));
+            if let Some(ci) = res.mean_ci {
+                fields.push(Cow::Owned(ci.lower.to_string().into_bytes()));
+                fields.push(Cow::Owned(ci.upper.to_string().into_bytes()));
+            }
+            for percentile in &res.percentiles {
+                fields.push(Cow::Owned(percentile.time.to_string().into_bytes()));
+            }
             for v in res.parameters.values() {
                 fields.push(Cow::Borrowed(v.as_bytes()))
             }
@@ -60,6 +104,52 @@ impl Exporter for CsvExporter {
     }
 }
 
+/// Exports the times of the individual runs with one column per command and one row
+/// per run. Commands with fewer runs have empty cells at the end of their column.
+pub struct WideCsvExporter {
+    delimiter: u8,
+}
+
+impl WideCsvExporter {
+    pub fn new(delimiter: u8) -> Self {
+        WideCsvExporter { delimiter }
+    }
+}
+
+impl Exporter for WideCsvExporter {
+    fn serialize(
+        &self,
+        results: &[BenchmarkResult],
+        _unit: Option<Unit>,
+        _sort_order: SortOrder,
+    ) -> Result<Vec<u8>> {
+        let mut writer = WriterBuilder::new()
+            .delimiter(self.delimiter)
+            .from_writer(vec![]);
+
+        let mut headers = vec!["run"];
+        headers.extend(results.iter().map(|res| res.command.as_str()));
+        writer.write_record(headers)?;
+
+        let times: Vec<&[f64]> = results
+            .iter()
+            .map(|res| res.times.as_deref().unwrap_or_default())
+            .collect();
+        let runs = times.iter().map(|t| t.len()).max().unwrap_or(0);
+        for run in 0..runs {
+            let mut fields = vec![run.to_string()];
+            fields.extend(
+                times
+                    .iter()
+                    .map(|t| t.get(run).map_or(String::new(), |time| time.to_string())),
+            );
+            writer.write_record(fields)?;
+        }
+
+        Ok(writer.into_inner()?)
+    }
+}
+
 #[test]
 fn test_csv() {
     use std::collections::BTreeMap;
@@ -77,6 +167,7 @@ fn test_csv() {
             min: 5.0,
             max: 6.0,
             times: Some(vec![7.0, 8.0, 9.0]),
+            timestamps: Some(vec![1700000000.5, 1700000008.0, 1700000016.0]),
             exit_codes: vec![Some(0), Some(0), Some(0)],
             parameters: {
                 let mut params = BTreeMap::new();
@@ -84,6 +175,7 @@ fn test_csv() {
                 params.insert("bar".into(), "two".into());
                 params
             },
+            ..Default::default()
         },
         BenchmarkResult {
             command: String::from("FOO=one BAR=seven command | 2"),
@@ -103,12 +195,13 @@ fn test_csv() {
                 params.insert("bar".into(), "seven".into());
                 params
             },
+            ..Default::default()
         },
     ];
     let exps: String = String::from(
-        "command,mean,stddev,median,user,system,min,max,parameter_bar,parameter_foo\n\
-        FOO=one BAR=two command | 1,1,2,1,3,4,5,6,two,one\n\
-        FOO=one BAR=seven command | 2,11,12,11,13,14,15,16.5,seven,one\n\
+        "command,mean,stddev,median,user,system,min,max,timestamp,parameter_bar,parameter_foo\n\
+        FOO=one BAR=two command | 1,1,2,1,3,4,5,6,1700000000.5,two,one\n\
+        FOO=one BAR=seven command | 2,11,12,11,13,14,15,16.5,,seven,one\n\
         ",
     );
     let gens = String::from_utf8(
@@ -120,3 +213,108 @@ fn test_csv() {
 
     assert_eq!(exps, gens);
 }
+
+#[test]
+fn test_csv_optional_columns() {
+    use crate::benchmark::benchmark_result::Percentile;
+    use crate::statistics::ConfidenceInterval;
+
+    let exporter = CsvExporter::default();
+
+    let results = vec![BenchmarkResult {
+        command: String::from("sleep 1"),
+        mean: 1.0,
+        mean_ci: Some(ConfidenceInterval {
+            lower: 0.5,
+            upper: 1.25,
+        }),
+        percentiles: vec![
+            Percentile {
+                percentile: 50.0,
+                time: 1.0,
+            },
+            Percentile {
+                percentile: 99.9,
+                time: 1.5,
+            },
+        ],
+        ..Default::default()
+    }];
+    let exps: String = String::from(
+        "command,mean,stddev,median,user,system,min,max,timestamp,mean_ci_lower,mean_ci_upper,p50,p99.9\n\
+        sleep 1,1,0,0,0,0,0,0,,0.5,1.25,1,1.5\n\
+        ",
+    );
+    let gens = String::from_utf8(
+        exporter
+            .serialize(&results, Some(Unit::Second), SortOrder::Command)
+            .unwrap(),
+    )
+    .unwrap();
+
+    assert_eq!(exps, gens);
+}
+
+#[test]
+fn test_csv_delimiter() {
+    let exporter = CsvExporter::new(b'\t');
+
+    let results = vec![BenchmarkResult {
+        command: String::from("sleep 1, 2"),
+        command_with_unused_parameters: String::from("sleep 1, 2"),
+        mean: 1.0,
+        stddev: Some(2.0),
+        median: 1.0,
+        user: 3.0,
+        system: 4.0,
+        min: 5.0,
+        max: 6.0,
+        times: Some(vec![7.0, 8.0, 9.0]),
+        exit_codes: vec![Some(0), Some(0), Some(0)],
+        ..Default::default()
+    }];
+    let exps: String = String::from(
+        "command\tmean\tstddev\tmedian\tuser\tsystem\tmin\tmax\ttimestamp\n\
+        sleep 1, 2\t1\t2\t1\t3\t4\t5\t6\t\n\
+        ",
+    );
+    let gens = String::from_utf8(
+        exporter
+            .serialize(&results, Some(Unit::Second), SortOrder::Command)
+            .unwrap(),
+    )
+    .unwrap();
+
+    assert_eq!(exps, gens);
+}
+
+#[test]
+fn test_csv_wide() {
+    let exporter = WideCsvExporter::new(b',');
+
+    let result = |command: &str, times: Vec<f64>| BenchmarkResult {
+        command: command.to_string(),
+        times: Some(times),
+        ..Default::default()
+    };
+    let results = vec![
+        result("sleep 0.1", vec![0.1, 0.11, 0.12]),
+        result("sleep 0.2, 0.3", vec![0.2, 0.21]),
+    ];
+
+    let exps = String::from(
+        "run,sleep 0.1,\"sleep 0.2, 0.3\"\n\
+        0,0.1,0.2\n\
+        1,0.11,0.21\n\
+        2,0.12,\n\
+        ",
+    );
+    let gens = String::from_utf8(
+        exporter
+            .serialize(&results, None, SortOrder::Command)
+            .unwrap(),
+    )
+    .unwrap();
+
+    assert_eq!(exps, gens);
+}
@@ -0,0 +1,250 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::markup::determine_unit_from_results;
+use super::Exporter;
+use crate::benchmark::benchmark_result::BenchmarkResult;
+use crate::benchmark::relative_speed;
+use crate::options::SortOrder;
+use crate::util::units::Unit;
+
+use anyhow::{bail, Context, Result};
+
+/// Number of bins that the range of all run times is split into for the histogram
+const HISTOGRAM_BINS: f64 = 20.0;
+
+/// Exports the run times to a `.dat` file, along with a gnuplot script (written to the
+/// given file) that plots a histogram and a box plot for each command. The plots are
+/// written to an SVG file next to the script when running `gnuplot <script>`.
+#[derive(Default)]
+pub struct GnuplotExporter {}
+
+fn data_path(filename: &str) -> PathBuf {
+    Path::new(filename).with_extension("dat")
+}
+
+fn output_path(filename: &str) -> PathBuf {
+    Path::new(filename).with_extension("svg")
+}
+
+/// Quote a string for use in a gnuplot script
+fn quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
+impl GnuplotExporter {
+    /// The run times of each command in a separate data block, such that they can be
+    /// selected with `index` in gnuplot.
+    fn data(results: &[&BenchmarkResult], unit: Unit) -> String {
+        results
+            .iter()
+            .map(|result| {
+                let mut block = format!("# {}\n", result.command);
+                for (run, &time) in result.times.iter().flatten().enumerate() {
+                    block.push_str(&format!("{} {}\n", run + 1, unit.convert(time)));
+                }
+                block
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+
+    fn script(results: &[&BenchmarkResult], unit: Unit, data: &Path, output: &Path) -> String {
+        let time_label = quote(&format!("Time [{}]", unit.short_name()));
+
+        let times = || results.iter().flat_map(|r| r.times.iter().flatten());
+        let min = times().cloned().fold(f64::INFINITY, f64::min);
+        let max = times().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let range = unit.convert(max - min);
+        let binwidth = if range > 0.0 {
+            range / HISTOGRAM_BINS
+        } else {
+            unit.convert(max).max(1.0) / HISTOGRAM_BINS
+        };
+
+        let mut script = String::new();
+        script.push_str("# Generated by hyperfine, run with 'gnuplot <this file>'\n");
+        script.push_str(&format!("datafile = {}\n", quote(&data.to_string_lossy())));
+        script.push_str("set terminal svg size 1200,500 dynamic\n");
+        script.push_str(&format!(
+            "set output {}\n",
+            quote(&output.to_string_lossy())
+        ));
+        script.push_str("set multiplot layout 1,2\n\n");
+
+        script.push_str("set title 'Histogram'\n");
+        script.push_str(&format!("set xlabel {}\n", time_label));
+        script.push_str("set ylabel 'Runs'\n");
+        script.push_str("set style fill transparent solid 0.5\n");
+        script.push_str(&format!("binwidth = {}\n", binwidth));
+        script.push_str("bin(x) = binwidth * (floor(x / binwidth) + 0.5)\n");
+        let histograms: Vec<String> = results
+            .iter()
+            .enumerate()
+            .map(|(i, result)| {
+                format!(
+                    "datafile index {} using (bin($2)):(1.0) smooth freq with boxes title {}",
+                    i,
+                    quote(&result.command)
+                )
+            })
+            .collect();
+        script.push_str(&format!("plot {}\n\n", histograms.join(", \\\n     ")));
+
+        script.push_str("set title 'Run times'\n");
+        script.push_str("set xlabel ''\n");
+        script.push_str(&format!("set ylabel {}\n", time_label));
+        let xtics: Vec<String> = results
+            .iter()
+            .enumerate()
+            .map(|(i, result)| format!("{} {}", quote(&result.command), i + 1))
+            .collect();
+        script.push_str(&format!("set xtics ({})\n", xtics.join(", ")));
+        script.push_str("unset key\n");
+        let boxplots: Vec<String> = (0..results.len())
+            .map(|i| format!("datafile index {} using ({}):2 with boxplot", i, i + 1))
+            .collect();
+        script.push_str(&format!("plot {}\n\n", boxplots.join(", \\\n     ")));
+
+        script.push_str("unset multiplot\n");
+        script
+    }
+}
+
+impl Exporter for GnuplotExporter {
+    fn serialize(
+        &self,
+        _results: &[BenchmarkResult],
+        _unit: Option<Unit>,
+        _sort_order: SortOrder,
+    ) -> Result<Vec<u8>> {
+        bail!("The gnuplot export can only be written to a file")
+    }
+
+    fn create_file(&mut self, filename: &str) -> Result<()> {
+        if data_path(filename) == Path::new(filename) {
+            bail!(
+                "The gnuplot script '{}' can not have the extension of the data file (.dat)",
+                filename
+            );
+        }
+
+        for path in [PathBuf::from(filename), data_path(filename)] {
+            fs::File::create(&path)
+                .with_context(|| format!("Could not create export file '{}'", path.display()))?;
+        }
+        Ok(())
+    }
+
+    fn write_to_file(
+        &self,
+        filename: &str,
+        results: &[BenchmarkResult],
+        unit: Option<Unit>,
+        sort_order: SortOrder,
+    ) -> Result<()> {
+        let unit = unit.unwrap_or_else(|| determine_unit_from_results(results));
+        let entries = relative_speed::compute(results, sort_order);
+        // Commands without any runs would lead to empty data blocks, which are skipped by gnuplot
+        let results: Vec<&BenchmarkResult> = entries
+            .iter()
+            .map(|e| e.result)
+            .filter(|r| r.times.as_ref().is_some_and(|t| !t.is_empty()))
+            .collect();
+
+        let data = data_path(filename);
+        let script = Self::script(&results, unit, &data, &output_path(filename));
+
+        fs::write(&data, Self::data(&results, unit))
+            .with_context(|| format!("Failed to export results to '{}'", data.display()))?;
+        fs::write(filename, script)
+            .with_context(|| format!("Failed to export results to '{}'", filename))
+    }
+}
+
+#[cfg(test)]
+fn cfg_test_results() -> Vec<BenchmarkResult> {
+    vec![
+        BenchmarkResult {
+            command: String::from("sleep 0.1"),
+            command_with_unused_parameters: String::from("sleep 0.1"),
+            mean: 0.1,
+            stddev: Some(0.01),
+            median: 0.1,
+            user: 0.0,
+            system: 0.0,
+            min: 0.09,
+            max: 0.11,
+            times: Some(vec![0.09, 0.11]),
+            exit_codes: vec![Some(0), Some(0)],
+            ..Default::default()
+        },
+        BenchmarkResult {
+            command: String::from("echo 'a'"),
+            command_with_unused_parameters: String::from("echo 'a'"),
+            mean: 0.05,
+            stddev: Some(0.0),
+            median: 0.05,
+            user: 0.0,
+            system: 0.0,
+            min: 0.05,
+            max: 0.05,
+            times: Some(vec![0.05]),
+            exit_codes: vec![Some(0)],
+            ..Default::default()
+        },
+    ]
+}
+
+#[test]
+fn test_gnuplot_data() {
+    let results = cfg_test_results();
+    let results: Vec<&BenchmarkResult> = results.iter().collect();
+
+    assert_eq!(
+        GnuplotExporter::data(&results, Unit::MilliSecond),
+        "# sleep 0.1\n1 90\n2 110\n\n\n# echo 'a'\n1 50\n"
+    );
+}
+
+#[test]
+fn test_gnuplot_script() {
+    let results = cfg_test_results();
+    let results: Vec<&BenchmarkResult> = results.iter().collect();
+
+    let script = GnuplotExporter::script(
+        &results,
+        Unit::MilliSecond,
+        Path::new("out.dat"),
+        Path::new("out.svg"),
+    );
+
+    assert!(script.contains("datafile = 'out.dat'\n"));
+    assert!(script.contains("set output 'out.svg'\n"));
+    assert!(script.contains("set xlabel 'Time [ms]'\n"));
+    assert!(script.contains("binwidth = 3\n"));
+    assert!(script.contains(
+        "datafile index 1 using (bin($2)):(1.0) smooth freq with boxes title 'echo ''a'''"
+    ));
+    assert!(script.contains("set xtics ('sleep 0.1' 1, 'echo ''a''' 2)\n"));
+    assert!(script.contains("datafile index 1 using (2):2 with boxplot\n"));
+}
+
+#[test]
+fn test_gnuplot_export_files() {
+    let tempdir = tempfile::tempdir().unwrap();
+    let script = tempdir.path().join("results.gp");
+    let script = script.to_str().unwrap();
+
+    let mut exporter = GnuplotExporter::default();
+    exporter.create_file(script).unwrap();
+    exporter
+        .write_to_file(script, &cfg_test_results(), None, SortOrder::Command)
+        .unwrap();
+
+    let data = fs::read_to_string(tempdir.path().join("results.dat")).unwrap();
+    assert!(data.starts_with("# sleep 0.1\n"));
+    assert!(fs::read_to_string(script).unwrap().contains("results.dat"));
+
+    assert!(exporter.create_file("results.dat").is_err());
+}
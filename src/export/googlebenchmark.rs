@@ -0,0 +1,190 @@
+use std::time::SystemTime;
+
+use serde::Serialize;
+use serde_json::to_vec_pretty;
+
+use super::markup::determine_unit_from_results;
+use super::Exporter;
+use crate::benchmark::benchmark_result::BenchmarkResult;
+use crate::options::SortOrder;
+use crate::util::date::format_rfc3339_utc;
+use crate::util::units::{Second, Unit};
+
+use anyhow::Result;
+
+#[derive(Serialize, Debug)]
+struct Context {
+    date: String,
+    executable: &'static str,
+    num_cpus: usize,
+}
+
+#[derive(Serialize, Debug)]
+struct Benchmark<'a> {
+    name: String,
+    run_name: &'a str,
+    run_type: &'static str,
+    repetitions: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    repetition_index: Option<usize>,
+    threads: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    aggregate_name: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    aggregate_unit: Option<&'static str>,
+    iterations: usize,
+    real_time: f64,
+    cpu_time: f64,
+    time_unit: &'static str,
+}
+
+#[derive(Serialize, Debug)]
+struct GoogleBenchmarkSummary<'a> {
+    context: Context,
+    benchmarks: Vec<Benchmark<'a>>,
+}
+
+/// Exports the results in the JSON format of Google Benchmark, such that tools like its
+/// `compare.py` script can be used. Each timing run is written as a repetition of the
+/// benchmark, followed by the mean, median and stddev aggregates.
+pub struct GoogleBenchmarkExporter {
+    date: String,
+}
+
+impl Default for GoogleBenchmarkExporter {
+    fn default() -> Self {
+        GoogleBenchmarkExporter {
+            date: format_rfc3339_utc(SystemTime::now()),
+        }
+    }
+}
+
+impl Exporter for GoogleBenchmarkExporter {
+    fn serialize(
+        &self,
+        results: &[BenchmarkResult],
+        unit: Option<Unit>,
+        _sort_order: SortOrder,
+    ) -> Result<Vec<u8>> {
+        let unit = unit.unwrap_or_else(|| determine_unit_from_results(results));
+        let (time_unit, factor) = match unit {
+            Unit::Second => ("s", 1.0),
+            Unit::MilliSecond => ("ms", 1e3),
+            Unit::MicroSecond => ("us", 1e6),
+        };
+
+        let mut benchmarks = vec![];
+        for res in results {
+            let runs: &[Second] = res.times.as_deref().unwrap_or_default();
+            let repetitions = runs.len();
+            // Only the mean CPU time is known, individual runs use this value as well
+            let cpu_time = (res.user + res.system) * factor;
+
+            let entry = |name: String, real_time: Second| Benchmark {
+                name,
+                run_name: &res.command,
+                run_type: "iteration",
+                repetitions,
+                repetition_index: None,
+                threads: 1,
+                aggregate_name: None,
+                aggregate_unit: None,
+                iterations: 1,
+                real_time: real_time * factor,
+                cpu_time,
+                time_unit,
+            };
+
+            for (index, &time) in runs.iter().enumerate() {
+                benchmarks.push(Benchmark {
+                    repetition_index: Some(index),
+                    ..entry(res.command.clone(), time)
+                });
+            }
+
+            let aggregates = [
+                ("mean", Some(res.mean)),
+                ("median", Some(res.median)),
+                ("stddev", res.stddev),
+            ];
+            for (aggregate_name, value) in aggregates {
+                if let Some(value) = value {
+                    benchmarks.push(Benchmark {
+                        run_type: "aggregate",
+                        aggregate_name: Some(aggregate_name),
+                        aggregate_unit: Some("time"),
+                        iterations: repetitions,
+                        cpu_time: if aggregate_name == "stddev" {
+                            0.0
+                        } else {
+                            cpu_time
+                        },
+                        ..entry(format!("{}_{}", res.command, aggregate_name), value)
+                    });
+                }
+            }
+        }
+
+        let summary = GoogleBenchmarkSummary {
+            context: Context {
+                date: self.date.clone(),
+                executable: "hyperfine",
+                num_cpus: std::thread::available_parallelism().map_or(1, |n| n.get()),
+            },
+            benchmarks,
+        };
+
+        let mut output = to_vec_pretty(&summary)?;
+        output.push(b'\n');
+
+        Ok(output)
+    }
+}
+
+#[test]
+fn test_google_benchmark() {
+    let exporter = GoogleBenchmarkExporter {
+        date: String::from("2023-10-23T12:34:56+00:00"),
+    };
+
+    let results = vec![BenchmarkResult {
+        command: String::from("sleep 0.1"),
+        command_with_unused_parameters: String::from("sleep 0.1"),
+        mean: 0.1,
+        stddev: Some(0.01),
+        median: 0.1,
+        user: 0.001,
+        system: 0.002,
+        min: 0.09,
+        max: 0.11,
+        times: Some(vec![0.09, 0.11]),
+        exit_codes: vec![Some(0), Some(0)],
+        ..Default::default()
+    }];
+
+    let actual = exporter
+        .serialize(&results, Some(Unit::MilliSecond), SortOrder::Command)
+        .unwrap();
+    let actual: serde_json::Value = serde_json::from_slice(&actual).unwrap();
+
+    assert_eq!(actual["context"]["date"], "2023-10-23T12:34:56+00:00");
+    assert_eq!(actual["context"]["executable"], "hyperfine");
+
+    let benchmarks = actual["benchmarks"].as_array().unwrap();
+    assert_eq!(benchmarks.len(), 5);
+
+    assert_eq!(benchmarks[1]["name"], "sleep 0.1");
+    assert_eq!(benchmarks[1]["run_type"], "iteration");
+    assert_eq!(benchmarks[1]["repetitions"], 2);
+    assert_eq!(benchmarks[1]["repetition_index"], 1);
+    assert_eq!(benchmarks[1]["iterations"], 1);
+    assert_eq!(benchmarks[1]["real_time"], 110.0);
+    assert_eq!(benchmarks[1]["time_unit"], "ms");
+
+    assert_eq!(benchmarks[4]["name"], "sleep 0.1_stddev");
+    assert_eq!(benchmarks[4]["run_name"], "sleep 0.1");
+    assert_eq!(benchmarks[4]["run_type"], "aggregate");
+    assert_eq!(benchmarks[4]["aggregate_name"], "stddev");
+    assert_eq!(benchmarks[4]["iterations"], 2);
+    assert_eq!(benchmarks[4]["real_time"], 10.0);
+}
@@ -0,0 +1,359 @@
+use std::fmt::Write;
+
+use super::markup::determine_unit_from_results;
+use super::xml::escape;
+use super::Exporter;
+use crate::benchmark::benchmark_result::BenchmarkResult;
+use crate::benchmark::relative_speed::{self, BenchmarkResultWithRelativeSpeed};
+use crate::options::SortOrder;
+use crate::output::format::format_duration_value;
+use crate::statistics::quantile;
+use crate::util::units::{Second, Unit};
+
+use anyhow::Result;
+
+const STYLE: &str = "
+body { font-family: sans-serif; margin: 2em; color: #222; }
+table { border-collapse: collapse; margin-bottom: 2em; }
+th, td { padding: 0.3em 0.8em; border-bottom: 1px solid #ddd; }
+th { background: #f3f3f3; }
+td { text-align: right; }
+td:first-child, th:first-child { text-align: left; }
+code { font-size: 0.95em; }
+svg text { font-size: 12px; }
+";
+
+/// Width of the plot area of the SVG charts (without labels)
+const PLOT_WIDTH: f64 = 600.0;
+
+/// Width of the command labels left of the SVG charts
+const LABEL_WIDTH: f64 = 200.0;
+
+/// Height of a single row in the SVG charts
+const ROW_HEIGHT: f64 = 36.0;
+
+/// Height of the horizontal axis of the SVG charts
+const AXIS_HEIGHT: f64 = 40.0;
+
+/// Exports a standalone HTML report, including a summary table, box plots of
+/// the run time distributions and a relative speed comparison.
+#[derive(Default)]
+pub struct HtmlExporter {}
+
+impl Exporter for HtmlExporter {
+    fn serialize(
+        &self,
+        results: &[BenchmarkResult],
+        unit: Option<Unit>,
+        sort_order: SortOrder,
+    ) -> Result<Vec<u8>> {
+        let unit = unit.unwrap_or_else(|| determine_unit_from_results(results));
+        let entries = relative_speed::compute(results, sort_order);
+
+        let mut html = String::new();
+        html.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+        html.push_str("<title>hyperfine benchmark report</title>\n");
+        writeln!(html, "<style>{}</style>", STYLE)?;
+        html.push_str("</head>\n<body>\n");
+
+        html.push_str("<h1>Benchmark report</h1>\n<h2>Summary</h2>\n");
+        html.push_str(&summary_table(&entries, unit));
+
+        html.push_str("<h2>Run time distribution</h2>\n");
+        html.push_str(&box_plots(&entries, unit));
+
+        html.push_str("<h2>Relative speed</h2>\n");
+        html.push_str(&relative_speed_chart(&entries));
+
+        html.push_str("</body>\n</html>\n");
+
+        Ok(html.into_bytes())
+    }
+}
+
+fn summary_table(entries: &[BenchmarkResultWithRelativeSpeed], unit: Unit) -> String {
+    let format = |value| format_duration_value(value, Some(unit)).0;
+    let notation = unit.short_name();
+
+    let mut table = String::from("<table>\n");
+    let _ = writeln!(
+        table,
+        "<tr><th>Command</th><th>Mean [{unit}]</th><th>Min [{unit}]</th>\
+         <th>Max [{unit}]</th><th>Relative</th></tr>",
+        unit = notation
+    );
+
+    for entry in entries {
+        let result = entry.result;
+        let stddev_str = result
+            .stddev
+            .map(|stddev| format!(" ± {}", format(stddev)))
+            .unwrap_or_default();
+        let rel_stddev_str = match entry.relative_speed_stddev {
+            Some(stddev) if !entry.is_fastest => format!(" ± {:.2}", stddev),
+            _ => "".into(),
+        };
+
+        let _ = writeln!(
+            table,
+            "<tr><td><code>{}</code></td><td>{}{}</td><td>{}</td><td>{}</td><td>{:.2}{}</td></tr>",
+            escape(&result.command_with_unused_parameters),
+            format(result.mean),
+            stddev_str,
+            format(result.min),
+            format(result.max),
+            entry.relative_speed,
+            rel_stddev_str,
+        );
+    }
+
+    table.push_str("</table>\n");
+    table
+}
+
+/// Choose a 'nice' distance between ticks for an axis from zero to `max`
+pub(super) fn tick_step(max: f64) -> f64 {
+    let raw_step = max / 5.0;
+    let magnitude = 10f64.powf(raw_step.log10().floor());
+    let normalized = raw_step / magnitude;
+    let nice = if normalized <= 1.0 {
+        1.0
+    } else if normalized <= 2.0 {
+        2.0
+    } else if normalized <= 5.0 {
+        5.0
+    } else {
+        10.0
+    };
+    nice * magnitude
+}
+
+/// Start an SVG chart with one row for each entry and a horizontal axis from zero to
+/// `max`, labelled at each tick with the output of `label`.
+fn svg_chart(
+    entries: &[BenchmarkResultWithRelativeSpeed],
+    max: f64,
+    label: impl Fn(f64) -> String,
+) -> String {
+    let height = entries.len() as f64 * ROW_HEIGHT + AXIS_HEIGHT;
+    let width = LABEL_WIDTH + PLOT_WIDTH + 20.0;
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+         viewBox=\"0 0 {width} {height}\">",
+        width = width,
+        height = height
+    );
+
+    for (i, entry) in entries.iter().enumerate() {
+        let y = (i as f64 + 0.5) * ROW_HEIGHT;
+        let _ = writeln!(
+            svg,
+            "<text x=\"{x:.1}\" y=\"{y:.1}\" text-anchor=\"end\" dominant-baseline=\"middle\">{}</text>",
+            escape(&entry.result.command_with_unused_parameters),
+            x = LABEL_WIDTH - 10.0,
+            y = y
+        );
+    }
+
+    let axis_y = entries.len() as f64 * ROW_HEIGHT;
+    let _ = writeln!(
+        svg,
+        "<line x1=\"{x1:.1}\" y1=\"{y:.1}\" x2=\"{x2:.1}\" y2=\"{y:.1}\" stroke=\"#222\"/>",
+        x1 = LABEL_WIDTH,
+        x2 = LABEL_WIDTH + PLOT_WIDTH,
+        y = axis_y
+    );
+
+    if max > 0.0 {
+        let step = tick_step(max);
+        let mut tick = 0.0;
+        while tick <= max * (1.0 + 1e-9) {
+            let x = LABEL_WIDTH + tick / max * PLOT_WIDTH;
+            let _ = writeln!(
+                svg,
+                "<line x1=\"{x:.1}\" y1=\"0\" x2=\"{x:.1}\" y2=\"{y:.1}\" stroke=\"#eee\"/>\
+                 <text x=\"{x:.1}\" y=\"{ty:.1}\" text-anchor=\"middle\">{}</text>",
+                label(tick),
+                x = x,
+                y = axis_y,
+                ty = axis_y + 18.0
+            );
+            tick += step;
+        }
+    }
+
+    svg
+}
+
+fn box_plots(entries: &[BenchmarkResultWithRelativeSpeed], unit: Unit) -> String {
+    let max = entries.iter().map(|e| e.result.max).fold(0.0, f64::max) * 1.05;
+    let scale = |t: Second| LABEL_WIDTH + if max > 0.0 { t / max * PLOT_WIDTH } else { 0.0 };
+
+    let mut svg = svg_chart(entries, max, |tick| {
+        format!("{} {}", unit.format(tick), unit.short_name())
+    });
+
+    for (i, entry) in entries.iter().enumerate() {
+        let mut times = entry
+            .result
+            .times
+            .clone()
+            .unwrap_or_else(|| vec![entry.result.mean]);
+        if times.is_empty() {
+            continue;
+        }
+        times.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        let q1 = quantile(&times, 0.25);
+        let q2 = quantile(&times, 0.5);
+        let q3 = quantile(&times, 0.75);
+        let iqr = q3 - q1;
+
+        // Whiskers extend to the most extreme data points within 1.5 IQR
+        let lower_whisker = *times.iter().find(|&&t| t >= q1 - 1.5 * iqr).unwrap();
+        let upper_whisker = *times.iter().rev().find(|&&t| t <= q3 + 1.5 * iqr).unwrap();
+
+        let top = i as f64 * ROW_HEIGHT + ROW_HEIGHT * 0.2;
+        let box_height = ROW_HEIGHT * 0.6;
+        let center = top + box_height / 2.0;
+
+        let _ = writeln!(
+            svg,
+            "<line x1=\"{x1:.1}\" y1=\"{y:.1}\" x2=\"{x2:.1}\" y2=\"{y:.1}\" stroke=\"#222\"/>",
+            x1 = scale(lower_whisker),
+            x2 = scale(upper_whisker),
+            y = center
+        );
+        for whisker in [lower_whisker, upper_whisker] {
+            let _ = writeln!(
+                svg,
+                "<line x1=\"{x:.1}\" y1=\"{y1:.1}\" x2=\"{x:.1}\" y2=\"{y2:.1}\" stroke=\"#222\"/>",
+                x = scale(whisker),
+                y1 = top + box_height * 0.25,
+                y2 = top + box_height * 0.75
+            );
+        }
+        let _ = writeln!(
+            svg,
+            "<rect x=\"{x:.1}\" y=\"{y:.1}\" width=\"{w:.1}\" height=\"{h:.1}\" \
+             fill=\"#8ec1e8\" stroke=\"#222\"/>",
+            x = scale(q1),
+            y = top,
+            w = scale(q3) - scale(q1),
+            h = box_height
+        );
+        let _ = writeln!(
+            svg,
+            "<line x1=\"{x:.1}\" y1=\"{y1:.1}\" x2=\"{x:.1}\" y2=\"{y2:.1}\" stroke=\"#222\" \
+             stroke-width=\"2\"/>",
+            x = scale(q2),
+            y1 = top,
+            y2 = top + box_height
+        );
+        for &t in times
+            .iter()
+            .filter(|&&t| t < lower_whisker || t > upper_whisker)
+        {
+            let _ = writeln!(
+                svg,
+                "<circle cx=\"{x:.1}\" cy=\"{y:.1}\" r=\"2.5\" fill=\"none\" stroke=\"#222\"/>",
+                x = scale(t),
+                y = center
+            );
+        }
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn relative_speed_chart(entries: &[BenchmarkResultWithRelativeSpeed]) -> String {
+    let max = entries
+        .iter()
+        .map(|e| e.relative_speed)
+        .filter(|r| r.is_finite())
+        .fold(1.0, f64::max)
+        * 1.05;
+
+    let mut svg = svg_chart(entries, max, |tick| format!("{:.2}", tick));
+
+    for (i, entry) in entries.iter().enumerate() {
+        let relative_speed = entry.relative_speed.min(max);
+        let _ =
+            writeln!(
+            svg,
+            "<rect x=\"{x:.1}\" y=\"{y:.1}\" width=\"{w:.1}\" height=\"{h:.1}\" fill=\"{fill}\"/>\
+             <text x=\"{tx:.1}\" y=\"{ty:.1}\" dominant-baseline=\"middle\">{:.2}</text>",
+            entry.relative_speed,
+            x = LABEL_WIDTH,
+            y = i as f64 * ROW_HEIGHT + ROW_HEIGHT * 0.2,
+            w = relative_speed / max * PLOT_WIDTH,
+            h = ROW_HEIGHT * 0.6,
+            fill = if entry.is_fastest { "#5cb85c" } else { "#8ec1e8" },
+            tx = LABEL_WIDTH + relative_speed / max * PLOT_WIDTH + 5.0,
+            ty = (i as f64 + 0.5) * ROW_HEIGHT
+        );
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+#[test]
+fn test_html_format() {
+    let exporter = HtmlExporter::default();
+
+    let results = vec![
+        BenchmarkResult {
+            command: String::from("sleep 0.1 && echo '<done>'"),
+            command_with_unused_parameters: String::from("sleep 0.1 && echo '<done>'"),
+            mean: 0.1057,
+            stddev: Some(0.0016),
+            median: 0.1057,
+            user: 0.0009,
+            system: 0.0011,
+            min: 0.1023,
+            max: 0.1080,
+            times: Some(vec![0.1023, 0.1068, 0.1080]),
+            exit_codes: vec![Some(0), Some(0), Some(0)],
+            ..Default::default()
+        },
+        BenchmarkResult {
+            command: String::from("sleep 0.2"),
+            command_with_unused_parameters: String::from("sleep 0.2"),
+            mean: 0.2050,
+            stddev: Some(0.0020),
+            median: 0.2050,
+            user: 0.0009,
+            system: 0.0012,
+            min: 0.2020,
+            max: 0.2080,
+            times: Some(vec![0.2020, 0.2050, 0.2080]),
+            exit_codes: vec![Some(0), Some(0), Some(0)],
+            ..Default::default()
+        },
+    ];
+
+    let actual = String::from_utf8(
+        exporter
+            .serialize(&results, None, SortOrder::Command)
+            .unwrap(),
+    )
+    .unwrap();
+
+    assert!(actual.starts_with("<!DOCTYPE html>"));
+    assert!(actual.contains("<th>Mean [ms]</th>"));
+    assert!(actual.contains(
+        "<tr><td><code>sleep 0.1 &amp;&amp; echo &#39;&lt;done&gt;&#39;</code></td>\
+         <td>105.7 ± 1.6</td><td>102.3</td><td>108.0</td><td>1.00</td></tr>"
+    ));
+    assert!(actual.contains(
+        "<tr><td><code>sleep 0.2</code></td>\
+         <td>205.0 ± 2.0</td><td>202.0</td><td>208.0</td><td>1.94 ± 0.03</td></tr>"
+    ));
+    assert_eq!(actual.matches("<svg").count(), 2);
+    assert!(actual.ends_with("</html>\n"));
+}
@@ -0,0 +1,133 @@
+use std::fmt::Write;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::Exporter;
+use crate::benchmark::benchmark_result::BenchmarkResult;
+use crate::options::SortOrder;
+use crate::util::units::Unit;
+
+use anyhow::Result;
+
+/// Exports the results in the InfluxDB line protocol. Each benchmarked command is written
+/// as one point of the `hyperfine` measurement, tagged with the command and its parameters.
+pub struct InfluxdbExporter {
+    /// Timestamp of all points (in nanoseconds since the Unix epoch)
+    timestamp: u128,
+}
+
+impl Default for InfluxdbExporter {
+    fn default() -> Self {
+        InfluxdbExporter {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_nanos()),
+        }
+    }
+}
+
+impl Exporter for InfluxdbExporter {
+    fn serialize(
+        &self,
+        results: &[BenchmarkResult],
+        _unit: Option<Unit>,
+        _sort_order: SortOrder,
+    ) -> Result<Vec<u8>> {
+        let mut output = String::new();
+
+        for res in results {
+            write!(output, "hyperfine,command={}", escape(&res.command))?;
+            for (name, value) in res.parameters.iter().filter(|(_, v)| !v.is_empty()) {
+                write!(output, ",{}={}", escape(name), escape(value))?;
+            }
+
+            write!(output, " mean={}", res.mean)?;
+            if let Some(stddev) = res.stddev {
+                write!(output, ",stddev={}", stddev)?;
+            }
+            write!(
+                output,
+                ",median={},min={},max={},user={},system={}",
+                res.median, res.min, res.max, res.user, res.system
+            )?;
+            if let Some(ref times) = res.times {
+                write!(output, ",runs={}i", times.len())?;
+            }
+
+            writeln!(output, " {}", self.timestamp)?;
+        }
+
+        Ok(output.into_bytes())
+    }
+}
+
+/// Escape commas, equal signs, spaces and line feeds in tag keys and values
+fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            ',' | '=' | ' ' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' => escaped.push_str("\\n"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[test]
+fn test_influxdb() {
+    use std::collections::BTreeMap;
+    let exporter = InfluxdbExporter {
+        timestamp: 1_600_000_000_000_000_000,
+    };
+
+    let results = vec![
+        BenchmarkResult {
+            command: String::from("sleep 0.1"),
+            command_with_unused_parameters: String::from("sleep 0.1"),
+            mean: 0.1,
+            stddev: Some(0.01),
+            median: 0.1,
+            user: 0.001,
+            system: 0.002,
+            min: 0.09,
+            max: 0.11,
+            times: Some(vec![0.09, 0.1, 0.11]),
+            exit_codes: vec![Some(0), Some(0), Some(0)],
+            parameters: {
+                let mut params = BTreeMap::new();
+                params.insert("compiler".into(), "gcc -O2".into());
+                params.insert("empty".into(), "".into());
+                params
+            },
+            ..Default::default()
+        },
+        BenchmarkResult {
+            command: String::from("a=1,b=2"),
+            command_with_unused_parameters: String::from("a=1,b=2"),
+            mean: 2.0,
+            median: 2.0,
+            user: 0.0,
+            system: 0.0,
+            min: 2.0,
+            max: 2.0,
+            exit_codes: vec![Some(0)],
+            ..Default::default()
+        },
+    ];
+
+    let expected = "\
+hyperfine,command=sleep\\ 0.1,compiler=gcc\\ -O2 mean=0.1,stddev=0.01,median=0.1,min=0.09,max=0.11,user=0.001,system=0.002,runs=3i 1600000000000000000
+hyperfine,command=a\\=1\\,b\\=2 mean=2,median=2,min=2,max=2,user=0,system=0 1600000000000000000
+";
+    let actual = String::from_utf8(
+        exporter
+            .serialize(&results, None, SortOrder::Command)
+            .unwrap(),
+    )
+    .unwrap();
+
+    assert_eq!(expected, actual);
+}
@@ -1,20 +1,95 @@
+use std::fs::File;
+use std::path::Path;
+
 use serde::*;
-use serde_json::to_vec_pretty;
+use serde_json::{to_vec_pretty, Value};
 
-use super::Exporter;
+use super::metadata::Metadata;
+use super::{read_from_file, Exporter};
 use crate::benchmark::benchmark_result::BenchmarkResult;
+use crate::benchmark::comparison_matrix::{self, ComparisonMatrix};
 use crate::options::SortOrder;
 use crate::util::units::Unit;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
+
+/// Version of the export format. It needs to be incremented whenever fields are removed
+/// or change their meaning. New fields can be added without changing the version.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// JSON Schema of the export format, see `--export-schema`
+pub const JSON_SCHEMA: &str = include_str!("schema.json");
 
 #[derive(Serialize, Debug)]
-struct HyperfineSummary<'a> {
-    results: &'a [BenchmarkResult],
+pub struct HyperfineSummary<'a> {
+    pub schema_version: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<&'a Metadata>,
+    pub results: &'a [BenchmarkResult],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comparison_matrix: Option<ComparisonMatrix>,
+}
+
+#[derive(Serialize, Debug)]
+struct MergedSummary<'a> {
+    schema_version: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    metadata: Option<&'a Metadata>,
+    results: Vec<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    comparison_matrix: Option<ComparisonMatrix>,
 }
 
 #[derive(Default)]
-pub struct JsonExporter {}
+pub struct JsonExporter {
+    metadata: Option<Metadata>,
+
+    /// Whether new results are merged into the existing export file
+    append: bool,
+
+    /// Settings of the comparison matrix, if it is exported
+    comparison_matrix: Option<comparison_matrix::Settings>,
+
+    /// Results that have been read from the existing export file
+    existing_results: Vec<Value>,
+}
+
+impl JsonExporter {
+    pub fn new(
+        metadata: Option<Metadata>,
+        append: bool,
+        comparison_matrix: Option<comparison_matrix::Settings>,
+    ) -> Self {
+        JsonExporter {
+            metadata,
+            append,
+            comparison_matrix,
+            existing_results: vec![],
+        }
+    }
+
+    /// Results are identified by their command name and parameters
+    fn key(result: &Value) -> (&Value, Option<&Value>) {
+        (&result["command"], result.get("parameters"))
+    }
+
+    /// Merge the given results into the existing ones. Existing results for the same
+    /// command and parameters are replaced, all others are kept.
+    fn merge(&self, results: &[BenchmarkResult]) -> Result<Vec<Value>> {
+        let mut merged = self.existing_results.clone();
+        for result in results {
+            let value = serde_json::to_value(result)?;
+            match merged
+                .iter()
+                .position(|e| Self::key(e) == Self::key(&value))
+            {
+                Some(index) => merged[index] = value,
+                None => merged.push(value),
+            }
+        }
+        Ok(merged)
+    }
+}
 
 impl Exporter for JsonExporter {
     fn serialize(
@@ -23,11 +98,175 @@ impl Exporter for JsonExporter {
         _unit: Option<Unit>,
         _sort_order: SortOrder,
     ) -> Result<Vec<u8>> {
-        let mut output = to_vec_pretty(&HyperfineSummary { results });
+        // The matrix only covers the results of the current run, also when appending
+        let comparison_matrix = self
+            .comparison_matrix
+            .map(|settings| ComparisonMatrix::compute(results, settings));
+
+        let mut output = if self.append {
+            to_vec_pretty(&MergedSummary {
+                schema_version: SCHEMA_VERSION,
+                metadata: self.metadata.as_ref(),
+                results: self.merge(results)?,
+                comparison_matrix,
+            })
+        } else {
+            to_vec_pretty(&HyperfineSummary {
+                schema_version: SCHEMA_VERSION,
+                metadata: self.metadata.as_ref(),
+                results,
+                comparison_matrix,
+            })
+        };
         if let Ok(ref mut content) = output {
             content.push(b'\n');
         }
 
         Ok(output?)
     }
+
+    fn create_file(&mut self, filename: &str) -> Result<()> {
+        if self.append && Path::new(filename).exists() {
+            let content = read_from_file(filename)
+                .with_context(|| format!("Could not read export file '{}'", filename))?;
+            if !content.trim().is_empty() {
+                let summary: Value = serde_json::from_str(&content).with_context(|| {
+                    format!("Could not read the existing results in '{}'", filename)
+                })?;
+                self.existing_results = summary["results"].as_array().cloned().unwrap_or_default();
+            }
+            return Ok(());
+        }
+
+        File::create(filename)
+            .with_context(|| format!("Could not create export file '{}'", filename))?;
+        Ok(())
+    }
+}
+
+#[test]
+fn test_json_append() {
+    let tempdir = tempfile::tempdir().unwrap();
+    let filename = tempdir.path().join("results.json");
+    let filename = filename.to_str().unwrap();
+
+    let result = |command: &str, mean, size: Option<&str>| BenchmarkResult {
+        command: command.to_string(),
+        mean,
+        parameters: size
+            .map(|s| [("size".to_string(), s.to_string())].into())
+            .unwrap_or_default(),
+        ..Default::default()
+    };
+
+    let mut exporter = JsonExporter::new(None, true, None);
+    exporter.create_file(filename).unwrap();
+    let first = [result("a", 1.0, None), result("b", 2.0, Some("1"))];
+    exporter
+        .write_to_file(filename, &first, None, SortOrder::Command)
+        .unwrap();
+
+    let mut exporter = JsonExporter::new(None, true, None);
+    exporter.create_file(filename).unwrap();
+    let second = [result("b", 3.0, Some("1")), result("b", 4.0, Some("2"))];
+    exporter
+        .write_to_file(filename, &second, None, SortOrder::Command)
+        .unwrap();
+
+    let summary: Value = serde_json::from_str(&std::fs::read_to_string(filename).unwrap()).unwrap();
+    let means: Vec<(&str, f64)> = summary["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| (r["command"].as_str().unwrap(), r["mean"].as_f64().unwrap()))
+        .collect();
+    assert_eq!(means, [("a", 1.0), ("b", 3.0), ("b", 4.0)]);
+}
+
+#[test]
+fn test_json_schema() {
+    use crate::benchmark::benchmark_result::{OutlierRun, Percentile};
+    use crate::benchmark::timing_result::CpuFrequency;
+    use crate::statistics::ConfidenceInterval;
+
+    let schema: Value = serde_json::from_str(JSON_SCHEMA).unwrap();
+    assert_eq!(
+        schema["properties"]["schema_version"]["const"],
+        SCHEMA_VERSION
+    );
+
+    // All fields of the export need to be documented in the schema
+    let result = BenchmarkResult {
+        command: "sleep 1".into(),
+        stddev: Some(0.0),
+        mean_ci: Some(ConfidenceInterval {
+            lower: 1.0,
+            upper: 1.0,
+        }),
+        percentiles: vec![Percentile {
+            percentile: 50.0,
+            time: 1.0,
+        }],
+        times: Some(vec![1.0]),
+        timestamps: Some(vec![1700000000.0]),
+        memory_usage_byte: Some(vec![1 << 20]),
+        io_read_byte: Some(vec![4096]),
+        io_write_byte: Some(vec![0]),
+        voluntary_context_switches: Some(vec![2]),
+        involuntary_context_switches: Some(vec![1]),
+        minor_page_faults: Some(vec![100]),
+        major_page_faults: Some(vec![0]),
+        perf_counters: [("cycles".to_string(), vec![1_000_000])].into(),
+        energy_joule: Some(vec![0.5]),
+        cpu_frequency_mhz: Some(vec![CpuFrequency {
+            min: 1200.0,
+            mean: 2400.0,
+            max: 3600.0,
+        }]),
+        time_to_first_output: Some(vec![0.25]),
+        cpu_utilization: Some(0.5),
+        exit_codes: vec![None],
+        signals: vec![Some(9)],
+        outliers: vec![true],
+        outlier_runs: vec![OutlierRun {
+            index: 0,
+            time: 1.0,
+            zscore: 12.0,
+        }],
+        zscores: vec![12.0],
+        parameters: [("time".to_string(), "1".to_string())].into(),
+        ..Default::default()
+    };
+    let metadata = Metadata::collect();
+    let summary = serde_json::to_value(HyperfineSummary {
+        schema_version: SCHEMA_VERSION,
+        metadata: Some(&metadata),
+        results: &[result],
+        comparison_matrix: Some(ComparisonMatrix {
+            commands: vec!["sleep 1".into()],
+            ratios: vec![vec![1.0]],
+            p_values: vec![vec![None]],
+            significance_level: 0.05,
+        }),
+    })
+    .unwrap();
+
+    let documented = |properties: &Value, object: &Value| {
+        for key in object.as_object().unwrap().keys() {
+            assert!(properties.get(key).is_some(), "'{}' is not documented", key);
+        }
+    };
+    documented(&schema["properties"], &summary);
+    documented(
+        &schema["properties"]["metadata"]["properties"],
+        &summary["metadata"],
+    );
+    documented(
+        &schema["properties"]["comparison_matrix"]["properties"],
+        &summary["comparison_matrix"],
+    );
+    documented(
+        &schema["$defs"]["result"]["properties"],
+        &summary["results"][0],
+    );
 }
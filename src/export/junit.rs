@@ -0,0 +1,134 @@
+use std::fmt::Write;
+
+use super::xml::escape;
+use super::Exporter;
+use crate::benchmark::benchmark_result::BenchmarkResult;
+use crate::options::SortOrder;
+use crate::output::format::{format_duration, format_duration_unit};
+use crate::util::units::{Second, Unit};
+
+use anyhow::Result;
+
+/// Exports the results as a JUnit XML test report. Each benchmarked command is reported as a
+/// test case. Commands with a mean run time above the threshold are reported as failures.
+#[derive(Default)]
+pub struct JunitExporter {
+    threshold: Option<Second>,
+}
+
+impl JunitExporter {
+    pub fn new(threshold: Option<Second>) -> Self {
+        JunitExporter { threshold }
+    }
+
+    fn exceeds_threshold(&self, result: &BenchmarkResult) -> bool {
+        self.threshold
+            .is_some_and(|threshold| result.mean > threshold)
+    }
+}
+
+impl Exporter for JunitExporter {
+    fn serialize(
+        &self,
+        results: &[BenchmarkResult],
+        unit: Option<Unit>,
+        _sort_order: SortOrder,
+    ) -> Result<Vec<u8>> {
+        let failures = results
+            .iter()
+            .filter(|res| self.exceeds_threshold(res))
+            .count();
+        let total_time: Second = results.iter().map(|res| res.mean).sum();
+
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites>\n");
+        writeln!(
+            xml,
+            "  <testsuite name=\"hyperfine\" tests=\"{}\" failures=\"{}\" time=\"{}\">",
+            results.len(),
+            failures,
+            total_time
+        )?;
+
+        for res in results {
+            write!(
+                xml,
+                "    <testcase name=\"{}\" classname=\"hyperfine\" time=\"{}\"",
+                escape(&res.command),
+                res.mean
+            )?;
+
+            match self.threshold {
+                Some(threshold) if self.exceeds_threshold(res) => {
+                    let (mean_str, mean_unit) = format_duration_unit(res.mean, unit);
+                    xml.push_str(">\n");
+                    writeln!(
+                        xml,
+                        "      <failure message=\"Mean time of {} exceeds the threshold of {}\"/>",
+                        mean_str,
+                        format_duration(threshold, Some(mean_unit))
+                    )?;
+                    xml.push_str("    </testcase>\n");
+                }
+                _ => xml.push_str("/>\n"),
+            }
+        }
+
+        xml.push_str("  </testsuite>\n</testsuites>\n");
+
+        Ok(xml.into_bytes())
+    }
+}
+
+#[test]
+fn test_junit() {
+    let exporter = JunitExporter::new(Some(1.0));
+
+    let create_result = |command: &str, mean| BenchmarkResult {
+        command: String::from(command),
+        command_with_unused_parameters: String::from(command),
+        mean,
+        stddev: Some(0.0),
+        median: mean,
+        user: 0.0,
+        system: 0.0,
+        min: mean,
+        max: mean,
+        times: Some(vec![mean]),
+        exit_codes: vec![Some(0)],
+        ..Default::default()
+    };
+    let results = vec![
+        create_result("sleep 0.5 && true", 0.5),
+        create_result("sleep 1.5", 1.5),
+    ];
+
+    let expected = "\
+<?xml version=\"1.0\" encoding=\"UTF-8\"?>
+<testsuites>
+  <testsuite name=\"hyperfine\" tests=\"2\" failures=\"1\" time=\"2\">
+    <testcase name=\"sleep 0.5 &amp;&amp; true\" classname=\"hyperfine\" time=\"0.5\"/>
+    <testcase name=\"sleep 1.5\" classname=\"hyperfine\" time=\"1.5\">
+      <failure message=\"Mean time of 1.500 s exceeds the threshold of 1.000 s\"/>
+    </testcase>
+  </testsuite>
+</testsuites>
+";
+    let actual = String::from_utf8(
+        exporter
+            .serialize(&results, Some(Unit::Second), SortOrder::Command)
+            .unwrap(),
+    )
+    .unwrap();
+
+    assert_eq!(expected, actual);
+
+    // Without a threshold, no test case fails
+    let actual = String::from_utf8(
+        JunitExporter::default()
+            .serialize(&results, None, SortOrder::Command)
+            .unwrap(),
+    )
+    .unwrap();
+    assert!(actual.contains("failures=\"0\""));
+    assert!(!actual.contains("<failure"));
+}
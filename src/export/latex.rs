@@ -0,0 +1,157 @@
+use crate::export::markup::MarkupExporter;
+
+use super::markup::{Alignment, Columns};
+
+/// Exports the results as a LaTeX table. The table uses the rules of the `booktabs`
+/// package, which needs to be loaded in the preamble of the document.
+#[derive(Default)]
+pub struct LatexExporter {
+    columns: Columns,
+}
+
+impl LatexExporter {
+    pub fn new(columns: Columns) -> Self {
+        LatexExporter { columns }
+    }
+}
+
+impl MarkupExporter for LatexExporter {
+    fn columns(&self) -> &Columns {
+        &self.columns
+    }
+
+    fn table_header(&self, cell_aligmnents: &[Alignment]) -> String {
+        format!(
+            "\\begin{{tabular}}{{{}}}\n\\toprule\n",
+            cell_aligmnents
+                .iter()
+                .map(|a| match a {
+                    Alignment::Left => 'l',
+                    Alignment::Right => 'r',
+                })
+                .collect::<String>()
+        )
+    }
+
+    fn table_footer(&self, _cell_aligmnents: &[Alignment]) -> String {
+        "\\bottomrule\n\\end{tabular}\n".to_string()
+    }
+
+    fn table_row(&self, cells: &[&str]) -> String {
+        format!("{} \\\\\n", cells.join(" & "))
+    }
+
+    fn table_divider(&self, _cell_aligmnents: &[Alignment]) -> String {
+        "\\midrule\n".to_string()
+    }
+
+    fn command(&self, cmd: &str) -> String {
+        format!("\\texttt{{{}}}", cmd)
+    }
+
+    fn escape(&self, text: &str) -> String {
+        let mut escaped = String::with_capacity(text.len());
+        for c in text.chars() {
+            match c {
+                '\\' => escaped.push_str("\\textbackslash{}"),
+                '~' => escaped.push_str("\\textasciitilde{}"),
+                '^' => escaped.push_str("\\textasciicircum{}"),
+                '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                    escaped.push('\\');
+                    escaped.push(c);
+                }
+                c => escaped.push(c),
+            }
+        }
+        escaped
+    }
+}
+
+#[cfg(test)]
+use crate::options::SortOrder;
+
+/// Check LaTeX-based data row formatting
+#[test]
+fn test_latex_exporter_table_data() {
+    let exporter = LatexExporter::default();
+
+    assert_eq!(exporter.table_row(&["a", "b", "c"]), "a & b & c \\\\\n");
+}
+
+/// Check LaTeX-based table header formatting
+#[test]
+fn test_latex_exporter_table_header() {
+    let exporter = LatexExporter::default();
+
+    let header = exporter.table_header(&[Alignment::Left, Alignment::Right, Alignment::Right]);
+    assert_eq!(header, "\\begin{tabular}{lrr}\n\\toprule\n");
+}
+
+/// Check that special characters in commands are escaped
+#[test]
+fn test_latex_exporter_escape() {
+    let exporter = LatexExporter::default();
+
+    assert_eq!(
+        exporter.escape(r"grep -c '^a_b$' ~/x | wc & echo 100% {}\n"),
+        r"grep -c '\textasciicircum{}a\_b\$' \textasciitilde{}/x | wc \& echo 100\% \{\}\textbackslash{}n"
+    );
+}
+
+/// Ensure the LaTeX output includes the booktabs rules and the benchmark results
+#[test]
+fn test_latex_format() {
+    use super::Exporter;
+    use crate::benchmark::benchmark_result::BenchmarkResult;
+    let exporter = LatexExporter::default();
+
+    let timing_results = vec![
+        BenchmarkResult {
+            command: String::from("sleep 0.1"),
+            command_with_unused_parameters: String::from("sleep 0.1"),
+            mean: 0.1057,
+            stddev: Some(0.0016),
+            median: 0.1057,
+            user: 0.0009,
+            system: 0.0011,
+            min: 0.1023,
+            max: 0.1080,
+            times: Some(vec![0.1, 0.1, 0.1]),
+            exit_codes: vec![Some(0), Some(0), Some(0)],
+            ..Default::default()
+        },
+        BenchmarkResult {
+            command: String::from("sleep 2"),
+            command_with_unused_parameters: String::from("sleep 2"),
+            mean: 2.0050,
+            stddev: Some(0.0020),
+            median: 2.0050,
+            user: 0.0009,
+            system: 0.0012,
+            min: 2.0020,
+            max: 2.0080,
+            times: Some(vec![2.0, 2.0, 2.0]),
+            exit_codes: vec![Some(0), Some(0), Some(0)],
+            ..Default::default()
+        },
+    ];
+
+    let actual = String::from_utf8(
+        exporter
+            .serialize(&timing_results, None, SortOrder::Command)
+            .unwrap(),
+    )
+    .unwrap();
+    let expect = "\
+\\begin{tabular}{lrrrr}
+\\toprule
+Command & Mean [ms] & Min [ms] & Max [ms] & Relative \\\\
+\\midrule
+\\texttt{sleep 0.1} & 105.7 ± 1.6 & 102.3 & 108.0 & 1.00 \\\\
+\\texttt{sleep 2} & 2005.0 ± 2.0 & 2002.0 & 2008.0 & 18.97 ± 0.29 \\\\
+\\bottomrule
+\\end{tabular}
+";
+
+    assert_eq!(expect, actual);
+}
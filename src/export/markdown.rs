@@ -1,11 +1,23 @@
 use crate::export::markup::MarkupExporter;
 
-use super::markup::Alignment;
+use super::markup::{Alignment, Columns};
 
 #[derive(Default)]
-pub struct MarkdownExporter {}
+pub struct MarkdownExporter {
+    columns: Columns,
+}
+
+impl MarkdownExporter {
+    pub fn new(columns: Columns) -> Self {
+        MarkdownExporter { columns }
+    }
+}
 
 impl MarkupExporter for MarkdownExporter {
+    fn columns(&self) -> &Columns {
+        &self.columns
+    }
+
     fn table_row(&self, cells: &[&str]) -> String {
         format!("| {} |\n", cells.join(" | "))
     }
@@ -68,7 +80,6 @@ fn cfg_test_table_header(unit_short_name: String) -> String {
 fn test_markdown_format_ms() {
     use super::Exporter;
     use crate::benchmark::benchmark_result::BenchmarkResult;
-    use std::collections::BTreeMap;
     let exporter = MarkdownExporter::default();
 
     let timing_results = vec![
@@ -84,7 +95,7 @@ fn test_markdown_format_ms() {
             max: 0.1080,
             times: Some(vec![0.1, 0.1, 0.1]),
             exit_codes: vec![Some(0), Some(0), Some(0)],
-            parameters: BTreeMap::new(),
+            ..Default::default()
         },
         BenchmarkResult {
             command: String::from("sleep 2"),
@@ -98,7 +109,7 @@ fn test_markdown_format_ms() {
             max: 2.0080,
             times: Some(vec![2.0, 2.0, 2.0]),
             exit_codes: vec![Some(0), Some(0), Some(0)],
-            parameters: BTreeMap::new(),
+            ..Default::default()
         },
     ];
 
@@ -125,7 +136,6 @@ fn test_markdown_format_ms() {
 fn test_markdown_format_s() {
     use super::Exporter;
     use crate::benchmark::benchmark_result::BenchmarkResult;
-    use std::collections::BTreeMap;
     let exporter = MarkdownExporter::default();
 
     let timing_results = vec![
@@ -141,7 +151,7 @@ fn test_markdown_format_s() {
             max: 2.0080,
             times: Some(vec![2.0, 2.0, 2.0]),
             exit_codes: vec![Some(0), Some(0), Some(0)],
-            parameters: BTreeMap::new(),
+            ..Default::default()
         },
         BenchmarkResult {
             command: String::from("sleep 0.1"),
@@ -155,7 +165,7 @@ fn test_markdown_format_s() {
             max: 0.1080,
             times: Some(vec![0.1, 0.1, 0.1]),
             exit_codes: vec![Some(0), Some(0), Some(0)],
-            parameters: BTreeMap::new(),
+            ..Default::default()
         },
     ];
 
@@ -182,7 +192,6 @@ fn test_markdown_format_time_unit_s() {
     use super::Exporter;
     use crate::benchmark::benchmark_result::BenchmarkResult;
     use crate::util::units::Unit;
-    use std::collections::BTreeMap;
     let exporter = MarkdownExporter::default();
 
     let timing_results = vec![
@@ -198,7 +207,7 @@ fn test_markdown_format_time_unit_s() {
             max: 2.0080,
             times: Some(vec![2.0, 2.0, 2.0]),
             exit_codes: vec![Some(0), Some(0), Some(0)],
-            parameters: BTreeMap::new(),
+            ..Default::default()
         },
         BenchmarkResult {
             command: String::from("sleep 0.1"),
@@ -212,7 +221,7 @@ fn test_markdown_format_time_unit_s() {
             max: 0.1080,
             times: Some(vec![0.1, 0.1, 0.1]),
             exit_codes: vec![Some(0), Some(0), Some(0)],
-            parameters: BTreeMap::new(),
+            ..Default::default()
         },
     ];
 
@@ -260,7 +269,6 @@ fn test_markdown_format_time_unit_ms() {
     use super::Exporter;
     use crate::benchmark::benchmark_result::BenchmarkResult;
     use crate::util::units::Unit;
-    use std::collections::BTreeMap;
     let exporter = MarkdownExporter::default();
 
     let timing_results = vec![
@@ -276,7 +284,7 @@ fn test_markdown_format_time_unit_ms() {
             max: 2.0080,
             times: Some(vec![2.0, 2.0, 2.0]),
             exit_codes: vec![Some(0), Some(0), Some(0)],
-            parameters: BTreeMap::new(),
+            ..Default::default()
         },
         BenchmarkResult {
             command: String::from("sleep 0.1"),
@@ -290,7 +298,7 @@ fn test_markdown_format_time_unit_ms() {
             max: 0.1080,
             times: Some(vec![0.1, 0.1, 0.1]),
             exit_codes: vec![Some(0), Some(0), Some(0)],
-            parameters: BTreeMap::new(),
+            ..Default::default()
         },
     ];
 
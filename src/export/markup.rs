@@ -12,31 +12,88 @@ pub enum Alignment {
     Right,
 }
 
+/// A column of the exported table, in addition to the command
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Column {
+    /// Mean time. Includes the standard deviation if that column is selected as well
+    Mean,
+    Stddev,
+    Median,
+    Min,
+    Max,
+    User,
+    System,
+    /// Relative speed with respect to the fastest command
+    Relative,
+}
+
+impl Column {
+    pub fn from_name(name: &str) -> Option<Column> {
+        match name {
+            "mean" => Some(Column::Mean),
+            "stddev" => Some(Column::Stddev),
+            "median" => Some(Column::Median),
+            "min" => Some(Column::Min),
+            "max" => Some(Column::Max),
+            "user" => Some(Column::User),
+            "system" => Some(Column::System),
+            "relative" => Some(Column::Relative),
+            _ => None,
+        }
+    }
+}
+
+/// The columns that are included in the table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Columns(pub Vec<Column>);
+
+impl Default for Columns {
+    fn default() -> Self {
+        Columns(vec![
+            Column::Mean,
+            Column::Stddev,
+            Column::Min,
+            Column::Max,
+            Column::Relative,
+        ])
+    }
+}
+
 pub trait MarkupExporter {
     fn table_results(&self, entries: &[BenchmarkResultWithRelativeSpeed], unit: Unit) -> String {
         // prepare table header strings
         let notation = format!("[{}]", unit.short_name());
 
+        // the standard deviation is shown next to the mean, if both are selected
+        let columns = &self.columns().0;
+        let has_mean = columns.contains(&Column::Mean);
+        let has_stddev = columns.contains(&Column::Stddev);
+        let columns: Vec<Column> = columns
+            .iter()
+            .copied()
+            .filter(|&c| !(c == Column::Stddev && has_mean))
+            .collect();
+
         // prepare table cells alignment
-        let cells_alignment = [
-            Alignment::Left,
-            Alignment::Right,
-            Alignment::Right,
-            Alignment::Right,
-            Alignment::Right,
-        ];
+        let mut cells_alignment = vec![Alignment::Left];
+        cells_alignment.extend(columns.iter().map(|_| Alignment::Right));
 
         // emit table header format
         let mut table = self.table_header(&cells_alignment);
 
         // emit table header data
-        table.push_str(&self.table_row(&[
-            "Command",
-            &format!("Mean {}", notation),
-            &format!("Min {}", notation),
-            &format!("Max {}", notation),
-            "Relative",
-        ]));
+        let mut header = vec![String::from("Command")];
+        header.extend(columns.iter().map(|column| match column {
+            Column::Mean => format!("Mean {}", notation),
+            Column::Stddev => format!("Stddev {}", notation),
+            Column::Median => format!("Median {}", notation),
+            Column::Min => format!("Min {}", notation),
+            Column::Max => format!("Max {}", notation),
+            Column::User => format!("User {}", notation),
+            Column::System => format!("System {}", notation),
+            Column::Relative => String::from("Relative"),
+        }));
+        table.push_str(&self.table_row(&header.iter().map(|h| h.as_str()).collect::<Vec<_>>()));
 
         // emit horizontal line
         table.push_str(&self.table_divider(&cells_alignment));
@@ -44,34 +101,37 @@ pub trait MarkupExporter {
         for entry in entries {
             let measurement = &entry.result;
             // prepare data row strings
-            let cmd_str = measurement
-                .command_with_unused_parameters
-                .replace('|', "\\|");
-            let mean_str = format_duration_value(measurement.mean, Some(unit)).0;
-            let stddev_str = if let Some(stddev) = measurement.stddev {
-                format!(" ± {}", format_duration_value(stddev, Some(unit)).0)
-            } else {
-                "".into()
-            };
-            let min_str = format_duration_value(measurement.min, Some(unit)).0;
-            let max_str = format_duration_value(measurement.max, Some(unit)).0;
-            let rel_str = format!("{:.2}", entry.relative_speed);
-            let rel_stddev_str = if entry.is_fastest {
-                "".into()
-            } else if let Some(stddev) = entry.relative_speed_stddev {
-                format!(" ± {:.2}", stddev)
-            } else {
-                "".into()
-            };
+            let cmd_str = self.escape(&measurement.command_with_unused_parameters);
+            let duration = |value| format_duration_value(value, Some(unit)).0;
+            let stddev_str = measurement.stddev.map(duration);
+
+            let mut row = vec![self.command(&cmd_str)];
+            row.extend(columns.iter().map(|column| match column {
+                Column::Mean => match stddev_str {
+                    Some(ref stddev) if has_stddev => {
+                        format!("{} ± {}", duration(measurement.mean), stddev)
+                    }
+                    _ => duration(measurement.mean),
+                },
+                Column::Stddev => stddev_str.clone().unwrap_or_default(),
+                Column::Median => duration(measurement.median),
+                Column::Min => duration(measurement.min),
+                Column::Max => duration(measurement.max),
+                Column::User => duration(measurement.user),
+                Column::System => duration(measurement.system),
+                Column::Relative => {
+                    let rel_str = format!("{:.2}", entry.relative_speed);
+                    match entry.relative_speed_stddev {
+                        Some(stddev) if !entry.is_fastest => {
+                            format!("{} ± {:.2}", rel_str, stddev)
+                        }
+                        _ => rel_str,
+                    }
+                }
+            }));
 
             // prepare table row entries
-            table.push_str(&self.table_row(&[
-                &self.command(&cmd_str),
-                &format!("{}{}", mean_str, stddev_str),
-                &min_str,
-                &max_str,
-                &format!("{}{}", rel_str, rel_stddev_str),
-            ]))
+            table.push_str(&self.table_row(&row.iter().map(|c| c.as_str()).collect::<Vec<_>>()))
         }
 
         // emit table footer format
@@ -80,6 +140,9 @@ pub trait MarkupExporter {
         table
     }
 
+    /// The columns that are included in the table
+    fn columns(&self) -> &Columns;
+
     fn table_row(&self, cells: &[&str]) -> String;
 
     fn table_divider(&self, cell_aligmnents: &[Alignment]) -> String;
@@ -93,9 +156,14 @@ pub trait MarkupExporter {
     }
 
     fn command(&self, size: &str) -> String;
+
+    /// Escape characters in the command that have a special meaning in the markup
+    fn escape(&self, text: &str) -> String {
+        text.replace('|', "\\|")
+    }
 }
 
-fn determine_unit_from_results(results: &[BenchmarkResult]) -> Unit {
+pub fn determine_unit_from_results(results: &[BenchmarkResult]) -> Unit {
     if let Some(first_result) = results.first() {
         // Use the first BenchmarkResult entry to determine the unit for all entries.
         format_duration_value(first_result.mean, None).1
@@ -122,8 +190,8 @@ impl<T: MarkupExporter> Exporter for T {
 
 /// Check unit resolving for timing results and given unit 's'
 #[test]
+#[allow(clippy::unnecessary_literal_unwrap)]
 fn test_determine_unit_from_results_unit_given_s() {
-    use std::collections::BTreeMap;
     let results = vec![
         BenchmarkResult {
             command: String::from("sleep 2"),
@@ -137,7 +205,7 @@ fn test_determine_unit_from_results_unit_given_s() {
             max: 2.0080,
             times: Some(vec![2.0, 2.0, 2.0]),
             exit_codes: vec![Some(0), Some(0), Some(0)],
-            parameters: BTreeMap::new(),
+            ..Default::default()
         },
         BenchmarkResult {
             command: String::from("sleep 0.1"),
@@ -151,7 +219,7 @@ fn test_determine_unit_from_results_unit_given_s() {
             max: 0.1080,
             times: Some(vec![0.1, 0.1, 0.1]),
             exit_codes: vec![Some(0), Some(0), Some(0)],
-            parameters: BTreeMap::new(),
+            ..Default::default()
         },
     ];
     let unit = Some(Unit::Second);
@@ -164,8 +232,8 @@ fn test_determine_unit_from_results_unit_given_s() {
 
 /// Check unit resolving for timing results and given unit 'ms'
 #[test]
+#[allow(clippy::unnecessary_literal_unwrap)]
 fn test_determine_unit_from_results_unit_given_ms() {
-    use std::collections::BTreeMap;
     let results = vec![
         BenchmarkResult {
             command: String::from("sleep 2"),
@@ -179,7 +247,7 @@ fn test_determine_unit_from_results_unit_given_ms() {
             max: 2.0080,
             times: Some(vec![2.0, 2.0, 2.0]),
             exit_codes: vec![Some(0), Some(0), Some(0)],
-            parameters: BTreeMap::new(),
+            ..Default::default()
         },
         BenchmarkResult {
             command: String::from("sleep 0.1"),
@@ -193,7 +261,7 @@ fn test_determine_unit_from_results_unit_given_ms() {
             max: 0.1080,
             times: Some(vec![0.1, 0.1, 0.1]),
             exit_codes: vec![Some(0), Some(0), Some(0)],
-            parameters: BTreeMap::new(),
+            ..Default::default()
         },
     ];
     let unit = Some(Unit::MilliSecond);
@@ -206,8 +274,8 @@ fn test_determine_unit_from_results_unit_given_ms() {
 
 /// Check unit resolving for timing results using the first result entry as 's'
 #[test]
+#[allow(clippy::unnecessary_literal_unwrap)]
 fn test_determine_unit_from_results_unit_first_s() {
-    use std::collections::BTreeMap;
     let results = vec![
         BenchmarkResult {
             command: String::from("sleep 2"),
@@ -221,7 +289,7 @@ fn test_determine_unit_from_results_unit_first_s() {
             max: 2.0080,
             times: Some(vec![2.0, 2.0, 2.0]),
             exit_codes: vec![Some(0), Some(0), Some(0)],
-            parameters: BTreeMap::new(),
+            ..Default::default()
         },
         BenchmarkResult {
             command: String::from("sleep 0.1"),
@@ -235,7 +303,7 @@ fn test_determine_unit_from_results_unit_first_s() {
             max: 0.1080,
             times: Some(vec![0.1, 0.1, 0.1]),
             exit_codes: vec![Some(0), Some(0), Some(0)],
-            parameters: BTreeMap::new(),
+            ..Default::default()
         },
     ];
     let unit = None;
@@ -248,8 +316,8 @@ fn test_determine_unit_from_results_unit_first_s() {
 
 /// Check unit resolving for timing results using the first result entry as 'ms'
 #[test]
+#[allow(clippy::unnecessary_literal_unwrap)]
 fn test_determine_unit_from_results_unit_first_ms() {
-    use std::collections::BTreeMap;
     let results = vec![
         BenchmarkResult {
             command: String::from("sleep 0.1"),
@@ -263,7 +331,7 @@ fn test_determine_unit_from_results_unit_first_ms() {
             max: 0.1080,
             times: Some(vec![0.1, 0.1, 0.1]),
             exit_codes: vec![Some(0), Some(0), Some(0)],
-            parameters: BTreeMap::new(),
+            ..Default::default()
         },
         BenchmarkResult {
             command: String::from("sleep 2"),
@@ -277,7 +345,7 @@ fn test_determine_unit_from_results_unit_first_ms() {
             max: 2.0080,
             times: Some(vec![2.0, 2.0, 2.0]),
             exit_codes: vec![Some(0), Some(0), Some(0)],
-            parameters: BTreeMap::new(),
+            ..Default::default()
         },
     ];
     let unit = None;
@@ -290,6 +358,7 @@ fn test_determine_unit_from_results_unit_first_ms() {
 
 /// Check unit resolving for not timing results and no given unit defaulting to 's'
 #[test]
+#[allow(clippy::unnecessary_literal_unwrap)]
 fn test_determine_unit_from_results_unit_default_s() {
     let results: Vec<BenchmarkResult> = vec![];
     let unit = None;
@@ -299,3 +368,62 @@ fn test_determine_unit_from_results_unit_default_s() {
 
     assert_eq!(markup_expected, markup_actual);
 }
+
+/// Check that the selected columns are exported, with the standard deviation
+/// in a separate column if the mean is not selected
+#[test]
+fn test_table_results_columns() {
+    use super::markdown::MarkdownExporter;
+
+    let exporter = MarkdownExporter::new(Columns(vec![
+        Column::Median,
+        Column::Stddev,
+        Column::User,
+        Column::System,
+    ]));
+    let results = vec![BenchmarkResult {
+        command: String::from("sleep 0.1"),
+        command_with_unused_parameters: String::from("sleep 0.1"),
+        mean: 0.1057,
+        stddev: Some(0.0016),
+        median: 0.1050,
+        user: 0.0009,
+        system: 0.0011,
+        min: 0.1023,
+        max: 0.1080,
+        times: Some(vec![0.1, 0.1, 0.1]),
+        perf_counters: std::collections::BTreeMap::new(),
+        exit_codes: vec![Some(0), Some(0), Some(0)],
+        parameters: std::collections::BTreeMap::new(),
+        ..Default::default()
+    }];
+
+    let actual = String::from_utf8(
+        exporter
+            .serialize(&results, Some(Unit::MilliSecond), SortOrder::Command)
+            .unwrap(),
+    )
+    .unwrap();
+    let expect = "\
+| Command | Median [ms] | Stddev [ms] | User [ms] | System [ms] |
+|:---|---:|---:|---:|---:|
+| `sleep 0.1` | 105.0 | 1.6 | 0.9 | 1.1 |
+";
+
+    assert_eq!(expect, actual);
+
+    let exporter = MarkdownExporter::new(Columns(vec![Column::Mean, Column::Relative]));
+    let actual = String::from_utf8(
+        exporter
+            .serialize(&results, Some(Unit::MilliSecond), SortOrder::Command)
+            .unwrap(),
+    )
+    .unwrap();
+    let expect = "\
+| Command | Mean [ms] | Relative |
+|:---|---:|---:|
+| `sleep 0.1` | 105.7 | 1.00 |
+";
+
+    assert_eq!(expect, actual);
+}
@@ -0,0 +1,124 @@
+use serde::Serialize;
+
+/// Information about the system and the hyperfine invocation, which is included in
+/// the exports if `--export-metadata` is given.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Metadata {
+    /// Version of hyperfine
+    pub hyperfine_version: &'static str,
+
+    /// The full command line of the hyperfine invocation
+    pub command_line: Vec<String>,
+
+    pub hostname: Option<String>,
+
+    /// Operating system, as reported by Rust (e.g. "linux" or "macos")
+    pub os: &'static str,
+
+    /// Name and release of the kernel
+    pub kernel: Option<String>,
+
+    pub arch: &'static str,
+
+    pub cpu_model: Option<String>,
+
+    /// Number of logical CPU cores available to hyperfine
+    pub cpu_count: usize,
+}
+
+impl Metadata {
+    /// Gather the metadata of the current system
+    pub fn collect() -> Self {
+        Metadata {
+            hyperfine_version: env!("CARGO_PKG_VERSION"),
+            command_line: std::env::args_os()
+                .map(|arg| arg.to_string_lossy().into_owned())
+                .collect(),
+            hostname: hostname(),
+            os: std::env::consts::OS,
+            kernel: kernel(),
+            arch: std::env::consts::ARCH,
+            cpu_model: cpu_model(),
+            cpu_count: std::thread::available_parallelism().map_or(1, |n| n.get()),
+        }
+    }
+}
+
+/// Convert a NUL-terminated C string buffer
+#[cfg(unix)]
+fn from_c_chars(chars: &[libc::c_char]) -> String {
+    let bytes: Vec<u8> = chars
+        .iter()
+        .take_while(|&&c| c != 0)
+        .map(|&c| c as u8)
+        .collect();
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+#[cfg(unix)]
+fn hostname() -> Option<String> {
+    let mut buffer = [0 as libc::c_char; 256];
+    let result = unsafe { libc::gethostname(buffer.as_mut_ptr(), buffer.len()) };
+    (result == 0).then(|| from_c_chars(&buffer))
+}
+
+#[cfg(windows)]
+fn hostname() -> Option<String> {
+    std::env::var("COMPUTERNAME").ok()
+}
+
+#[cfg(unix)]
+fn kernel() -> Option<String> {
+    let mut name = std::mem::MaybeUninit::<libc::utsname>::uninit();
+    if unsafe { libc::uname(name.as_mut_ptr()) } != 0 {
+        return None;
+    }
+    let name = unsafe { name.assume_init() };
+    Some(format!(
+        "{} {}",
+        from_c_chars(&name.sysname),
+        from_c_chars(&name.release)
+    ))
+}
+
+#[cfg(windows)]
+fn kernel() -> Option<String> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+fn cpu_model() -> Option<String> {
+    let cpuinfo = std::fs::read_to_string("/proc/cpuinfo").ok()?;
+    cpuinfo
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(key, _)| matches!(key.trim(), "model name" | "Hardware" | "cpu model"))
+        .map(|(_, value)| value.trim().to_string())
+}
+
+#[cfg(target_os = "macos")]
+fn cpu_model() -> Option<String> {
+    let output = std::process::Command::new("sysctl")
+        .args(["-n", "machdep.cpu.brand_string"])
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn cpu_model() -> Option<String> {
+    None
+}
+
+#[test]
+fn test_collect_metadata() {
+    let metadata = Metadata::collect();
+
+    assert_eq!(metadata.hyperfine_version, env!("CARGO_PKG_VERSION"));
+    assert!(!metadata.command_line.is_empty());
+    assert!(metadata.cpu_count >= 1);
+    assert!(!metadata.os.is_empty());
+}
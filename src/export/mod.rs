@@ -1,43 +1,150 @@
+use std::collections::BTreeMap;
 use std::fs::{File, OpenOptions};
-use std::io::Write;
+use std::io::{self, Read, Write};
 
 mod asciidoc;
+mod bmf;
+mod criterion;
 mod csv;
+mod gnuplot;
+mod googlebenchmark;
+mod html;
+mod influxdb;
 mod json;
+mod junit;
+mod latex;
 mod markdown;
 mod markup;
+mod metadata;
+mod msgpack;
+mod ndjson;
 mod orgmode;
+mod parquet;
+mod prometheus;
+mod sqlite;
+mod template;
+mod timeline;
+mod vega;
+mod xml;
+mod yaml;
 
 use self::asciidoc::AsciidocExporter;
-use self::csv::CsvExporter;
+use self::bmf::BmfExporter;
+use self::criterion::CriterionExporter;
+use self::csv::{CsvExporter, WideCsvExporter};
+use self::gnuplot::GnuplotExporter;
+use self::googlebenchmark::GoogleBenchmarkExporter;
+use self::html::HtmlExporter;
+use self::influxdb::InfluxdbExporter;
 use self::json::JsonExporter;
+pub use self::json::JSON_SCHEMA;
+use self::junit::JunitExporter;
+use self::latex::LatexExporter;
 use self::markdown::MarkdownExporter;
+use self::markup::{Column, Columns};
+use self::metadata::Metadata;
+use self::msgpack::MsgpackExporter;
+use self::ndjson::NdjsonExporter;
 use self::orgmode::OrgmodeExporter;
+use self::parquet::ParquetExporter;
+use self::prometheus::PrometheusExporter;
+use self::sqlite::SqliteExporter;
+use self::template::TemplateExporter;
+use self::timeline::TimelineExporter;
+use self::vega::VegaExporter;
+use self::xml::XmlExporter;
+use self::yaml::YamlExporter;
 
 use crate::benchmark::benchmark_result::BenchmarkResult;
-use crate::options::SortOrder;
-use crate::util::units::Unit;
+use crate::benchmark::comparison_matrix;
+use crate::benchmark::timing_result::TimingResult;
+use crate::error::OptionsError;
+use crate::options::{Options, SortOrder};
+use crate::util::system_state::SystemState;
+use crate::util::units::{Scalar, Second, Unit};
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use clap::ArgMatches;
+use flate2::write::GzEncoder;
+use flate2::Compression;
 
 /// The desired form of exporter to use for a given file.
 #[derive(Clone)]
 pub enum ExportType {
-    /// Asciidoc Table
-    Asciidoc,
+    /// Asciidoc Table, with the given columns
+    Asciidoc(Columns),
 
-    /// CSV (comma separated values) format
-    Csv,
+    /// Bencher Metric Format (JSON)
+    Bmf,
 
-    /// JSON format
-    Json,
+    /// Directory layout of Criterion.rs
+    Criterion,
 
-    /// Markdown table
-    Markdown,
+    /// CSV (comma separated values) format, with the given delimiter
+    Csv(u8),
+
+    /// CSV format with one column per command and one row per run, with the given delimiter
+    CsvWide(u8),
+
+    /// Gnuplot script with a data file of the run times
+    Gnuplot,
+
+    /// JSON format of Google Benchmark
+    GoogleBenchmark,
+
+    /// Standalone HTML report
+    Html,
+
+    /// InfluxDB line protocol
+    Influxdb,
+
+    /// JSON format with optional metadata, merged into the existing file if the flag is set,
+    /// and with the comparison matrix if it was requested
+    Json(Option<Metadata>, bool, Option<comparison_matrix::Settings>),
+
+    /// JUnit XML test report, with an optional threshold for the mean time
+    Junit(Option<Second>),
+
+    /// LaTeX table, using the booktabs package
+    Latex(Columns),
+
+    /// Markdown table, with the given columns
+    Markdown(Columns),
+
+    /// MessagePack format, with the same schema as the JSON export
+    Msgpack(Option<Metadata>),
+
+    /// Newline delimited JSON, with one line per run
+    Ndjson,
 
     /// Emacs org-mode tables
-    Orgmode,
+    Orgmode(Columns),
+
+    /// Apache Parquet format, with one row per run
+    Parquet,
+
+    /// Prometheus text exposition format
+    Prometheus,
+
+    /// SQLite database
+    Sqlite,
+
+    /// Custom format, rendered through the Handlebars template at the given path, with
+    /// confidence intervals of the relative speeds at the given level and optionally
+    /// without the outliers
+    Template(String, Scalar, bool),
+
+    /// HTML timeline of all runs
+    Timeline,
+
+    /// Vega-Lite specification with embedded data
+    Vega,
+
+    /// XML format
+    Xml,
+
+    /// YAML format
+    Yaml(Option<Metadata>),
 }
 
 /// Interface for different exporters.
@@ -49,6 +156,75 @@ trait Exporter {
         unit: Option<Unit>,
         sort_order: SortOrder,
     ) -> Result<Vec<u8>>;
+
+    /// Prepare the export file before any benchmarks are run. By default, the file
+    /// is created (or truncated, if it already exists).
+    fn create_file(&mut self, filename: &str) -> Result<()> {
+        File::create(filename)
+            .with_context(|| format!("Could not create export file '{}'", filename))?;
+        Ok(())
+    }
+
+    /// Write the given entries to the export file. By default, the file is overwritten
+    /// with the serialized form of the entries.
+    fn write_to_file(
+        &self,
+        filename: &str,
+        results: &[BenchmarkResult],
+        unit: Option<Unit>,
+        sort_order: SortOrder,
+    ) -> Result<()> {
+        write_to_file(filename, &self.serialize(results, unit, sort_order)?)
+    }
+
+    /// Write the result of a single run to the export file, while the benchmark is still
+    /// in progress. By default, nothing is written.
+    fn write_run(&self, _filename: &str, _run: &RunResult) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// The phase of a benchmark in which a command was run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunKind {
+    /// Warmup run of the benchmarked command
+    Warmup,
+
+    /// Run of the command specified by `--prepare`
+    Preparation,
+
+    /// Timing run of the benchmarked command
+    Timing,
+}
+
+/// The measurements of a single run
+pub struct RunResult<'a> {
+    /// The name of the command that is being benchmarked
+    pub command: &'a str,
+
+    /// Parameter values for this benchmark
+    pub parameters: &'a BTreeMap<String, String>,
+
+    /// Whether this was a warmup, preparation or timing run
+    pub kind: RunKind,
+
+    /// Index of the run, starting at zero for each kind of run
+    pub run: usize,
+
+    /// Start of the run, in seconds since the Unix epoch
+    pub timestamp: f64,
+
+    /// Load average and available memory of the system at the start of the run
+    pub system_state: SystemState,
+
+    /// Wall clock, user and system time of the run
+    pub timing: TimingResult,
+
+    /// Exit code of the command
+    pub exit_code: Option<i32>,
+
+    /// Signal that terminated the command, if any
+    pub signal: Option<i32>,
 }
 
 pub enum ExportTarget {
@@ -71,11 +247,42 @@ pub struct ExportManager {
 impl ExportManager {
     /// Build the ExportManager that will export the results specified
     /// in the given ArgMatches
-    pub fn from_cli_arguments(matches: &ArgMatches, time_unit: Option<Unit>) -> Result<Self> {
+    pub fn from_cli_arguments(matches: &ArgMatches, options: &Options) -> Result<Self> {
         let mut export_manager = Self {
             exporters: vec![],
-            time_unit,
+            time_unit: options.time_unit,
+        };
+        let junit_threshold = matches
+            .get_one::<String>("junit-threshold")
+            .map(|t| {
+                t.parse::<Second>()
+                    .map_err(|e| OptionsError::FloatParsingError("junit-threshold", e))
+            })
+            .transpose()?;
+        let csv_delimiter = match matches
+            .get_one::<String>("csv-delimiter")
+            .map(|d| d.as_str())
+        {
+            None => b',',
+            Some("\\t") => b'\t',
+            Some(d) if d.len() == 1 && d.is_ascii() => d.as_bytes()[0],
+            Some(d) => return Err(OptionsError::InvalidCsvDelimiter(d.to_string()).into()),
         };
+        let columns = matches
+            .get_many::<String>("export-columns")
+            .map(|names| {
+                Columns(
+                    names
+                        .map(|name| Column::from_name(name).expect("validated by clap"))
+                        .collect(),
+                )
+            })
+            .unwrap_or_default();
+        let metadata = matches.get_flag("export-metadata").then(Metadata::collect);
+        let append = matches.get_flag("export-append");
+        let comparison_matrix = options
+            .comparison_matrix
+            .then(|| comparison_matrix::Settings::from_options(options));
         {
             let mut add_exporter = |flag, exporttype| -> Result<()> {
                 if let Some(filename) = matches.get_one::<String>(flag) {
@@ -83,39 +290,125 @@ impl ExportManager {
                 }
                 Ok(())
             };
-            add_exporter("export-asciidoc", ExportType::Asciidoc)?;
-            add_exporter("export-json", ExportType::Json)?;
-            add_exporter("export-csv", ExportType::Csv)?;
-            add_exporter("export-markdown", ExportType::Markdown)?;
-            add_exporter("export-orgmode", ExportType::Orgmode)?;
+            add_exporter("export-asciidoc", ExportType::Asciidoc(columns.clone()))?;
+            add_exporter("export-bmf", ExportType::Bmf)?;
+            add_exporter("export-gnuplot", ExportType::Gnuplot)?;
+            add_exporter("export-google-benchmark", ExportType::GoogleBenchmark)?;
+            add_exporter("export-html", ExportType::Html)?;
+            add_exporter("export-influxdb", ExportType::Influxdb)?;
+            add_exporter(
+                "export-json",
+                ExportType::Json(metadata.clone(), append, comparison_matrix),
+            )?;
+            add_exporter("export-junit", ExportType::Junit(junit_threshold))?;
+            add_exporter("export-criterion", ExportType::Criterion)?;
+            add_exporter("export-csv", ExportType::Csv(csv_delimiter))?;
+            add_exporter("export-csv-wide", ExportType::CsvWide(csv_delimiter))?;
+            add_exporter("export-tsv", ExportType::Csv(b'\t'))?;
+            add_exporter("export-latex", ExportType::Latex(columns.clone()))?;
+            add_exporter("export-markdown", ExportType::Markdown(columns.clone()))?;
+            add_exporter("export-msgpack", ExportType::Msgpack(metadata.clone()))?;
+            add_exporter("export-ndjson", ExportType::Ndjson)?;
+            add_exporter("export-orgmode", ExportType::Orgmode(columns.clone()))?;
+            add_exporter("export-parquet", ExportType::Parquet)?;
+            add_exporter("export-prometheus", ExportType::Prometheus)?;
+            add_exporter("export-sqlite", ExportType::Sqlite)?;
+            add_exporter("export-timeline", ExportType::Timeline)?;
+            add_exporter("export-vega", ExportType::Vega)?;
+            add_exporter("export-xml", ExportType::Xml)?;
+            add_exporter("export-yaml", ExportType::Yaml(metadata.clone()))?;
+        }
+        if let Some(mut values) = matches.get_many::<String>("export-template") {
+            let template = values.next().unwrap();
+            let filename = values.next().unwrap();
+            export_manager.add_exporter(
+                ExportType::Template(
+                    template.clone(),
+                    options.confidence_level,
+                    options.discard_outliers,
+                ),
+                filename,
+            )?;
         }
         Ok(export_manager)
     }
 
     /// Add an additional exporter to the ExportManager
     pub fn add_exporter(&mut self, export_type: ExportType, filename: &str) -> Result<()> {
-        let exporter: Box<dyn Exporter> = match export_type {
-            ExportType::Asciidoc => Box::<AsciidocExporter>::default(),
-            ExportType::Csv => Box::<CsvExporter>::default(),
-            ExportType::Json => Box::<JsonExporter>::default(),
-            ExportType::Markdown => Box::<MarkdownExporter>::default(),
-            ExportType::Orgmode => Box::<OrgmodeExporter>::default(),
+        let mut exporter: Box<dyn Exporter> = match export_type {
+            ExportType::Asciidoc(columns) => Box::new(AsciidocExporter::new(columns)),
+            ExportType::Bmf => Box::<BmfExporter>::default(),
+            ExportType::Criterion if filename == "-" => {
+                bail!("The Criterion export can not be written to stdout")
+            }
+            ExportType::Criterion => Box::<CriterionExporter>::default(),
+            ExportType::Csv(delimiter) => Box::new(CsvExporter::new(delimiter)),
+            ExportType::CsvWide(delimiter) => Box::new(WideCsvExporter::new(delimiter)),
+            ExportType::Gnuplot if filename == "-" => {
+                bail!("The gnuplot export can not be written to stdout")
+            }
+            ExportType::Gnuplot => Box::<GnuplotExporter>::default(),
+            ExportType::GoogleBenchmark => Box::<GoogleBenchmarkExporter>::default(),
+            ExportType::Html => Box::<HtmlExporter>::default(),
+            ExportType::Influxdb => Box::<InfluxdbExporter>::default(),
+            ExportType::Json(metadata, append, comparison_matrix) => {
+                Box::new(JsonExporter::new(metadata, append, comparison_matrix))
+            }
+            ExportType::Junit(threshold) => Box::new(JunitExporter::new(threshold)),
+            ExportType::Latex(columns) => Box::new(LatexExporter::new(columns)),
+            ExportType::Markdown(columns) => Box::new(MarkdownExporter::new(columns)),
+            ExportType::Msgpack(_) if filename == "-" => {
+                bail!("The MessagePack export can not be written to stdout")
+            }
+            ExportType::Msgpack(metadata) => Box::new(MsgpackExporter::new(metadata)),
+            ExportType::Ndjson if filename == "-" => {
+                bail!("The NDJSON export can not be written to stdout")
+            }
+            ExportType::Ndjson => Box::<NdjsonExporter>::default(),
+            ExportType::Orgmode(columns) => Box::new(OrgmodeExporter::new(columns)),
+            ExportType::Parquet if filename == "-" => {
+                bail!("The Parquet export can not be written to stdout")
+            }
+            ExportType::Parquet => Box::<ParquetExporter>::default(),
+            ExportType::Prometheus => Box::<PrometheusExporter>::default(),
+            ExportType::Sqlite if filename == "-" => {
+                bail!("The SQLite export can not be written to stdout")
+            }
+            ExportType::Sqlite => Box::<SqliteExporter>::default(),
+            ExportType::Template(template, confidence_level, discard_outliers) => Box::new(
+                TemplateExporter::new(&template, confidence_level, discard_outliers)?,
+            ),
+            ExportType::Timeline => Box::<TimelineExporter>::default(),
+            ExportType::Vega => Box::<VegaExporter>::default(),
+            ExportType::Xml => Box::<XmlExporter>::default(),
+            ExportType::Yaml(metadata) => Box::new(YamlExporter::new(metadata)),
         };
 
-        self.exporters.push(ExporterWithTarget {
-            exporter,
-            target: if filename == "-" {
-                ExportTarget::Stdout
-            } else {
-                let _ = File::create(filename)
-                    .with_context(|| format!("Could not create export file '{}'", filename))?;
-                ExportTarget::File(filename.to_string())
-            },
-        });
+        let target = if filename == "-" {
+            ExportTarget::Stdout
+        } else {
+            exporter.create_file(filename)?;
+            ExportTarget::File(filename.to_string())
+        };
+
+        self.exporters.push(ExporterWithTarget { exporter, target });
 
         Ok(())
     }
 
+    /// Pass the result of a single run to all exporters. Stdout targets receive "-" as
+    /// the file name, such that they can collect the runs for the final output.
+    pub fn write_run(&self, run: &RunResult) -> Result<()> {
+        for e in &self.exporters {
+            let filename = match e.target {
+                ExportTarget::File(ref filename) => filename,
+                ExportTarget::Stdout => "-",
+            };
+            e.exporter.write_run(filename, run)?;
+        }
+        Ok(())
+    }
+
     /// Write the given results to all Exporters. The 'intermediate' flag specifies
     /// whether this is being called while still performing benchmarks, or if this
     /// is the final call after all benchmarks have been finished. In the former case,
@@ -134,13 +427,13 @@ impl ExportManager {
             match e.target {
                 ExportTarget::File(ref filename) => {
                     if intermediate {
-                        write_to_file(filename, &content()?)?
+                        e.exporter
+                            .write_to_file(filename, results, self.time_unit, sort_order)?
                     }
                 }
                 ExportTarget::Stdout => {
                     if !intermediate {
-                        println!();
-                        println!("{}", String::from_utf8(content()?).unwrap());
+                        io::stdout().write_all(&content()?)?;
                     }
                 }
             }
@@ -149,9 +442,61 @@ impl ExportManager {
     }
 }
 
-/// Write the given content to a file with the specified name
+/// Write the given content to a file with the specified name. The content is compressed
+/// if the file name ends in `.gz` (gzip) or `.zst` (Zstandard).
 fn write_to_file(filename: &str, content: &[u8]) -> Result<()> {
-    let mut file = OpenOptions::new().write(true).open(filename)?;
-    file.write_all(content)
+    let file = OpenOptions::new()
+        .write(true)
+        .truncate(true)
+        .open(filename)?;
+    write_compressed(file, filename, content)
         .with_context(|| format!("Failed to export results to '{}'", filename))
 }
+
+/// Read the content of a file that has been written by `write_to_file`
+fn read_from_file(filename: &str) -> Result<String> {
+    let file = File::open(filename)?;
+    let mut content = String::new();
+    if filename.ends_with(".gz") {
+        flate2::read::GzDecoder::new(file).read_to_string(&mut content)?;
+    } else if filename.ends_with(".zst") {
+        zstd::stream::Decoder::new(file)?.read_to_string(&mut content)?;
+    } else {
+        io::BufReader::new(file).read_to_string(&mut content)?;
+    }
+    Ok(content)
+}
+
+fn write_compressed<W: Write>(mut writer: W, filename: &str, content: &[u8]) -> io::Result<()> {
+    if filename.ends_with(".gz") {
+        let mut encoder = GzEncoder::new(writer, Compression::default());
+        encoder.write_all(content)?;
+        encoder.finish()?;
+    } else if filename.ends_with(".zst") {
+        zstd::stream::copy_encode(content, writer, 0)?;
+    } else {
+        writer.write_all(content)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn test_write_compressed() {
+    let content = b"{\"results\": []}\n";
+
+    let mut plain = vec![];
+    write_compressed(&mut plain, "results.json", content).unwrap();
+    assert_eq!(plain, content);
+
+    let mut gzip = vec![];
+    write_compressed(&mut gzip, "results.json.gz", content).unwrap();
+    let mut decoded = vec![];
+    flate2::read::GzDecoder::new(&gzip[..])
+        .read_to_end(&mut decoded)
+        .unwrap();
+    assert_eq!(decoded, content);
+
+    let mut zst = vec![];
+    write_compressed(&mut zst, "results.json.zst", content).unwrap();
+    assert_eq!(zstd::decode_all(&zst[..]).unwrap(), content);
+}
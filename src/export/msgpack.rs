@@ -0,0 +1,87 @@
+use rmp_serde::to_vec_named;
+
+use super::json::{HyperfineSummary, SCHEMA_VERSION};
+use super::metadata::Metadata;
+use super::Exporter;
+use crate::benchmark::benchmark_result::BenchmarkResult;
+use crate::options::SortOrder;
+use crate::util::units::Unit;
+
+use anyhow::Result;
+
+/// Exports the results in the MessagePack format, using the same schema as the JSON
+/// export. Field names are included, such that the data can be decoded into maps.
+#[derive(Default)]
+pub struct MsgpackExporter {
+    metadata: Option<Metadata>,
+}
+
+impl MsgpackExporter {
+    pub fn new(metadata: Option<Metadata>) -> Self {
+        MsgpackExporter { metadata }
+    }
+}
+
+impl Exporter for MsgpackExporter {
+    fn serialize(
+        &self,
+        results: &[BenchmarkResult],
+        _unit: Option<Unit>,
+        _sort_order: SortOrder,
+    ) -> Result<Vec<u8>> {
+        Ok(to_vec_named(&HyperfineSummary {
+            schema_version: SCHEMA_VERSION,
+            metadata: self.metadata.as_ref(),
+            results,
+            comparison_matrix: None,
+        })?)
+    }
+}
+
+#[test]
+fn test_msgpack() {
+    use std::collections::BTreeMap;
+    let exporter = MsgpackExporter::default();
+
+    let results = vec![BenchmarkResult {
+        command: String::from("sleep 0.1"),
+        command_with_unused_parameters: String::from("sleep 0.1"),
+        mean: 0.1,
+        median: 0.1,
+        user: 0.001,
+        system: 0.002,
+        min: 0.1,
+        max: 0.1,
+        times: Some(vec![0.1]),
+        exit_codes: vec![Some(0)],
+        parameters: {
+            let mut params = BTreeMap::new();
+            params.insert("foo".into(), "one".into());
+            params
+        },
+        ..Default::default()
+    }];
+
+    let actual = exporter
+        .serialize(&results, None, SortOrder::Command)
+        .unwrap();
+    let actual: serde_json::Value = rmp_serde::from_slice(&actual).unwrap();
+
+    let expected = serde_json::json!({
+        "schema_version": 1,
+        "results": [{
+            "command": "sleep 0.1",
+            "mean": 0.1,
+            "stddev": null,
+            "median": 0.1,
+            "user": 0.001,
+            "system": 0.002,
+            "min": 0.1,
+            "max": 0.1,
+            "times": [0.1],
+            "exit_codes": [0],
+            "parameters": { "foo": "one" },
+        }]
+    });
+    assert_eq!(expected, actual);
+}
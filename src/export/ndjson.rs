@@ -0,0 +1,212 @@
+use std::collections::BTreeMap;
+use std::fs::OpenOptions;
+use std::io::Write;
+
+use serde::Serialize;
+
+use super::{Exporter, RunKind, RunResult};
+use crate::benchmark::benchmark_result::BenchmarkResult;
+use crate::benchmark::timing_result::{CpuFrequency, GpuUsage};
+use crate::options::SortOrder;
+use crate::util::units::{Second, Unit};
+
+use anyhow::{bail, Context, Result};
+
+#[derive(Serialize, Debug)]
+struct Run<'a> {
+    command: &'a str,
+    run: usize,
+    time: Second,
+    user: Second,
+    system: Second,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    memory_usage_byte: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    io_read_byte: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    io_write_byte: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    voluntary_context_switches: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    involuntary_context_switches: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    minor_page_faults: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    major_page_faults: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    peak_tasks: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    total_processes: Option<u64>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    perf_counters: BTreeMap<&'static str, u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    energy_joule: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cpu_frequency_mhz: Option<CpuFrequency>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    gpu_usage: Option<GpuUsage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    time_to_first_output: Option<Second>,
+    exit_code: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    signal: Option<i32>,
+    timestamp: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    load_average: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    available_memory_byte: Option<u64>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    parameters: &'a BTreeMap<String, String>,
+}
+
+/// Appends one JSON object per line for each timing run, as soon as the run has
+/// finished. Warmup runs are not included.
+#[derive(Default)]
+pub struct NdjsonExporter {}
+
+impl NdjsonExporter {
+    fn line(run: &RunResult) -> Result<Vec<u8>> {
+        let mut line = serde_json::to_vec(&Run {
+            command: run.command,
+            run: run.run,
+            time: run.timing.time_real,
+            user: run.timing.time_user,
+            system: run.timing.time_system,
+            memory_usage_byte: run.timing.memory_usage_byte,
+            io_read_byte: run.timing.io.map(|io| io.read_bytes),
+            io_write_byte: run.timing.io.map(|io| io.written_bytes),
+            voluntary_context_switches: run.timing.context_switches.map(|c| c.voluntary),
+            involuntary_context_switches: run.timing.context_switches.map(|c| c.involuntary),
+            minor_page_faults: run.timing.page_faults.map(|p| p.minor),
+            major_page_faults: run.timing.page_faults.map(|p| p.major),
+            peak_tasks: run.timing.peak_tasks,
+            total_processes: run.timing.total_processes,
+            perf_counters: run
+                .timing
+                .perf_counts
+                .iter()
+                .map(|(counter, value)| (counter.name(), value))
+                .collect(),
+            energy_joule: run.timing.energy_joule,
+            cpu_frequency_mhz: run.timing.cpu_frequency,
+            gpu_usage: run.timing.gpu_usage,
+            time_to_first_output: run.timing.time_to_first_output,
+            exit_code: run.exit_code,
+            signal: run.signal,
+            timestamp: run.timestamp,
+            load_average: run.system_state.load_average,
+            available_memory_byte: run.system_state.available_memory_byte,
+            parameters: run.parameters,
+        })?;
+        line.push(b'\n');
+        Ok(line)
+    }
+}
+
+impl Exporter for NdjsonExporter {
+    fn serialize(
+        &self,
+        _results: &[BenchmarkResult],
+        _unit: Option<Unit>,
+        _sort_order: SortOrder,
+    ) -> Result<Vec<u8>> {
+        bail!("The NDJSON export can only be written to a file")
+    }
+
+    fn write_to_file(
+        &self,
+        _filename: &str,
+        _results: &[BenchmarkResult],
+        _unit: Option<Unit>,
+        _sort_order: SortOrder,
+    ) -> Result<()> {
+        // All runs have already been written by `write_run`
+        Ok(())
+    }
+
+    fn write_run(&self, filename: &str, run: &RunResult) -> Result<()> {
+        if run.kind != RunKind::Timing {
+            return Ok(());
+        }
+        let mut file = OpenOptions::new().append(true).open(filename)?;
+        file.write_all(&Self::line(run)?)
+            .with_context(|| format!("Failed to export results to '{}'", filename))
+    }
+}
+
+#[test]
+fn test_ndjson_line() {
+    use crate::benchmark::timing_result::{ContextSwitches, IoCounters, PerfCounts, TimingResult};
+    use crate::options::PerfCounter;
+    use crate::util::system_state::SystemState;
+
+    let mut parameters = BTreeMap::new();
+    let timing = TimingResult {
+        time_real: 0.5,
+        time_real_raw: 0.5,
+        time_user: 0.25,
+        time_system: 0.125,
+        memory_usage_byte: None,
+        io: None,
+        context_switches: None,
+        page_faults: None,
+        peak_tasks: None,
+        total_processes: None,
+        perf_counts: PerfCounts::default(),
+        energy_joule: None,
+        cpu_frequency: None,
+        gpu_usage: None,
+        time_to_first_output: None,
+    };
+    let mut perf_counts = PerfCounts::default();
+    perf_counts.set(PerfCounter::Instructions, 1000);
+
+    let run = RunResult {
+        command: "sleep 0.5",
+        parameters: &parameters,
+        kind: RunKind::Timing,
+        run: 2,
+        timestamp: 1700000000.5,
+        system_state: SystemState::default(),
+        timing,
+        exit_code: Some(0),
+        signal: None,
+    };
+    assert_eq!(
+        String::from_utf8(NdjsonExporter::line(&run).unwrap()).unwrap(),
+        "{\"command\":\"sleep 0.5\",\"run\":2,\"time\":0.5,\"user\":0.25,\"system\":0.125,\"exit_code\":0,\"timestamp\":1700000000.5}\n"
+    );
+
+    parameters.insert("time".into(), "0.5".into());
+    let run = RunResult {
+        command: "sleep 0.5",
+        parameters: &parameters,
+        kind: RunKind::Timing,
+        run: 0,
+        timestamp: 1700000000.5,
+        system_state: SystemState {
+            load_average: Some(0.25),
+            available_memory_byte: Some(1 << 30),
+        },
+        timing: TimingResult {
+            memory_usage_byte: Some(4096),
+            io: Some(IoCounters {
+                read_bytes: 8192,
+                written_bytes: 0,
+            }),
+            context_switches: Some(ContextSwitches {
+                voluntary: 2,
+                involuntary: 1,
+            }),
+            peak_tasks: Some(5),
+            perf_counts,
+            ..timing
+        },
+        exit_code: Some(137),
+        signal: Some(9),
+    };
+    assert_eq!(
+        String::from_utf8(NdjsonExporter::line(&run).unwrap()).unwrap(),
+        "{\"command\":\"sleep 0.5\",\"run\":0,\"time\":0.5,\"user\":0.25,\"system\":0.125,\"memory_usage_byte\":4096,\"io_read_byte\":8192,\"io_write_byte\":0,\"voluntary_context_switches\":2,\"involuntary_context_switches\":1,\"peak_tasks\":5,\"perf_counters\":{\"instructions\":1000},\"exit_code\":137,\"signal\":9,\"timestamp\":1700000000.5,\"load_average\":0.25,\"available_memory_byte\":1073741824,\"parameters\":{\"time\":\"0.5\"}}\n"
+    );
+}
@@ -1,10 +1,22 @@
-use super::markup::Alignment;
+use super::markup::{Alignment, Columns};
 use crate::export::markup::MarkupExporter;
 
 #[derive(Default)]
-pub struct OrgmodeExporter {}
+pub struct OrgmodeExporter {
+    columns: Columns,
+}
+
+impl OrgmodeExporter {
+    pub fn new(columns: Columns) -> Self {
+        OrgmodeExporter { columns }
+    }
+}
 
 impl MarkupExporter for OrgmodeExporter {
+    fn columns(&self) -> &Columns {
+        &self.columns
+    }
+
     fn table_row(&self, cells: &[&str]) -> String {
         format!(
             "| {}  |  {} |\n",
@@ -73,7 +85,6 @@ fn cfg_test_table_header(unit_short_name: String) -> String {
 fn test_orgmode_format_ms() {
     use super::Exporter;
     use crate::benchmark::benchmark_result::BenchmarkResult;
-    use std::collections::BTreeMap;
 
     let exporter = OrgmodeExporter::default();
 
@@ -90,7 +101,7 @@ fn test_orgmode_format_ms() {
             max: 0.1080,
             times: Some(vec![0.1, 0.1, 0.1]),
             exit_codes: vec![Some(0), Some(0), Some(0)],
-            parameters: BTreeMap::new(),
+            ..Default::default()
         },
         BenchmarkResult {
             command: String::from("sleep 2"),
@@ -104,7 +115,7 @@ fn test_orgmode_format_ms() {
             max: 2.0080,
             times: Some(vec![2.0, 2.0, 2.0]),
             exit_codes: vec![Some(0), Some(0), Some(0)],
-            parameters: BTreeMap::new(),
+            ..Default::default()
         },
     ];
 
@@ -132,7 +143,6 @@ fn test_orgmode_format_s() {
     use super::Exporter;
     use crate::benchmark::benchmark_result::BenchmarkResult;
     use crate::util::units::Unit;
-    use std::collections::BTreeMap;
 
     let exporter = OrgmodeExporter::default();
 
@@ -149,7 +159,7 @@ fn test_orgmode_format_s() {
             max: 2.0080,
             times: Some(vec![2.0, 2.0, 2.0]),
             exit_codes: vec![Some(0), Some(0), Some(0)],
-            parameters: BTreeMap::new(),
+            ..Default::default()
         },
         BenchmarkResult {
             command: String::from("sleep 0.1"),
@@ -163,7 +173,7 @@ fn test_orgmode_format_s() {
             max: 0.1080,
             times: Some(vec![0.1, 0.1, 0.1]),
             exit_codes: vec![Some(0), Some(0), Some(0)],
-            parameters: BTreeMap::new(),
+            ..Default::default()
         },
     ];
 
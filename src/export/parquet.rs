@@ -0,0 +1,196 @@
+use std::collections::BTreeSet;
+use std::io::Write;
+use std::sync::Arc;
+
+use parquet::basic::{LogicalType, Repetition, Type as PhysicalType};
+use parquet::data_type::{ByteArray, ByteArrayType, DataType, DoubleType, Int32Type, Int64Type};
+use parquet::file::properties::WriterProperties;
+use parquet::file::writer::{SerializedFileWriter, SerializedRowGroupWriter};
+use parquet::schema::types::Type;
+
+use super::Exporter;
+use crate::benchmark::benchmark_result::BenchmarkResult;
+use crate::options::SortOrder;
+use crate::util::units::Unit;
+
+use anyhow::{Context, Result};
+
+/// Exports the individual runs in the columnar Apache Parquet format, with one row per
+/// run. Parameters are stored in one `parameter_<name>` column per parameter.
+#[derive(Default)]
+pub struct ParquetExporter {}
+
+fn column(name: &str, physical_type: PhysicalType, repetition: Repetition) -> Result<Arc<Type>> {
+    let logical_type = match physical_type {
+        PhysicalType::BYTE_ARRAY => Some(LogicalType::String),
+        _ => None,
+    };
+    Ok(Arc::new(
+        Type::primitive_type_builder(name, physical_type)
+            .with_repetition(repetition)
+            .with_logical_type(logical_type)
+            .build()?,
+    ))
+}
+
+/// Definition levels for an optional column: 1 if the value is present, 0 if not
+fn definition_levels<T>(values: &[Option<T>]) -> Vec<i16> {
+    values.iter().map(|v| i16::from(v.is_some())).collect()
+}
+
+/// Write the values of the next column in the schema
+fn write_column<T: DataType, W: Write + Send>(
+    row_group: &mut SerializedRowGroupWriter<'_, W>,
+    values: &[T::T],
+    definition_levels: Option<&[i16]>,
+) -> Result<()> {
+    let mut column = row_group
+        .next_column()?
+        .context("Unexpected end of the Parquet schema")?;
+    column
+        .typed::<T>()
+        .write_batch(values, definition_levels, None)?;
+    column.close()?;
+    Ok(())
+}
+
+impl Exporter for ParquetExporter {
+    fn serialize(
+        &self,
+        results: &[BenchmarkResult],
+        _unit: Option<Unit>,
+        _sort_order: SortOrder,
+    ) -> Result<Vec<u8>> {
+        let parameter_names: BTreeSet<&String> =
+            results.iter().flat_map(|r| r.parameters.keys()).collect();
+
+        let mut fields = vec![
+            column("command", PhysicalType::BYTE_ARRAY, Repetition::REQUIRED)?,
+            column("run", PhysicalType::INT64, Repetition::REQUIRED)?,
+            column("time", PhysicalType::DOUBLE, Repetition::REQUIRED)?,
+            column("exit_code", PhysicalType::INT32, Repetition::OPTIONAL)?,
+        ];
+        for name in &parameter_names {
+            fields.push(column(
+                &format!("parameter_{}", name),
+                PhysicalType::BYTE_ARRAY,
+                Repetition::OPTIONAL,
+            )?);
+        }
+        let schema = Type::group_type_builder("hyperfine")
+            .with_fields(fields)
+            .build()?;
+
+        let mut commands: Vec<ByteArray> = vec![];
+        let mut runs: Vec<i64> = vec![];
+        let mut times: Vec<f64> = vec![];
+        let mut exit_codes: Vec<Option<i32>> = vec![];
+        let mut parameters: Vec<Vec<Option<ByteArray>>> = vec![vec![]; parameter_names.len()];
+        for result in results {
+            for (run, &time) in result.times.iter().flatten().enumerate() {
+                commands.push(result.command.as_str().into());
+                runs.push(run as i64);
+                times.push(time);
+                exit_codes.push(result.exit_codes.get(run).copied().flatten());
+                for (values, name) in parameters.iter_mut().zip(&parameter_names) {
+                    values.push(result.parameters.get(*name).map(|v| v.as_str().into()));
+                }
+            }
+        }
+
+        let mut buffer = vec![];
+        let properties = Arc::new(WriterProperties::builder().build());
+        let mut writer = SerializedFileWriter::new(&mut buffer, Arc::new(schema), properties)
+            .context("Could not create the Parquet export")?;
+        let mut row_group = writer.next_row_group()?;
+
+        write_column::<ByteArrayType, _>(&mut row_group, &commands, None)?;
+        write_column::<Int64Type, _>(&mut row_group, &runs, None)?;
+        write_column::<DoubleType, _>(&mut row_group, &times, None)?;
+
+        let present: Vec<i32> = exit_codes.iter().flatten().copied().collect();
+        let levels = definition_levels(&exit_codes);
+        write_column::<Int32Type, _>(&mut row_group, &present, Some(&levels))?;
+
+        for values in &parameters {
+            let present: Vec<ByteArray> = values.iter().flatten().cloned().collect();
+            let levels = definition_levels(values);
+            write_column::<ByteArrayType, _>(&mut row_group, &present, Some(&levels))?;
+        }
+
+        row_group.close()?;
+        writer.close()?;
+
+        Ok(buffer)
+    }
+}
+
+#[test]
+fn test_parquet() {
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+    use parquet::record::Field;
+    use std::collections::BTreeMap;
+
+    let exporter = ParquetExporter::default();
+
+    let results = vec![
+        BenchmarkResult {
+            command: String::from("sleep 0.1"),
+            command_with_unused_parameters: String::from("sleep 0.1"),
+            mean: 0.1,
+            stddev: Some(0.01),
+            median: 0.1,
+            user: 0.0,
+            system: 0.0,
+            min: 0.09,
+            max: 0.11,
+            times: Some(vec![0.09, 0.11]),
+            exit_codes: vec![Some(0), None],
+            parameters: {
+                let mut params = BTreeMap::new();
+                params.insert("time".into(), "0.1".into());
+                params
+            },
+            ..Default::default()
+        },
+        BenchmarkResult {
+            command: String::from("true"),
+            command_with_unused_parameters: String::from("true"),
+            mean: 0.001,
+            median: 0.001,
+            user: 0.0,
+            system: 0.0,
+            min: 0.001,
+            max: 0.001,
+            times: Some(vec![0.001]),
+            exit_codes: vec![Some(1)],
+            ..Default::default()
+        },
+    ];
+
+    let actual = exporter
+        .serialize(&results, None, SortOrder::Command)
+        .unwrap();
+    let tempdir = tempfile::tempdir().unwrap();
+    let filename = tempdir.path().join("results.parquet");
+    std::fs::write(&filename, actual).unwrap();
+    let reader = SerializedFileReader::new(std::fs::File::open(filename).unwrap()).unwrap();
+
+    let rows: Vec<Vec<(String, Field)>> = reader
+        .get_row_iter(None)
+        .unwrap()
+        .map(|row| row.unwrap().into_columns())
+        .collect();
+    assert_eq!(rows.len(), 3);
+
+    let row = &rows[1];
+    assert_eq!(row[0], ("command".into(), Field::Str("sleep 0.1".into())));
+    assert_eq!(row[1], ("run".into(), Field::Long(1)));
+    assert_eq!(row[2], ("time".into(), Field::Double(0.11)));
+    assert_eq!(row[3], ("exit_code".into(), Field::Null));
+    assert_eq!(row[4], ("parameter_time".into(), Field::Str("0.1".into())));
+
+    let row = &rows[2];
+    assert_eq!(row[3], ("exit_code".into(), Field::Int(1)));
+    assert_eq!(row[4], ("parameter_time".into(), Field::Null));
+}
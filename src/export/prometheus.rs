@@ -0,0 +1,147 @@
+use std::fmt::Write;
+
+use super::Exporter;
+use crate::benchmark::benchmark_result::BenchmarkResult;
+use crate::options::SortOrder;
+use crate::util::units::{Second, Unit};
+
+use anyhow::Result;
+
+type MetricValue = fn(&BenchmarkResult) -> Option<Second>;
+
+/// Name, description and value of all exported metrics
+const METRICS: &[(&str, &str, MetricValue)] = &[
+    ("mean", "Mean wall clock time", |r| Some(r.mean)),
+    ("stddev", "Standard deviation of the wall clock time", |r| {
+        r.stddev
+    }),
+    ("median", "Median wall clock time", |r| Some(r.median)),
+    ("min", "Minimum wall clock time", |r| Some(r.min)),
+    ("max", "Maximum wall clock time", |r| Some(r.max)),
+    ("user", "Mean time spent in user mode", |r| Some(r.user)),
+    ("system", "Mean time spent in kernel mode", |r| {
+        Some(r.system)
+    }),
+];
+
+/// Exports the results in the Prometheus text exposition format, which can be picked up
+/// by the textfile collector of the node exporter or be pushed to a Pushgateway.
+#[derive(Default)]
+pub struct PrometheusExporter {}
+
+impl Exporter for PrometheusExporter {
+    fn serialize(
+        &self,
+        results: &[BenchmarkResult],
+        _unit: Option<Unit>,
+        _sort_order: SortOrder,
+    ) -> Result<Vec<u8>> {
+        let mut output = String::new();
+
+        for (name, description, value) in METRICS {
+            writeln!(output, "# HELP hyperfine_{}_seconds {}", name, description)?;
+            writeln!(output, "# TYPE hyperfine_{}_seconds gauge", name)?;
+
+            for res in results {
+                if let Some(value) = value(res) {
+                    writeln!(
+                        output,
+                        "hyperfine_{}_seconds{} {}",
+                        name,
+                        labels(res),
+                        value
+                    )?;
+                }
+            }
+        }
+
+        Ok(output.into_bytes())
+    }
+}
+
+/// Format the label set (command name and parameters) of the given result
+fn labels(result: &BenchmarkResult) -> String {
+    let mut labels = vec![format!("command=\"{}\"", escape(&result.command))];
+    for (name, value) in &result.parameters {
+        labels.push(format!(
+            "parameter_{}=\"{}\"",
+            sanitize_label_name(name),
+            escape(value)
+        ));
+    }
+    format!("{{{}}}", labels.join(","))
+}
+
+/// Label names may only consist of ASCII letters, digits and underscores
+fn sanitize_label_name(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+/// Escape backslashes, double quotes and line feeds in label values
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[test]
+fn test_prometheus() {
+    use std::collections::BTreeMap;
+    let exporter = PrometheusExporter::default();
+
+    let results = vec![
+        BenchmarkResult {
+            command: String::from("echo \"a\\b\""),
+            command_with_unused_parameters: String::from("echo \"a\\b\""),
+            mean: 0.1,
+            stddev: Some(0.01),
+            median: 0.1,
+            user: 0.001,
+            system: 0.002,
+            min: 0.09,
+            max: 0.11,
+            times: Some(vec![0.09, 0.1, 0.11]),
+            exit_codes: vec![Some(0), Some(0), Some(0)],
+            parameters: {
+                let mut params = BTreeMap::new();
+                params.insert("num-threads".into(), "4".into());
+                params
+            },
+            ..Default::default()
+        },
+        BenchmarkResult {
+            command: String::from("sleep 2"),
+            command_with_unused_parameters: String::from("sleep 2"),
+            mean: 2.0,
+            median: 2.0,
+            user: 0.0,
+            system: 0.0,
+            min: 2.0,
+            max: 2.0,
+            times: Some(vec![2.0]),
+            exit_codes: vec![Some(0)],
+            ..Default::default()
+        },
+    ];
+
+    let actual = String::from_utf8(
+        exporter
+            .serialize(&results, None, SortOrder::Command)
+            .unwrap(),
+    )
+    .unwrap();
+
+    assert!(actual.starts_with(
+        "# HELP hyperfine_mean_seconds Mean wall clock time\n\
+         # TYPE hyperfine_mean_seconds gauge\n\
+         hyperfine_mean_seconds{command=\"echo \\\"a\\\\b\\\"\",parameter_num_threads=\"4\"} 0.1\n\
+         hyperfine_mean_seconds{command=\"sleep 2\"} 2\n"
+    ));
+
+    // The standard deviation is not available for the second command
+    assert_eq!(actual.matches("hyperfine_stddev_seconds{").count(), 1);
+    assert_eq!(actual.matches("# TYPE").count(), METRICS.len());
+}
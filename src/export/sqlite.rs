@@ -0,0 +1,187 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rusqlite::{params, Connection};
+
+use super::Exporter;
+use crate::benchmark::benchmark_result::BenchmarkResult;
+use crate::options::SortOrder;
+use crate::util::units::Unit;
+
+use anyhow::{bail, Context, Result};
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS benchmarks (
+        id         INTEGER PRIMARY KEY,
+        session    INTEGER NOT NULL,
+        timestamp  INTEGER NOT NULL,
+        command    TEXT NOT NULL,
+        mean       REAL NOT NULL,
+        stddev     REAL,
+        median     REAL NOT NULL,
+        user       REAL NOT NULL,
+        system     REAL NOT NULL,
+        min        REAL NOT NULL,
+        max        REAL NOT NULL,
+        parameters TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS runs (
+        benchmark_id INTEGER NOT NULL REFERENCES benchmarks(id),
+        run          INTEGER NOT NULL,
+        time         REAL NOT NULL,
+        exit_code    INTEGER
+    );
+";
+
+/// Appends results to an SQLite database. Every invocation of hyperfine is recorded as
+/// a new session, such that results of multiple sessions can be collected in one file.
+#[derive(Default)]
+pub struct SqliteExporter {
+    session: i64,
+    timestamp: i64,
+}
+
+impl SqliteExporter {
+    fn open(filename: &str) -> Result<Connection> {
+        let connection = Connection::open(filename)
+            .with_context(|| format!("Could not open SQLite database '{}'", filename))?;
+        connection.execute_batch(SCHEMA)?;
+        Ok(connection)
+    }
+}
+
+impl Exporter for SqliteExporter {
+    fn serialize(
+        &self,
+        _results: &[BenchmarkResult],
+        _unit: Option<Unit>,
+        _sort_order: SortOrder,
+    ) -> Result<Vec<u8>> {
+        bail!("The SQLite export can only be written to a file")
+    }
+
+    fn create_file(&mut self, filename: &str) -> Result<()> {
+        let connection = Self::open(filename)?;
+
+        self.session = connection.query_row(
+            "SELECT COALESCE(MAX(session), 0) + 1 FROM benchmarks",
+            [],
+            |row| row.get(0),
+        )?;
+        self.timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs() as i64);
+
+        Ok(())
+    }
+
+    fn write_to_file(
+        &self,
+        filename: &str,
+        results: &[BenchmarkResult],
+        _unit: Option<Unit>,
+        _sort_order: SortOrder,
+    ) -> Result<()> {
+        let mut connection = Self::open(filename)?;
+        let transaction = connection.transaction()?;
+
+        // Results are exported after each benchmark, so we replace everything that has
+        // been written during this session before.
+        transaction.execute(
+            "DELETE FROM runs WHERE benchmark_id IN (SELECT id FROM benchmarks WHERE session = ?1)",
+            params![self.session],
+        )?;
+        transaction.execute(
+            "DELETE FROM benchmarks WHERE session = ?1",
+            params![self.session],
+        )?;
+
+        for res in results {
+            transaction.execute(
+                "INSERT INTO benchmarks \
+                 (session, timestamp, command, mean, stddev, median, user, system, min, max, parameters) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+                params![
+                    self.session,
+                    self.timestamp,
+                    res.command,
+                    res.mean,
+                    res.stddev,
+                    res.median,
+                    res.user,
+                    res.system,
+                    res.min,
+                    res.max,
+                    serde_json::to_string(&res.parameters)?,
+                ],
+            )?;
+            let benchmark_id = transaction.last_insert_rowid();
+
+            let times = res.times.as_deref().unwrap_or_default();
+            for (run, (time, exit_code)) in times.iter().zip(&res.exit_codes).enumerate() {
+                transaction.execute(
+                    "INSERT INTO runs (benchmark_id, run, time, exit_code) VALUES (?1, ?2, ?3, ?4)",
+                    params![benchmark_id, run as i64, time, exit_code],
+                )?;
+            }
+        }
+
+        transaction
+            .commit()
+            .with_context(|| format!("Failed to export results to '{}'", filename))
+    }
+}
+
+#[test]
+fn test_sqlite_appends_sessions() {
+    use std::collections::BTreeMap;
+
+    let tempdir = tempfile::tempdir().unwrap();
+    let filename = tempdir.path().join("results.db");
+    let filename = filename.to_str().unwrap();
+
+    let results = vec![BenchmarkResult {
+        command: String::from("sleep 0.1"),
+        command_with_unused_parameters: String::from("sleep 0.1"),
+        mean: 0.1,
+        stddev: Some(0.01),
+        median: 0.1,
+        user: 0.0,
+        system: 0.0,
+        min: 0.09,
+        max: 0.11,
+        times: Some(vec![0.09, 0.1, 0.11]),
+        exit_codes: vec![Some(0), Some(0), Some(0)],
+        parameters: {
+            let mut params = BTreeMap::new();
+            params.insert("foo".into(), "one".into());
+            params
+        },
+        ..Default::default()
+    }];
+
+    for _ in 0..2 {
+        let mut exporter = SqliteExporter::default();
+        exporter.create_file(filename).unwrap();
+
+        // Intermediate exports must not lead to duplicate entries
+        for _ in 0..2 {
+            exporter
+                .write_to_file(filename, &results, None, SortOrder::Command)
+                .unwrap();
+        }
+    }
+
+    let connection = Connection::open(filename).unwrap();
+    let count = |query: &str| -> i64 { connection.query_row(query, [], |row| row.get(0)).unwrap() };
+
+    assert_eq!(2, count("SELECT COUNT(*) FROM benchmarks"));
+    assert_eq!(2, count("SELECT COUNT(DISTINCT session) FROM benchmarks"));
+    assert_eq!(6, count("SELECT COUNT(*) FROM runs"));
+
+    let parameters: String = connection
+        .query_row("SELECT parameters FROM benchmarks LIMIT 1", [], |row| {
+            row.get(0)
+        })
+        .unwrap();
+    assert_eq!(parameters, r#"{"foo":"one"}"#);
+}
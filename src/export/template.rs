@@ -0,0 +1,309 @@
+use std::fs;
+
+use handlebars::{
+    no_escape, Context as TemplateContext, Handlebars, Helper, HelperResult, Output, RenderContext,
+    RenderError,
+};
+use serde_json::{json, Value};
+
+use super::markup::determine_unit_from_results;
+use super::Exporter;
+use crate::benchmark::benchmark_result::BenchmarkResult;
+use crate::benchmark::relative_speed;
+use crate::options::SortOrder;
+use crate::statistics::fieller_ratio_of_means;
+use crate::util::units::{Scalar, Unit};
+
+use anyhow::{Context, Result};
+
+const TEMPLATE_NAME: &str = "export";
+
+/// Renders the results through a user-provided Handlebars template. The template has
+/// access to all fields of the JSON export, the relative speed of each command and the
+/// time unit. A `format_time` helper formats a time (in seconds) in this unit.
+pub struct TemplateExporter {
+    template: String,
+
+    /// Confidence level of the intervals of the relative speeds
+    confidence_level: Scalar,
+
+    /// Whether the intervals of the relative speeds are computed without the outliers
+    discard_outliers: bool,
+}
+
+impl TemplateExporter {
+    pub fn new(
+        template_path: &str,
+        confidence_level: Scalar,
+        discard_outliers: bool,
+    ) -> Result<Self> {
+        let template = fs::read_to_string(template_path)
+            .with_context(|| format!("Could not read the template file '{}'", template_path))?;
+
+        let exporter = TemplateExporter {
+            template,
+            confidence_level,
+            discard_outliers,
+        };
+        // Make sure that syntax errors are reported before running any benchmarks
+        exporter
+            .registry(Unit::Second)
+            .with_context(|| format!("Invalid template '{}'", template_path))?;
+
+        Ok(exporter)
+    }
+
+    fn registry(&self, unit: Unit) -> Result<Handlebars<'static>> {
+        let mut registry = Handlebars::new();
+        registry.set_strict_mode(true);
+        registry.register_escape_fn(no_escape);
+        registry.register_template_string(TEMPLATE_NAME, &self.template)?;
+        registry.register_helper(
+            "format_time",
+            Box::new(
+                move |h: &Helper,
+                      _: &Handlebars,
+                      _: &TemplateContext,
+                      _: &mut RenderContext,
+                      out: &mut dyn Output|
+                      -> HelperResult {
+                    let time = h
+                        .param(0)
+                        .and_then(|v| v.value().as_f64())
+                        .ok_or_else(|| RenderError::new("'format_time' expects a number"))?;
+                    out.write(&unit.format(time))?;
+                    Ok(())
+                },
+            ),
+        );
+        Ok(registry)
+    }
+}
+
+impl Exporter for TemplateExporter {
+    fn serialize(
+        &self,
+        results: &[BenchmarkResult],
+        unit: Option<Unit>,
+        sort_order: SortOrder,
+    ) -> Result<Vec<u8>> {
+        let unit = unit.unwrap_or_else(|| determine_unit_from_results(results));
+
+        let annotated_results = relative_speed::compute(results, sort_order);
+        let times = |result: &BenchmarkResult| {
+            result
+                .times
+                .as_ref()
+                .map(|_| result.retained_times(self.discard_outliers))
+        };
+        let reference_times = annotated_results
+            .iter()
+            .find(|entry| entry.is_reference)
+            .and_then(|entry| times(entry.result));
+
+        let mut entries = vec![];
+        for entry in annotated_results.iter() {
+            // Confidence interval of the relative speed (Fieller's theorem), only available
+            // if the individual run times are known
+            let relative_speed_ci = match (times(entry.result), &reference_times) {
+                (Some(times), Some(reference_times)) if !entry.is_reference => {
+                    fieller_ratio_of_means(&times, reference_times, self.confidence_level)
+                }
+                _ => None,
+            };
+
+            let mut value = serde_json::to_value(entry.result)?;
+            if let Value::Object(ref mut map) = value {
+                map.insert(
+                    "command_with_unused_parameters".into(),
+                    json!(entry.result.command_with_unused_parameters),
+                );
+                map.insert("relative_speed".into(), json!(entry.relative_speed));
+                map.insert(
+                    "relative_speed_stddev".into(),
+                    json!(entry.relative_speed_stddev),
+                );
+                map.insert("relative_speed_ci".into(), json!(relative_speed_ci));
+                map.insert("is_fastest".into(), json!(entry.is_fastest));
+            }
+            entries.push(value);
+        }
+
+        let data = json!({
+            "unit": unit.short_name(),
+            "results": entries,
+        });
+
+        let output = self.registry(unit)?.render(TEMPLATE_NAME, &data)?;
+        Ok(output.into_bytes())
+    }
+}
+
+#[cfg(test)]
+fn cfg_test_exporter(template: &str) -> TemplateExporter {
+    TemplateExporter {
+        template: template.to_string(),
+        confidence_level: crate::statistics::DEFAULT_CONFIDENCE_LEVEL,
+        discard_outliers: false,
+    }
+}
+
+#[test]
+fn test_template() {
+    use std::collections::BTreeMap;
+
+    let exporter = cfg_test_exporter(
+        "{{#each results}}{{command}} & <{{format_time mean}} {{../unit}}>\
+         {{#if is_fastest}} (fastest){{/if}} {{parameters.size}}\n{{/each}}",
+    );
+
+    let results = vec![
+        BenchmarkResult {
+            command: String::from("sleep 2"),
+            command_with_unused_parameters: String::from("sleep 2"),
+            mean: 2.0,
+            stddev: Some(0.01),
+            median: 2.0,
+            user: 0.0,
+            system: 0.0,
+            min: 2.0,
+            max: 2.0,
+            times: Some(vec![2.0]),
+            exit_codes: vec![Some(0)],
+            parameters: {
+                let mut params = BTreeMap::new();
+                params.insert("size".into(), "2".into());
+                params
+            },
+            ..Default::default()
+        },
+        BenchmarkResult {
+            command: String::from("sleep 1"),
+            command_with_unused_parameters: String::from("sleep 1"),
+            mean: 1.0,
+            stddev: Some(0.01),
+            median: 1.0,
+            user: 0.0,
+            system: 0.0,
+            min: 1.0,
+            max: 1.0,
+            times: Some(vec![1.0]),
+            exit_codes: vec![Some(0)],
+            parameters: {
+                let mut params = BTreeMap::new();
+                params.insert("size".into(), "1".into());
+                params
+            },
+            ..Default::default()
+        },
+    ];
+
+    let actual = String::from_utf8(
+        exporter
+            .serialize(&results, Some(Unit::MilliSecond), SortOrder::MeanTime)
+            .unwrap(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        actual,
+        "sleep 1 & <1000.0 ms> (fastest) 1\nsleep 2 & <2000.0 ms> 2\n"
+    );
+}
+
+#[test]
+fn test_template_errors() {
+    assert!(cfg_test_exporter("{{#each results}}")
+        .registry(Unit::Second)
+        .is_err());
+
+    // Unknown fields are reported in strict mode
+    let exporter = cfg_test_exporter("{{unknown}}");
+    let results = vec![BenchmarkResult::default()];
+    assert!(exporter
+        .serialize(&results, None, SortOrder::Command)
+        .is_err());
+}
+
+#[test]
+fn test_template_relative_speed_ci() {
+    let results = vec![
+        BenchmarkResult {
+            command: String::from("cmd1"),
+            mean: 2.0,
+            times: Some(vec![1.9, 2.0, 2.1]),
+            ..Default::default()
+        },
+        BenchmarkResult {
+            command: String::from("cmd2"),
+            mean: 4.0,
+            times: Some(vec![3.8, 4.0, 4.2]),
+            ..Default::default()
+        },
+    ];
+
+    let ci_width = |confidence_level| {
+        let exporter = TemplateExporter {
+            confidence_level,
+            ..cfg_test_exporter(
+                "{{#each results}}{{#if relative_speed_ci}}\
+                 {{relative_speed_ci.lower}} {{relative_speed_ci.upper}}{{/if}}{{/each}}",
+            )
+        };
+        let output = String::from_utf8(
+            exporter
+                .serialize(&results, None, SortOrder::Command)
+                .unwrap(),
+        )
+        .unwrap();
+        let bounds: Vec<f64> = output.split(' ').map(|b| b.parse().unwrap()).collect();
+        assert!(bounds[0] < 2.0 && 2.0 < bounds[1]);
+        bounds[1] - bounds[0]
+    };
+
+    assert!(ci_width(0.99) > ci_width(0.95));
+}
+
+#[test]
+fn test_template_relative_speed_ci_without_outliers() {
+    let results = vec![
+        BenchmarkResult {
+            command: String::from("cmd1"),
+            mean: 2.0,
+            times: Some(vec![1.9, 2.0, 2.1]),
+            ..Default::default()
+        },
+        BenchmarkResult {
+            command: String::from("cmd2"),
+            mean: 4.0,
+            times: Some(vec![3.8, 4.0, 4.2, 40.0]),
+            outliers: vec![false, false, false, true],
+            ..Default::default()
+        },
+    ];
+
+    let bounds = |discard_outliers| {
+        let exporter = TemplateExporter {
+            discard_outliers,
+            ..cfg_test_exporter(
+                "{{#each results}}{{#if relative_speed_ci}}\
+                 {{relative_speed_ci.lower}} {{relative_speed_ci.upper}}{{/if}}{{/each}}",
+            )
+        };
+        let output = String::from_utf8(
+            exporter
+                .serialize(&results, None, SortOrder::Command)
+                .unwrap(),
+        )
+        .unwrap();
+        output
+            .split(' ')
+            .map(|b| b.parse().unwrap())
+            .collect::<Vec<f64>>()
+    };
+
+    let with_outliers = bounds(false);
+    let without_outliers = bounds(true);
+    assert!(without_outliers[0] < 2.0 && 2.0 < without_outliers[1]);
+    assert!(with_outliers[1] - with_outliers[0] > without_outliers[1] - without_outliers[0]);
+}
@@ -0,0 +1,247 @@
+use std::cell::RefCell;
+use std::fmt::Write;
+
+use super::html::tick_step;
+use super::xml::escape;
+use super::{Exporter, RunKind, RunResult};
+use crate::benchmark::benchmark_result::BenchmarkResult;
+use crate::options::SortOrder;
+use crate::output::format::{format_duration, format_duration_unit};
+use crate::util::units::{Second, Unit};
+
+use anyhow::Result;
+
+const STYLE: &str = "
+body { font-family: sans-serif; margin: 2em; color: #222; }
+.legend span { display: inline-block; width: 1em; height: 1em; margin: 0 0.3em 0 1em; vertical-align: middle; }
+svg text { font-size: 12px; }
+";
+
+/// Width of the plot area of the timeline (without labels)
+const PLOT_WIDTH: f64 = 900.0;
+
+/// Width of the command labels left of the timeline
+const LABEL_WIDTH: f64 = 200.0;
+
+/// Height of a single row in the timeline
+const ROW_HEIGHT: f64 = 36.0;
+
+/// Height of the horizontal axis of the timeline
+const AXIS_HEIGHT: f64 = 40.0;
+
+/// A single run of a command, as it is shown on the timeline
+struct Span {
+    command: String,
+    kind: RunKind,
+    run: usize,
+    start: f64,
+    duration: Second,
+}
+
+impl Span {
+    fn color(&self) -> &'static str {
+        match self.kind {
+            RunKind::Warmup => "#f0ad4e",
+            RunKind::Preparation => "#cccccc",
+            RunKind::Timing => "#8ec1e8",
+        }
+    }
+
+    fn description(&self) -> String {
+        let kind = match self.kind {
+            RunKind::Warmup => "warmup run",
+            RunKind::Preparation => "preparation",
+            RunKind::Timing => "run",
+        };
+        format!(
+            "{}: {} {}, {}",
+            self.command,
+            kind,
+            self.run + 1,
+            format_duration(self.duration, None)
+        )
+    }
+}
+
+/// Exports a standalone HTML page that shows all runs of all commands on a shared
+/// timeline, including warmup runs and preparation commands.
+#[derive(Default)]
+pub struct TimelineExporter {
+    spans: RefCell<Vec<Span>>,
+}
+
+impl Exporter for TimelineExporter {
+    fn serialize(
+        &self,
+        _results: &[BenchmarkResult],
+        unit: Option<Unit>,
+        _sort_order: SortOrder,
+    ) -> Result<Vec<u8>> {
+        let mut html = String::new();
+        html.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+        html.push_str("<title>hyperfine benchmark timeline</title>\n");
+        writeln!(html, "<style>{}</style>", STYLE)?;
+        html.push_str("</head>\n<body>\n");
+
+        html.push_str("<h1>Benchmark timeline</h1>\n");
+        html.push_str(
+            "<p class=\"legend\">\
+             <span style=\"background: #f0ad4e\"></span>Warmup\
+             <span style=\"background: #cccccc\"></span>Preparation\
+             <span style=\"background: #8ec1e8\"></span>Timing run</p>\n",
+        );
+        html.push_str(&timeline(&self.spans.borrow(), unit));
+
+        html.push_str("</body>\n</html>\n");
+
+        Ok(html.into_bytes())
+    }
+
+    fn write_run(&self, _filename: &str, run: &RunResult) -> Result<()> {
+        self.spans.borrow_mut().push(Span {
+            command: run.command.to_string(),
+            kind: run.kind,
+            run: run.run,
+            start: run.timestamp,
+            duration: run.timing.time_real,
+        });
+        Ok(())
+    }
+}
+
+fn timeline(spans: &[Span], unit: Option<Unit>) -> String {
+    // One row per command, in the order in which they have been run
+    let mut commands: Vec<&str> = vec![];
+    for span in spans {
+        if !commands.contains(&span.command.as_str()) {
+            commands.push(&span.command);
+        }
+    }
+
+    let origin = spans.iter().map(|s| s.start).fold(f64::INFINITY, f64::min);
+    let end = spans
+        .iter()
+        .map(|s| s.start + s.duration)
+        .fold(f64::NEG_INFINITY, f64::max);
+    let total = if spans.is_empty() { 0.0 } else { end - origin };
+    let (_, unit) = format_duration_unit(total, unit);
+    let pixels_per_second = if total > 0.0 { PLOT_WIDTH / total } else { 0.0 };
+    let scale = |t: f64| LABEL_WIDTH + t * pixels_per_second;
+
+    let height = commands.len() as f64 * ROW_HEIGHT + AXIS_HEIGHT;
+    let width = LABEL_WIDTH + PLOT_WIDTH + 20.0;
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+         viewBox=\"0 0 {width} {height}\">",
+        width = width,
+        height = height
+    );
+
+    let axis_y = commands.len() as f64 * ROW_HEIGHT;
+    if total > 0.0 {
+        let step = tick_step(total);
+        let mut tick = 0.0;
+        while tick <= total * (1.0 + 1e-9) {
+            let _ = writeln!(
+                svg,
+                "<line x1=\"{x:.1}\" y1=\"0\" x2=\"{x:.1}\" y2=\"{y:.1}\" stroke=\"#eee\"/>\
+                 <text x=\"{x:.1}\" y=\"{ty:.1}\" text-anchor=\"middle\">{} {}</text>",
+                unit.format(tick),
+                unit.short_name(),
+                x = scale(tick),
+                y = axis_y,
+                ty = axis_y + 18.0
+            );
+            tick += step;
+        }
+    }
+    let _ = writeln!(
+        svg,
+        "<line x1=\"{x1:.1}\" y1=\"{y:.1}\" x2=\"{x2:.1}\" y2=\"{y:.1}\" stroke=\"#222\"/>",
+        x1 = LABEL_WIDTH,
+        x2 = LABEL_WIDTH + PLOT_WIDTH,
+        y = axis_y
+    );
+
+    for (i, command) in commands.iter().enumerate() {
+        let _ = writeln!(
+            svg,
+            "<text x=\"{x:.1}\" y=\"{y:.1}\" text-anchor=\"end\" dominant-baseline=\"middle\">{}</text>",
+            escape(command),
+            x = LABEL_WIDTH - 10.0,
+            y = (i as f64 + 0.5) * ROW_HEIGHT
+        );
+    }
+
+    for span in spans {
+        let row = commands.iter().position(|c| *c == span.command).unwrap();
+        let x = scale(span.start - origin);
+        let _ = writeln!(
+            svg,
+            "<rect x=\"{x:.2}\" y=\"{y:.1}\" width=\"{w:.2}\" height=\"{h:.1}\" fill=\"{fill}\">\
+             <title>{}</title></rect>",
+            escape(&span.description()),
+            x = x,
+            y = row as f64 * ROW_HEIGHT + ROW_HEIGHT * 0.2,
+            // Make sure that very short runs remain visible
+            w = (scale(span.start + span.duration - origin) - x).max(0.5),
+            h = ROW_HEIGHT * 0.6,
+            fill = span.color()
+        );
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+#[test]
+fn test_timeline() {
+    use crate::benchmark::timing_result::TimingResult;
+    use std::collections::BTreeMap;
+
+    let exporter = TimelineExporter::default();
+    let parameters = BTreeMap::new();
+    let add_run = |command, kind, run, timestamp, time_real| {
+        exporter
+            .write_run(
+                "-",
+                &RunResult {
+                    command,
+                    parameters: &parameters,
+                    kind,
+                    run,
+                    timestamp,
+                    system_state: Default::default(),
+                    timing: TimingResult {
+                        time_real,
+                        ..Default::default()
+                    },
+                    exit_code: Some(0),
+                    signal: None,
+                },
+            )
+            .unwrap()
+    };
+    add_run("sleep 1", RunKind::Warmup, 0, 100.0, 1.0);
+    add_run("sleep 1", RunKind::Preparation, 0, 101.0, 0.5);
+    add_run("sleep 1", RunKind::Timing, 0, 101.5, 1.0);
+    add_run("sleep 2 && true", RunKind::Timing, 0, 102.5, 2.0);
+
+    let html =
+        String::from_utf8(exporter.serialize(&[], None, SortOrder::MeanTime).unwrap()).unwrap();
+
+    assert!(html.contains(">sleep 1</text>"));
+    assert!(html.contains(">sleep 2 &amp;&amp; true</text>"));
+    assert_eq!(html.matches("<rect ").count(), 4);
+    assert!(html.contains(
+        "<rect x=\"200.00\" y=\"7.2\" width=\"200.00\" height=\"21.6\" fill=\"#f0ad4e\">\
+         <title>sleep 1: warmup run 1, 1.000 s</title></rect>"
+    ));
+    assert!(html.contains(
+        "<rect x=\"700.00\" y=\"43.2\" width=\"400.00\" height=\"21.6\" fill=\"#8ec1e8\">"
+    ));
+    assert!(html.contains(">4.000 s</text>"));
+}
@@ -0,0 +1,128 @@
+use serde_json::{json, to_vec_pretty, Value};
+
+use super::markup::determine_unit_from_results;
+use super::Exporter;
+use crate::benchmark::benchmark_result::BenchmarkResult;
+use crate::benchmark::relative_speed;
+use crate::options::SortOrder;
+use crate::util::units::Unit;
+
+use anyhow::Result;
+
+const SCHEMA: &str = "https://vega.github.io/schema/vega-lite/v5.json";
+
+/// Exports a Vega-Lite specification with a box plot of the run times for each command.
+/// The data is embedded in the specification, such that it can be rendered directly
+/// (e.g. with vega-embed or in the online Vega editor).
+#[derive(Default)]
+pub struct VegaExporter {}
+
+impl Exporter for VegaExporter {
+    fn serialize(
+        &self,
+        results: &[BenchmarkResult],
+        unit: Option<Unit>,
+        sort_order: SortOrder,
+    ) -> Result<Vec<u8>> {
+        let unit = unit.unwrap_or_else(|| determine_unit_from_results(results));
+        let entries = relative_speed::compute(results, sort_order);
+
+        let values: Vec<Value> = entries
+            .iter()
+            .flat_map(|entry| {
+                let command = &entry.result.command;
+                entry
+                    .result
+                    .times
+                    .iter()
+                    .flatten()
+                    .map(move |&time| json!({ "command": command, "time": unit.convert(time) }))
+            })
+            .collect();
+        let commands: Vec<&str> = entries.iter().map(|e| e.result.command.as_str()).collect();
+
+        let spec = json!({
+            "$schema": SCHEMA,
+            "description": "Benchmark results of hyperfine",
+            "data": { "values": values },
+            "mark": { "type": "boxplot", "extent": 1.5 },
+            "encoding": {
+                "y": {
+                    "field": "command",
+                    "type": "nominal",
+                    "title": "Command",
+                    "sort": commands,
+                },
+                "x": {
+                    "field": "time",
+                    "type": "quantitative",
+                    "title": format!("Time [{}]", unit.short_name()),
+                    "scale": { "zero": false },
+                },
+                "color": { "field": "command", "type": "nominal", "legend": null },
+            },
+        });
+
+        let mut output = to_vec_pretty(&spec)?;
+        output.push(b'\n');
+
+        Ok(output)
+    }
+}
+
+#[test]
+fn test_vega() {
+    let exporter = VegaExporter::default();
+
+    let results = vec![
+        BenchmarkResult {
+            command: String::from("sleep 2"),
+            command_with_unused_parameters: String::from("sleep 2"),
+            mean: 2.0,
+            stddev: Some(0.0),
+            median: 2.0,
+            user: 0.0,
+            system: 0.0,
+            min: 2.0,
+            max: 2.0,
+            times: Some(vec![2.0, 2.0]),
+            exit_codes: vec![Some(0), Some(0)],
+            ..Default::default()
+        },
+        BenchmarkResult {
+            command: String::from("sleep 0.5"),
+            command_with_unused_parameters: String::from("sleep 0.5"),
+            mean: 0.5,
+            stddev: Some(0.0),
+            median: 0.5,
+            user: 0.0,
+            system: 0.0,
+            min: 0.5,
+            max: 0.5,
+            times: Some(vec![0.5]),
+            exit_codes: vec![Some(0)],
+            ..Default::default()
+        },
+    ];
+
+    let actual = exporter
+        .serialize(&results, Some(Unit::MilliSecond), SortOrder::MeanTime)
+        .unwrap();
+    let actual: Value = serde_json::from_slice(&actual).unwrap();
+
+    assert_eq!(actual["$schema"], SCHEMA);
+    assert_eq!(actual["mark"]["type"], "boxplot");
+    assert_eq!(actual["encoding"]["x"]["title"], "Time [ms]");
+    assert_eq!(
+        actual["encoding"]["y"]["sort"],
+        json!(["sleep 0.5", "sleep 2"])
+    );
+    assert_eq!(
+        actual["data"]["values"],
+        json!([
+            { "command": "sleep 0.5", "time": 500.0 },
+            { "command": "sleep 2", "time": 2000.0 },
+            { "command": "sleep 2", "time": 2000.0 },
+        ])
+    );
+}
@@ -0,0 +1,311 @@
+use std::fmt::Write;
+
+use serde_json::Value;
+
+use super::Exporter;
+use crate::benchmark::benchmark_result::BenchmarkResult;
+use crate::options::SortOrder;
+use crate::util::units::Unit;
+
+use anyhow::Result;
+
+/// Fields whose values are maps with arbitrary keys, e.g. parameter names. Their entries are
+/// written as elements with a `name` attribute.
+const NAMED_ENTRY_FIELDS: &[&str] = &["parameters", "perf_counters"];
+
+/// Exports the results in an XML document. Every result contains the same fields as in the
+/// JSON export, in the same order (serde_json preserves the order of the fields with its
+/// `preserve_order` feature), and missing values are left out. The elements of lists are
+/// named after the singular of the list (e.g. `<time>` in `<times>`), or `<value>` if the
+/// name is not a plural. Empty elements stand for unknown values in lists, e.g. the exit
+/// code of a process that was killed by a signal.
+#[derive(Default)]
+pub struct XmlExporter {}
+
+impl Exporter for XmlExporter {
+    fn serialize(
+        &self,
+        results: &[BenchmarkResult],
+        _unit: Option<Unit>,
+        _sort_order: SortOrder,
+    ) -> Result<Vec<u8>> {
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<results>\n");
+
+        for res in results {
+            write_element(&mut xml, 1, "result", None, &serde_json::to_value(res)?)?;
+        }
+
+        xml.push_str("</results>\n");
+
+        Ok(xml.into_bytes())
+    }
+}
+
+/// Writes the given value as an element (with an optional `name` attribute) at the given
+/// level of indentation
+fn write_element(
+    xml: &mut String,
+    level: usize,
+    tag: &str,
+    name: Option<&str>,
+    value: &Value,
+) -> Result<()> {
+    let indent = "  ".repeat(level);
+    let attribute = name
+        .map(|name| format!(" name=\"{}\"", escape(name)))
+        .unwrap_or_default();
+
+    match value {
+        Value::Null => writeln!(xml, "{}<{}{}/>", indent, tag, attribute)?,
+        Value::Bool(b) => writeln!(xml, "{}<{}{}>{}</{}>", indent, tag, attribute, b, tag)?,
+        Value::Number(n) => {
+            let text = match (n.as_u64(), n.as_i64(), n.as_f64()) {
+                (Some(n), _, _) => n.to_string(),
+                (None, Some(n), _) => n.to_string(),
+                (None, None, Some(n)) => n.to_string(),
+                _ => n.to_string(),
+            };
+            writeln!(xml, "{}<{}{}>{}</{}>", indent, tag, attribute, text, tag)?
+        }
+        Value::String(s) => writeln!(
+            xml,
+            "{}<{}{}>{}</{}>",
+            indent,
+            tag,
+            attribute,
+            escape(s),
+            tag
+        )?,
+        Value::Array(items) => {
+            writeln!(xml, "{}<{}{}>", indent, tag, attribute)?;
+            let item_tag = singular(tag);
+            for item in items {
+                write_element(xml, level + 1, &item_tag, None, item)?;
+            }
+            writeln!(xml, "{}</{}>", indent, tag)?;
+        }
+        Value::Object(fields) => {
+            writeln!(xml, "{}<{}{}>", indent, tag, attribute)?;
+            let named_entries = NAMED_ENTRY_FIELDS.contains(&tag);
+            for (key, field) in fields {
+                if named_entries {
+                    write_element(xml, level + 1, &singular(tag), Some(key), field)?;
+                } else if !field.is_null() {
+                    write_element(xml, level + 1, key, None, field)?;
+                }
+            }
+            writeln!(xml, "{}</{}>", indent, tag)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Name of the elements of the list with the given name, e.g. `exit_code` for `exit_codes`
+fn singular(plural: &str) -> String {
+    if plural.ends_with("sses") || plural.ends_with("ches") {
+        plural[..plural.len() - 2].to_string()
+    } else if let Some(singular) = plural.strip_suffix('s') {
+        singular.to_string()
+    } else {
+        "value".to_string()
+    }
+}
+
+/// Escape the given string for use in XML (or HTML) text and attribute values
+pub fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[test]
+fn test_escape() {
+    assert_eq!(
+        escape("echo '<a>' && \"b\""),
+        "echo &#39;&lt;a&gt;&#39; &amp;&amp; &quot;b&quot;"
+    );
+}
+
+#[test]
+fn test_xml() {
+    use std::collections::BTreeMap;
+    let exporter = XmlExporter::default();
+
+    let results = vec![
+        BenchmarkResult {
+            command: String::from("sleep 0.1 && echo <done>"),
+            command_with_unused_parameters: String::from("sleep 0.1 && echo <done>"),
+            mean: 0.1,
+            stddev: Some(0.01),
+            median: 0.1,
+            user: 0.001,
+            system: 0.002,
+            min: 0.09,
+            max: 0.11,
+            times: Some(vec![0.09, 0.1, 0.11]),
+            exit_codes: vec![Some(0), Some(0), None],
+            parameters: {
+                let mut params = BTreeMap::new();
+                params.insert("delay".into(), "0.1".into());
+                params
+            },
+            ..Default::default()
+        },
+        BenchmarkResult {
+            command: String::from("sleep 2"),
+            command_with_unused_parameters: String::from("sleep 2"),
+            mean: 2.0,
+            median: 2.0,
+            user: 0.0,
+            system: 0.0,
+            min: 2.0,
+            max: 2.0,
+            exit_codes: vec![Some(0)],
+            ..Default::default()
+        },
+    ];
+
+    let expected = "\
+<?xml version=\"1.0\" encoding=\"UTF-8\"?>
+<results>
+  <result>
+    <command>sleep 0.1 &amp;&amp; echo &lt;done&gt;</command>
+    <mean>0.1</mean>
+    <stddev>0.01</stddev>
+    <median>0.1</median>
+    <user>0.001</user>
+    <system>0.002</system>
+    <min>0.09</min>
+    <max>0.11</max>
+    <times>
+      <time>0.09</time>
+      <time>0.1</time>
+      <time>0.11</time>
+    </times>
+    <exit_codes>
+      <exit_code>0</exit_code>
+      <exit_code>0</exit_code>
+      <exit_code/>
+    </exit_codes>
+    <parameters>
+      <parameter name=\"delay\">0.1</parameter>
+    </parameters>
+  </result>
+  <result>
+    <command>sleep 2</command>
+    <mean>2</mean>
+    <median>2</median>
+    <user>0</user>
+    <system>0</system>
+    <min>2</min>
+    <max>2</max>
+    <exit_codes>
+      <exit_code>0</exit_code>
+    </exit_codes>
+  </result>
+</results>
+";
+    let actual = String::from_utf8(
+        exporter
+            .serialize(&results, None, SortOrder::Command)
+            .unwrap(),
+    )
+    .unwrap();
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn test_xml_contains_all_fields() {
+    use crate::statistics::ConfidenceInterval;
+    use std::collections::BTreeMap;
+    let exporter = XmlExporter::default();
+
+    let results = vec![BenchmarkResult {
+        command: String::from("make"),
+        mean: 1.5,
+        stddev: Some(0.5),
+        mean_ci: Some(ConfidenceInterval {
+            lower: 1.0,
+            upper: 2.0,
+        }),
+        median: 1.5,
+        min: 1.0,
+        max: 2.0,
+        times: Some(vec![1.0, 2.0]),
+        memory_usage_byte: Some(vec![1024, 2048]),
+        total_processes: Some(vec![3, 3]),
+        perf_counters: {
+            let mut counters = BTreeMap::new();
+            counters.insert("cpu-cycles".into(), vec![100, 200]);
+            counters
+        },
+        exit_codes: vec![Some(0), Some(0)],
+        outliers: vec![false, true],
+        ..Default::default()
+    }];
+
+    let expected = "\
+<?xml version=\"1.0\" encoding=\"UTF-8\"?>
+<results>
+  <result>
+    <command>make</command>
+    <mean>1.5</mean>
+    <stddev>0.5</stddev>
+    <mean_ci>
+      <lower>1</lower>
+      <upper>2</upper>
+    </mean_ci>
+    <median>1.5</median>
+    <user>0</user>
+    <system>0</system>
+    <min>1</min>
+    <max>2</max>
+    <times>
+      <time>1</time>
+      <time>2</time>
+    </times>
+    <memory_usage_byte>
+      <value>1024</value>
+      <value>2048</value>
+    </memory_usage_byte>
+    <total_processes>
+      <total_process>3</total_process>
+      <total_process>3</total_process>
+    </total_processes>
+    <perf_counters>
+      <perf_counter name=\"cpu-cycles\">
+        <value>100</value>
+        <value>200</value>
+      </perf_counter>
+    </perf_counters>
+    <exit_codes>
+      <exit_code>0</exit_code>
+      <exit_code>0</exit_code>
+    </exit_codes>
+    <outliers>
+      <outlier>false</outlier>
+      <outlier>true</outlier>
+    </outliers>
+  </result>
+</results>
+";
+    let actual = String::from_utf8(
+        exporter
+            .serialize(&results, None, SortOrder::Command)
+            .unwrap(),
+    )
+    .unwrap();
+
+    assert_eq!(expected, actual);
+}
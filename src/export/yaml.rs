@@ -0,0 +1,95 @@
+use serde_yaml::to_string;
+
+use super::json::{HyperfineSummary, SCHEMA_VERSION};
+use super::metadata::Metadata;
+use super::Exporter;
+use crate::benchmark::benchmark_result::BenchmarkResult;
+use crate::options::SortOrder;
+use crate::util::units::Unit;
+
+use anyhow::Result;
+
+#[derive(Default)]
+pub struct YamlExporter {
+    metadata: Option<Metadata>,
+}
+
+impl YamlExporter {
+    pub fn new(metadata: Option<Metadata>) -> Self {
+        YamlExporter { metadata }
+    }
+}
+
+impl Exporter for YamlExporter {
+    fn serialize(
+        &self,
+        results: &[BenchmarkResult],
+        _unit: Option<Unit>,
+        _sort_order: SortOrder,
+    ) -> Result<Vec<u8>> {
+        Ok(to_string(&HyperfineSummary {
+            schema_version: SCHEMA_VERSION,
+            metadata: self.metadata.as_ref(),
+            results,
+            comparison_matrix: None,
+        })?
+        .into_bytes())
+    }
+}
+
+#[test]
+fn test_yaml() {
+    use std::collections::BTreeMap;
+    let exporter = YamlExporter::default();
+
+    let results = vec![BenchmarkResult {
+        command: String::from("sleep 0.1"),
+        command_with_unused_parameters: String::from("sleep 0.1"),
+        mean: 0.1,
+        stddev: Some(0.01),
+        median: 0.1,
+        user: 0.001,
+        system: 0.002,
+        min: 0.09,
+        max: 0.11,
+        times: Some(vec![0.09, 0.1, 0.11]),
+        exit_codes: vec![Some(0), Some(0), None],
+        parameters: {
+            let mut params = BTreeMap::new();
+            params.insert("delay".into(), "0.1".into());
+            params
+        },
+        ..Default::default()
+    }];
+
+    let expected = "\
+schema_version: 1
+results:
+- command: sleep 0.1
+  mean: 0.1
+  stddev: 0.01
+  median: 0.1
+  user: 0.001
+  system: 0.002
+  min: 0.09
+  max: 0.11
+  times:
+  - 0.09
+  - 0.1
+  - 0.11
+  exit_codes:
+  - 0
+  - 0
+  - null
+  parameters:
+    delay: '0.1'
+";
+    let actual = String::from_utf8(
+        exporter
+            .serialize(&results, None, SortOrder::Command)
+            .unwrap(),
+    )
+    .unwrap();
+
+    assert_eq!(expected, actual);
+}
@@ -4,14 +4,15 @@
 )]
 
 use std::env;
+use std::io::{self, Write};
 
 use benchmark::scheduler::Scheduler;
 use cli::get_cli_arguments;
 use command::Commands;
-use export::ExportManager;
-use options::Options;
+use export::{ExportManager, JSON_SCHEMA};
+use options::{ExecutorKind, Options};
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use colored::*;
 
 pub mod benchmark;
@@ -23,6 +24,7 @@ pub mod options;
 pub mod outlier_detection;
 pub mod output;
 pub mod parameter;
+pub mod statistics;
 pub mod timer;
 pub mod util;
 
@@ -32,15 +34,47 @@ fn run() -> Result<()> {
     colored::control::set_virtual_terminal(true).unwrap();
 
     let cli_arguments = get_cli_arguments(env::args_os());
+    if cli_arguments.get_flag("export-schema") {
+        io::stdout().write_all(JSON_SCHEMA.as_bytes())?;
+        return Ok(());
+    }
+
     let options = Options::from_cli_arguments(&cli_arguments)?;
     let commands = Commands::from_cli_arguments(&cli_arguments)?;
-    let export_manager = ExportManager::from_cli_arguments(&cli_arguments, options.time_unit)?;
+    let export_manager = ExportManager::from_cli_arguments(&cli_arguments, &options)?;
 
     options.validate_against_command_list(&commands)?;
+    #[cfg(any(target_os = "linux", windows))]
+    {
+        util::cpu_affinity::check_available(&options.cpu_affinity)?;
+        util::cpu_affinity::check_available(&options.hyperfine_cpu_affinity)?;
+    }
+    #[cfg(any(target_os = "linux", windows))]
+    if !options.hyperfine_cpu_affinity.is_empty() {
+        util::cpu_affinity::set_for_current_process(&options.hyperfine_cpu_affinity)
+            .context("Could not pin hyperfine to the CPUs given by '--hyperfine-cpu'")?;
+    }
+    // Before any threads are started, e.g. by the measurements
+    #[cfg(target_os = "linux")]
+    if options.isolate_network && !matches!(options.executor_kind, ExecutorKind::Mock(_)) {
+        util::network::isolate_current_process()
+            .context("Could not isolate the benchmarks from the network ('--isolate-network')")?;
+    }
+    if !matches!(options.executor_kind, ExecutorKind::Mock(_)) {
+        timer::check_measurements(&options)?;
+
+        // Drop the caches once up front, such that 'sudo' can ask for a password before
+        // the progress bars are shown
+        #[cfg(any(target_os = "linux", target_os = "macos", windows))]
+        if options.drop_caches.is_some() {
+            util::drop_caches::drop_caches(true)?;
+        }
+    }
 
     let mut scheduler = Scheduler::new(&commands, &options, &export_manager);
     scheduler.run_benchmarks()?;
     scheduler.print_relative_speed_comparison();
+    scheduler.print_complexity();
     scheduler.final_export()?;
 
     Ok(())
@@ -6,10 +6,16 @@ use std::{cmp, env, fmt, io};
 
 use anyhow::ensure;
 use clap::ArgMatches;
+use serde::Serialize;
 
 use crate::command::Commands;
 use crate::error::OptionsError;
-use crate::util::units::{Second, Unit};
+use crate::outlier_detection::{
+    OutlierMethod, DEFAULT_GRUBBS_THRESHOLD, DEFAULT_IQR_THRESHOLD, DEFAULT_MZSCORE_THRESHOLD,
+};
+use crate::statistics::DEFAULT_CONFIDENCE_LEVEL;
+use crate::util::priority::{MAX_NICE_LEVEL, MIN_NICE_LEVEL};
+use crate::util::units::{Scalar, Second, Unit};
 
 use anyhow::Result;
 
@@ -95,10 +101,246 @@ pub enum OutputStyleOption {
     Disabled,
 }
 
+/// Statistical test for the comparison of benchmarks
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SignificanceTest {
+    /// Welch's t-test, which assumes normally distributed run times
+    #[default]
+    Welch,
+
+    /// Mann-Whitney U test, which does not make assumptions about the distribution
+    MannWhitney,
+}
+
+/// Hardware performance counter that can be measured with `--perf-counters`, or the number
+/// of system calls
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PerfCounter {
+    Cycles,
+    Instructions,
+    CacheMisses,
+    BranchMisses,
+    Syscalls,
+}
+
+impl PerfCounter {
+    pub const ALL: [PerfCounter; 5] = [
+        PerfCounter::Cycles,
+        PerfCounter::Instructions,
+        PerfCounter::CacheMisses,
+        PerfCounter::BranchMisses,
+        PerfCounter::Syscalls,
+    ];
+
+    /// Name of the counter on the command line and in the exports
+    pub fn name(self) -> &'static str {
+        match self {
+            PerfCounter::Cycles => "cycles",
+            PerfCounter::Instructions => "instructions",
+            PerfCounter::CacheMisses => "cache-misses",
+            PerfCounter::BranchMisses => "branch-misses",
+            PerfCounter::Syscalls => "syscalls",
+        }
+    }
+}
+
+/// Treatment of the most extreme run times for the computation of the mean (`--trim`)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Trim {
+    /// Remove the given fraction of the fastest and of the slowest runs
+    Trimmed(Scalar),
+
+    /// Replace the given fraction of the fastest and of the slowest runs by the fastest and
+    /// slowest remaining run, respectively
+    Winsorized(Scalar),
+}
+
+/// What the benchmarked command processes, for the throughput computation (`--throughput`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThroughputUnit {
+    Bytes,
+    Items,
+}
+
+/// Amount of bytes or items that is processed in each run (`--throughput`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Throughput {
+    pub unit: ThroughputUnit,
+
+    /// The amount, possibly containing parameter placeholders like `{size}`. If it is not
+    /// given, the value of the (first) numeric parameter is used.
+    pub amount: Option<String>,
+}
+
+impl Throughput {
+    fn from_str(s: &str) -> Result<Self, OptionsError<'static>> {
+        let (unit, amount) = match s.split_once('=') {
+            Some((unit, amount)) => (unit, Some(amount.to_string())),
+            None => (s, None),
+        };
+        let unit = match unit {
+            "bytes" => ThroughputUnit::Bytes,
+            "items" => ThroughputUnit::Items,
+            _ => return Err(OptionsError::InvalidThroughput(s.to_string())),
+        };
+        if let Some(ref amount) = amount {
+            let is_valid = amount.contains('{')
+                || amount
+                    .parse::<f64>()
+                    .is_ok_and(|a| a.is_finite() && a > 0.0);
+            if !is_valid {
+                return Err(OptionsError::InvalidThroughput(s.to_string()));
+            }
+        }
+
+        Ok(Throughput { unit, amount })
+    }
+}
+
+/// Parse a list of CPUs like '0,2-3' (for `--cpu` and `--hyperfine-cpu`)
+fn parse_cpu_list(list: &str) -> Result<Vec<usize>, OptionsError<'static>> {
+    let invalid = || OptionsError::InvalidCpuList(list.to_string());
+
+    let mut cpus = vec![];
+    for item in list.split(',') {
+        let (first, last) = match item.split_once('-') {
+            Some((first, last)) => (first, last),
+            None => (item, item),
+        };
+        let first: usize = first.trim().parse().map_err(|_| invalid())?;
+        let last: usize = last.trim().parse().map_err(|_| invalid())?;
+        if first > last {
+            return Err(invalid());
+        }
+        for cpu in first..=last {
+            if !cpus.contains(&cpu) {
+                cpus.push(cpu);
+            }
+        }
+    }
+
+    #[cfg(any(target_os = "linux", windows))]
+    if cpus
+        .iter()
+        .any(|&cpu| cpu >= crate::util::cpu_affinity::MAX_CPUS)
+    {
+        return Err(invalid());
+    }
+
+    Ok(cpus)
+}
+
+/// Parse an environment variable like 'KEY=VALUE' (for `--env` and `--env-file`)
+fn parse_environment_variable(variable: &str) -> Result<(String, String), OptionsError<'static>> {
+    match variable.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+        _ => Err(OptionsError::InvalidEnvironmentVariable(
+            variable.to_string(),
+        )),
+    }
+}
+
+/// Parse the content of an environment file (`--env-file`). Every line contains a variable
+/// like 'KEY=VALUE'. Empty lines and lines that start with '#' are ignored.
+fn parse_environment_file(content: &str) -> Result<Vec<(String, String)>, OptionsError<'static>> {
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+        .map(parse_environment_variable)
+        .collect()
+}
+
+/// Parse a memory size like '512M' or '1G' in bytes, with binary suffixes (for
+/// `--limit-memory`)
+fn parse_memory_size(size: &str) -> Result<u64, OptionsError<'static>> {
+    let invalid = || OptionsError::InvalidMemoryLimit(size.to_string());
+
+    let size = size.trim();
+    let (number, factor) = match size.char_indices().last() {
+        Some((i, suffix)) if suffix.is_ascii_alphabetic() => {
+            let factor: u64 = match suffix.to_ascii_uppercase() {
+                'K' => 1 << 10,
+                'M' => 1 << 20,
+                'G' => 1 << 30,
+                'T' => 1 << 40,
+                _ => return Err(invalid()),
+            };
+            (&size[..i], factor)
+        }
+        _ => (size, 1),
+    };
+    let number: f64 = number.parse().map_err(|_| invalid())?;
+    if number <= 0.0 || !number.is_finite() {
+        return Err(invalid());
+    }
+    Ok((number * factor as f64).round() as u64)
+}
+
+/// Time that is used to compute the relative speeds in the summary
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum RelativeSpeedMetric {
+    /// Mean time
+    #[default]
+    Mean,
+
+    /// Median time, which is more robust for skewed distributions
+    Median,
+
+    /// Minimum time
+    Min,
+}
+
+/// The clock that measures the run time of the benchmarked commands (`--clock`)
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Clock {
+    /// The monotonic clock of the operating system
+    #[default]
+    Monotonic,
+
+    /// The monotonic clock without the frequency adjustments of NTP (CLOCK_MONOTONIC_RAW)
+    MonotonicRaw,
+
+    /// The CPU time of the benchmarked process, i.e. the sum of its user and system time
+    ProcessCpuTime,
+}
+
+/// When the file system caches are dropped (`--drop-caches`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DropCaches {
+    /// Before every warmup and timing run
+    BeforeEachRun,
+
+    /// Once before each benchmark, after the setup command
+    BeforeEachBenchmark,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SortOrder {
+    /// The order in which the commands were specified
     Command,
+
+    /// Sort by mean time
     MeanTime,
+
+    /// Sort by median time
+    MedianTime,
+
+    /// Sort by the time that is used to compute the relative speeds (`--relative-speed-metric`)
+    RelativeSpeed,
+
+    /// Sort alphabetically by command name
+    Alphabetical,
+}
+
+impl SortOrder {
+    /// Whether the benchmarks are ordered by their speed
+    pub fn is_by_speed(self) -> bool {
+        matches!(
+            self,
+            SortOrder::MeanTime | SortOrder::MedianTime | SortOrder::RelativeSpeed
+        )
+    }
 }
 
 /// Bounds for the number of benchmark runs
@@ -195,9 +437,26 @@ pub struct Options {
     /// Upper and lower bound for the number of benchmark runs
     pub run_bounds: RunBounds,
 
+    /// Number of times that the command is executed in a single shell invocation per run
+    /// (`--batch`). The measured times are divided by this number.
+    pub batch_size: u64,
+
     /// Number of warmup runs
     pub warmup_count: u64,
 
+    /// Whether warmup runs are performed until the run times are stable (`--warmup auto`)
+    pub auto_warmup: bool,
+
+    /// Maximum time that is spent on benchmarking a single command, if any
+    pub max_time: Option<Second>,
+
+    /// Keep benchmarking until the relative standard deviation drops below this value
+    pub max_relative_stddev: Option<Scalar>,
+
+    /// Keep benchmarking until the confidence interval of the mean is within this fraction
+    /// of the mean
+    pub max_ci_width: Option<Scalar>,
+
     /// Minimum benchmarking time
     pub min_benchmarking_time: Second,
 
@@ -207,18 +466,37 @@ pub struct Options {
     /// Command(s) to run before each timing run
     pub preparation_command: Option<Vec<String>>,
 
+    /// Command to run before each timing run of the cold-cache benchmarks (`--cold-prepare`)
+    pub cold_preparation_command: Option<String>,
+
     /// Command to run before each *batch* of timing runs, i.e. before each individual benchmark
     pub setup_command: Option<String>,
 
     /// Command to run after each *batch* of timing runs, i.e. after each individual benchmark
     pub cleanup_command: Option<String>,
 
+    /// When the file system caches are dropped, if at all
+    pub drop_caches: Option<DropCaches>,
+
+    /// Whether dirty pages are written to the disks before each run (`--sync-before-run`)
+    pub sync_before_run: bool,
+
     /// What color mode to use for the terminal output
     pub output_style: OutputStyleOption,
 
+    /// Whether the regular output is written to stderr, because results are exported to stdout
+    pub output_to_stderr: bool,
+
     /// How to order benchmarks in the relative speed comparison
     pub sort_order_speed_comparison: SortOrder,
 
+    /// Time that is used to compute the relative speeds in the summary
+    pub relative_speed_metric: RelativeSpeedMetric,
+
+    /// Command that all other commands are compared to in the summary (`--reference`). It is
+    /// benchmarked first.
+    pub reference_command: Option<String>,
+
     /// How to order benchmarks in the markup format exports
     pub sort_order_exports: SortOrder,
 
@@ -231,26 +509,202 @@ pub struct Options {
     /// What to do with the output of the benchmarked command
     pub command_output_policy: CommandOutputPolicy,
 
+    /// Whether the time until the first output of the command is measured
+    pub time_to_first_output: bool,
+
     /// Which time unit to use when displaying results
     pub time_unit: Option<Unit>,
+
+    /// Percentiles (between 0 and 100) of the run times that are computed
+    pub percentiles: Vec<f64>,
+
+    /// Confidence level of all confidence intervals
+    pub confidence_level: Scalar,
+
+    /// Significance level for the comparison of benchmarks
+    pub significance_level: f64,
+
+    /// Statistical test for the comparison of benchmarks
+    pub significance_test: SignificanceTest,
+
+    /// Relative difference of the mean times for which the number of required runs is
+    /// reported (`--power-analysis`)
+    pub power_analysis: Option<Scalar>,
+
+    /// Whether the standard error of the mean is shown
+    pub show_sem: bool,
+
+    /// Whether the resource usage of the runs is shown in the results. It is always exported.
+    pub show_resource_usage: bool,
+
+    /// Whether bootstrap confidence intervals are computed
+    pub bootstrap: bool,
+
+    /// Whether confidence intervals of the relative speeds are shown (Fieller's theorem)
+    pub relative_speed_ci: bool,
+
+    /// Whether the probability that the reference is faster than each other command is shown
+    pub probability_faster: bool,
+
+    /// Whether the relative speeds of all pairs of commands are shown and exported
+    pub comparison_matrix: bool,
+
+    /// Whether the runs that were detected as outliers are listed
+    pub show_outliers: bool,
+
+    /// Method that is used to detect statistical outliers
+    pub outlier_method: OutlierMethod,
+
+    /// Whether outliers are removed before computing the statistics
+    pub discard_outliers: bool,
+
+    /// Whether a trimmed or winsorized mean is computed instead of the arithmetic mean
+    pub trim: Option<Trim>,
+
+    /// Amount of data processed per run, to report the throughput of the commands
+    pub throughput: Option<Throughput>,
+
+    /// Whether a histogram of the run times is shown after each benchmark
+    pub histogram: bool,
+
+    /// Whether a sparkline of the run times is shown after each benchmark
+    pub sparkline: bool,
+
+    /// Whether the geometric mean of the relative speeds across parameter values is reported
+    pub geometric_mean: bool,
+
+    /// Whether the mean times of a parameter scan are fitted against complexity models
+    pub complexity: bool,
+
+    /// Hardware performance counters that are measured for every run (Linux only)
+    pub perf_counters: Vec<PerfCounter>,
+
+    /// Whether the performance counters include the child processes of the benchmarked
+    /// process (Linux only, see `--no-perf-counters-children`)
+    pub perf_counters_children: bool,
+
+    /// Directory to which a profile of every benchmarked command is written (Linux and macOS
+    /// only)
+    pub profile_dir: Option<PathBuf>,
+
+    /// Whether the energy consumption of the CPU is measured for every run (Linux only)
+    pub measure_energy: bool,
+
+    /// Whether the frequency of the CPU(s) is sampled during every run (Linux only)
+    pub sample_cpu_frequency: bool,
+
+    /// Whether the utilization and memory usage of the GPU(s) are sampled during every run
+    /// (Linux only)
+    pub sample_gpu: bool,
+
+    /// Whether every run is performed in a fresh cgroup (Linux only)
+    pub cgroup: bool,
+
+    /// Maximum memory usage of the cgroup of a run in bytes (`--limit-memory`)
+    pub memory_limit: Option<u64>,
+
+    /// Maximum number of CPUs that the cgroup of a run may use (`--limit-cpus`)
+    pub cpu_limit: Option<f64>,
+
+    /// Whether all descendants of the benchmarked command are waited for and included in the
+    /// resource usage, even if the command does not wait for them (Linux and Windows only)
+    pub aggregate_children: bool,
+
+    /// Nice level of the benchmarked commands, if given
+    pub priority: Option<i32>,
+
+    /// Whether address space layout randomization is disabled for the benchmarked commands
+    /// (Linux only)
+    pub disable_aslr: bool,
+
+    /// Whether all commands are run in a network namespace without access to other hosts
+    /// (Linux only)
+    pub isolate_network: bool,
+
+    /// The clock that measures the run time of the benchmarked commands
+    pub clock: Clock,
+
+    /// CPUs that the benchmarked commands are pinned to, if not empty
+    pub cpu_affinity: Vec<usize>,
+
+    /// CPUs that hyperfine itself is pinned to, if not empty
+    pub hyperfine_cpu_affinity: Vec<usize>,
+
+    /// Environment variables that are set for all commands (`--env-file` and `--env`), in
+    /// this order. The values can contain parameters.
+    pub environment: Vec<(String, String)>,
+
+    /// Whether the commands are run without the environment of hyperfine, except for the
+    /// variables that are required to run them (`--env-clear`)
+    pub clear_environment: bool,
 }
 
 impl Default for Options {
     fn default() -> Options {
         Options {
             run_bounds: RunBounds::default(),
+            batch_size: 1,
             warmup_count: 0,
+            auto_warmup: false,
+            max_time: None,
+            max_relative_stddev: None,
+            max_ci_width: None,
             min_benchmarking_time: 3.0,
             command_failure_action: CmdFailureAction::RaiseError,
             preparation_command: None,
+            cold_preparation_command: None,
             setup_command: None,
             cleanup_command: None,
+            drop_caches: None,
+            sync_before_run: false,
             output_style: OutputStyleOption::Full,
-            sort_order_speed_comparison: SortOrder::MeanTime,
+            output_to_stderr: false,
+            sort_order_speed_comparison: SortOrder::RelativeSpeed,
+            relative_speed_metric: RelativeSpeedMetric::default(),
+            reference_command: None,
             sort_order_exports: SortOrder::Command,
             executor_kind: ExecutorKind::default(),
             command_output_policy: CommandOutputPolicy::Null,
+            time_to_first_output: false,
             time_unit: None,
+            percentiles: vec![],
+            confidence_level: DEFAULT_CONFIDENCE_LEVEL,
+            significance_level: 0.05,
+            significance_test: SignificanceTest::default(),
+            power_analysis: None,
+            show_sem: false,
+            show_resource_usage: false,
+            bootstrap: false,
+            relative_speed_ci: false,
+            probability_faster: false,
+            comparison_matrix: false,
+            show_outliers: false,
+            outlier_method: OutlierMethod::default(),
+            discard_outliers: false,
+            trim: None,
+            throughput: None,
+            histogram: false,
+            sparkline: false,
+            geometric_mean: false,
+            complexity: false,
+            perf_counters: vec![],
+            perf_counters_children: true,
+            profile_dir: None,
+            measure_energy: false,
+            sample_cpu_frequency: false,
+            sample_gpu: false,
+            cgroup: false,
+            memory_limit: None,
+            cpu_limit: None,
+            aggregate_children: false,
+            priority: None,
+            disable_aslr: false,
+            isolate_network: false,
+            clock: Clock::default(),
+            cpu_affinity: vec![],
+            hyperfine_cpu_affinity: vec![],
+            environment: vec![],
+            clear_environment: false,
             command_input_policy: CommandInputPolicy::Null,
         }
     }
@@ -269,7 +723,31 @@ impl Options {
                 .transpose()
         };
 
-        options.warmup_count = param_to_u64("warmup")?.unwrap_or(options.warmup_count);
+        if matches.get_one::<String>("warmup").map(|s| s.as_str()) == Some("auto") {
+            options.auto_warmup = true;
+        } else {
+            options.warmup_count = param_to_u64("warmup")?.unwrap_or(options.warmup_count);
+        }
+
+        let param_to_percentage = |param| {
+            matches
+                .get_one::<String>(param)
+                .map(|pct| {
+                    let value = pct
+                        .trim_end_matches('%')
+                        .parse::<f64>()
+                        .map_err(|e| OptionsError::FloatParsingError(param, e))?;
+                    if !(value > 0.0 && value.is_finite()) {
+                        return Err(OptionsError::InvalidPercentage(param, pct.to_string()));
+                    }
+                    Ok(value / 100.0)
+                })
+                .transpose()
+        };
+
+        options.max_relative_stddev = param_to_percentage("max-relative-stddev")?;
+        options.max_ci_width = param_to_percentage("max-ci-width")?;
+        options.power_analysis = param_to_percentage("power-analysis")?;
 
         let mut min_runs = param_to_u64("min-runs")?;
         let mut max_runs = param_to_u64("max-runs")?;
@@ -303,9 +781,27 @@ impl Options {
         options.preparation_command = matches
             .get_many::<String>("prepare")
             .map(|values| values.map(String::from).collect::<Vec<String>>());
+        options.cold_preparation_command = matches.get_one::<String>("cold-prepare").cloned();
 
         options.cleanup_command = matches.get_one::<String>("cleanup").map(String::from);
 
+        options.drop_caches = match matches.get_one::<String>("drop-caches").map(|s| s.as_str()) {
+            None => None,
+            Some("before-each-run") => Some(DropCaches::BeforeEachRun),
+            Some("before-each-benchmark") => Some(DropCaches::BeforeEachBenchmark),
+            Some(_) => unreachable!("Unknown value for '--drop-caches'"),
+        };
+        if options.drop_caches.is_some()
+            && !cfg!(any(target_os = "linux", target_os = "macos", windows))
+        {
+            return Err(OptionsError::DropCachesNotSupported);
+        }
+
+        options.sync_before_run = matches.get_flag("sync-before-run");
+        if options.sync_before_run && !cfg!(any(unix, windows)) {
+            return Err(OptionsError::SyncNotSupported);
+        }
+
         options.command_output_policy = if matches.get_flag("show-output") {
             CommandOutputPolicy::Inherit
         } else if let Some(output) = matches.get_one::<String>("output").map(|s| s.as_str()) {
@@ -321,9 +817,28 @@ impl Options {
                     CommandOutputPolicy::File(path)
                 }
             }
+        } else if matches.get_flag("time-to-first-output") {
+            // The output has to be read by hyperfine to notice when it starts
+            CommandOutputPolicy::Pipe
         } else {
             CommandOutputPolicy::Null
         };
+        options.time_to_first_output = matches.get_flag("time-to-first-output");
+
+        // Results that are exported to stdout should not be mixed with the regular output
+        options.output_to_stderr = matches.ids().any(|id| {
+            id.as_str().starts_with("export-")
+                && matches
+                    .try_get_raw(id.as_str())
+                    .ok()
+                    .flatten()
+                    .is_some_and(|mut values| values.any(|v| v == "-"))
+        });
+        let output_is_terminal = if options.output_to_stderr {
+            io::stderr().is_terminal()
+        } else {
+            io::stdout().is_terminal()
+        };
 
         options.output_style = match matches.get_one::<String>("style").map(|s| s.as_str()) {
             Some("full") => OutputStyleOption::Full,
@@ -333,7 +848,7 @@ impl Options {
             Some("none") => OutputStyleOption::Disabled,
             _ => {
                 if options.command_output_policy == CommandOutputPolicy::Inherit
-                    || !io::stdout().is_terminal()
+                    || !output_is_terminal
                 {
                     OutputStyleOption::Basic
                 } else if env::var_os("TERM")
@@ -364,12 +879,35 @@ impl Options {
             options.sort_order_speed_comparison,
             options.sort_order_exports,
         ) = match matches.get_one::<String>("sort").map(|s| s.as_str()) {
-            None | Some("auto") => (SortOrder::MeanTime, SortOrder::Command),
+            None | Some("auto") => (SortOrder::RelativeSpeed, SortOrder::Command),
             Some("command") => (SortOrder::Command, SortOrder::Command),
             Some("mean-time") => (SortOrder::MeanTime, SortOrder::MeanTime),
             Some(_) => unreachable!("Unknown sort order"),
         };
 
+        if let Some(sort_by) = matches.get_one::<String>("sort-by") {
+            options.sort_order_speed_comparison = match sort_by.as_str() {
+                "relative-speed" => SortOrder::RelativeSpeed,
+                "mean" => SortOrder::MeanTime,
+                "median" => SortOrder::MedianTime,
+                "command" => SortOrder::Alphabetical,
+                "order" => SortOrder::Command,
+                _ => unreachable!("Unknown sort order"),
+            };
+        }
+
+        options.reference_command = matches.get_one::<String>("reference").cloned();
+
+        options.relative_speed_metric = match matches
+            .get_one::<String>("relative-speed-metric")
+            .map(|s| s.as_str())
+        {
+            None | Some("mean") => RelativeSpeedMetric::Mean,
+            Some("median") => RelativeSpeedMetric::Median,
+            Some("min") => RelativeSpeedMetric::Min,
+            Some(_) => unreachable!("Unknown relative speed metric"),
+        };
+
         options.executor_kind = if matches.get_flag("no-shell") {
             ExecutorKind::Raw
         } else {
@@ -386,6 +924,24 @@ impl Options {
             }
         };
 
+        if let Some(batch_size) = param_to_u64("batch")? {
+            if batch_size == 0 {
+                return Err(OptionsError::InvalidBatchSize);
+            }
+            // The loop is run by a POSIX shell
+            let on_windows_cmd = cfg!(windows)
+                && matches!(
+                    options.executor_kind,
+                    ExecutorKind::Shell(Shell::Default(_))
+                );
+            if batch_size > 1
+                && (matches!(options.executor_kind, ExecutorKind::Raw) || on_windows_cmd)
+            {
+                return Err(OptionsError::BatchRequiresShell);
+            }
+            options.batch_size = batch_size;
+        }
+
         if matches.get_flag("ignore-failure") {
             options.command_failure_action = CmdFailureAction::Ignore;
         }
@@ -397,6 +953,248 @@ impl Options {
             _ => None,
         };
 
+        if let Some(percentiles) = matches.get_many::<String>("percentiles") {
+            options.percentiles = percentiles
+                .map(|p| match p.trim().parse::<f64>() {
+                    Ok(p) if (0.0..=100.0).contains(&p) => Ok(p),
+                    _ => Err(OptionsError::InvalidPercentile(p.to_string())),
+                })
+                .collect::<Result<_, _>>()?;
+        }
+
+        if let Some(level) = matches.get_one::<String>("confidence") {
+            options.confidence_level = level
+                .parse::<f64>()
+                .map_err(|e| OptionsError::FloatParsingError("confidence", e))?;
+            if !(options.confidence_level > 0.0 && options.confidence_level < 1.0) {
+                return Err(OptionsError::InvalidConfidenceLevel(level.to_string()));
+            }
+            // Significance tests use the same level, unless --significance-level is given.
+            // Rounding avoids printing e.g. 'α = 0.010000000000000009'.
+            options.significance_level = ((1.0 - options.confidence_level) * 1e10).round() / 1e10;
+        }
+
+        if let Some(alpha) = matches.get_one::<String>("significance-level") {
+            options.significance_level = alpha
+                .parse::<f64>()
+                .map_err(|e| OptionsError::FloatParsingError("significance-level", e))?;
+            if !(options.significance_level > 0.0 && options.significance_level < 1.0) {
+                return Err(OptionsError::InvalidSignificanceLevel(alpha.to_string()));
+            }
+        }
+
+        options.significance_test = match matches
+            .get_one::<String>("significance-test")
+            .map(|s| s.as_str())
+        {
+            None | Some("welch") => SignificanceTest::Welch,
+            Some("mann-whitney") => SignificanceTest::MannWhitney,
+            Some(_) => unreachable!("Unknown significance test"),
+        };
+
+        if let Some(counters) = matches.get_many::<String>("perf-counters") {
+            if !cfg!(any(target_os = "linux", target_os = "macos")) {
+                return Err(OptionsError::PerfCountersNotSupported);
+            }
+            for name in counters {
+                let counter = PerfCounter::ALL
+                    .iter()
+                    .copied()
+                    .find(|c| c.name() == name)
+                    .expect("Unknown performance counter");
+                if cfg!(target_os = "macos")
+                    && !matches!(counter, PerfCounter::Cycles | PerfCounter::Instructions)
+                {
+                    return Err(OptionsError::PerfCounterNotSupported(counter.name()));
+                }
+                if !options.perf_counters.contains(&counter) {
+                    options.perf_counters.push(counter);
+                }
+            }
+        }
+
+        options.perf_counters_children = !matches.get_flag("no-perf-counters-children");
+
+        options.profile_dir = matches.get_one::<String>("profile").map(PathBuf::from);
+        if options.profile_dir.is_some() && !cfg!(any(target_os = "linux", target_os = "macos")) {
+            return Err(OptionsError::ProfilingNotSupported);
+        }
+
+        options.measure_energy = matches.get_flag("measure-energy");
+        if options.measure_energy && !cfg!(target_os = "linux") {
+            return Err(OptionsError::EnergyMeasurementNotSupported);
+        }
+
+        options.sample_cpu_frequency = matches.get_flag("cpu-frequency");
+        if options.sample_cpu_frequency && !cfg!(target_os = "linux") {
+            return Err(OptionsError::CpuFrequencyNotSupported);
+        }
+
+        options.sample_gpu = matches.get_flag("gpu");
+        if options.sample_gpu && !cfg!(target_os = "linux") {
+            return Err(OptionsError::GpuSamplingNotSupported);
+        }
+
+        options.memory_limit = matches
+            .get_one::<String>("limit-memory")
+            .map(|size| parse_memory_size(size))
+            .transpose()?;
+        options.cpu_limit = matches
+            .get_one::<String>("limit-cpus")
+            .map(|cpus| match cpus.parse::<f64>() {
+                Ok(n) if n > 0.0 && n.is_finite() => Ok(n),
+                _ => Err(OptionsError::InvalidCpuLimit(cpus.clone())),
+            })
+            .transpose()?;
+        // The limits are applied to the cgroup of every run
+        options.cgroup = matches.get_flag("cgroup")
+            || options.memory_limit.is_some()
+            || options.cpu_limit.is_some();
+        if options.cgroup && !cfg!(target_os = "linux") {
+            return Err(OptionsError::CgroupNotSupported);
+        }
+
+        options.aggregate_children = matches.get_flag("aggregate-children");
+        if options.aggregate_children && !cfg!(any(target_os = "linux", windows)) {
+            return Err(OptionsError::AggregateChildrenNotSupported);
+        }
+
+        options.priority = matches
+            .get_one::<String>("priority")
+            .map(|level| {
+                level
+                    .parse::<i32>()
+                    .map_err(|e| OptionsError::IntParsingError("priority", e))
+            })
+            .transpose()?;
+        if let Some(level) = options.priority {
+            if !(MIN_NICE_LEVEL..=MAX_NICE_LEVEL).contains(&level) {
+                return Err(OptionsError::InvalidPriority(level));
+            }
+        }
+
+        options.disable_aslr = matches.get_flag("no-aslr");
+        if options.disable_aslr && !cfg!(target_os = "linux") {
+            return Err(OptionsError::AslrNotSupported);
+        }
+
+        options.isolate_network = matches.get_flag("isolate-network");
+        if options.isolate_network && !cfg!(target_os = "linux") {
+            return Err(OptionsError::NetworkIsolationNotSupported);
+        }
+
+        options.clock = match matches.get_one::<String>("clock").map(|s| s.as_str()) {
+            None | Some("monotonic") => Clock::Monotonic,
+            Some("monotonic-raw") => Clock::MonotonicRaw,
+            Some("process-cputime") => Clock::ProcessCpuTime,
+            Some(_) => unreachable!("Unknown clock"),
+        };
+        if options.clock == Clock::MonotonicRaw
+            && !cfg!(any(target_os = "linux", target_os = "macos"))
+        {
+            return Err(OptionsError::MonotonicRawClockNotSupported);
+        }
+
+        for path in matches.get_many::<String>("env-file").unwrap_or_default() {
+            let content = std::fs::read_to_string(path)
+                .map_err(|e| OptionsError::EnvironmentFileUnreadable(path.to_string(), e))?;
+            options
+                .environment
+                .extend(parse_environment_file(&content)?);
+        }
+        for variable in matches.get_many::<String>("env").unwrap_or_default() {
+            options
+                .environment
+                .push(parse_environment_variable(variable)?);
+        }
+        options.clear_environment = matches.get_flag("env-clear");
+
+        if let Some(list) = matches.get_one::<String>("cpu") {
+            options.cpu_affinity = parse_cpu_list(list)?;
+        }
+        if let Some(list) = matches.get_one::<String>("hyperfine-cpu") {
+            options.hyperfine_cpu_affinity = parse_cpu_list(list)?;
+        }
+        let pins_cpus =
+            !options.cpu_affinity.is_empty() || !options.hyperfine_cpu_affinity.is_empty();
+        if pins_cpus && !cfg!(any(target_os = "linux", windows)) {
+            return Err(OptionsError::CpuAffinityNotSupported);
+        }
+
+        options.show_sem = matches.get_flag("show-sem");
+        options.show_resource_usage = matches.get_flag("show-resource-usage");
+        options.bootstrap = matches.get_flag("bootstrap");
+        options.relative_speed_ci = matches.get_flag("relative-speed-ci");
+        options.probability_faster = matches.get_flag("probability-faster");
+        options.comparison_matrix = matches.get_flag("comparison-matrix");
+
+        let outlier_threshold = matches
+            .get_one::<String>("outlier-threshold")
+            .map(|t| {
+                t.parse::<f64>()
+                    .map_err(|e| OptionsError::FloatParsingError("outlier-threshold", e))
+            })
+            .transpose()?;
+        options.outlier_method = match matches
+            .get_one::<String>("outlier-method")
+            .map(|s| s.as_str())
+        {
+            None | Some("mzscore") => OutlierMethod::ModifiedZScore(
+                outlier_threshold.unwrap_or(DEFAULT_MZSCORE_THRESHOLD),
+            ),
+            Some("iqr") => OutlierMethod::Iqr(outlier_threshold.unwrap_or(DEFAULT_IQR_THRESHOLD)),
+            Some("grubbs") => {
+                let alpha = outlier_threshold.unwrap_or(DEFAULT_GRUBBS_THRESHOLD);
+                if alpha >= 1.0 {
+                    return Err(OptionsError::InvalidOutlierThreshold(alpha.to_string()));
+                }
+                OutlierMethod::Grubbs(alpha)
+            }
+            Some("none") => OutlierMethod::None,
+            Some(_) => unreachable!("Unknown outlier method"),
+        };
+        if let Some(threshold) = outlier_threshold {
+            if !(threshold > 0.0 && threshold.is_finite()) {
+                return Err(OptionsError::InvalidOutlierThreshold(threshold.to_string()));
+            }
+        }
+
+        options.discard_outliers = matches.get_flag("discard-outliers");
+        options.show_outliers = matches.get_flag("show-outliers");
+
+        if let Some(pct) = matches.get_one::<String>("trim") {
+            let value = pct
+                .trim_end_matches('%')
+                .parse::<f64>()
+                .map_err(|e| OptionsError::FloatParsingError("trim", e))?;
+            if !(0.0..50.0).contains(&value) {
+                return Err(OptionsError::InvalidTrim(pct.to_string()));
+            }
+            options.trim = Some(if matches.get_flag("winsorize") {
+                Trim::Winsorized(value / 100.0)
+            } else {
+                Trim::Trimmed(value / 100.0)
+            });
+        }
+        options.throughput = matches
+            .get_one::<String>("throughput")
+            .map(|s| Throughput::from_str(s))
+            .transpose()?;
+        options.histogram = matches.get_flag("histogram");
+        options.sparkline = matches.get_flag("sparkline");
+        options.geometric_mean = matches.get_flag("geometric-mean");
+        options.complexity = matches.get_flag("complexity");
+
+        if let Some(time) = matches.get_one::<String>("max-time") {
+            let time = time
+                .parse::<Second>()
+                .map_err(|e| OptionsError::FloatParsingError("max-time", e))?;
+            if time <= 0.0 {
+                return Err(OptionsError::InvalidMaxTime(time));
+            }
+            options.max_time = Some(time);
+        }
+
         if let Some(time) = matches.get_one::<String>("min-benchmarking-time") {
             options.min_benchmarking_time = time
                 .parse::<f64>()
@@ -479,3 +1277,76 @@ fn test_can_parse_shell_command_line_from_str() {
         OptionsError::EmptyShell
     ));
 }
+
+#[test]
+fn test_parse_throughput() {
+    assert_eq!(
+        Throughput {
+            unit: ThroughputUnit::Bytes,
+            amount: Some("1024".into())
+        },
+        Throughput::from_str("bytes=1024").unwrap()
+    );
+    assert_eq!(
+        Some("{size}".into()),
+        Throughput::from_str("items={size}").unwrap().amount
+    );
+    assert_eq!(None, Throughput::from_str("items").unwrap().amount);
+
+    assert!(Throughput::from_str("lines=3").is_err());
+    assert!(Throughput::from_str("bytes=-1").is_err());
+    assert!(Throughput::from_str("bytes=many").is_err());
+}
+
+#[test]
+fn test_parse_memory_size() {
+    assert_eq!(4096, parse_memory_size("4096").unwrap());
+    assert_eq!(512 << 20, parse_memory_size("512M").unwrap());
+    assert_eq!(1 << 30, parse_memory_size("1g").unwrap());
+    assert_eq!(1536 << 20, parse_memory_size("1.5G").unwrap());
+
+    assert!(parse_memory_size("").is_err());
+    assert!(parse_memory_size("0").is_err());
+    assert!(parse_memory_size("-1G").is_err());
+    assert!(parse_memory_size("1X").is_err());
+    assert!(parse_memory_size("G").is_err());
+}
+
+#[test]
+fn test_parse_environment_variables() {
+    assert_eq!(
+        parse_environment_variable("RUST_LOG=debug").unwrap(),
+        ("RUST_LOG".to_string(), "debug".to_string())
+    );
+    assert_eq!(
+        parse_environment_variable("EMPTY=").unwrap(),
+        ("EMPTY".to_string(), "".to_string())
+    );
+    assert_eq!(
+        parse_environment_variable("OPTIONS=a=b c").unwrap(),
+        ("OPTIONS".to_string(), "a=b c".to_string())
+    );
+    assert!(parse_environment_variable("RUST_LOG").is_err());
+    assert!(parse_environment_variable("=debug").is_err());
+
+    assert_eq!(
+        parse_environment_file("# Knobs\n\nMALLOC_ARENA_MAX=2\nRUST_LOG={level}\n").unwrap(),
+        vec![
+            ("MALLOC_ARENA_MAX".to_string(), "2".to_string()),
+            ("RUST_LOG".to_string(), "{level}".to_string()),
+        ]
+    );
+    assert!(parse_environment_file("MALLOC_ARENA_MAX\n").is_err());
+}
+
+#[test]
+fn test_parse_cpu_list() {
+    assert_eq!(vec![3], parse_cpu_list("3").unwrap());
+    assert_eq!(vec![0, 2, 3, 4], parse_cpu_list("0,2-4").unwrap());
+    assert_eq!(vec![1, 2], parse_cpu_list("1-2,2").unwrap());
+
+    assert!(parse_cpu_list("").is_err());
+    assert!(parse_cpu_list("3-1").is_err());
+    assert!(parse_cpu_list("one").is_err());
+    assert!(parse_cpu_list("0,").is_err());
+}
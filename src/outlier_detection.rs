@@ -4,13 +4,71 @@
 //! - Boris Iglewicz and David Hoaglin (1993), "Volume 16: How to Detect and Handle Outliers",
 //!   The ASQC Basic References in Quality Control: Statistical Techniques, Edward F. Mykytka,
 //!   Ph.D., Editor.
+//! - Frank E. Grubbs (1969), "Procedures for Detecting Outlying Observations in Samples",
+//!   Technometrics, 11(1), 1–21.
 
-use statistical::median;
+use statistical::{mean, median, standard_deviation};
+
+use crate::statistics::{quantile, students_t_critical_value};
+
+/// Factor that converts the MAD to an estimator for the standard deviation
+const MAD_TO_STDDEV: f64 = 1.4826;
+
+/// Default number of standard deviations for the modified Z-score method
+pub const DEFAULT_MZSCORE_THRESHOLD: f64 = 10.0;
+
+/// Default multiple of the interquartile range for the IQR method
+pub const DEFAULT_IQR_THRESHOLD: f64 = 3.0;
+
+/// Default significance level for Grubbs' test
+pub const DEFAULT_GRUBBS_THRESHOLD: f64 = 0.05;
 
 /// Minimum modified Z-score for a datapoint to be an outlier. Here, 1.4826 is a factor that
 /// converts the MAD to an estimator for the standard deviation. The second factor is the number
 /// of standard deviations.
-pub const OUTLIER_THRESHOLD: f64 = 1.4826 * 10.0;
+pub const OUTLIER_THRESHOLD: f64 = MAD_TO_STDDEV * DEFAULT_MZSCORE_THRESHOLD;
+
+/// Method that is used to detect statistical outliers in the run times
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutlierMethod {
+    /// Modified Z-score larger than the given number of standard deviations
+    ModifiedZScore(f64),
+
+    /// Farther away from the quartiles than the given multiple of the interquartile range
+    /// (Tukey's fences)
+    Iqr(f64),
+
+    /// Grubbs' test at the given significance level, repeated until no more outliers are found
+    Grubbs(f64),
+
+    /// Do not detect any outliers
+    None,
+}
+
+impl Default for OutlierMethod {
+    fn default() -> Self {
+        OutlierMethod::ModifiedZScore(DEFAULT_MZSCORE_THRESHOLD)
+    }
+}
+
+impl OutlierMethod {
+    /// Determine for each data point in the sample whether it is an outlier
+    pub fn outliers(&self, xs: &[f64]) -> Vec<bool> {
+        if xs.is_empty() {
+            return vec![];
+        }
+
+        match *self {
+            OutlierMethod::ModifiedZScore(threshold) => modified_zscores(xs)
+                .iter()
+                .map(|s| s.abs() > MAD_TO_STDDEV * threshold)
+                .collect(),
+            OutlierMethod::Iqr(factor) => iqr_outliers(xs, factor),
+            OutlierMethod::Grubbs(alpha) => grubbs_outliers(xs, alpha),
+            OutlierMethod::None => vec![false; xs.len()],
+        }
+    }
+}
 
 /// Compute modifized Z-scores for a given sample. A (unmodified) Z-score is defined by
 /// `(x_i - x_mean)/x_stddev` whereas the modified Z-score is defined by `(x_i - x_median)/MAD`
@@ -37,6 +95,69 @@ pub fn modified_zscores(xs: &[f64]) -> Vec<f64> {
     xs.iter().map(|&x| (x - x_median) / mad).collect()
 }
 
+/// Modified Z-scores of a given sample in units of (estimated) standard deviations, i.e. the
+/// values that the 'mzscore' method compares against its threshold
+pub fn scaled_modified_zscores(xs: &[f64]) -> Vec<f64> {
+    modified_zscores(xs)
+        .iter()
+        .map(|z| z / MAD_TO_STDDEV)
+        .collect()
+}
+
+/// Detect outliers with Tukey's fences: data points below `Q1 - factor * IQR`
+/// or above `Q3 + factor * IQR`, where IQR is the interquartile range.
+fn iqr_outliers(xs: &[f64], factor: f64) -> Vec<bool> {
+    let mut sorted = xs.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+
+    let q1 = quantile(&sorted, 0.25);
+    let q3 = quantile(&sorted, 0.75);
+    let iqr = q3 - q1;
+
+    xs.iter()
+        .map(|&x| x < q1 - factor * iqr || x > q3 + factor * iqr)
+        .collect()
+}
+
+/// Detect outliers with Grubbs' test. The test detects a single outlier, so it is
+/// repeated on the remaining data points until no further outlier is found.
+///
+/// References:
+/// - <https://en.wikipedia.org/wiki/Grubbs%27s_test>
+fn grubbs_outliers(xs: &[f64], alpha: f64) -> Vec<bool> {
+    let mut outliers = vec![false; xs.len()];
+    let mut remaining: Vec<usize> = (0..xs.len()).collect();
+
+    while remaining.len() >= 3 {
+        let values: Vec<f64> = remaining.iter().map(|&i| xs[i]).collect();
+        let x_mean = mean(&values);
+        let x_stddev = standard_deviation(&values, Some(x_mean));
+        if x_stddev <= 0.0 {
+            break;
+        }
+
+        let (position, &index) = remaining
+            .iter()
+            .enumerate()
+            .max_by(|(_, &a), (_, &b)| (xs[a] - x_mean).abs().total_cmp(&(xs[b] - x_mean).abs()))
+            .unwrap();
+        let g = (xs[index] - x_mean).abs() / x_stddev;
+
+        let n = values.len() as f64;
+        let t = students_t_critical_value(alpha / n, n - 2.0);
+        let g_critical = (n - 1.0) / n.sqrt() * (t * t / (n - 2.0 + t * t)).sqrt();
+
+        if g <= g_critical {
+            break;
+        }
+
+        outliers[index] = true;
+        remaining.remove(position);
+    }
+
+    outliers
+}
+
 /// Return the number of outliers in a given sample. Outliers are defined as data points with a
 /// modified Z-score that is larger than `OUTLIER_THRESHOLD`.
 #[cfg(test)]
@@ -113,3 +234,74 @@ fn test_detect_outliers_if_mad_becomes_0() {
     let xs = [10.0, 10.0, 10.0, 10.0, 10.0, 10.0, 10.0, 100.0, 100.0];
     assert_eq!(2, num_outliers(&xs));
 }
+
+#[test]
+fn test_outlier_methods() {
+    let xs = [10.0, 10.2, 9.9, 10.1, 10.0, 9.8, 10.3, 10.1, 9.9, 14.0];
+    let expected = [
+        false, false, false, false, false, false, false, false, false, true,
+    ];
+
+    assert_eq!(expected.to_vec(), OutlierMethod::Iqr(3.0).outliers(&xs));
+    assert_eq!(expected.to_vec(), OutlierMethod::Grubbs(0.05).outliers(&xs));
+    assert_eq!(vec![false; 10], OutlierMethod::None.outliers(&xs));
+    assert_eq!(expected.to_vec(), OutlierMethod::default().outliers(&xs));
+    assert_eq!(
+        vec![false; 10],
+        OutlierMethod::ModifiedZScore(50.0).outliers(&xs)
+    );
+    assert_eq!(
+        expected.to_vec(),
+        OutlierMethod::ModifiedZScore(5.0).outliers(&xs)
+    );
+
+    // Grubbs' test removes outliers one by one
+    let xs = [
+        10.0, 10.2, 9.9, 10.1, 10.0, 9.8, 10.3, 10.1, 9.9, 14.0, 20.0,
+    ];
+    assert_eq!(
+        2,
+        OutlierMethod::Grubbs(0.05)
+            .outliers(&xs)
+            .iter()
+            .filter(|&&o| o)
+            .count()
+    );
+
+    // No outliers in the sample drawn from a normal distribution
+    let xs = [
+        2.33269488,
+        1.42195907,
+        -0.57527698,
+        -0.31293437,
+        2.2948158,
+        0.75813273,
+        -1.0712388,
+        -0.96394741,
+        -1.15897446,
+        1.10976285,
+    ];
+    assert!(!OutlierMethod::Iqr(1.5).outliers(&xs).contains(&true));
+    assert!(!OutlierMethod::Grubbs(0.05).outliers(&xs).contains(&true));
+
+    assert!(OutlierMethod::Grubbs(0.05).outliers(&[]).is_empty());
+    assert_eq!(vec![false], OutlierMethod::Iqr(1.5).outliers(&[1.0]));
+}
+
+#[test]
+fn test_scaled_modified_zscores() {
+    use approx::assert_relative_eq;
+
+    let xs = [1.0, 2.0, 3.0, 4.0, 100.0];
+    let zscores = scaled_modified_zscores(&xs);
+    assert_relative_eq!(-2.0 / MAD_TO_STDDEV, zscores[0]);
+    assert_relative_eq!(0.0, zscores[2]);
+    assert_relative_eq!(97.0 / MAD_TO_STDDEV, zscores[4]);
+
+    // The scores can be compared against the threshold of the 'mzscore' method
+    let outliers: Vec<bool> = zscores
+        .iter()
+        .map(|z| z.abs() > DEFAULT_MZSCORE_THRESHOLD)
+        .collect();
+    assert_eq!(outliers, OutlierMethod::default().outliers(&xs));
+}
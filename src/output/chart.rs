@@ -0,0 +1,141 @@
+use crate::util::min_max::{max, min};
+use crate::util::units::Second;
+
+/// Block characters of increasing height, used to draw bars that are one character wide
+const BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Number of bins of the histogram
+pub const HISTOGRAM_BINS: usize = 30;
+
+/// Maximum number of characters of a sparkline
+pub const SPARKLINE_WIDTH: usize = 60;
+
+/// Draw a bar for each value, scaled such that the largest value fills a whole character.
+/// Zero values are drawn as spaces.
+fn bars(values: &[f64]) -> String {
+    let largest = values.iter().cloned().fold(0.0, f64::max);
+
+    values
+        .iter()
+        .map(|&v| {
+            if v <= 0.0 || largest <= 0.0 {
+                ' '
+            } else {
+                let level = (v / largest * BLOCKS.len() as f64).ceil() as usize;
+                BLOCKS[level.clamp(1, BLOCKS.len()) - 1]
+            }
+        })
+        .collect()
+}
+
+/// Count how many of the given times fall into each of `bins` equally wide bins between the
+/// minimum and the maximum time.
+pub fn histogram_counts(times: &[Second], bins: usize) -> Vec<usize> {
+    let mut counts = vec![0; bins];
+    if times.is_empty() || bins == 0 {
+        return counts;
+    }
+
+    let t_min = min(times);
+    let t_max = max(times);
+    let width = (t_max - t_min) / bins as f64;
+
+    for &t in times {
+        let bin = if width > 0.0 {
+            (((t - t_min) / width) as usize).min(bins - 1)
+        } else {
+            0
+        };
+        counts[bin] += 1;
+    }
+
+    counts
+}
+
+/// Draw a histogram of the run times as a single line of block characters, ranging from the
+/// fastest run (left) to the slowest run (right).
+pub fn histogram(times: &[Second]) -> String {
+    if times.is_empty() {
+        return String::new();
+    }
+
+    let counts: Vec<f64> = histogram_counts(times, HISTOGRAM_BINS)
+        .into_iter()
+        .map(|c| c as f64)
+        .collect();
+
+    if min(times) == max(times) {
+        // All runs fall into a single bin, there is no distribution to show
+        return bars(&counts[..1]);
+    }
+
+    bars(&counts)
+}
+
+/// Draw the run times in chronological order as a single line of block characters. The
+/// fastest run is drawn as the lowest block, the slowest run as the highest one. If there are
+/// more runs than `SPARKLINE_WIDTH`, consecutive runs are averaged.
+pub fn sparkline(times: &[Second]) -> String {
+    if times.is_empty() {
+        return String::new();
+    }
+
+    let chunk_size = (times.len() + SPARKLINE_WIDTH - 1) / SPARKLINE_WIDTH;
+    let values: Vec<Second> = times
+        .chunks(chunk_size)
+        .map(|chunk| chunk.iter().sum::<Second>() / chunk.len() as f64)
+        .collect();
+
+    let v_min = min(&values);
+    let range = max(&values) - v_min;
+
+    values
+        .iter()
+        .map(|&v| {
+            let level = if range > 0.0 {
+                ((v - v_min) / range * (BLOCKS.len() - 1) as f64).round() as usize
+            } else {
+                0
+            };
+            BLOCKS[level]
+        })
+        .collect()
+}
+
+#[test]
+fn test_histogram_counts() {
+    assert_eq!(vec![0, 0, 0], histogram_counts(&[], 3));
+    assert_eq!(vec![2, 0, 0], histogram_counts(&[1.0, 1.0], 3));
+    assert_eq!(
+        vec![2, 0, 1, 2],
+        histogram_counts(&[1.0, 1.2, 1.5, 1.9, 2.0], 4)
+    );
+}
+
+#[test]
+fn test_histogram() {
+    assert_eq!("", histogram(&[]));
+    assert_eq!("█", histogram(&[1.0, 1.0, 1.0]));
+
+    let times: Vec<Second> = [1.0, 1.0, 1.0, 1.0, 1.1, 2.0, 2.0]
+        .iter()
+        .map(|t| t * 30.0)
+        .collect();
+    let hist = histogram(&times);
+    assert_eq!(HISTOGRAM_BINS, hist.chars().count());
+    assert!(hist.starts_with("█  ▂ "));
+    assert!(hist.ends_with('▄'));
+}
+
+#[test]
+fn test_sparkline() {
+    assert_eq!("", sparkline(&[]));
+    assert_eq!("▁▁▁", sparkline(&[1.0, 1.0, 1.0]));
+    assert_eq!("█▁▅▁", sparkline(&[2.0, 1.0, 1.6, 1.0]));
+
+    let times: Vec<Second> = (0..2 * SPARKLINE_WIDTH).map(|i| i as f64).collect();
+    let line = sparkline(&times);
+    assert_eq!(SPARKLINE_WIDTH, line.chars().count());
+    assert!(line.starts_with('▁'));
+    assert!(line.ends_with('█'));
+}
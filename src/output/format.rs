@@ -1,4 +1,5 @@
-use crate::util::units::{Second, Unit};
+use crate::options::ThroughputUnit;
+use crate::util::units::{Scalar, Second, Unit};
 
 /// Format the given duration as a string. The output-unit can be enforced by setting `unit` to
 /// `Some(target_unit)`. If `unit` is `None`, it will be determined automatically.
@@ -25,6 +26,53 @@ pub fn format_duration_value(duration: Second, unit: Option<Unit>) -> (String, U
     }
 }
 
+/// The largest decimal SI prefix (and its scale) for which the given value is at least one
+fn si_prefix(value: Scalar) -> (Scalar, &'static str) {
+    const PREFIXES: [&str; 5] = ["", "k", "M", "G", "T"];
+
+    let mut scale = 1.0;
+    let mut prefix = 0;
+    while value / scale >= 1000.0 && prefix < PREFIXES.len() - 1 {
+        scale *= 1000.0;
+        prefix += 1;
+    }
+    (scale, PREFIXES[prefix])
+}
+
+/// Format the given number of bytes with a decimal SI prefix, e.g. "12.3 MB"
+pub fn format_bytes(bytes: Scalar) -> String {
+    let (scale, prefix) = si_prefix(bytes);
+    format!("{:.1} {}B", bytes / scale, prefix)
+}
+
+/// Format the given count with a decimal SI prefix, e.g. "1.23 G"
+pub fn format_count(count: Scalar) -> String {
+    match si_prefix(count) {
+        (_, "") => format!("{:.0}", count),
+        (scale, prefix) => format!("{:.2} {}", count / scale, prefix),
+    }
+}
+
+/// Format the given throughput (per second) with a decimal SI prefix, e.g. "12.3 MB/s". If a
+/// standard deviation is given, it is formatted with the same prefix.
+pub fn format_throughput(
+    throughput: Scalar,
+    stddev: Option<Scalar>,
+    unit: ThroughputUnit,
+) -> String {
+    let (scale, prefix) = si_prefix(throughput);
+
+    let format = |value: Scalar| match unit {
+        ThroughputUnit::Bytes => format!("{:.1} {}B/s", value / scale, prefix),
+        ThroughputUnit::Items => format!("{:.1}{} items/s", value / scale, prefix),
+    };
+
+    match stddev {
+        Some(stddev) => format!("{} ± {}", format(throughput), format(stddev)),
+        None => format(throughput),
+    }
+}
+
 #[test]
 fn test_format_duration_unit_basic() {
     let (out_str, out_unit) = format_duration_unit(1.3, None);
@@ -75,3 +123,32 @@ fn test_format_duration_unit_with_unit() {
     assert_eq!("1300000.0 µs", out_str);
     assert_eq!(Unit::MicroSecond, out_unit);
 }
+
+#[test]
+fn test_format_throughput() {
+    let bytes = ThroughputUnit::Bytes;
+    assert_eq!("500.0 B/s", format_throughput(500.0, None, bytes));
+    assert_eq!("1.5 MB/s", format_throughput(1.5e6, None, bytes));
+    assert_eq!("2000.0 TB/s", format_throughput(2e15, None, bytes));
+    assert_eq!(
+        "1.5 MB/s ± 0.2 MB/s",
+        format_throughput(1.5e6, Some(2e5), bytes)
+    );
+    assert_eq!(
+        "12.3k items/s",
+        format_throughput(12_345.0, None, ThroughputUnit::Items)
+    );
+}
+
+#[test]
+fn test_format_bytes() {
+    assert_eq!("512.0 B", format_bytes(512.0));
+    assert_eq!("4.1 MB", format_bytes(4_100_000.0));
+    assert_eq!("1.0 GB", format_bytes(1e9));
+}
+
+#[test]
+fn test_format_count() {
+    assert_eq!("153", format_count(153.0));
+    assert_eq!("1.23 G", format_count(1_234_000_000.0));
+}
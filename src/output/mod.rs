@@ -1,3 +1,21 @@
+pub mod chart;
 pub mod format;
 pub mod progress_bar;
 pub mod warnings;
+
+/// Print a line of the regular output. It is written to stderr instead of stdout if
+/// results are exported to stdout (see `Options::output_to_stderr`).
+macro_rules! outputln {
+    ($options:expr) => {
+        $crate::output::outputln!($options, "")
+    };
+    ($options:expr, $($arg:tt)*) => {
+        if $options.output_to_stderr {
+            eprintln!($($arg)*)
+        } else {
+            println!($($arg)*)
+        }
+    };
+}
+
+pub(crate) use outputln;
@@ -2,7 +2,7 @@ use std::fmt;
 
 use crate::benchmark::MIN_EXECUTION_TIME;
 use crate::output::format::format_duration;
-use crate::util::units::Second;
+use crate::util::units::{Scalar, Second};
 
 pub struct OutlierWarningOptions {
     pub warmup_in_use: bool,
@@ -15,6 +15,15 @@ pub enum Warnings {
     NonZeroExitCode,
     SlowInitialRun(Second, OutlierWarningOptions),
     OutliersDetected(OutlierWarningOptions),
+    RelativeStddevTooHigh(Scalar, Scalar),
+    ConfidenceIntervalTooWide(Scalar, Scalar, Scalar),
+    DriftDetected(Scalar),
+    BimodalDistribution(Second, Second),
+    ManyInvoluntaryContextSwitches(Scalar),
+    BackgroundLoad(Scalar),
+    NoOutput,
+    UnsuitableCpuGovernor(String),
+    TurboBoostEnabled(String),
 }
 
 impl fmt::Display for Warnings {
@@ -25,7 +34,8 @@ impl fmt::Display for Warnings {
                 "Command took less than {:.0} ms to complete. Note that the results might be \
                 inaccurate because hyperfine can not calibrate the shell startup time much \
                 more precise than this limit. You can try to use the `-N`/`--shell=none` \
-                option to disable the shell completely.",
+                option to disable the shell completely, or the `--batch` option to run the \
+                command several times per measurement.",
                 MIN_EXECUTION_TIME * 1e3
             ),
             Warnings::NonZeroExitCode => write!(f, "Ignoring non-zero exit code."),
@@ -54,6 +64,79 @@ impl fmt::Display for Warnings {
                     option to clear the caches before each timing run."
                 }
             ),
+            Warnings::RelativeStddevTooHigh(relative_stddev, max_relative_stddev) => write!(
+                f,
+                "The relative standard deviation of {:.1}% is still larger than the requested \
+                 {}% after the maximum number of runs. Consider increasing '--max-runs' or \
+                 re-running this benchmark on a quiet system.",
+                relative_stddev * 100.0,
+                max_relative_stddev * 100.0
+            ),
+            Warnings::ConfidenceIntervalTooWide(confidence, half_width, max_ci_width) => write!(
+                f,
+                "The {}% confidence interval of the mean (±{:.1}%) is still wider than the \
+                 requested ±{}% after the maximum number of runs. Consider increasing \
+                 '--max-runs' or re-running this benchmark on a quiet system.",
+                confidence * 100.0,
+                half_width * 100.0,
+                max_ci_width * 100.0
+            ),
+            Warnings::DriftDetected(drift) if drift > 0.0 => write!(
+                f,
+                "The run times increased by {:.0}% over the course of this benchmark. This could \
+                 be caused by thermal throttling, growing caches or other programs that started \
+                 in the background. Consider re-running this benchmark on a quiet system.",
+                drift * 100.0
+            ),
+            Warnings::DriftDetected(drift) => write!(
+                f,
+                "The run times decreased by {:.0}% over the course of this benchmark. This could \
+                 be caused by caches that were not filled until after a few runs. Consider using \
+                 the '--warmup' option to fill those caches before the actual benchmark.",
+                -drift * 100.0
+            ),
+            Warnings::BimodalDistribution(lower, upper) => write!(
+                f,
+                "The run times seem to be bimodal, with two clusters at ~{} and ~{}. The results \
+                 may depend on caching effects or on which CPU the command was scheduled, and the \
+                 mean time might not be representative.",
+                format_duration(lower, None),
+                format_duration(upper, None)
+            ),
+            Warnings::ManyInvoluntaryContextSwitches(rate) => write!(
+                f,
+                "The command was preempted by the scheduler ~{:.0} times per second (involuntary \
+                 context switches), i.e. other programs competed with it for the CPU. This makes \
+                 the results noisy. Consider re-running this benchmark on a quiet system.",
+                rate
+            ),
+            Warnings::BackgroundLoad(load) => write!(
+                f,
+                "The system was ~{:.0}% busy with other processes during this benchmark, which \
+                 may have interfered with the results. Consider re-running this benchmark on a \
+                 quiet system.",
+                load * 100.0
+            ),
+            Warnings::NoOutput => write!(
+                f,
+                "The command did not write anything to stdout in some of the runs, so the time \
+                 to first output could not be measured."
+            ),
+            Warnings::UnsuitableCpuGovernor(ref governor) => write!(
+                f,
+                "The CPU frequency governor is '{}', so the CPU frequency changes with the load \
+                 and the results may be unstable. Consider switching to the 'performance' \
+                 governor for benchmarking, e.g. with 'sudo cpupower frequency-set --governor \
+                 performance'.",
+                governor
+            ),
+            Warnings::TurboBoostEnabled(ref disable_command) => write!(
+                f,
+                "Turbo boost is enabled, so the CPU frequency depends on the temperature of the \
+                 CPU and on the load of the other cores, and the results may be unstable. \
+                 Consider disabling it for benchmarking, e.g. with '{}'.",
+                disable_command
+            ),
             Warnings::OutliersDetected(ref options) => write!(
                 f,
                 "Statistical outliers were detected. Consider re-running this benchmark on a quiet \
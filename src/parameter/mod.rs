@@ -1,3 +1,5 @@
+use std::fmt;
+
 use crate::util::number::Number;
 
 pub mod range_step;
@@ -9,11 +11,11 @@ pub enum ParameterValue {
     Numeric(Number),
 }
 
-impl ToString for ParameterValue {
-    fn to_string(&self) -> String {
+impl fmt::Display for ParameterValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            ParameterValue::Text(ref value) => value.clone(),
-            ParameterValue::Numeric(value) => value.to_string(),
+            ParameterValue::Text(ref value) => write!(f, "{}", value),
+            ParameterValue::Numeric(value) => write!(f, "{}", value),
         }
     }
 }
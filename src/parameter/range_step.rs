@@ -1,5 +1,5 @@
 use std::convert::TryInto;
-use std::ops::{Add, AddAssign, Div, Sub};
+use std::ops::{Add, AddAssign, Div, Mul, Sub};
 
 use crate::error::ParameterScanError;
 use crate::util::number::Number;
@@ -7,6 +7,7 @@ use crate::util::number::Number;
 pub trait Numeric:
     Add<Output = Self>
     + Sub<Output = Self>
+    + Mul<Output = Self>
     + Div<Output = Self>
     + AddAssign
     + PartialOrd
@@ -19,6 +20,7 @@ pub trait Numeric:
 impl<
         T: Add<Output = Self>
             + Sub<Output = Self>
+            + Mul<Output = Self>
             + Div<Output = Self>
             + AddAssign
             + PartialOrd
@@ -30,11 +32,16 @@ impl<
 {
 }
 
+const MAX_PARAMETERS: usize = 100_000;
+
 #[derive(Debug)]
 pub struct RangeStep<T> {
-    state: T,
+    state: Option<T>,
     end: T,
     step: T,
+
+    /// Whether the values are multiplied by the step, instead of adding it
+    geometric: bool,
 }
 
 impl<T: Numeric> RangeStep<T> {
@@ -47,33 +54,87 @@ impl<T: Numeric> RangeStep<T> {
             return Err(ParameterScanError::ZeroStep);
         }
 
-        const MAX_PARAMETERS: usize = 100_000;
         match range_step_size_hint(start, end, step) {
             (_, Some(size)) if size <= MAX_PARAMETERS => Ok(Self {
-                state: start,
+                state: Some(start),
                 end,
                 step,
+                geometric: false,
             }),
             _ => Err(ParameterScanError::TooLarge),
         }
     }
+
+    /// A range where every value is the previous value multiplied by the given factor
+    pub fn new_geometric(start: T, end: T, factor: T) -> Result<Self, ParameterScanError> {
+        if end < start {
+            return Err(ParameterScanError::EmptyRange);
+        }
+
+        if start <= T::from(0) {
+            return Err(ParameterScanError::NonPositiveGeometricStart);
+        }
+
+        if factor <= T::from(1) {
+            return Err(ParameterScanError::InvalidFactor);
+        }
+
+        let range = Self {
+            state: Some(start),
+            end,
+            step: factor,
+            geometric: true,
+        };
+        match range.size_hint() {
+            (_, Some(size)) if size <= MAX_PARAMETERS => Ok(range),
+            _ => Err(ParameterScanError::TooLarge),
+        }
+    }
+
+    fn next_state(&self, state: T) -> Option<T> {
+        if !self.geometric {
+            let mut state = state;
+            state += self.step;
+            return Some(state);
+        }
+
+        // Stop before the multiplication could overflow
+        if state > self.end / self.step {
+            None
+        } else {
+            Some(state * self.step)
+        }
+    }
 }
 
 impl<T: Numeric> Iterator for RangeStep<T> {
     type Item = T;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.state > self.end {
-            return None;
-        }
-        let return_val = self.state;
-        self.state += self.step;
+        let return_val = self.state.filter(|state| *state <= self.end)?;
+        self.state = self.next_state(return_val);
 
         Some(return_val)
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
-        range_step_size_hint(self.state, self.end, self.step)
+        let Some(state) = self.state else {
+            return (0, Some(0));
+        };
+        if !self.geometric {
+            return range_step_size_hint(state, self.end, self.step);
+        }
+
+        let mut size = 0;
+        let mut state = Some(state);
+        while let Some(value) = state.filter(|value| *value <= self.end) {
+            size += 1;
+            if size > MAX_PARAMETERS {
+                return (usize::MAX, None);
+            }
+            state = self.next_state(value);
+        }
+        (size, Some(size))
     }
 }
 
@@ -147,4 +208,40 @@ mod tests {
             "Parameter range is too large"
         );
     }
+
+    #[test]
+    fn test_geometric_range() {
+        let param_range: Vec<i32> = RangeStep::new_geometric(1, 1000, 10).unwrap().collect();
+        assert_eq!(param_range, vec![1, 10, 100, 1000]);
+
+        let param_range: Vec<i32> = RangeStep::new_geometric(3, 100, 2).unwrap().collect();
+        assert_eq!(param_range, vec![3, 6, 12, 24, 48, 96]);
+
+        // No overflow at the end of the range
+        let param_range = RangeStep::new_geometric(1, i32::MAX, 2).unwrap();
+        assert_eq!(param_range.size_hint(), (31, Some(31)));
+        assert_eq!(param_range.last(), Some(1 << 30));
+
+        let param_range: Vec<Decimal> = RangeStep::new_geometric(
+            Decimal::from_str("0.5").unwrap(),
+            Decimal::from(5),
+            Decimal::from_str("1.5").unwrap(),
+        )
+        .unwrap()
+        .collect();
+        assert_eq!(
+            param_range,
+            ["0.5", "0.75", "1.125", "1.6875", "2.53125", "3.796875"]
+                .map(|v| Decimal::from_str(v).unwrap())
+        );
+
+        assert_eq!(
+            format!("{}", RangeStep::new_geometric(0, 10, 2).unwrap_err()),
+            "A geometric parameter range has to start at a positive value"
+        );
+        assert_eq!(
+            format!("{}", RangeStep::new_geometric(1, 10, 1).unwrap_err()),
+            "The factor of a geometric parameter step has to be larger than 1"
+        );
+    }
 }
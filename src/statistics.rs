@@ -0,0 +1,950 @@
+//! A module for statistical quantities that go beyond the basic mean/median/standard
+//! deviation of the `statistical` crate.
+
+use std::cmp;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::Serialize;
+use statistical::{mean, standard_deviation, variance};
+
+use crate::util::units::{Scalar, Second};
+
+/// Number of resamples for bootstrap confidence intervals
+pub const BOOTSTRAP_RESAMPLES: usize = 10_000;
+
+/// Default confidence level of all confidence intervals (`--confidence`)
+pub const DEFAULT_CONFIDENCE_LEVEL: Scalar = 0.95;
+
+/// Lower and upper bound of a confidence interval
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+pub struct ConfidenceInterval {
+    pub lower: Scalar,
+    pub upper: Scalar,
+}
+
+/// Result of a statistical hypothesis test
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TestResult {
+    /// The value of the test statistic
+    pub statistic: Scalar,
+
+    /// The (two-sided) probability of observing a statistic at least as extreme, if the
+    /// null hypothesis holds
+    pub p_value: Scalar,
+}
+
+impl TestResult {
+    /// Whether the null hypothesis is rejected at the given significance level
+    pub fn is_significant(&self, alpha: Scalar) -> bool {
+        self.p_value < alpha
+    }
+}
+
+/// Compute the q-th quantile of the given (sorted) sample by linear interpolation
+pub fn quantile(sorted: &[Second], q: f64) -> Second {
+    let position = q * (sorted.len() - 1) as f64;
+    let lower = position.floor() as usize;
+    let upper = position.ceil() as usize;
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (position - lower as f64)
+}
+
+/// Compute the p-th percentile (0 ≤ p ≤ 100) of the given (unsorted) sample
+pub fn percentile(xs: &[Second], p: f64) -> Second {
+    assert!(!xs.is_empty());
+
+    let mut sorted = xs.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    quantile(&sorted, p / 100.0)
+}
+
+/// Number of values that are trimmed or winsorized at each end of a sample of size `n`. At
+/// least one value is always kept.
+fn num_trimmed(n: usize, fraction: Scalar) -> usize {
+    cmp::min(
+        (fraction * n as Scalar).floor() as usize,
+        n.saturating_sub(1) / 2,
+    )
+}
+
+/// Remove the given fraction of the smallest and of the largest values from the sample
+pub fn trimmed(xs: &[Second], fraction: Scalar) -> Vec<Second> {
+    let mut sorted = xs.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    let k = num_trimmed(sorted.len(), fraction);
+    sorted[k..sorted.len() - k].to_vec()
+}
+
+/// Replace the given fraction of the smallest and of the largest values of the sample by the
+/// smallest and largest remaining value, respectively
+pub fn winsorized(xs: &[Second], fraction: Scalar) -> Vec<Second> {
+    let mut sorted = xs.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    let n = sorted.len();
+    let k = num_trimmed(n, fraction);
+    if k > 0 {
+        let (lowest, highest) = (sorted[k], sorted[n - 1 - k]);
+        sorted[..k].fill(lowest);
+        sorted[n - k..].fill(highest);
+    }
+    sorted
+}
+
+/// Compute the geometric mean of the given (positive) values
+pub fn geometric_mean(xs: &[Scalar]) -> Scalar {
+    assert!(!xs.is_empty());
+
+    (xs.iter().map(|x| x.ln()).sum::<Scalar>() / xs.len() as Scalar).exp()
+}
+
+/// Half width of the confidence interval (at the given confidence level) of the mean of the
+/// given sample, based on Student's t-distribution. Returns `None` if the sample has fewer
+/// than two elements.
+pub fn mean_ci_half_width(xs: &[Second], confidence: Scalar) -> Option<Second> {
+    if xs.len() < 2 {
+        return None;
+    }
+
+    let n = xs.len() as Scalar;
+    let stddev = standard_deviation(xs, None);
+    let t = students_t_critical_value(1.0 - confidence, n - 1.0);
+    Some(t * stddev / n.sqrt())
+}
+
+/// Confidence interval (at the given confidence level) of the ratio of the means of the given
+/// samples, i.e. `mean(xs) / mean(ys)`, based on Fieller's theorem. Unlike `ratio ± stddev`,
+/// the interval is asymmetric and accounts for the uncertainty of the denominator. The degrees
+/// of freedom are approximated with the Welch–Satterthwaite equation. Returns `None` if one of
+/// the samples has fewer than two elements, or if the mean of `ys` is not significantly
+/// different from zero (in which case the interval is unbounded).
+///
+/// References:
+/// - <https://en.wikipedia.org/wiki/Fieller%27s_theorem>
+pub fn fieller_ratio_of_means(
+    xs: &[Second],
+    ys: &[Second],
+    confidence: Scalar,
+) -> Option<ConfidenceInterval> {
+    if xs.len() < 2 || ys.len() < 2 {
+        return None;
+    }
+
+    let (n_x, n_y) = (xs.len() as f64, ys.len() as f64);
+    let (mean_x, mean_y) = (mean(xs), mean(ys));
+    if mean_y == 0.0 {
+        return None;
+    }
+    let var_x = variance(xs, Some(mean_x)) / n_x;
+    let var_y = variance(ys, Some(mean_y)) / n_y;
+    let ratio = mean_x / mean_y;
+
+    if var_x + var_y <= 0.0 {
+        return Some(ConfidenceInterval {
+            lower: ratio,
+            upper: ratio,
+        });
+    }
+
+    let df = (var_x + var_y).powi(2) / (var_x.powi(2) / (n_x - 1.0) + var_y.powi(2) / (n_y - 1.0));
+    let t = students_t_critical_value(1.0 - confidence, df);
+
+    let g = t.powi(2) * var_y / mean_y.powi(2);
+    if g >= 1.0 {
+        return None;
+    }
+
+    let half_width = t / mean_y.abs() * (var_x * (1.0 - g) + ratio.powi(2) * var_y).sqrt();
+    Some(ConfidenceInterval {
+        lower: (ratio - half_width) / (1.0 - g),
+        upper: (ratio + half_width) / (1.0 - g),
+    })
+}
+
+/// Bootstrap confidence interval of the mean of the given sample (percentile method).
+/// Returns `None` if the sample has fewer than two elements.
+pub fn bootstrap_mean(xs: &[Second], confidence: Scalar) -> Option<ConfidenceInterval> {
+    if xs.len() < 2 {
+        return None;
+    }
+    Some(bootstrap(|rng| mean(&resample(xs, rng)), confidence))
+}
+
+/// Bootstrap confidence interval of the ratio of the means of the given samples, i.e.
+/// `mean(xs) / mean(ys)`. The samples are resampled independently.
+pub fn bootstrap_ratio_of_means(
+    xs: &[Second],
+    ys: &[Second],
+    confidence: Scalar,
+) -> Option<ConfidenceInterval> {
+    if xs.len() < 2 || ys.len() < 2 {
+        return None;
+    }
+    Some(bootstrap(
+        |rng| mean(&resample(xs, rng)) / mean(&resample(ys, rng)),
+        confidence,
+    ))
+}
+
+/// Bootstrap estimate of the probability that the mean of the population behind `xs` is
+/// smaller than the mean of the population behind `ys`, i.e. the fraction of resamples
+/// in which `mean(xs) < mean(ys)`. Ties count as half. The samples are resampled
+/// independently, with the same fixed seed as the bootstrap confidence intervals.
+pub fn bootstrap_probability_smaller(xs: &[Second], ys: &[Second]) -> Option<Scalar> {
+    if xs.len() < 2 || ys.len() < 2 {
+        return None;
+    }
+    let mut rng = StdRng::seed_from_u64(0);
+    let wins: Scalar = (0..BOOTSTRAP_RESAMPLES)
+        .map(|_| {
+            let mean_x = mean(&resample(xs, &mut rng));
+            let mean_y = mean(&resample(ys, &mut rng));
+            match mean_x.partial_cmp(&mean_y) {
+                Some(cmp::Ordering::Less) => 1.0,
+                Some(cmp::Ordering::Equal) => 0.5,
+                _ => 0.0,
+            }
+        })
+        .sum();
+    Some(wins / BOOTSTRAP_RESAMPLES as Scalar)
+}
+
+/// Draw a sample of the same size with replacement
+fn resample(xs: &[Second], rng: &mut impl Rng) -> Vec<Second> {
+    (0..xs.len())
+        .map(|_| xs[rng.gen_range(0..xs.len())])
+        .collect()
+}
+
+/// Compute the statistic for `BOOTSTRAP_RESAMPLES` resamples and return the interval that
+/// contains the central `confidence` fraction of the values. A fixed seed is used,
+/// such that the interval is reproducible for the same measurements.
+fn bootstrap(statistic: impl Fn(&mut StdRng) -> Scalar, confidence: Scalar) -> ConfidenceInterval {
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut values: Vec<Scalar> = (0..BOOTSTRAP_RESAMPLES)
+        .map(|_| statistic(&mut rng))
+        .collect();
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    let tail = (1.0 - confidence) / 2.0;
+    ConfidenceInterval {
+        lower: quantile(&values, tail),
+        upper: quantile(&values, 1.0 - tail),
+    }
+}
+
+/// Welch's t-test for the null hypothesis that two samples have the same mean, without
+/// assuming equal variances. Returns `None` if one of the samples has fewer than two
+/// elements or if both samples are constant.
+///
+/// References:
+/// - <https://en.wikipedia.org/wiki/Welch%27s_t-test>
+pub fn welch_t_test(xs: &[Second], ys: &[Second]) -> Option<TestResult> {
+    if xs.len() < 2 || ys.len() < 2 {
+        return None;
+    }
+
+    let (n_x, n_y) = (xs.len() as f64, ys.len() as f64);
+    let (mean_x, mean_y) = (mean(xs), mean(ys));
+    let var_x = variance(xs, Some(mean_x)) / n_x;
+    let var_y = variance(ys, Some(mean_y)) / n_y;
+    if var_x + var_y <= 0.0 {
+        return None;
+    }
+
+    let t = (mean_x - mean_y) / (var_x + var_y).sqrt();
+
+    // Welch–Satterthwaite equation for the degrees of freedom
+    let df = (var_x + var_y).powi(2) / (var_x.powi(2) / (n_x - 1.0) + var_y.powi(2) / (n_y - 1.0));
+
+    Some(TestResult {
+        statistic: t,
+        p_value: students_t_two_sided_p_value(t, df),
+    })
+}
+
+/// Mann-Whitney U test for the null hypothesis that the values of both samples are
+/// equally likely to be larger than the other. This test does not assume normally
+/// distributed samples. For the p-value, the normal approximation with a correction for
+/// ties and continuity is used. Returns `None` if one of the samples is empty or if all
+/// values are equal.
+///
+/// References:
+/// - <https://en.wikipedia.org/wiki/Mann%E2%80%93Whitney_U_test>
+pub fn mann_whitney_u_test(xs: &[Second], ys: &[Second]) -> Option<TestResult> {
+    if xs.is_empty() || ys.is_empty() {
+        return None;
+    }
+
+    let mut values: Vec<(Second, bool)> = xs
+        .iter()
+        .map(|&x| (x, true))
+        .chain(ys.iter().map(|&y| (y, false)))
+        .collect();
+    values.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+
+    // Assign ranks, starting at one. Tied values get the average of their ranks.
+    let n = values.len();
+    let mut rank_sum_x = 0.0;
+    let mut tie_correction = 0.0;
+    let mut i = 0;
+    while i < n {
+        let mut j = i;
+        while j + 1 < n && values[j + 1].0 == values[i].0 {
+            j += 1;
+        }
+        let rank = (i + j) as f64 / 2.0 + 1.0;
+        rank_sum_x += rank * values[i..=j].iter().filter(|v| v.1).count() as f64;
+
+        let ties = (j - i + 1) as f64;
+        tie_correction += ties.powi(3) - ties;
+        i = j + 1;
+    }
+
+    let (n_x, n_y, n) = (xs.len() as f64, ys.len() as f64, n as f64);
+    let u = rank_sum_x - n_x * (n_x + 1.0) / 2.0;
+
+    let mean_u = n_x * n_y / 2.0;
+    let variance_u = n_x * n_y / 12.0 * ((n + 1.0) - tie_correction / (n * (n - 1.0)));
+    if variance_u <= 0.0 {
+        return None;
+    }
+    let z = ((u - mean_u).abs() - 0.5).max(0.0) / variance_u.sqrt();
+
+    Some(TestResult {
+        statistic: u,
+        p_value: erfc(z / std::f64::consts::SQRT_2).min(1.0),
+    })
+}
+
+/// Mann-Kendall test for the null hypothesis that there is no monotonic trend in the
+/// given time series. The statistic `S` is positive for increasing and negative for
+/// decreasing values. For the p-value, the normal approximation with a correction for
+/// ties and continuity is used. Returns `None` for fewer than three values or if all
+/// values are equal.
+///
+/// References:
+/// - <https://vsp.pnnl.gov/help/vsample/design_trend_mann_kendall.htm>
+pub fn mann_kendall_test(xs: &[Scalar]) -> Option<TestResult> {
+    if xs.len() < 3 {
+        return None;
+    }
+
+    let mut s: Scalar = 0.0;
+    for (i, &x) in xs.iter().enumerate() {
+        for &y in &xs[i + 1..] {
+            if y > x {
+                s += 1.0;
+            } else if y < x {
+                s -= 1.0;
+            }
+        }
+    }
+
+    let mut sorted = xs.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let mut tie_correction = 0.0;
+    let mut i = 0;
+    while i < sorted.len() {
+        let mut j = i;
+        while j + 1 < sorted.len() && sorted[j + 1] == sorted[i] {
+            j += 1;
+        }
+        let t = (j - i + 1) as Scalar;
+        tie_correction += t * (t - 1.0) * (2.0 * t + 5.0);
+        i = j + 1;
+    }
+
+    let n = xs.len() as Scalar;
+    let variance_s = (n * (n - 1.0) * (2.0 * n + 5.0) - tie_correction) / 18.0;
+    if variance_s <= 0.0 {
+        return None;
+    }
+    let z = (s.abs() - 1.0).max(0.0) / variance_s.sqrt();
+
+    Some(TestResult {
+        statistic: s,
+        p_value: erfc(z / std::f64::consts::SQRT_2).min(1.0),
+    })
+}
+
+/// Slope of the least-squares regression line through the given time series, where the
+/// i-th value is taken to be measured at time i
+pub fn linear_regression_slope(xs: &[Scalar]) -> Scalar {
+    assert!(!xs.is_empty());
+
+    let n = xs.len() as Scalar;
+    let i_mean = (n - 1.0) / 2.0;
+    let x_mean = mean(xs);
+
+    let (covariance, variance) = xs.iter().enumerate().fold((0.0, 0.0), |(c, v), (i, &x)| {
+        let di = i as Scalar - i_mean;
+        (c + di * (x - x_mean), v + di * di)
+    });
+
+    if variance > 0.0 {
+        covariance / variance
+    } else {
+        0.0
+    }
+}
+
+/// Two clusters of a sample, see `two_clusters`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Clusters {
+    /// Mean and number of values of the lower cluster
+    pub lower: (Scalar, usize),
+
+    /// Mean and number of values of the upper cluster
+    pub upper: (Scalar, usize),
+
+    /// Ashman's D, the distance between the cluster means relative to their spread. Values
+    /// above 2 are required for a clean separation of two normal distributions.
+    pub separation: Scalar,
+}
+
+/// Split the sample into two clusters such that the sum of squared deviations from the
+/// cluster means is minimal (one-dimensional 2-means clustering, which can be solved exactly
+/// by trying every split of the sorted sample). Returns `None` for fewer than four values.
+///
+/// References:
+/// - <https://en.wikipedia.org/wiki/Multimodal_distribution#Bimodal_separation>
+pub fn two_clusters(xs: &[Scalar]) -> Option<Clusters> {
+    if xs.len() < 4 {
+        return None;
+    }
+
+    let mut sorted = xs.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    let n = sorted.len();
+    let total: Scalar = sorted.iter().sum();
+    let total_squares: Scalar = sorted.iter().map(|x| x * x).sum();
+
+    // Sum of squared deviations of the values with the given sum and sum of squares
+    let ssd = |sum: Scalar, squares: Scalar, k: usize| squares - sum * sum / k as Scalar;
+
+    let mut best: Option<(Scalar, usize)> = None;
+    let (mut sum, mut squares) = (0.0, 0.0);
+    for k in 1..n {
+        sum += sorted[k - 1];
+        squares += sorted[k - 1] * sorted[k - 1];
+        let cost = ssd(sum, squares, k) + ssd(total - sum, total_squares - squares, n - k);
+        if best.map_or(true, |(best_cost, _)| cost < best_cost) {
+            best = Some((cost, k));
+        }
+    }
+    let (_, k) = best?;
+
+    let (lower, upper) = sorted.split_at(k);
+    let (mean_lower, mean_upper) = (mean(lower), mean(upper));
+    let variance_of = |xs: &[Scalar], m: Scalar| {
+        xs.iter().map(|x| (x - m).powi(2)).sum::<Scalar>() / xs.len() as Scalar
+    };
+    let spread = (variance_of(lower, mean_lower) + variance_of(upper, mean_upper)).sqrt();
+
+    Some(Clusters {
+        lower: (mean_lower, lower.len()),
+        upper: (mean_upper, upper.len()),
+        separation: if spread > 0.0 {
+            std::f64::consts::SQRT_2 * (mean_upper - mean_lower) / spread
+        } else {
+            Scalar::INFINITY
+        },
+    })
+}
+
+/// The complementary error function, with a fractional error below 1.2e-7
+///
+/// References:
+/// - W. H. Press et al. (1992), "Numerical Recipes in C", 2nd edition, section 6.2
+fn erfc(x: Scalar) -> Scalar {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let polynomial = -z * z - 1.265_512_23
+        + t * (1.000_023_68
+            + t * (0.374_091_96
+                + t * (0.096_784_18
+                    + t * (-0.186_288_06
+                        + t * (0.278_868_07
+                            + t * (-1.135_203_98
+                                + t * (1.488_515_87 + t * (-0.822_152_23 + t * 0.170_872_77))))))));
+    let result = t * polynomial.exp();
+
+    if x >= 0.0 {
+        result
+    } else {
+        2.0 - result
+    }
+}
+
+/// Two-sided p-value of the statistic `t` for Student's t-distribution with `df` degrees
+/// of freedom
+fn students_t_two_sided_p_value(t: Scalar, df: Scalar) -> Scalar {
+    regularized_incomplete_beta(df / (df + t * t), df / 2.0, 0.5)
+}
+
+/// Critical value of Student's t-distribution with `df` degrees of freedom, i.e. the
+/// value of `t` for which the two-sided p-value is `alpha`
+pub fn students_t_critical_value(alpha: Scalar, df: Scalar) -> Scalar {
+    let mut lower = 0.0;
+    let mut upper = 1.0;
+    while students_t_two_sided_p_value(upper, df) > alpha {
+        lower = upper;
+        upper *= 2.0;
+    }
+
+    // The p-value is monotonically decreasing in t, so we can use bisection
+    for _ in 0..100 {
+        let t = 0.5 * (lower + upper);
+        if students_t_two_sided_p_value(t, df) > alpha {
+            lower = t;
+        } else {
+            upper = t;
+        }
+    }
+
+    0.5 * (lower + upper)
+}
+
+/// Critical value of the standard normal distribution, i.e. the value of `z` for which the
+/// two-sided p-value is `alpha`
+pub fn normal_critical_value(alpha: Scalar) -> Scalar {
+    let mut lower = 0.0;
+    let mut upper = 40.0;
+
+    // The p-value is monotonically decreasing in z, so we can use bisection
+    for _ in 0..100 {
+        let z = 0.5 * (lower + upper);
+        if erfc(z / std::f64::consts::SQRT_2) > alpha {
+            lower = z;
+        } else {
+            upper = z;
+        }
+    }
+
+    0.5 * (lower + upper)
+}
+
+/// Number of runs per command that is needed to detect a relative difference of the mean
+/// times with a two-sided test at the significance level `alpha` and with the given
+/// statistical power, for run times with the coefficient of variation `cv` (normal
+/// approximation for two samples of equal size).
+///
+/// References:
+/// - <https://en.wikipedia.org/wiki/Sample_size_determination#Means>
+pub fn required_runs(cv: Scalar, relative_difference: Scalar, alpha: Scalar, power: Scalar) -> u64 {
+    let z_alpha = normal_critical_value(alpha);
+    let z_beta = normal_critical_value(2.0 * (1.0 - power));
+    let n = 2.0 * ((z_alpha + z_beta) * cv / relative_difference).powi(2);
+
+    cmp::max(n.ceil() as u64, 2)
+}
+
+/// Natural logarithm of the gamma function, using the Lanczos approximation
+fn ln_gamma(x: Scalar) -> Scalar {
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+
+    if x < 0.5 {
+        // Reflection formula
+        return (std::f64::consts::PI / (std::f64::consts::PI * x).sin()).ln() - ln_gamma(1.0 - x);
+    }
+
+    let x = x - 1.0;
+    let t = x + 7.5;
+    let sum = COEFFICIENTS[1..]
+        .iter()
+        .enumerate()
+        .fold(COEFFICIENTS[0], |sum, (i, c)| {
+            sum + c / (x + i as f64 + 1.0)
+        });
+
+    0.5 * (2.0 * std::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + sum.ln()
+}
+
+/// The regularized incomplete beta function I_x(a, b)
+///
+/// References:
+/// - W. H. Press et al. (2007), "Numerical Recipes", 3rd edition, section 6.4
+fn regularized_incomplete_beta(x: Scalar, a: Scalar, b: Scalar) -> Scalar {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+
+    let ln_front = ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln();
+
+    // The continued fraction converges quickly only for x < (a + 1) / (a + b + 2)
+    if x < (a + 1.0) / (a + b + 2.0) {
+        ln_front.exp() * beta_continued_fraction(x, a, b) / a
+    } else {
+        1.0 - ln_front.exp() * beta_continued_fraction(1.0 - x, b, a) / b
+    }
+}
+
+/// Evaluate the continued fraction of the incomplete beta function with the modified
+/// Lentz's method
+fn beta_continued_fraction(x: Scalar, a: Scalar, b: Scalar) -> Scalar {
+    const MAX_ITERATIONS: usize = 300;
+    const EPSILON: f64 = 1e-14;
+    const TINY: f64 = 1e-300;
+
+    let mut c = 1.0;
+    let mut d = 1.0 - (a + b) * x / (a + 1.0);
+    if d.abs() < TINY {
+        d = TINY;
+    }
+    d = 1.0 / d;
+    let mut h = d;
+
+    for m in 1..=MAX_ITERATIONS {
+        let m = m as f64;
+
+        // Even step
+        let numerator = m * (b - m) * x / ((a + 2.0 * m - 1.0) * (a + 2.0 * m));
+        d = 1.0 + numerator * d;
+        if d.abs() < TINY {
+            d = TINY;
+        }
+        c = 1.0 + numerator / c;
+        if c.abs() < TINY {
+            c = TINY;
+        }
+        d = 1.0 / d;
+        h *= d * c;
+
+        // Odd step
+        let numerator = -(a + m) * (a + b + m) * x / ((a + 2.0 * m) * (a + 2.0 * m + 1.0));
+        d = 1.0 + numerator * d;
+        if d.abs() < TINY {
+            d = TINY;
+        }
+        c = 1.0 + numerator / c;
+        if c.abs() < TINY {
+            c = TINY;
+        }
+        d = 1.0 / d;
+        let delta = d * c;
+        h *= delta;
+
+        if (delta - 1.0).abs() < EPSILON {
+            break;
+        }
+    }
+
+    h
+}
+
+#[test]
+fn test_quantile() {
+    let xs = [1.0, 2.0, 3.0, 4.0, 5.0];
+    assert_eq!(quantile(&xs, 0.0), 1.0);
+    assert_eq!(quantile(&xs, 0.25), 2.0);
+    assert_eq!(quantile(&xs, 0.5), 3.0);
+    assert_eq!(quantile(&xs, 1.0), 5.0);
+    assert_eq!(quantile(&[1.0, 2.0], 0.5), 1.5);
+}
+
+#[test]
+fn test_trimmed_and_winsorized() {
+    let xs = [5.0, 1.0, 100.0, 2.0, 4.0, 3.0, 0.0, 6.0, 7.0, 8.0];
+
+    assert_eq!(
+        vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0],
+        trimmed(&xs, 0.1)
+    );
+    assert_eq!(vec![2.0, 3.0, 4.0, 5.0, 6.0, 7.0], trimmed(&xs, 0.25));
+    assert_eq!(
+        vec![1.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 8.0],
+        winsorized(&xs, 0.1)
+    );
+    assert_eq!(xs.len(), trimmed(&xs, 0.0).len());
+
+    // At least one value is kept
+    assert_eq!(vec![2.0], trimmed(&[1.0, 2.0, 3.0], 0.49));
+    assert_eq!(vec![2.0, 2.0, 2.0], winsorized(&[1.0, 2.0, 3.0], 0.49));
+    assert_eq!(vec![1.0, 2.0], trimmed(&[1.0, 2.0], 0.49));
+}
+
+#[test]
+fn test_geometric_mean() {
+    use approx::assert_relative_eq;
+
+    assert_relative_eq!(geometric_mean(&[2.0]), 2.0);
+    assert_relative_eq!(geometric_mean(&[1.0, 4.0]), 2.0);
+    assert_relative_eq!(geometric_mean(&[0.5, 2.0, 8.0]), 2.0);
+}
+
+#[test]
+fn test_percentile() {
+    let xs = [5.0, 1.0, 4.0, 2.0, 3.0];
+    assert_eq!(percentile(&xs, 50.0), 3.0);
+    assert_eq!(percentile(&xs, 90.0), 4.6);
+    assert_eq!(percentile(&xs, 100.0), 5.0);
+    assert_eq!(percentile(&[0.5], 99.0), 0.5);
+}
+
+#[test]
+fn test_ln_gamma() {
+    use approx::assert_relative_eq;
+
+    assert_relative_eq!(ln_gamma(1.0), 0.0, epsilon = 1e-12);
+    assert_relative_eq!(ln_gamma(5.0), 24f64.ln(), epsilon = 1e-12);
+    assert_relative_eq!(
+        ln_gamma(0.5),
+        std::f64::consts::PI.sqrt().ln(),
+        epsilon = 1e-12
+    );
+}
+
+#[test]
+fn test_students_t_p_value() {
+    use approx::assert_relative_eq;
+
+    // Reference values from numerical integration of the probability density function
+    assert_relative_eq!(
+        students_t_two_sided_p_value(0.0, 10.0),
+        1.0,
+        epsilon = 1e-12
+    );
+    assert_relative_eq!(
+        students_t_two_sided_p_value(2.228138851986274, 10.0),
+        0.05,
+        epsilon = 1e-9
+    );
+    assert_relative_eq!(
+        students_t_two_sided_p_value(-1.0, 3.5),
+        0.381_337_253_563_885,
+        epsilon = 1e-9
+    );
+}
+
+#[test]
+fn test_students_t_critical_value() {
+    use approx::assert_relative_eq;
+
+    assert_relative_eq!(
+        12.706204736,
+        students_t_critical_value(0.05, 1.0),
+        epsilon = 1e-6
+    );
+    assert_relative_eq!(
+        2.228138852,
+        students_t_critical_value(0.05, 10.0),
+        epsilon = 1e-6
+    );
+    assert_relative_eq!(
+        2.763262455,
+        students_t_critical_value(0.01, 28.0),
+        epsilon = 1e-6
+    );
+}
+
+#[test]
+fn test_normal_critical_value() {
+    use approx::assert_relative_eq;
+
+    assert_relative_eq!(1.959_964, normal_critical_value(0.05), epsilon = 1e-5);
+    assert_relative_eq!(2.575_829, normal_critical_value(0.01), epsilon = 1e-5);
+    assert_relative_eq!(0.841_621, normal_critical_value(0.4), epsilon = 1e-5);
+}
+
+#[test]
+fn test_required_runs() {
+    // (1.96 + 0.84)² · 2 · (0.1 / 0.02)² ≈ 392.4
+    assert_eq!(393, required_runs(0.1, 0.02, 0.05, 0.8));
+    assert_eq!(2, required_runs(0.0, 0.02, 0.05, 0.8));
+}
+
+#[test]
+fn test_mean_ci_half_width() {
+    use approx::assert_relative_eq;
+
+    assert_eq!(None, mean_ci_half_width(&[1.0], DEFAULT_CONFIDENCE_LEVEL));
+    assert_relative_eq!(
+        1.963243161,
+        mean_ci_half_width(&[1.0, 2.0, 3.0, 4.0, 5.0], DEFAULT_CONFIDENCE_LEVEL).unwrap(),
+        epsilon = 1e-6
+    );
+}
+
+#[test]
+fn test_welch_t_test() {
+    use approx::assert_relative_eq;
+
+    let xs = [19.8, 20.4, 19.6, 17.8, 18.5, 18.9, 18.3, 18.9, 19.5, 22.0];
+    let ys = [28.2, 26.6, 20.1, 23.3, 25.2, 22.1, 17.7, 27.6, 20.6, 13.7];
+
+    let result = welch_t_test(&xs, &ys).unwrap();
+    assert_relative_eq!(result.statistic, -2.074_014_626_678_365, epsilon = 1e-9);
+    assert_relative_eq!(result.p_value, 0.064_279_997_724_628, epsilon = 1e-9);
+    assert!(result.is_significant(0.1));
+    assert!(!result.is_significant(0.05));
+
+    assert!(welch_t_test(&[1.0], &ys).is_none());
+    assert!(welch_t_test(&[1.0, 1.0], &[2.0, 2.0]).is_none());
+}
+
+#[test]
+fn test_erfc() {
+    use approx::assert_relative_eq;
+
+    assert_relative_eq!(erfc(0.0), 1.0, epsilon = 1e-7);
+    assert_relative_eq!(erfc(1.0), 0.157_299_207_050_285_1, epsilon = 1e-7);
+    assert_relative_eq!(erfc(-1.0), 1.842_700_792_949_715, epsilon = 1e-7);
+}
+
+#[test]
+fn test_mann_whitney_u_test() {
+    use approx::assert_relative_eq;
+
+    let xs = [19.8, 20.4, 19.6, 17.8, 18.5, 18.9, 18.3, 18.9, 19.5, 22.0];
+    let ys = [28.2, 26.6, 20.1, 23.3, 25.2, 22.1, 17.7, 27.6, 20.6, 13.7];
+
+    let result = mann_whitney_u_test(&xs, &ys).unwrap();
+    assert_relative_eq!(result.statistic, 23.0);
+    assert_relative_eq!(result.p_value, 0.045_073_786_316_232, epsilon = 1e-6);
+
+    let result = mann_whitney_u_test(&[1.0, 2.0, 3.0, 4.0, 5.0], &[6.0, 7.0, 8.0, 9.0, 10.0]);
+    assert_relative_eq!(
+        result.unwrap().p_value,
+        0.012_185_780_355_345,
+        epsilon = 1e-6
+    );
+
+    assert!(mann_whitney_u_test(&[], &ys).is_none());
+    assert!(mann_whitney_u_test(&[1.0, 1.0], &[1.0, 1.0]).is_none());
+}
+
+#[test]
+fn test_mann_kendall_test() {
+    use approx::assert_relative_eq;
+
+    let xs = [1.0, 1.2, 1.1, 1.3, 1.5, 1.4, 1.6, 1.8, 1.7, 1.9];
+    let result = mann_kendall_test(&xs).unwrap();
+    assert_eq!(39.0, result.statistic);
+    assert_relative_eq!(0.000676764, result.p_value, epsilon = 1e-6);
+
+    let ys: Vec<_> = xs.iter().rev().cloned().collect();
+    let result = mann_kendall_test(&ys).unwrap();
+    assert_eq!(-39.0, result.statistic);
+
+    let xs = [1.0, 1.0, 1.1, 1.0, 1.0, 0.9, 1.0, 1.1, 1.0, 1.0];
+    let result = mann_kendall_test(&xs).unwrap();
+    assert_relative_eq!(1.0, result.p_value);
+
+    assert!(mann_kendall_test(&[1.0, 2.0]).is_none());
+    assert!(mann_kendall_test(&[1.0, 1.0, 1.0]).is_none());
+}
+
+#[test]
+fn test_linear_regression_slope() {
+    use approx::assert_relative_eq;
+
+    assert_relative_eq!(0.0, linear_regression_slope(&[3.0]));
+    assert_relative_eq!(0.5, linear_regression_slope(&[1.0, 1.5, 2.0, 2.5]));
+    assert_relative_eq!(-0.8, linear_regression_slope(&[3.0, 1.0, 2.0, 0.0]));
+}
+
+#[test]
+fn test_two_clusters() {
+    use approx::assert_relative_eq;
+
+    let xs = [12.0, 45.0, 12.5, 11.5, 44.0, 46.0, 12.0, 45.0];
+    let clusters = two_clusters(&xs).unwrap();
+    assert_relative_eq!(12.0, clusters.lower.0);
+    assert_eq!(4, clusters.lower.1);
+    assert_relative_eq!(45.0, clusters.upper.0);
+    assert_eq!(4, clusters.upper.1);
+    assert!(clusters.separation > 20.0);
+
+    // Sample drawn from a normal distribution
+    let xs = [
+        2.33269488,
+        1.42195907,
+        -0.57527698,
+        -0.31293437,
+        2.2948158,
+        0.75813273,
+        -1.0712388,
+        -0.96394741,
+        -1.15897446,
+        1.10976285,
+    ];
+    assert!(two_clusters(&xs).unwrap().separation < 6.0);
+
+    assert!(two_clusters(&[1.0, 2.0, 3.0]).is_none());
+}
+
+#[test]
+fn test_bootstrap() {
+    let xs: Vec<f64> = (0..20).map(|i| 1.0 + 0.01 * (i % 10) as f64).collect();
+    let ci = bootstrap_mean(&xs, DEFAULT_CONFIDENCE_LEVEL).unwrap();
+    assert!(ci.lower < mean(&xs) && mean(&xs) < ci.upper);
+    assert!(ci.upper - ci.lower < 0.03);
+
+    // Reproducible for the same measurements
+    assert_eq!(bootstrap_mean(&xs, DEFAULT_CONFIDENCE_LEVEL), Some(ci));
+
+    let ys: Vec<f64> = xs.iter().map(|x| 2.0 * x).collect();
+    let ratio = bootstrap_ratio_of_means(&ys, &xs, DEFAULT_CONFIDENCE_LEVEL).unwrap();
+    assert!(ratio.lower < 2.0 && 2.0 < ratio.upper);
+
+    // Constant samples lead to an interval of zero width
+    let ci = bootstrap_mean(&[1.0, 1.0, 1.0], DEFAULT_CONFIDENCE_LEVEL).unwrap();
+    assert_eq!((ci.lower, ci.upper), (1.0, 1.0));
+
+    assert!(bootstrap_mean(&[1.0], DEFAULT_CONFIDENCE_LEVEL).is_none());
+    assert!(bootstrap_ratio_of_means(&[1.0], &xs, DEFAULT_CONFIDENCE_LEVEL).is_none());
+}
+
+#[test]
+fn test_bootstrap_probability_smaller() {
+    let xs = [1.0, 1.1, 0.9, 1.0];
+    let ys = [2.0, 2.1, 1.9, 2.0];
+    assert_eq!(bootstrap_probability_smaller(&xs, &ys), Some(1.0));
+    assert_eq!(bootstrap_probability_smaller(&ys, &xs), Some(0.0));
+    assert_eq!(
+        bootstrap_probability_smaller(&[1.0, 1.0], &[1.0, 1.0]),
+        Some(0.5)
+    );
+
+    // Overlapping samples
+    let zs = [1.05, 1.15, 0.95, 1.05];
+    let p = bootstrap_probability_smaller(&xs, &zs).unwrap();
+    assert!(p > 0.5 && p < 1.0);
+
+    assert!(bootstrap_probability_smaller(&[1.0], &ys).is_none());
+}
+
+#[test]
+fn test_fieller_ratio_of_means() {
+    use approx::assert_relative_eq;
+
+    let xs = [1.0, 2.0, 3.0, 4.0, 5.0];
+    let ys = [10.0, 11.0, 12.0, 13.0, 14.0];
+    let ci = fieller_ratio_of_means(&xs, &ys, DEFAULT_CONFIDENCE_LEVEL).unwrap();
+    assert_relative_eq!(0.113249, ci.lower, epsilon = 1e-5);
+    assert_relative_eq!(0.396157, ci.upper, epsilon = 1e-5);
+
+    // Constant samples lead to an interval of zero width
+    let ci = fieller_ratio_of_means(&[2.0, 2.0], &[1.0, 1.0], DEFAULT_CONFIDENCE_LEVEL).unwrap();
+    assert_eq!((ci.lower, ci.upper), (2.0, 2.0));
+
+    // The mean of the denominator is not significantly different from zero
+    assert!(fieller_ratio_of_means(&ys, &[-1.0, 1.0, 2.0], DEFAULT_CONFIDENCE_LEVEL).is_none());
+    assert!(fieller_ratio_of_means(&[1.0], &ys, DEFAULT_CONFIDENCE_LEVEL).is_none());
+}
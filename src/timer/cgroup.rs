@@ -0,0 +1,363 @@
+#![cfg(target_os = "linux")]
+
+use std::ffi::CString;
+use std::fs;
+use std::io;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::process::CommandExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::OnceLock;
+use std::thread;
+use std::time::Duration;
+
+use crate::options::Options;
+use crate::util::units::Second;
+
+use anyhow::{bail, Context, Result};
+
+/// Length of a period of the CPU bandwidth controller (`cpu.max`), in microseconds
+const CPU_PERIOD_USEC: u64 = 100_000;
+
+/// Resource usage of all processes that ran in a cgroup
+pub struct CgroupUsage {
+    pub time_user: Second,
+    pub time_system: Second,
+
+    /// Peak memory usage of all processes combined, if the memory controller is available
+    pub memory_peak_byte: Option<u64>,
+
+    /// Peak number of processes and threads that were in the cgroup at the same time, if the
+    /// pids controller is available
+    pub peak_tasks: Option<u64>,
+}
+
+/// Root and mount point of the cgroup v2 hierarchy, from the content of
+/// /proc/self/mountinfo. The mount point is /sys/fs/cgroup on most systems, and
+/// /sys/fs/cgroup/unified in the 'hybrid' mode of systemd.
+fn cgroup2_mount(mountinfo: &str) -> Option<(PathBuf, PathBuf)> {
+    mountinfo.lines().find_map(|line| {
+        let (mount, filesystem) = line.split_once(" - ")?;
+        if filesystem.split_whitespace().next()? != "cgroup2" {
+            return None;
+        }
+        let mut fields = mount.split_whitespace().skip(3);
+        let root = fields.next()?;
+        let mount_point = fields.next()?;
+        Some((PathBuf::from(root), PathBuf::from(mount_point)))
+    })
+}
+
+/// Path of the cgroup v2 of a process in the hierarchy, from the content of
+/// /proc/<pid>/cgroup
+fn cgroup2_path(cgroup: &str) -> Option<&str> {
+    cgroup.lines().find_map(|line| line.strip_prefix("0::"))
+}
+
+/// Mount point of the cgroup v2 hierarchy and the directory of the cgroup in which
+/// hyperfine runs
+fn own_cgroup_dir() -> Result<(PathBuf, PathBuf)> {
+    let mountinfo = fs::read_to_string("/proc/self/mountinfo")
+        .context("Could not read /proc/self/mountinfo")?;
+    let (root, mount_point) = cgroup2_mount(&mountinfo)
+        .context("Could not find the cgroup v2 hierarchy. Is it mounted?")?;
+    let cgroup =
+        fs::read_to_string("/proc/self/cgroup").context("Could not read /proc/self/cgroup")?;
+    let path =
+        Path::new(cgroup2_path(&cgroup).context("Could not find the cgroup v2 of hyperfine")?);
+
+    // Only a subtree of the hierarchy is mounted inside of a cgroup namespace
+    let path = path.strip_prefix(&root).unwrap_or(path);
+    let dir = match path.strip_prefix("/") {
+        Ok(path) if path.as_os_str().is_empty() => mount_point.clone(),
+        Ok(path) => mount_point.join(path),
+        Err(_) => mount_point.join(path),
+    };
+    Ok((mount_point, dir))
+}
+
+fn contains(list: &str, controller: &str) -> bool {
+    list.split_whitespace().any(|c| c == controller)
+}
+
+/// Enable the given controllers for the children of the given cgroup, if they are available
+/// in it. Due to the 'no internal processes' rule of cgroup v2, this is only possible if
+/// hyperfine is the only process in it, and moves itself into a child cgroup beforehand.
+/// This is the case for a delegated scope like 'systemd-run --user --scope -p Delegate=yes'.
+fn enable_controllers(parent: &Path, controllers: &[&str]) -> Result<()> {
+    let available = fs::read_to_string(parent.join("cgroup.controllers")).unwrap_or_default();
+    let enabled = fs::read_to_string(parent.join("cgroup.subtree_control")).unwrap_or_default();
+    let missing: Vec<_> = controllers
+        .iter()
+        .filter(|&&c| contains(&available, c) && !contains(&enabled, c))
+        .collect();
+    if missing.is_empty() {
+        return Ok(());
+    }
+
+    let pid = std::process::id().to_string();
+    let procs = fs::read_to_string(parent.join("cgroup.procs")).unwrap_or_default();
+    if procs.lines().any(|p| p != pid) {
+        bail!(
+            "Other processes than hyperfine run in '{}'",
+            parent.display()
+        );
+    }
+    let leaf = parent.join(format!("hyperfine-{}-main", pid));
+    if !leaf.exists() {
+        fs::create_dir(&leaf)
+            .with_context(|| format!("Could not create the cgroup '{}'", leaf.display()))?;
+    }
+    write_file(&leaf.join("cgroup.procs"), "0")?;
+
+    for controller in missing {
+        write_file(
+            &parent.join("cgroup.subtree_control"),
+            &format!("+{}", controller),
+        )?;
+    }
+    Ok(())
+}
+
+/// The cgroup below which the cgroups of the runs are created, which is only determined once
+/// since hyperfine might move itself into a child cgroup in the process
+fn parent_cgroup(options: &Options) -> Result<&'static Path> {
+    static PARENT: OnceLock<Result<PathBuf, String>> = OnceLock::new();
+
+    let parent = PARENT.get_or_init(|| {
+        let (mount_point, parent) = own_cgroup_dir().map_err(|e| e.to_string())?;
+
+        // The controllers of the root cgroup are left alone, since that would be a
+        // system-wide change. Whether the controllers that are needed for the limits could
+        // be enabled is checked by the caller.
+        if parent != mount_point {
+            let controllers = [
+                ("memory", true),
+                ("cpu", options.cpu_limit.is_some()),
+                ("pids", true),
+            ];
+            let controllers: Vec<_> = controllers
+                .iter()
+                .filter(|(_, wanted)| *wanted)
+                .map(|(c, _)| *c)
+                .collect();
+            let _ = enable_controllers(&parent, &controllers);
+        }
+        Ok(parent)
+    });
+
+    match parent {
+        Ok(parent) => Ok(parent.as_path()),
+        Err(message) => bail!("{}", message),
+    }
+}
+
+fn write_file(path: &Path, content: &str) -> Result<()> {
+    fs::write(path, content).with_context(|| format!("Could not write to '{}'", path.display()))
+}
+
+/// A fresh cgroup (v2) for a single run of a command (`--cgroup`), which is removed when
+/// it is dropped. Processes that are still running in it at that point are killed.
+pub struct Cgroup {
+    path: PathBuf,
+}
+
+impl Cgroup {
+    pub fn create(options: &Options) -> Result<Self> {
+        // The cgroup is created below the one of hyperfine, which can be a subtree that is
+        // delegated to the user, e.g. with 'systemd-run --user --scope -p Delegate=yes'.
+        let parent = parent_cgroup(options)?;
+
+        // The controllers for the limits have to be enabled for the children of the parent
+        // cgroup. The memory and pids controllers are also used to measure the peak memory
+        // usage and the peak number of tasks, if they are enabled.
+        let enabled = fs::read_to_string(parent.join("cgroup.subtree_control")).unwrap_or_default();
+        for (controller, option) in [
+            ("memory", options.memory_limit.map(|_| "--limit-memory")),
+            ("cpu", options.cpu_limit.map(|_| "--limit-cpus")),
+        ] {
+            if let Some(option) = option {
+                if !contains(&enabled, controller) {
+                    bail!(
+                        "The '{}' controller, which is needed for '{}', is not enabled for the \
+                         children of the cgroup '{}' of hyperfine, and could not be enabled. \
+                         Run hyperfine in a cgroup of its own with this controller delegated \
+                         to it, e.g. with 'systemd-run --user --scope -p Delegate=yes \
+                         hyperfine ...'.",
+                        controller,
+                        option,
+                        parent.display()
+                    );
+                }
+            }
+        }
+
+        let cgroup = Cgroup {
+            path: parent.join(format!("hyperfine-{}", std::process::id())),
+        };
+        if cgroup.path.exists() {
+            // Left behind by a previous run that failed
+            cgroup.remove()?;
+        }
+        fs::create_dir(&cgroup.path).with_context(|| {
+            format!(
+                "Could not create the cgroup '{}'. This requires write access to the cgroup of \
+                 hyperfine, i.e. root privileges or a delegated subtree.",
+                cgroup.path.display()
+            )
+        })?;
+
+        if let Some(limit) = options.memory_limit {
+            write_file(&cgroup.path.join("memory.max"), &limit.to_string())?;
+            // Otherwise, the command would be swapped out instead of running out of memory
+            let _ = write_file(&cgroup.path.join("memory.swap.max"), "0");
+        }
+        if let Some(cpus) = options.cpu_limit {
+            let quota = (cpus * CPU_PERIOD_USEC as f64).round() as u64;
+            write_file(
+                &cgroup.path.join("cpu.max"),
+                &format!("{} {}", quota, CPU_PERIOD_USEC),
+            )?;
+        }
+
+        Ok(cgroup)
+    }
+
+    /// Move the processes that are spawned by the given command into this cgroup, before
+    /// they execute the program
+    pub fn add_command(&self, command: &mut Command) -> Result<()> {
+        let procs = CString::new(self.path.join("cgroup.procs").as_os_str().as_bytes())?;
+
+        // SAFETY: Only system calls are used between `fork` and `exec`, without allocating.
+        // Writing "0" to 'cgroup.procs' moves the writing process.
+        unsafe {
+            command.pre_exec(move || {
+                let fd = libc::open(procs.as_ptr(), libc::O_WRONLY | libc::O_CLOEXEC);
+                if fd < 0 {
+                    return Err(io::Error::last_os_error());
+                }
+                let ret = libc::write(fd, b"0".as_ptr() as *const libc::c_void, 1);
+                let error = io::Error::last_os_error();
+                libc::close(fd);
+                if ret < 0 {
+                    Err(error)
+                } else {
+                    Ok(())
+                }
+            });
+        }
+        Ok(())
+    }
+
+    pub fn usage(&self) -> Result<CgroupUsage> {
+        let path = self.path.join("cpu.stat");
+        let cpu_stat = fs::read_to_string(&path)
+            .with_context(|| format!("Could not read '{}'", path.display()))?;
+        let field = |name: &str| -> Result<Second> {
+            let usec: u64 = cpu_stat
+                .lines()
+                .find_map(|line| line.strip_prefix(name)?.strip_prefix(' ')?.parse().ok())
+                .with_context(|| format!("Could not find '{}' in '{}'", name, path.display()))?;
+            Ok(usec as Second * 1e-6)
+        };
+
+        Ok(CgroupUsage {
+            time_user: field("user_usec")?,
+            time_system: field("system_usec")?,
+            // Only available since Linux 5.19
+            memory_peak_byte: fs::read_to_string(self.path.join("memory.peak"))
+                .ok()
+                .and_then(|peak| peak.trim().parse().ok()),
+            // Not available on older kernels either
+            peak_tasks: fs::read_to_string(self.path.join("pids.peak"))
+                .ok()
+                .and_then(|peak| peak.trim().parse().ok()),
+        })
+    }
+
+    /// Kill all processes in the cgroup and remove it
+    fn remove(&self) -> Result<()> {
+        // 'cgroup.kill' is only available since Linux 5.14
+        if write_file(&self.path.join("cgroup.kill"), "1").is_err() {
+            for pid in fs::read_to_string(self.path.join("cgroup.procs"))
+                .unwrap_or_default()
+                .lines()
+                .filter_map(|pid| pid.parse().ok())
+            {
+                // SAFETY: Sending a signal is always safe
+                unsafe { libc::kill(pid, libc::SIGKILL) };
+            }
+        }
+
+        // The cgroup can only be removed after the killed processes have exited
+        for _ in 0..1000 {
+            match fs::remove_dir(&self.path) {
+                Err(e) if e.raw_os_error() == Some(libc::EBUSY) => {
+                    thread::sleep(Duration::from_millis(1))
+                }
+                result => {
+                    return result.with_context(|| {
+                        format!("Could not remove the cgroup '{}'", self.path.display())
+                    })
+                }
+            }
+        }
+        bail!(
+            "Could not remove the cgroup '{}', because processes are still running in it",
+            self.path.display()
+        );
+    }
+}
+
+impl Drop for Cgroup {
+    fn drop(&mut self) {
+        let _ = self.remove();
+    }
+}
+
+#[test]
+fn test_cgroup2_mount() {
+    let mountinfo = "\
+        25 30 0:23 / /sys rw,nosuid,nodev,noexec,relatime shared:7 - sysfs sysfs rw\n\
+        35 25 0:30 / /sys/fs/cgroup rw,nosuid,nodev,noexec,relatime shared:9 - cgroup2 cgroup2 rw,nsdelegate\n";
+    assert_eq!(
+        cgroup2_mount(mountinfo),
+        Some((PathBuf::from("/"), PathBuf::from("/sys/fs/cgroup")))
+    );
+
+    let mountinfo = "\
+        36 35 0:31 / /sys/fs/cgroup/unified rw,relatime shared:10 - cgroup2 cgroup2 rw\n\
+        37 35 0:32 / /sys/fs/cgroup/cpu rw,relatime shared:11 - cgroup cgroup rw,cpu\n";
+    assert_eq!(
+        cgroup2_mount(mountinfo),
+        Some((PathBuf::from("/"), PathBuf::from("/sys/fs/cgroup/unified")))
+    );
+
+    let mountinfo = "\
+        35 25 0:30 /user.slice /sys/fs/cgroup rw,relatime - cgroup2 cgroup2 rw\n";
+    assert_eq!(
+        cgroup2_mount(mountinfo),
+        Some((
+            PathBuf::from("/user.slice"),
+            PathBuf::from("/sys/fs/cgroup")
+        ))
+    );
+
+    assert_eq!(
+        cgroup2_mount("25 30 0:23 / /sys rw shared:7 - sysfs sysfs rw\n"),
+        None
+    );
+}
+
+#[test]
+fn test_cgroup2_path() {
+    let cgroup = "\
+        4:memory:/user.slice/user-1000.slice\n\
+        1:name=systemd:/user.slice/user-1000.slice/session-2.scope\n\
+        0::/user.slice/user-1000.slice/session-2.scope\n";
+    assert_eq!(
+        cgroup2_path(cgroup),
+        Some("/user.slice/user-1000.slice/session-2.scope")
+    );
+    assert_eq!(cgroup2_path("4:memory:/\n"), None);
+}
@@ -0,0 +1,113 @@
+#![cfg(target_os = "linux")]
+
+use std::fs;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::benchmark::timing_result::CpuFrequency;
+use crate::util::cpu_frequency::current_frequency_mhz;
+
+use anyhow::{bail, Result};
+
+/// Time between two samples of the CPU frequency
+const SAMPLING_INTERVAL: Duration = Duration::from_millis(10);
+
+/// The CPU that the given process ran on most recently, from the 'processor' field of
+/// /proc/<pid>/stat
+fn parse_processor(stat: &str) -> Option<usize> {
+    // The command name in the second field may contain spaces and parentheses. The
+    // 'processor' field is the 39th field, i.e. the 37th after the command name.
+    let (_, fields) = stat.rsplit_once(')')?;
+    fields.split_whitespace().nth(36)?.parse().ok()
+}
+
+fn current_processor(pid: u32) -> Option<usize> {
+    parse_processor(&fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?)
+}
+
+/// Make sure that the CPU frequency can be sampled, which is not possible in most virtual
+/// machines
+pub fn check_available() -> Result<()> {
+    if current_frequency_mhz(0).is_none() {
+        bail!(
+            "Could not read the CPU frequency from \
+             /sys/devices/system/cpu/cpu0/cpufreq/scaling_cur_freq. CPU frequency scaling is \
+             not available in most virtual machines."
+        );
+    }
+    Ok(())
+}
+
+/// Samples the frequency of the CPU(s) that run a process in a background thread, for
+/// `--cpu-frequency`
+pub struct FrequencySampler {
+    stop: Arc<AtomicBool>,
+    thread: JoinHandle<Vec<f64>>,
+}
+
+impl FrequencySampler {
+    /// Start sampling the mean frequency of the given CPUs, or of the CPU that the process
+    /// with the given ID is currently running on if the list is empty
+    pub fn start(pid: u32, cpus: &[usize]) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let cpus = cpus.to_vec();
+
+        let thread = {
+            let stop = stop.clone();
+            thread::spawn(move || {
+                let mut samples = vec![];
+                loop {
+                    let frequencies: Vec<f64> = if cpus.is_empty() {
+                        current_processor(pid)
+                            .and_then(current_frequency_mhz)
+                            .into_iter()
+                            .collect()
+                    } else {
+                        cpus.iter()
+                            .filter_map(|&cpu| current_frequency_mhz(cpu))
+                            .collect()
+                    };
+                    if !frequencies.is_empty() {
+                        samples.push(frequencies.iter().sum::<f64>() / frequencies.len() as f64);
+                    }
+
+                    // The thread is unparked when sampling is stopped, which also takes a
+                    // final sample
+                    if stop.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    thread::park_timeout(SAMPLING_INTERVAL);
+                }
+                samples
+            })
+        };
+
+        FrequencySampler { stop, thread }
+    }
+
+    /// Stop sampling. This is `None` if no sample could be taken.
+    pub fn stop(self) -> Option<CpuFrequency> {
+        self.stop.store(true, Ordering::Relaxed);
+        self.thread.thread().unpark();
+        let samples = self.thread.join().ok()?;
+
+        if samples.is_empty() {
+            return None;
+        }
+        Some(CpuFrequency {
+            min: samples.iter().copied().fold(f64::INFINITY, f64::min),
+            mean: samples.iter().sum::<f64>() / samples.len() as f64,
+            max: samples.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+        })
+    }
+}
+
+#[test]
+fn test_parse_processor() {
+    let stat = "1234 (a (b) c) S 1 1234 1234 0 -1 4194560 100 0 0 0 0 0 0 0 20 0 1 0 12345 \
+                4096 100 18446744073709551615 1 1 0 0 0 0 0 0 0 0 0 0 17 3 0 0 0 0 0";
+    assert_eq!(parse_processor(stat), Some(3));
+    assert_eq!(parse_processor("1234 (short) S 1"), None);
+}
@@ -0,0 +1,287 @@
+#![cfg(target_os = "linux")]
+#![warn(unsafe_op_in_unsafe_fn)]
+
+use std::ffi::c_void;
+use std::mem;
+use std::os::raw::{c_int, c_uint};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::benchmark::timing_result::GpuUsage;
+
+use anyhow::{anyhow, bail, Result};
+
+/// Time between two samples of the GPU utilization
+const SAMPLING_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Utilization and memory usage of all GPUs at one point in time
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct GpuSample {
+    /// Mean utilization of all GPUs in percent
+    pub utilization_percent: f64,
+
+    /// Memory in use on all GPUs combined, in bytes
+    pub memory_used_byte: u64,
+}
+
+/// Interface to the GPUs of one vendor. Other vendors can be supported by implementing this
+/// trait and trying the implementation in `open_backend`.
+pub trait GpuBackend: Send + Sync {
+    /// Sample the current utilization and memory usage of all GPUs. This is `None` if they
+    /// could not be read.
+    fn sample(&self) -> Option<GpuSample>;
+}
+
+/// NVML device handle (`nvmlDevice_t`)
+type NvmlDevice = *mut c_void;
+
+/// `nvmlUtilization_t`
+#[repr(C)]
+#[derive(Default)]
+struct NvmlUtilization {
+    gpu: c_uint,
+    memory: c_uint,
+}
+
+/// `nvmlMemory_t`
+#[repr(C)]
+#[derive(Default)]
+struct NvmlMemory {
+    total: u64,
+    free: u64,
+    used: u64,
+}
+
+const NVML_SUCCESS: c_int = 0;
+
+/// NVIDIA GPUs, via the NVIDIA Management Library (NVML) that is part of the driver. The
+/// library is loaded at runtime, such that hyperfine does not depend on it.
+struct Nvml {
+    devices: Vec<NvmlDevice>,
+    get_utilization_rates: unsafe extern "C" fn(NvmlDevice, *mut NvmlUtilization) -> c_int,
+    get_memory_info: unsafe extern "C" fn(NvmlDevice, *mut NvmlMemory) -> c_int,
+}
+
+// SAFETY: NVML is thread-safe, and the device handles stay valid since the library is never
+// shut down or unloaded
+unsafe impl Send for Nvml {}
+unsafe impl Sync for Nvml {}
+
+/// Look up the function with the given (null-terminated) name in the given library
+///
+/// # Safety
+///
+/// `T` has to be the function pointer type of the function
+unsafe fn symbol<T>(library: *mut c_void, name: &[u8]) -> Result<T> {
+    // SAFETY: The library handle is valid and the name is null-terminated
+    let pointer = unsafe { libc::dlsym(library, name.as_ptr().cast()) };
+    if pointer.is_null() {
+        bail!(
+            "Could not find '{}' in the NVIDIA Management Library",
+            String::from_utf8_lossy(&name[..name.len() - 1])
+        );
+    }
+    // SAFETY: Guaranteed by the caller
+    Ok(unsafe { mem::transmute_copy(&pointer) })
+}
+
+impl Nvml {
+    fn open() -> Result<Self> {
+        // SAFETY: The name is null-terminated
+        let library = unsafe {
+            libc::dlopen(
+                b"libnvidia-ml.so.1\0".as_ptr().cast(),
+                libc::RTLD_NOW | libc::RTLD_LOCAL,
+            )
+        };
+        if library.is_null() {
+            bail!(
+                "Could not load the NVIDIA Management Library (libnvidia-ml.so.1). Only NVIDIA \
+                 GPUs are supported, and the library is installed with their driver."
+            );
+        }
+
+        // SAFETY: The types are those of the NVML API
+        let (init, get_count, get_handle, get_utilization_rates, get_memory_info) = unsafe {
+            (
+                symbol::<unsafe extern "C" fn() -> c_int>(library, b"nvmlInit_v2\0")?,
+                symbol::<unsafe extern "C" fn(*mut c_uint) -> c_int>(
+                    library,
+                    b"nvmlDeviceGetCount_v2\0",
+                )?,
+                symbol::<unsafe extern "C" fn(c_uint, *mut NvmlDevice) -> c_int>(
+                    library,
+                    b"nvmlDeviceGetHandleByIndex_v2\0",
+                )?,
+                symbol(library, b"nvmlDeviceGetUtilizationRates\0")?,
+                symbol(library, b"nvmlDeviceGetMemoryInfo\0")?,
+            )
+        };
+
+        // SAFETY: NVML may be initialized more than once
+        let ret = unsafe { init() };
+        if ret != NVML_SUCCESS {
+            bail!(
+                "Could not initialize the NVIDIA Management Library (error {})",
+                ret
+            );
+        }
+
+        let mut count = 0;
+        // SAFETY: The count is valid for writes
+        let ret = unsafe { get_count(&mut count) };
+        if ret != NVML_SUCCESS || count == 0 {
+            bail!("Could not find any NVIDIA GPUs");
+        }
+
+        let devices = (0..count)
+            .map(|index| {
+                let mut device = std::ptr::null_mut();
+                // SAFETY: The index is smaller than the count, the handle is valid for writes
+                let ret = unsafe { get_handle(index, &mut device) };
+                if ret == NVML_SUCCESS {
+                    Ok(device)
+                } else {
+                    Err(anyhow!(
+                        "Could not access the NVIDIA GPU {} (error {})",
+                        index,
+                        ret
+                    ))
+                }
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Nvml {
+            devices,
+            get_utilization_rates,
+            get_memory_info,
+        })
+    }
+}
+
+impl GpuBackend for Nvml {
+    fn sample(&self) -> Option<GpuSample> {
+        let mut utilization_percent = 0.0;
+        let mut memory_used_byte = 0;
+        for &device in &self.devices {
+            let mut utilization = NvmlUtilization::default();
+            let mut memory = NvmlMemory::default();
+            // SAFETY: The device handle is valid, the results are valid for writes
+            let ret = unsafe {
+                [
+                    (self.get_utilization_rates)(device, &mut utilization),
+                    (self.get_memory_info)(device, &mut memory),
+                ]
+            };
+            if ret.iter().any(|&r| r != NVML_SUCCESS) {
+                return None;
+            }
+            utilization_percent += utilization.gpu as f64;
+            memory_used_byte += memory.used;
+        }
+
+        Some(GpuSample {
+            utilization_percent: utilization_percent / self.devices.len() as f64,
+            memory_used_byte,
+        })
+    }
+}
+
+/// Try the backends of all supported vendors
+fn open_backend() -> Result<Box<dyn GpuBackend>> {
+    Ok(Box::new(Nvml::open()?))
+}
+
+/// The backend for the GPUs of this system, which is only opened once
+pub fn backend() -> Result<&'static dyn GpuBackend> {
+    static BACKEND: OnceLock<Result<Box<dyn GpuBackend>, String>> = OnceLock::new();
+
+    match BACKEND.get_or_init(|| open_backend().map_err(|e| e.to_string())) {
+        Ok(backend) => Ok(backend.as_ref()),
+        Err(message) => bail!("{}", message),
+    }
+}
+
+fn summarize(samples: &[GpuSample]) -> Option<GpuUsage> {
+    if samples.is_empty() {
+        return None;
+    }
+    Some(GpuUsage {
+        utilization_mean: samples.iter().map(|s| s.utilization_percent).sum::<f64>()
+            / samples.len() as f64,
+        utilization_max: samples
+            .iter()
+            .map(|s| s.utilization_percent)
+            .fold(0.0, f64::max),
+        memory_peak_byte: samples.iter().map(|s| s.memory_used_byte).max()?,
+    })
+}
+
+/// Samples the utilization and memory usage of the GPUs in a background thread, for `--gpu`
+pub struct GpuSampler {
+    stop: Arc<AtomicBool>,
+    thread: JoinHandle<Vec<GpuSample>>,
+}
+
+impl GpuSampler {
+    pub fn start(backend: &'static dyn GpuBackend) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let thread = {
+            let stop = stop.clone();
+            thread::spawn(move || {
+                let mut samples = vec![];
+                loop {
+                    samples.extend(backend.sample());
+
+                    // The thread is unparked when sampling is stopped, which also takes a
+                    // final sample
+                    if stop.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    thread::park_timeout(SAMPLING_INTERVAL);
+                }
+                samples
+            })
+        };
+
+        GpuSampler { stop, thread }
+    }
+
+    /// Stop sampling. This is `None` if no sample could be taken.
+    pub fn stop(self) -> Option<GpuUsage> {
+        self.stop.store(true, Ordering::Relaxed);
+        self.thread.thread().unpark();
+        summarize(&self.thread.join().ok()?)
+    }
+}
+
+#[test]
+fn test_summarize() {
+    assert_eq!(summarize(&[]), None);
+
+    let samples = [
+        GpuSample {
+            utilization_percent: 20.0,
+            memory_used_byte: 1 << 20,
+        },
+        GpuSample {
+            utilization_percent: 60.0,
+            memory_used_byte: 3 << 20,
+        },
+        GpuSample {
+            utilization_percent: 40.0,
+            memory_used_byte: 2 << 20,
+        },
+    ];
+    assert_eq!(
+        summarize(&samples),
+        Some(GpuUsage {
+            utilization_mean: 40.0,
+            utilization_max: 60.0,
+            memory_peak_byte: 3 << 20,
+        })
+    );
+}
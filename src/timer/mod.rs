@@ -6,6 +6,24 @@ mod windows_timer;
 #[cfg(not(windows))]
 mod unix_timer;
 
+#[cfg(target_os = "linux")]
+mod perf_counters;
+
+#[cfg(target_os = "linux")]
+mod rapl;
+
+#[cfg(target_os = "linux")]
+mod frequency_sampler;
+
+#[cfg(target_os = "linux")]
+mod gpu_sampler;
+
+#[cfg(target_os = "linux")]
+mod cgroup;
+
+#[cfg(target_os = "linux")]
+mod process_counter;
+
 #[cfg(target_os = "linux")]
 use nix::fcntl::{splice, SpliceFFlags};
 #[cfg(target_os = "linux")]
@@ -16,23 +34,26 @@ use std::os::unix::io::AsRawFd;
 #[cfg(target_os = "windows")]
 use windows_sys::Win32::System::Threading::CREATE_SUSPENDED;
 
+use crate::benchmark::timing_result::{
+    ContextSwitches, CpuFrequency, GpuUsage, IoCounters, PageFaults, PerfCounts,
+};
+#[cfg(target_os = "macos")]
+use crate::options::PerfCounter;
+use crate::options::{Clock, Options};
+#[cfg(target_os = "linux")]
+use crate::util::aslr;
+#[cfg(any(target_os = "linux", windows))]
+use crate::util::cpu_affinity;
+use crate::util::priority;
 use crate::util::units::Second;
 use wall_clock_timer::WallClockTimer;
 
 use std::io::Read;
 use std::process::{ChildStdout, Command, ExitStatus};
+use std::time::Instant;
 
 use anyhow::Result;
-
-#[cfg(not(windows))]
-#[derive(Debug, Copy, Clone)]
-struct CPUTimes {
-    /// Total amount of time spent executing in user mode
-    pub user_usec: i64,
-
-    /// Total amount of time spent executing in kernel mode
-    pub system_usec: i64,
-}
+use statistical::median;
 
 /// Used to indicate the result of running a command
 #[derive(Debug, Copy, Clone)]
@@ -41,14 +62,51 @@ pub struct TimerResult {
     pub time_user: Second,
     pub time_system: Second,
 
+    /// Peak memory usage of the process in bytes, if it could be measured
+    pub memory_usage_byte: Option<u64>,
+
+    /// Bytes read and written by the process, if they could be measured
+    pub io: Option<IoCounters>,
+
+    /// Context switches of the process, if they could be measured
+    pub context_switches: Option<ContextSwitches>,
+
+    /// Page faults of the process, if they could be measured
+    pub page_faults: Option<PageFaults>,
+
+    /// Peak number of processes and threads of the run, if it ran in a cgroup (Linux only)
+    pub peak_tasks: Option<u64>,
+
+    /// Number of processes that the run created, including the process itself (Windows only)
+    pub total_processes: Option<u64>,
+
+    /// Hardware performance counters of the process, if any were requested
+    pub perf_counts: PerfCounts,
+
+    /// Energy consumption of the CPU in joules, if it was requested
+    pub energy_joule: Option<f64>,
+
+    /// Frequency of the CPU(s) that ran the process, if it was requested
+    pub cpu_frequency: Option<CpuFrequency>,
+
+    /// Utilization and memory usage of the GPU(s) during the process, if it was requested
+    pub gpu_usage: Option<GpuUsage>,
+
+    /// Time from spawning the process until its first output, if it was requested and the
+    /// process wrote anything to stdout
+    pub time_to_first_output: Option<Second>,
+
     /// The exit status of the process
     pub status: ExitStatus,
 }
 
-/// Discard the output of a child process.
-fn discard(output: ChildStdout) {
+/// Discard the output of a child process. Returns the time at which the first output
+/// arrived, if there was any.
+fn discard(output: ChildStdout) -> Option<Instant> {
     const CHUNK_SIZE: usize = 64 << 10;
 
+    let mut first_output = None;
+
     #[cfg(target_os = "linux")]
     {
         if let Ok(file) = File::create("/dev/null") {
@@ -63,6 +121,7 @@ fn discard(output: ChildStdout) {
                 if bytes == 0 {
                     break;
                 }
+                first_output.get_or_insert_with(Instant::now);
             }
         }
     }
@@ -73,45 +132,364 @@ fn discard(output: ChildStdout) {
         if bytes == 0 {
             break;
         }
+        first_output.get_or_insert_with(Instant::now);
     }
+
+    first_output
 }
 
-/// Execute the given command and return a timing summary
-pub fn execute_and_measure(mut command: Command) -> Result<TimerResult> {
-    #[cfg(not(windows))]
-    let cpu_timer = self::unix_timer::CPUTimer::start();
+/// Make sure that the additional measurements that are enabled in the given options are
+/// available on this system, before any benchmark is run
+pub fn check_measurements(options: &Options) -> Result<()> {
+    #[cfg(target_os = "linux")]
+    {
+        self::perf_counters::PerfTimer::attach(
+            &options.perf_counters,
+            std::process::id() as libc::pid_t,
+            options.perf_counters_children,
+        )?;
+        if options.measure_energy {
+            self::rapl::EnergyMeter::start()?;
+        }
+        if options.sample_cpu_frequency {
+            self::frequency_sampler::check_available()?;
+        }
+        if options.sample_gpu {
+            self::gpu_sampler::backend()?;
+        }
+        if options.cgroup {
+            self::cgroup::Cgroup::create(options)?;
+        }
+    }
+    #[cfg(not(target_os = "linux"))]
+    let _ = options;
+
+    Ok(())
+}
+
+/// Measure the overhead of the bookkeeping in `execute_and_measure` that is included in the
+/// wall clock time of every run, apart from spawning and waiting for the process: attaching
+/// the performance counters, reading the clock and stopping the additional measurements.
+/// These steps are repeated for hyperfine itself instead of a child process, and the median
+/// is returned.
+pub fn measure_overhead(options: &Options) -> Result<Second> {
+    const COUNT: usize = 100;
+
+    // The bookkeeping does not use any CPU time of the benchmarked process
+    if options.clock == Clock::ProcessCpuTime {
+        return Ok(0.0);
+    }
+
+    #[cfg(target_os = "linux")]
+    let pid = std::process::id();
+    #[cfg(not(target_os = "linux"))]
+    let _ = options;
+
+    let mut times = Vec::with_capacity(COUNT);
+    for _ in 0..COUNT {
+        #[cfg(target_os = "linux")]
+        let energy_meter = if options.measure_energy {
+            Some(self::rapl::EnergyMeter::start()?)
+        } else {
+            None
+        };
+
+        let wallclock_timer = WallClockTimer::start(options.clock);
+
+        // The counters are never enabled, since hyperfine does not execute a program
+        #[cfg(target_os = "linux")]
+        let perf_timer = self::perf_counters::PerfTimer::attach(
+            &options.perf_counters,
+            pid as libc::pid_t,
+            options.perf_counters_children,
+        )?;
+
+        #[cfg(target_os = "linux")]
+        {
+            let frequency_sampler = options.sample_cpu_frequency.then(|| {
+                self::frequency_sampler::FrequencySampler::start(pid, &options.cpu_affinity)
+            });
+            let gpu_sampler = if options.sample_gpu {
+                Some(self::gpu_sampler::GpuSampler::start(
+                    self::gpu_sampler::backend()?,
+                ))
+            } else {
+                None
+            };
+            let _ = self::unix_timer::read_io_counters_of(pid);
+            if let Some(sampler) = frequency_sampler {
+                sampler.stop();
+            }
+            if let Some(sampler) = gpu_sampler {
+                sampler.stop();
+            }
+            perf_timer.stop()?;
+            if let Some(meter) = energy_meter {
+                meter.stop()?;
+            }
+        }
+
+        times.push(wallclock_timer.stop());
+    }
+
+    Ok(median(&times))
+}
+
+/// Execute the given command and return a timing summary, including the additional
+/// measurements that are enabled in the given options
+pub fn execute_and_measure(mut command: Command, options: &Options) -> Result<TimerResult> {
+    // Energy measurements and performance counters via perf_event_open(2) are only supported
+    // on Linux, which is checked in `Options`. On macOS, some of the counters are read from
+    // the extended resource usage of the process instead.
+    #[cfg(not(target_os = "linux"))]
+    let _ = options;
 
     #[cfg(windows)]
     {
         use std::os::windows::process::CommandExt;
 
         // Create the process in a suspended state so that we don't miss any cpu time between process creation and `CPUTimer` start.
-        command.creation_flags(CREATE_SUSPENDED);
+        command.creation_flags(
+            CREATE_SUSPENDED | options.priority.map_or(0, priority::priority_class),
+        );
     }
 
-    let wallclock_timer = WallClockTimer::start();
+    #[cfg(unix)]
+    if let Some(level) = options.priority {
+        priority::set_for_command(&mut command, level);
+    }
+
+    #[cfg(target_os = "linux")]
+    if options.aggregate_children {
+        self::unix_timer::become_subreaper()?;
+    }
+
+    #[cfg(target_os = "linux")]
+    self::unix_timer::fork_for_command(&mut command);
+
+    #[cfg(target_os = "linux")]
+    if !options.cpu_affinity.is_empty() {
+        cpu_affinity::set_for_command(&mut command, &options.cpu_affinity);
+    }
+
+    #[cfg(target_os = "linux")]
+    if options.disable_aslr {
+        aslr::disable_for_command(&mut command);
+    }
+
+    #[cfg(target_os = "linux")]
+    let cgroup = if options.cgroup {
+        let cgroup = self::cgroup::Cgroup::create(options)?;
+        cgroup.add_command(&mut command)?;
+        Some(cgroup)
+    } else {
+        None
+    };
+
+    // Counting the created processes requires the same privileges as the cgroup
+    #[cfg(target_os = "linux")]
+    let process_counter = options
+        .cgroup
+        .then(|| self::process_counter::ProcessCounter::start().ok())
+        .flatten();
+
+    #[cfg(target_os = "linux")]
+    let energy_meter = if options.measure_energy {
+        Some(self::rapl::EnergyMeter::start()?)
+    } else {
+        None
+    };
+
+    let wallclock_timer = WallClockTimer::start(options.clock);
+    #[cfg(target_os = "linux")]
+    let (mut child, perf_timer) = self::perf_counters::spawn(
+        &mut command,
+        &options.perf_counters,
+        options.perf_counters_children,
+    )?;
+    #[cfg(not(target_os = "linux"))]
     let mut child = command.spawn()?;
 
+    #[cfg(target_os = "linux")]
+    let frequency_sampler = options.sample_cpu_frequency.then(|| {
+        self::frequency_sampler::FrequencySampler::start(child.id(), &options.cpu_affinity)
+    });
+    #[cfg(target_os = "linux")]
+    let gpu_sampler = if options.sample_gpu {
+        Some(self::gpu_sampler::GpuSampler::start(
+            self::gpu_sampler::backend()?,
+        ))
+    } else {
+        None
+    };
+
+    #[cfg(windows)]
+    if !options.cpu_affinity.is_empty() {
+        cpu_affinity::set_for_process(&child, &options.cpu_affinity)?;
+    }
+
     #[cfg(windows)]
     let cpu_timer = {
         // SAFETY: We created a suspended process
         unsafe { self::windows_timer::CPUTimer::start_suspended_process(&child) }
     };
 
-    if let Some(output) = child.stdout.take() {
-        // Handle CommandOutputPolicy::Pipe
-        discard(output);
-    }
+    // Handle CommandOutputPolicy::Pipe
+    let first_output = child.stdout.take().and_then(discard);
+    let time_to_first_output = first_output
+        .filter(|_| options.time_to_first_output)
+        .map(|instant| wallclock_timer.time_until(instant));
+
+    // The I/O counters of a process can only be read before it is reaped
+    #[cfg(target_os = "linux")]
+    let io = self::unix_timer::wait_for_exit(&child)
+        .ok()
+        .and_then(|_| self::unix_timer::read_io_counters(&child));
+    #[cfg(all(not(windows), not(target_os = "linux")))]
+    let io = None;
+
+    // The extended resource usage on macOS is also only available before the process is reaped
+    #[cfg(target_os = "macos")]
+    let rusage_info = self::unix_timer::wait_for_exit(&child)
+        .ok()
+        .and_then(|_| self::unix_timer::read_rusage_info(&child));
+
+    #[cfg(not(windows))]
+    let (status, (time_user, time_system), memory_usage_byte, context_switches, page_faults) = {
+        #[allow(unused_mut)]
+        let (status, mut usage) = self::unix_timer::wait_for_child(&child)?;
 
-    let status = child.wait()?;
+        #[cfg(target_os = "linux")]
+        if options.aggregate_children {
+            for descendant in self::unix_timer::wait_for_descendants()? {
+                self::unix_timer::add_rusage(&mut usage, &descendant);
+            }
+        }
+
+        #[allow(unused_mut)]
+        let mut memory_usage_byte = self::unix_timer::max_rss(&usage);
+
+        // The peak physical footprint is more accurate than the maximum resident set size,
+        // since it includes compressed memory and excludes shared pages. It only covers the
+        // process itself, while the resident set size also covers its waited-for descendants.
+        #[cfg(target_os = "macos")]
+        if let Some(ref info) = rusage_info {
+            memory_usage_byte = memory_usage_byte.max(info.ri_lifetime_max_phys_footprint);
+        }
+
+        (
+            status,
+            self::unix_timer::cpu_times(&usage),
+            Some(memory_usage_byte),
+            Some(self::unix_timer::context_switches(&usage)),
+            Some(self::unix_timer::page_faults(&usage)),
+        )
+    };
+
+    #[cfg(target_os = "linux")]
+    let cpu_frequency = frequency_sampler.and_then(|sampler| sampler.stop());
+    #[cfg(not(target_os = "linux"))]
+    let cpu_frequency = None;
+
+    #[cfg(target_os = "linux")]
+    let gpu_usage = gpu_sampler.and_then(|sampler| sampler.stop());
+    #[cfg(not(target_os = "linux"))]
+    let gpu_usage = None;
+
+    #[cfg(target_os = "linux")]
+    let perf_counts = perf_timer.stop()?;
+    #[cfg(target_os = "macos")]
+    let perf_counts = {
+        let mut perf_counts = PerfCounts::default();
+        if let Some(ref info) = rusage_info {
+            for &counter in &options.perf_counters {
+                match counter {
+                    PerfCounter::Cycles => perf_counts.set(counter, info.ri_cycles),
+                    PerfCounter::Instructions => perf_counts.set(counter, info.ri_instructions),
+                    // Rejected in `Options`
+                    PerfCounter::CacheMisses
+                    | PerfCounter::BranchMisses
+                    | PerfCounter::Syscalls => {}
+                }
+            }
+        }
+        perf_counts
+    };
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+    let perf_counts = PerfCounts::default();
+
+    #[cfg(target_os = "linux")]
+    let energy_joule = energy_meter.map(|meter| meter.stop()).transpose()?;
+    #[cfg(not(target_os = "linux"))]
+    let energy_joule = None;
+
+    #[cfg(windows)]
+    let (status, memory_usage_byte, io, context_switches, page_faults) = (
+        {
+            let status = child.wait()?;
+            if options.aggregate_children {
+                cpu_timer.wait_for_all_processes();
+            }
+            status
+        },
+        cpu_timer.peak_memory_usage(options.aggregate_children),
+        cpu_timer.io_counters(),
+        None,
+        None,
+    );
+
+    // All processes of the job object have been waited for at this point
+    #[cfg(windows)]
+    let total_processes = cpu_timer.total_processes();
+    #[cfg(all(not(windows), not(target_os = "linux")))]
+    let total_processes = None;
 
     let time_real = wallclock_timer.stop();
+    #[cfg(windows)]
     let (time_user, time_system) = cpu_timer.stop();
 
+    // The cgroup also includes the processes that have not been waited for. It is removed
+    // (and all processes in it are killed) after the usage has been read.
+    #[cfg(target_os = "linux")]
+    let (time_user, time_system, memory_usage_byte, peak_tasks) =
+        match cgroup.map(|cgroup| cgroup.usage()).transpose()? {
+            Some(usage) => (
+                usage.time_user,
+                usage.time_system,
+                usage.memory_peak_byte.or(memory_usage_byte),
+                usage.peak_tasks,
+            ),
+            None => (time_user, time_system, memory_usage_byte, None),
+        };
+    #[cfg(not(target_os = "linux"))]
+    let peak_tasks = None;
+
+    // All processes in the cgroup have exited (or were killed) at this point
+    #[cfg(target_os = "linux")]
+    let total_processes = process_counter.and_then(|counter| counter.stop(child.id()));
+
+    // With `--clock process-cputime`, the CPU time of the process is used as its run time
+    let time_real = if options.clock == Clock::ProcessCpuTime {
+        time_user + time_system
+    } else {
+        time_real
+    };
+
     Ok(TimerResult {
         time_real,
         time_user,
         time_system,
+        memory_usage_byte,
+        io,
+        context_switches,
+        page_faults,
+        peak_tasks,
+        total_processes,
+        perf_counts,
+        energy_joule,
+        cpu_frequency,
+        gpu_usage,
+        time_to_first_output,
         status,
     })
 }
@@ -0,0 +1,345 @@
+#![cfg(target_os = "linux")]
+
+use std::convert::TryInto;
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::mem;
+use std::os::unix::io::{AsRawFd, FromRawFd};
+use std::os::unix::process::CommandExt;
+use std::process::{Child, Command};
+use std::thread;
+
+use crate::benchmark::timing_result::PerfCounts;
+use crate::options::PerfCounter;
+
+use anyhow::{bail, Context, Result};
+use nix::fcntl::OFlag;
+
+const PERF_TYPE_HARDWARE: u32 = 0;
+const PERF_TYPE_TRACEPOINT: u32 = 2;
+
+/// Mount points of tracefs, which provides the ID of the tracepoint for counting system calls
+const TRACEFS_DIRS: [&str; 2] = ["/sys/kernel/tracing", "/sys/kernel/debug/tracing"];
+
+const PERF_FORMAT_TOTAL_TIME_ENABLED: u64 = 1 << 0;
+const PERF_FORMAT_TOTAL_TIME_RUNNING: u64 = 1 << 1;
+
+const PERF_FLAG_FD_CLOEXEC: libc::c_ulong = 1 << 3;
+
+// Bits of the bit field in `perf_event_attr`
+const ATTR_DISABLED: u64 = 1 << 0;
+const ATTR_INHERIT: u64 = 1 << 1;
+const ATTR_EXCLUDE_KERNEL: u64 = 1 << 5;
+const ATTR_EXCLUDE_HV: u64 = 1 << 6;
+const ATTR_ENABLE_ON_EXEC: u64 = 1 << 12;
+const ATTR_INHERIT_THREAD: u64 = 1 << 35;
+
+nix::ioctl_none!(perf_event_ioc_enable, b'$', 0);
+nix::ioctl_none!(perf_event_ioc_disable, b'$', 1);
+
+/// `struct perf_event_attr` from `linux/perf_event.h`, in the version with a size of 112 bytes
+/// (`PERF_ATTR_SIZE_VER5`) that is supported by all kernels since Linux 4.1
+#[repr(C)]
+#[derive(Default)]
+struct PerfEventAttr {
+    type_: u32,
+    size: u32,
+    config: u64,
+    sample_period: u64,
+    sample_type: u64,
+    read_format: u64,
+    flags: u64,
+    wakeup_events: u32,
+    bp_type: u32,
+    config1: u64,
+    config2: u64,
+    branch_sample_type: u64,
+    sample_regs_user: u64,
+    sample_stack_user: u32,
+    clockid: i32,
+    sample_regs_intr: u64,
+    aux_watermark: u32,
+    sample_max_stack: u16,
+    reserved: u16,
+}
+
+/// ID of the tracepoint that is hit on every entry into a system call
+fn syscall_tracepoint_id() -> Result<u64> {
+    let content = TRACEFS_DIRS
+        .iter()
+        .find_map(|dir| {
+            fs::read_to_string(format!("{}/events/raw_syscalls/sys_enter/id", dir)).ok()
+        })
+        .context(
+            "Could not find the 'raw_syscalls:sys_enter' tracepoint in /sys/kernel/tracing. \
+             Accessing it usually requires root privileges.",
+        )?;
+    content
+        .trim()
+        .parse()
+        .context("Could not parse the ID of the 'raw_syscalls:sys_enter' tracepoint")
+}
+
+/// The type and the configuration of the perf event of the given counter: a generalized
+/// hardware event (`PERF_COUNT_HW_*`) or a tracepoint
+fn event(counter: PerfCounter) -> Result<(u32, u64)> {
+    Ok(match counter {
+        PerfCounter::Cycles => (PERF_TYPE_HARDWARE, 0),
+        PerfCounter::Instructions => (PERF_TYPE_HARDWARE, 1),
+        PerfCounter::CacheMisses => (PERF_TYPE_HARDWARE, 3),
+        PerfCounter::BranchMisses => (PERF_TYPE_HARDWARE, 5),
+        PerfCounter::Syscalls => (PERF_TYPE_TRACEPOINT, syscall_tracepoint_id()?),
+    })
+}
+
+/// Which tasks of the benchmarked process are included in its counters
+#[derive(Clone, Copy, PartialEq)]
+enum Inherit {
+    /// All threads and child processes, like `perf stat` (the default)
+    All,
+
+    /// All threads, but no child processes (`--no-perf-counters-children`, since Linux 5.13)
+    Threads,
+
+    /// Only the initial thread
+    None,
+}
+
+/// Open a disabled counter for the given event of the process with the given pid, which is
+/// enabled when the process executes a program
+fn open_counter(
+    (type_, config): (u32, u64),
+    pid: libc::pid_t,
+    inherit: Inherit,
+    exclude_kernel: bool,
+) -> io::Result<File> {
+    let attr = PerfEventAttr {
+        type_,
+        size: mem::size_of::<PerfEventAttr>() as u32,
+        config,
+        read_format: PERF_FORMAT_TOTAL_TIME_ENABLED | PERF_FORMAT_TOTAL_TIME_RUNNING,
+        flags: ATTR_DISABLED
+            | ATTR_ENABLE_ON_EXEC
+            | ATTR_EXCLUDE_HV
+            | match inherit {
+                Inherit::All => ATTR_INHERIT,
+                Inherit::Threads => ATTR_INHERIT | ATTR_INHERIT_THREAD,
+                Inherit::None => 0,
+            }
+            | if exclude_kernel {
+                ATTR_EXCLUDE_KERNEL
+            } else {
+                0
+            },
+        ..Default::default()
+    };
+
+    // SAFETY: The attributes are valid for reads. The counter measures the given process on
+    // any CPU (-1) and does not belong to a group (-1).
+    let fd = unsafe {
+        libc::syscall(
+            libc::SYS_perf_event_open,
+            &attr as *const PerfEventAttr,
+            pid,
+            -1 as libc::c_int,
+            -1 as libc::c_int,
+            PERF_FLAG_FD_CLOEXEC,
+        )
+    };
+    if fd < 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    // SAFETY: The file descriptor has just been opened and is not owned by anything else
+    Ok(unsafe { File::from_raw_fd(fd as libc::c_int) })
+}
+
+/// Open a counter for the given event, with the fallbacks for older kernels and for
+/// unprivileged users
+fn open_counter_with_fallbacks(
+    counter: PerfCounter,
+    event: (u32, u64),
+    pid: libc::pid_t,
+    children: bool,
+) -> io::Result<File> {
+    let open = |exclude_kernel| {
+        if children {
+            return open_counter(event, pid, Inherit::All, exclude_kernel);
+        }
+        // Kernels before Linux 5.13 do not know about `inherit_thread`
+        match open_counter(event, pid, Inherit::Threads, exclude_kernel) {
+            Err(e) if e.raw_os_error() == Some(libc::EINVAL) => {
+                open_counter(event, pid, Inherit::None, exclude_kernel)
+            }
+            result => result,
+        }
+    };
+
+    // Unprivileged users can only count events in user space, depending on
+    // /proc/sys/kernel/perf_event_paranoid. System calls are always entered in kernel space.
+    match open(false) {
+        Err(e)
+            if counter != PerfCounter::Syscalls
+                && matches!(e.raw_os_error(), Some(libc::EACCES | libc::EPERM)) =>
+        {
+            open(true)
+        }
+        result => result,
+    }
+}
+
+fn pipe() -> Result<(File, File)> {
+    let (read, write) = nix::unistd::pipe2(OFlag::O_CLOEXEC).context("Could not create a pipe")?;
+    // SAFETY: The file descriptors have just been opened and are not owned by anything else
+    Ok(unsafe { (File::from_raw_fd(read), File::from_raw_fd(write)) })
+}
+
+/// Spawn the given command with the given performance counters attached to its process.
+/// The child waits (before executing the program) until the counters are attached, which
+/// are only enabled once it executes the program, like with `perf stat`. The counters do
+/// not include hyperfine itself, nor any of its threads.
+pub fn spawn(
+    command: &mut Command,
+    counters: &[PerfCounter],
+    children: bool,
+) -> Result<(Child, PerfTimer)> {
+    if counters.is_empty() {
+        return Ok((command.spawn()?, PerfTimer { counters: vec![] }));
+    }
+
+    // The child sends its pid through the first pipe, and then waits for a byte from the
+    // second one. It only continues if the counters could be attached.
+    let (pid_read, pid_write) = pipe()?;
+    let (ready_read, ready_write) = pipe()?;
+    let (pid_write_fd, ready_read_fd, ready_write_fd) = (
+        pid_write.as_raw_fd(),
+        ready_read.as_raw_fd(),
+        ready_write.as_raw_fd(),
+    );
+
+    // SAFETY: Only system calls are used between `fork` and `exec`, without allocating
+    unsafe {
+        command.pre_exec(move || {
+            // Otherwise, the child would keep the pipe open if hyperfine fails to attach the
+            // counters
+            libc::close(ready_write_fd);
+
+            let pid = libc::getpid().to_ne_bytes();
+            if libc::write(pid_write_fd, pid.as_ptr() as *const libc::c_void, pid.len()) < 0 {
+                return Err(io::Error::last_os_error());
+            }
+
+            let mut ready = 0u8;
+            loop {
+                match libc::read(ready_read_fd, &mut ready as *mut u8 as *mut libc::c_void, 1) {
+                    1 => return Ok(()),
+                    n if n < 0
+                        && io::Error::last_os_error().kind() == io::ErrorKind::Interrupted =>
+                    {
+                        continue
+                    }
+                    _ => return Err(io::Error::from_raw_os_error(libc::ECANCELED)),
+                }
+            }
+        });
+    }
+
+    // `spawn` only returns after the child executed the program, so the counters are
+    // attached in another thread
+    thread::scope(|scope| {
+        let attach = scope.spawn(move || -> Result<Option<PerfTimer>> {
+            let mut pid = [0u8; mem::size_of::<libc::pid_t>()];
+            if (&pid_read).read_exact(&mut pid).is_err() {
+                // The command could not be spawned
+                return Ok(None);
+            }
+            let timer = PerfTimer::attach(counters, libc::pid_t::from_ne_bytes(pid), children)?;
+            (&ready_write).write_all(&[1])?;
+            Ok(Some(timer))
+        });
+
+        let child = command.spawn();
+
+        // The pipe has to be closed in case the child did not get to send its pid
+        drop(pid_write);
+        drop(ready_read);
+
+        match (
+            child,
+            attach
+                .join()
+                .expect("Attaching the performance counters panicked"),
+        ) {
+            (Ok(child), Ok(Some(timer))) => Ok((child, timer)),
+            (Ok(mut child), _) => {
+                let _ = child.kill();
+                let _ = child.wait();
+                bail!("Could not attach the performance counters to the command")
+            }
+            // The child exits without executing the program if the counters could not be
+            // attached
+            (Err(_), Err(e)) => Err(e),
+            (Err(e), Ok(_)) => Err(e.into()),
+        }
+    })
+}
+
+/// Hardware performance counters (`--perf-counters`) of a spawned process. They include
+/// all threads of the process, and, unless `--no-perf-counters-children` is given, all
+/// processes that it spawns, since the kernel adds the counts of a child to the counters of
+/// its parent when it exits.
+pub struct PerfTimer {
+    counters: Vec<(PerfCounter, File)>,
+}
+
+impl PerfTimer {
+    /// Attach the given counters to the process with the given pid
+    pub fn attach(counters: &[PerfCounter], pid: libc::pid_t, children: bool) -> Result<Self> {
+        let counters = counters
+            .iter()
+            .map(|&counter| {
+                let event = event(counter)?;
+                let file = open_counter_with_fallbacks(counter, event, pid, children)
+                    .with_context(|| {
+                        if counter == PerfCounter::Syscalls {
+                            "Could not open the 'raw_syscalls:sys_enter' tracepoint for counting \
+                             the system calls"
+                                .to_string()
+                        } else {
+                            format!(
+                                "Could not open the hardware performance counter '{}'",
+                                counter.name()
+                            )
+                        }
+                    })?;
+                Ok((counter, file))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(PerfTimer { counters })
+    }
+
+    pub fn stop(&self) -> Result<PerfCounts> {
+        let mut counts = PerfCounts::default();
+        for (counter, file) in &self.counters {
+            // SAFETY: The file descriptor belongs to a perf event
+            unsafe { perf_event_ioc_disable(file.as_raw_fd()) }?;
+
+            let mut buffer = [0; 3 * mem::size_of::<u64>()];
+            (&*file).read_exact(&mut buffer)?;
+            let value =
+                |i: usize| u64::from_ne_bytes(buffer[8 * i..8 * (i + 1)].try_into().unwrap());
+            let (count, time_enabled, time_running) = (value(0), value(1), value(2));
+
+            // The kernel multiplexes the counters if there are more of them than hardware
+            // registers, in which case the count has to be extrapolated
+            if time_running > 0 {
+                counts.set(
+                    *counter,
+                    (count as f64 * time_enabled as f64 / time_running as f64).round() as u64,
+                );
+            }
+        }
+        Ok(counts)
+    }
+}
@@ -0,0 +1,248 @@
+#![cfg(target_os = "linux")]
+
+use std::collections::HashSet;
+use std::convert::TryInto;
+use std::io;
+use std::mem;
+use std::os::unix::io::{AsRawFd, FromRawFd, OwnedFd};
+
+use anyhow::{Context, Result};
+
+/// Netlink multicast group and message type of the process events connector, from
+/// <linux/connector.h>
+const CN_IDX_PROC: u32 = 1;
+const CN_VAL_PROC: u32 = 1;
+
+/// Subscription to the process events, from <linux/cn_proc.h>
+const PROC_CN_MCAST_LISTEN: u32 = 1;
+const PROC_CN_MCAST_IGNORE: u32 = 2;
+
+/// Types of process events, from <linux/cn_proc.h>
+const PROC_EVENT_FORK: u32 = 0x0000_0001;
+const PROC_EVENT_EXIT: u32 = 0x8000_0000;
+
+/// Sizes of the headers of a message, i.e. `struct nlmsghdr` and `struct cn_msg`
+const NLMSG_HEADER_SIZE: usize = 16;
+const CN_MSG_HEADER_SIZE: usize = 20;
+
+/// Offset of the event data in `struct proc_event`, after the type, the CPU and the timestamp
+const EVENT_DATA_OFFSET: usize = 16;
+
+/// The events of a run are only read after the command exited. The default size of the
+/// receive buffer only holds a few thousand events of all processes on the system.
+const RECEIVE_BUFFER_SIZE: libc::c_int = 16 * 1024 * 1024;
+
+fn read_u32(buffer: &[u8], offset: usize) -> Option<u32> {
+    let bytes = buffer.get(offset..offset + 4)?;
+    Some(u32::from_ne_bytes(bytes.try_into().ok()?))
+}
+
+/// Send a subscription message to the process events connector. The kernel counts the
+/// listeners, and only sends events while there is at least one.
+fn send_operation(fd: libc::c_int, operation: u32) -> io::Result<()> {
+    let mut message = vec![];
+    let length = NLMSG_HEADER_SIZE + CN_MSG_HEADER_SIZE + 4;
+    message.extend((length as u32).to_ne_bytes());
+    message.extend((libc::NLMSG_DONE as u16).to_ne_bytes());
+    message.extend(0u16.to_ne_bytes());
+    message.extend(0u32.to_ne_bytes());
+    message.extend(std::process::id().to_ne_bytes());
+    message.extend(CN_IDX_PROC.to_ne_bytes());
+    message.extend(CN_VAL_PROC.to_ne_bytes());
+    message.extend([0u8; 8]);
+    message.extend(4u16.to_ne_bytes());
+    message.extend(0u16.to_ne_bytes());
+    message.extend(operation.to_ne_bytes());
+
+    // SAFETY: The message is valid for its length
+    let sent = unsafe {
+        libc::send(
+            fd,
+            message.as_ptr() as *const libc::c_void,
+            message.len(),
+            0,
+        )
+    };
+    if sent < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// A fork or exit of a process (not a thread), from a message of the process events
+/// connector
+#[derive(Debug, PartialEq)]
+enum ProcessEvent {
+    Fork { parent: u32, child: u32 },
+    Exit { process: u32 },
+}
+
+fn parse_event(message: &[u8]) -> Option<ProcessEvent> {
+    let event = message.get(NLMSG_HEADER_SIZE + CN_MSG_HEADER_SIZE..)?;
+    let data = EVENT_DATA_OFFSET;
+    match read_u32(event, 0)? {
+        PROC_EVENT_FORK => {
+            // Threads are created with a new pid in the thread group of the parent
+            let parent_tgid = read_u32(event, data + 4)?;
+            let (child_pid, child_tgid) = (read_u32(event, data + 8)?, read_u32(event, data + 12)?);
+            (child_pid == child_tgid).then_some(ProcessEvent::Fork {
+                parent: parent_tgid,
+                child: child_tgid,
+            })
+        }
+        PROC_EVENT_EXIT => {
+            let (pid, tgid) = (read_u32(event, data)?, read_u32(event, data + 4)?);
+            (pid == tgid).then_some(ProcessEvent::Exit { process: tgid })
+        }
+        _ => None,
+    }
+}
+
+/// Counts the processes that a command creates, based on the fork events of the process
+/// events connector of the kernel. This requires the `CAP_NET_ADMIN` capability, i.e. root
+/// privileges like `--cgroup`.
+pub struct ProcessCounter {
+    socket: OwnedFd,
+}
+
+impl ProcessCounter {
+    /// Subscribe to the process events, before the command is spawned
+    pub fn start() -> Result<Self> {
+        // SAFETY: The socket is owned by the returned `OwnedFd`, and the addresses and
+        // options are valid for the given sizes
+        unsafe {
+            let fd = libc::socket(
+                libc::AF_NETLINK,
+                libc::SOCK_DGRAM | libc::SOCK_CLOEXEC,
+                libc::NETLINK_CONNECTOR,
+            );
+            if fd < 0 {
+                return Err(io::Error::last_os_error())
+                    .context("Could not open the process events connector");
+            }
+            let socket = OwnedFd::from_raw_fd(fd);
+
+            // Without the privileges, the buffer size is limited by 'net.core.rmem_max'
+            if libc::setsockopt(
+                fd,
+                libc::SOL_SOCKET,
+                libc::SO_RCVBUFFORCE,
+                &RECEIVE_BUFFER_SIZE as *const libc::c_int as *const libc::c_void,
+                mem::size_of::<libc::c_int>() as libc::socklen_t,
+            ) < 0
+            {
+                libc::setsockopt(
+                    fd,
+                    libc::SOL_SOCKET,
+                    libc::SO_RCVBUF,
+                    &RECEIVE_BUFFER_SIZE as *const libc::c_int as *const libc::c_void,
+                    mem::size_of::<libc::c_int>() as libc::socklen_t,
+                );
+            }
+
+            let mut address: libc::sockaddr_nl = mem::zeroed();
+            address.nl_family = libc::AF_NETLINK as libc::sa_family_t;
+            address.nl_groups = CN_IDX_PROC;
+            if libc::bind(
+                fd,
+                &address as *const libc::sockaddr_nl as *const libc::sockaddr,
+                mem::size_of::<libc::sockaddr_nl>() as libc::socklen_t,
+            ) < 0
+            {
+                return Err(io::Error::last_os_error())
+                    .context("Could not subscribe to the process events");
+            }
+
+            send_operation(fd, PROC_CN_MCAST_LISTEN)
+                .context("Could not subscribe to the process events")?;
+
+            Ok(ProcessCounter { socket })
+        }
+    }
+
+    /// Number of processes that were created by the process with the given pid and its
+    /// descendants, including the process itself, after all of them exited. Returns `None`
+    /// if events were lost.
+    pub fn stop(self, pid: u32) -> Option<u64> {
+        let mut processes = HashSet::from([pid]);
+        let mut count = 1;
+
+        // The events are queued in the order in which they happened
+        let mut buffer = [0u8; 4096];
+        loop {
+            // SAFETY: The buffer is valid for its length
+            let received = unsafe {
+                libc::recv(
+                    self.socket.as_raw_fd(),
+                    buffer.as_mut_ptr() as *mut libc::c_void,
+                    buffer.len(),
+                    libc::MSG_DONTWAIT,
+                )
+            };
+            if received < 0 {
+                return match io::Error::last_os_error().raw_os_error() {
+                    Some(libc::EAGAIN) => Some(count),
+                    Some(libc::EINTR) => continue,
+                    // ENOBUFS if the receive buffer overflowed
+                    _ => None,
+                };
+            }
+
+            match parse_event(&buffer[..received as usize]) {
+                Some(ProcessEvent::Fork { parent, child }) if processes.contains(&parent) => {
+                    processes.insert(child);
+                    count += 1;
+                }
+                // The pid could be reused by an unrelated process
+                Some(ProcessEvent::Exit { process }) => {
+                    processes.remove(&process);
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+impl Drop for ProcessCounter {
+    /// Unsubscribe again, since closing the socket does not decrement the number of
+    /// listeners in the kernel
+    fn drop(&mut self) {
+        let _ = send_operation(self.socket.as_raw_fd(), PROC_CN_MCAST_IGNORE);
+    }
+}
+
+#[cfg(test)]
+fn cfg_test_message(what: u32, data: [u32; 4]) -> Vec<u8> {
+    let mut message = vec![0u8; NLMSG_HEADER_SIZE + CN_MSG_HEADER_SIZE];
+    message.extend(what.to_ne_bytes());
+    message.extend([0u8; 12]);
+    for value in data {
+        message.extend(value.to_ne_bytes());
+    }
+    message
+}
+
+#[test]
+fn test_parse_event() {
+    assert_eq!(
+        parse_event(&cfg_test_message(PROC_EVENT_FORK, [10, 10, 11, 11])),
+        Some(ProcessEvent::Fork {
+            parent: 10,
+            child: 11
+        })
+    );
+    // A new thread of process 10
+    assert_eq!(
+        parse_event(&cfg_test_message(PROC_EVENT_FORK, [10, 10, 12, 10])),
+        None
+    );
+    assert_eq!(
+        parse_event(&cfg_test_message(PROC_EVENT_EXIT, [11, 11, 0, 0])),
+        Some(ProcessEvent::Exit { process: 11 })
+    );
+    assert_eq!(
+        parse_event(&cfg_test_message(PROC_EVENT_EXIT, [12, 10, 0, 0])),
+        None
+    );
+    assert_eq!(parse_event(&[0u8; 8]), None);
+}
@@ -0,0 +1,101 @@
+#![cfg(target_os = "linux")]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+
+const POWERCAP_DIR: &str = "/sys/class/powercap";
+
+/// Energy counter of one CPU package, from the Intel RAPL (Running Average Power Limit)
+/// interface, which is also provided for recent AMD CPUs
+struct Zone {
+    path: PathBuf,
+
+    /// The value at which the counter wraps around, in microjoules
+    max_energy_uj: u64,
+}
+
+fn read_number(path: &Path) -> Result<u64> {
+    let content =
+        fs::read_to_string(path).with_context(|| format!("Could not read '{}'", path.display()))?;
+    content
+        .trim()
+        .parse()
+        .with_context(|| format!("Could not parse the content of '{}'", path.display()))
+}
+
+/// Difference between two values of an energy counter, which wraps around at the given value
+fn energy_difference(start: u64, end: u64, max_energy_uj: u64) -> u64 {
+    if end >= start {
+        end - start
+    } else {
+        max_energy_uj - start + end
+    }
+}
+
+/// Energy that the CPU packages consumed between `start` and `stop`. This is a system-wide
+/// measurement that includes the energy of all other processes, and of idle cores.
+pub struct EnergyMeter {
+    zones: Vec<(Zone, u64)>,
+}
+
+impl EnergyMeter {
+    pub fn start() -> Result<Self> {
+        let mut zones = vec![];
+
+        // The package zones are called 'intel-rapl:N', their sub-zones 'intel-rapl:N:M'
+        if let Ok(entries) = fs::read_dir(POWERCAP_DIR) {
+            for entry in entries.flatten() {
+                let name = entry.file_name().to_string_lossy().into_owned();
+                if name
+                    .strip_prefix("intel-rapl:")
+                    .is_some_and(|n| !n.contains(':'))
+                {
+                    let path = entry.path();
+                    let max_energy_uj = read_number(&path.join("max_energy_range_uj"))?;
+                    zones.push(Zone {
+                        path: path.join("energy_uj"),
+                        max_energy_uj,
+                    });
+                }
+            }
+        }
+
+        if zones.is_empty() {
+            bail!(
+                "Could not find any energy counters of the CPU (Intel RAPL in {}). They are not \
+                 available in most virtual machines.",
+                POWERCAP_DIR
+            );
+        }
+
+        let zones = zones
+            .into_iter()
+            .map(|zone| {
+                let energy_uj = read_number(&zone.path).context(
+                    "Reading the energy counters of the CPU usually requires root privileges",
+                )?;
+                Ok((zone, energy_uj))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(EnergyMeter { zones })
+    }
+
+    /// The consumed energy in joules
+    pub fn stop(&self) -> Result<f64> {
+        let mut energy_uj = 0;
+        for (zone, start) in &self.zones {
+            let end = read_number(&zone.path)?;
+            energy_uj += energy_difference(*start, end, zone.max_energy_uj);
+        }
+        Ok(energy_uj as f64 * 1e-6)
+    }
+}
+
+#[test]
+fn test_energy_difference() {
+    assert_eq!(energy_difference(1000, 1500, 10_000), 500);
+    assert_eq!(energy_difference(9000, 500, 10_000), 1500);
+}
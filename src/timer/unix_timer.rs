@@ -1,91 +1,266 @@
 #![cfg(not(windows))]
 
+use std::io;
 use std::mem;
+#[cfg(target_os = "linux")]
+use std::os::unix::process::CommandExt;
+use std::os::unix::process::ExitStatusExt;
+#[cfg(target_os = "linux")]
+use std::process::Command;
+use std::process::{Child, ExitStatus};
 
-use crate::timer::CPUTimes;
+#[cfg(target_os = "linux")]
+use crate::benchmark::timing_result::IoCounters;
+use crate::benchmark::timing_result::{ContextSwitches, PageFaults};
 use crate::util::units::Second;
 
-#[derive(Debug, Copy, Clone)]
-pub struct CPUInterval {
-    /// Total amount of time spent executing in user mode
-    pub user: Second,
+/// Wait for the given child process to exit. In addition to the exit status, this returns
+/// the resource usage of the process (including its descendants that it waited for). The
+/// resource usage is the primary source of the CPU times of a run: it is returned atomically
+/// along with the reaped process, so it is complete even for very short-lived processes.
+pub fn wait_for_child(child: &Child) -> io::Result<(ExitStatus, libc::rusage)> {
+    let pid = child.id() as libc::pid_t;
+    loop {
+        let mut status = 0;
+        // SAFETY: An all-zero `rusage` is a valid value
+        let mut usage: libc::rusage = unsafe { mem::zeroed() };
 
-    /// Total amount of time spent executing in kernel mode
-    pub system: Second,
+        // SAFETY: Both pointers are valid for writes
+        let ret = unsafe { libc::wait4(pid, &mut status, 0, &mut usage) };
+        if ret == pid {
+            return Ok((ExitStatus::from_raw(status), usage));
+        }
+
+        let error = io::Error::last_os_error();
+        if error.kind() != io::ErrorKind::Interrupted {
+            return Err(error);
+        }
+    }
 }
 
-pub struct CPUTimer {
-    start_cpu: CPUTimes,
+/// Spawn the processes of the given command with `fork`, instead of `vfork` (through
+/// `posix_spawn`), which the standard library uses if there are no `pre_exec` hooks. The
+/// maximum resident set size survives the `exec`: after a `vfork`, it would start at the
+/// resident set size of hyperfine, while a forked process only shares its anonymous pages.
+#[cfg(target_os = "linux")]
+pub fn fork_for_command(command: &mut Command) {
+    // SAFETY: The hook does nothing
+    unsafe {
+        command.pre_exec(|| Ok(()));
+    }
 }
 
-impl CPUTimer {
-    pub fn start() -> Self {
-        CPUTimer {
-            start_cpu: get_cpu_times(),
+/// Make the current process a subreaper, such that orphaned descendants are reparented to
+/// it instead of the init process. This allows to wait for them with `wait_for_descendants`.
+#[cfg(target_os = "linux")]
+pub fn become_subreaper() -> io::Result<()> {
+    // SAFETY: This only sets a flag of the current process
+    let ret = unsafe { libc::prctl(libc::PR_SET_CHILD_SUBREAPER, 1, 0, 0, 0) };
+    if ret == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+/// Wait until all remaining child processes, e.g. orphaned descendants of the benchmarked
+/// command (see `become_subreaper`), have exited. Returns their resource usage.
+#[cfg(target_os = "linux")]
+pub fn wait_for_descendants() -> io::Result<Vec<libc::rusage>> {
+    let mut usages = vec![];
+    loop {
+        let mut status = 0;
+        // SAFETY: An all-zero `rusage` is a valid value
+        let mut usage: libc::rusage = unsafe { mem::zeroed() };
+
+        // SAFETY: Both pointers are valid for writes
+        let ret = unsafe { libc::wait4(-1, &mut status, 0, &mut usage) };
+        if ret > 0 {
+            usages.push(usage);
+            continue;
+        }
+
+        let error = io::Error::last_os_error();
+        match error.raw_os_error() {
+            Some(libc::ECHILD) => return Ok(usages),
+            Some(libc::EINTR) => {}
+            _ => return Err(error),
         }
     }
+}
 
-    pub fn stop(&self) -> (Second, Second) {
-        let end_cpu = get_cpu_times();
-        let cpu_interval = cpu_time_interval(&self.start_cpu, &end_cpu);
-        (cpu_interval.user, cpu_interval.system)
+#[cfg(target_os = "linux")]
+fn add_timeval(total: &mut libc::timeval, other: &libc::timeval) {
+    total.tv_sec += other.tv_sec;
+    total.tv_usec += other.tv_usec;
+    if total.tv_usec >= 1_000_000 {
+        total.tv_sec += 1;
+        total.tv_usec -= 1_000_000;
     }
 }
 
-/// Read CPU execution times ('user' and 'system')
-fn get_cpu_times() -> CPUTimes {
-    use libc::{getrusage, rusage, RUSAGE_CHILDREN};
+/// Add the resource usage of another process to the given (total) resource usage. The
+/// maximum resident set size is the maximum of both.
+#[cfg(target_os = "linux")]
+pub fn add_rusage(total: &mut libc::rusage, other: &libc::rusage) {
+    add_timeval(&mut total.ru_utime, &other.ru_utime);
+    add_timeval(&mut total.ru_stime, &other.ru_stime);
+    total.ru_maxrss = total.ru_maxrss.max(other.ru_maxrss);
+    total.ru_minflt += other.ru_minflt;
+    total.ru_majflt += other.ru_majflt;
+    total.ru_nvcsw += other.ru_nvcsw;
+    total.ru_nivcsw += other.ru_nivcsw;
+}
+
+/// Wait until the given child process has exited, without reaping it
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+pub fn wait_for_exit(child: &Child) -> io::Result<()> {
+    loop {
+        // SAFETY: An all-zero `siginfo_t` is a valid value
+        let mut info: libc::siginfo_t = unsafe { mem::zeroed() };
+
+        // SAFETY: The pointer is valid for writes
+        let ret = unsafe {
+            libc::waitid(
+                libc::P_PID,
+                child.id() as libc::id_t,
+                &mut info,
+                libc::WEXITED | libc::WNOWAIT,
+            )
+        };
+        if ret == 0 {
+            return Ok(());
+        }
+
+        let error = io::Error::last_os_error();
+        if error.kind() != io::ErrorKind::Interrupted {
+            return Err(error);
+        }
+    }
+}
+
+/// Read the number of bytes that the given (exited, but not yet reaped) child process and its
+/// waited-for descendants read and wrote, from `/proc/<pid>/io`
+#[cfg(target_os = "linux")]
+pub fn read_io_counters(child: &Child) -> Option<IoCounters> {
+    read_io_counters_of(child.id())
+}
+
+/// Read the number of bytes that the process with the given ID read and wrote
+#[cfg(target_os = "linux")]
+pub fn read_io_counters_of(pid: u32) -> Option<IoCounters> {
+    let content = std::fs::read_to_string(format!("/proc/{}/io", pid)).ok()?;
+    parse_io_counters(&content)
+}
+
+/// Parse the `rchar` and `wchar` fields of `/proc/<pid>/io`. These include all reads and
+/// writes, not only those that reached the storage device.
+#[cfg(target_os = "linux")]
+fn parse_io_counters(content: &str) -> Option<IoCounters> {
+    let field = |name: &str| {
+        content
+            .lines()
+            .find_map(|line| line.strip_prefix(name)?.strip_prefix(':'))
+            .and_then(|value| value.trim().parse().ok())
+    };
+    Some(IoCounters {
+        read_bytes: field("rchar")?,
+        written_bytes: field("wchar")?,
+    })
+}
+
+/// Read the extended resource usage of the given (exited, but not yet reaped) child process.
+/// Unlike `rusage`, this does not include any of its descendants.
+#[cfg(target_os = "macos")]
+pub fn read_rusage_info(child: &Child) -> Option<libc::rusage_info_v4> {
+    // SAFETY: An all-zero `rusage_info_v4` is a valid value
+    let mut info: libc::rusage_info_v4 = unsafe { mem::zeroed() };
 
-    let result: rusage = unsafe {
-        let mut buf = mem::zeroed();
-        let success = getrusage(RUSAGE_CHILDREN, &mut buf);
-        assert_eq!(0, success);
-        buf
+    // SAFETY: The buffer is valid for writes and has the size of the requested flavor
+    let ret = unsafe {
+        libc::proc_pid_rusage(
+            child.id() as libc::c_int,
+            libc::RUSAGE_INFO_V4,
+            (&mut info as *mut libc::rusage_info_v4).cast(),
+        )
     };
+    (ret == 0).then_some(info)
+}
+
+fn seconds(time: &libc::timeval) -> Second {
+    time.tv_sec as Second + time.tv_usec as Second * 1e-6
+}
+
+/// User and system CPU time from the given resource usage
+pub fn cpu_times(usage: &libc::rusage) -> (Second, Second) {
+    (seconds(&usage.ru_utime), seconds(&usage.ru_stime))
+}
 
-    const MICROSEC_PER_SEC: i64 = 1000 * 1000;
+/// Maximum resident set size from the given resource usage, in bytes. This is at least the
+/// resident set size of the process right before it executed the command, i.e. the memory
+/// that a forked process shares with hyperfine (see `fork_for_command`), about 1-2 MB.
+pub fn max_rss(usage: &libc::rusage) -> u64 {
+    // macOS reports the size in bytes, Linux and the BSDs in kilobytes
+    let factor = if cfg!(target_os = "macos") { 1 } else { 1024 };
+    usage.ru_maxrss.max(0) as u64 * factor
+}
 
-    #[allow(clippy::useless_conversion)]
-    CPUTimes {
-        user_usec: i64::from(result.ru_utime.tv_sec) * MICROSEC_PER_SEC
-            + i64::from(result.ru_utime.tv_usec),
-        system_usec: i64::from(result.ru_stime.tv_sec) * MICROSEC_PER_SEC
-            + i64::from(result.ru_stime.tv_usec),
+/// Voluntary and involuntary context switches from the given resource usage
+pub fn context_switches(usage: &libc::rusage) -> ContextSwitches {
+    ContextSwitches {
+        voluntary: usage.ru_nvcsw.max(0) as u64,
+        involuntary: usage.ru_nivcsw.max(0) as u64,
     }
 }
 
-/// Compute the time intervals in between two `CPUTimes` snapshots
-fn cpu_time_interval(start: &CPUTimes, end: &CPUTimes) -> CPUInterval {
-    CPUInterval {
-        user: ((end.user_usec - start.user_usec) as f64) * 1e-6,
-        system: ((end.system_usec - start.system_usec) as f64) * 1e-6,
+/// Minor and major page faults from the given resource usage
+pub fn page_faults(usage: &libc::rusage) -> PageFaults {
+    PageFaults {
+        minor: usage.ru_minflt.max(0) as u64,
+        major: usage.ru_majflt.max(0) as u64,
     }
 }
 
 #[cfg(test)]
 use approx::assert_relative_eq;
 
-#[test]
-fn test_cpu_time_interval() {
-    let t_a = CPUTimes {
-        user_usec: 12345,
-        system_usec: 54321,
-    };
-
-    let t_b = CPUTimes {
-        user_usec: 20000,
-        system_usec: 70000,
-    };
+#[cfg(test)]
+fn timeval(tv_sec: libc::time_t, tv_usec: libc::suseconds_t) -> libc::timeval {
+    libc::timeval { tv_sec, tv_usec }
+}
 
-    let t_zero = cpu_time_interval(&t_a, &t_a);
-    assert!(t_zero.user.abs() < f64::EPSILON);
-    assert!(t_zero.system.abs() < f64::EPSILON);
+#[test]
+fn test_cpu_times() {
+    // SAFETY: An all-zero `rusage` is a valid value
+    let mut usage: libc::rusage = unsafe { mem::zeroed() };
+    usage.ru_utime = timeval(1, 250_000);
+    usage.ru_stime = timeval(0, 54321);
+    let (user, system) = cpu_times(&usage);
+    assert_relative_eq!(user, 1.25);
+    assert_relative_eq!(system, 0.054321);
 
-    let t_ab = cpu_time_interval(&t_a, &t_b);
-    assert_relative_eq!(0.007655, t_ab.user);
-    assert_relative_eq!(0.015679, t_ab.system);
+    #[cfg(target_os = "linux")]
+    {
+        let mut descendant = usage;
+        descendant.ru_utime = timeval(0, 750_000);
+        add_rusage(&mut usage, &descendant);
+        let (user, system) = cpu_times(&usage);
+        assert_relative_eq!(user, 2.0);
+        assert_relative_eq!(system, 0.108642);
+    }
+}
 
-    let t_ba = cpu_time_interval(&t_b, &t_a);
-    assert_relative_eq!(-0.007655, t_ba.user);
-    assert_relative_eq!(-0.015679, t_ba.system);
+#[test]
+#[cfg(target_os = "linux")]
+fn test_parse_io_counters() {
+    let content = "rchar: 10017932\nwchar: 10000000\nsyscr: 679\nsyscw: 1259\n\
+                   read_bytes: 0\nwrite_bytes: 5017600\ncancelled_write_bytes: 0\n";
+    assert_eq!(
+        parse_io_counters(content),
+        Some(IoCounters {
+            read_bytes: 10017932,
+            written_bytes: 10000000,
+        })
+    );
+    assert_eq!(parse_io_counters("syscr: 679\n"), None);
 }
@@ -1,20 +1,58 @@
 use std::time::Instant;
 
+use crate::options::Clock;
 use crate::util::units::Second;
 
+/// Current value of the given clock of clock_gettime(2)
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn clock_gettime(clock: libc::clockid_t) -> Second {
+    // SAFETY: An all-zero `timespec` is a valid value
+    let mut time: libc::timespec = unsafe { std::mem::zeroed() };
+
+    // SAFETY: The pointer is valid for writes. The clock is supported on this platform, which
+    // is checked in `Options`.
+    let ret = unsafe { libc::clock_gettime(clock, &mut time) };
+    assert_eq!(ret, 0, "clock_gettime failed");
+
+    time.tv_sec as f64 + time.tv_nsec as f64 * 1e-9
+}
+
 pub struct WallClockTimer {
     start: Instant,
+
+    /// Start value of CLOCK_MONOTONIC_RAW, if this clock is used
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
+    start_raw: Option<Second>,
 }
 
 impl WallClockTimer {
-    pub fn start() -> WallClockTimer {
+    /// Start a timer that measures the elapsed time with the given clock. For the CPU time of
+    /// the process, the monotonic clock is used.
+    pub fn start(clock: Clock) -> WallClockTimer {
+        #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+        let _ = clock;
+
         WallClockTimer {
+            #[cfg(any(target_os = "linux", target_os = "macos"))]
+            start_raw: (clock == Clock::MonotonicRaw)
+                .then(|| clock_gettime(libc::CLOCK_MONOTONIC_RAW)),
             start: Instant::now(),
         }
     }
 
     pub fn stop(&self) -> Second {
+        #[cfg(any(target_os = "linux", target_os = "macos"))]
+        if let Some(start_raw) = self.start_raw {
+            return clock_gettime(libc::CLOCK_MONOTONIC_RAW) - start_raw;
+        }
+
         let duration = self.start.elapsed();
         duration.as_secs() as f64 + f64::from(duration.subsec_nanos()) * 1e-9
     }
+
+    /// Time from the start of the timer until the given instant, according to the monotonic
+    /// clock
+    pub fn time_until(&self, instant: Instant) -> Second {
+        instant.saturating_duration_since(self.start).as_secs_f64()
+    }
 }
@@ -1,13 +1,16 @@
 #![cfg(windows)]
 #![warn(unsafe_op_in_unsafe_fn)]
 
-use std::{mem, os::windows::io::AsRawHandle, process, ptr};
+use std::{mem, os::windows::io::AsRawHandle, process, ptr, thread, time::Duration};
 
 use windows_sys::Win32::{
     Foundation::{CloseHandle, HANDLE},
     System::JobObjects::{
         AssignProcessToJobObject, CreateJobObjectW, JobObjectBasicAccountingInformation,
-        QueryInformationJobObject, JOBOBJECT_BASIC_ACCOUNTING_INFORMATION,
+        JobObjectBasicAndIoAccountingInformation, JobObjectExtendedLimitInformation,
+        QueryInformationJobObject, SetInformationJobObject, JOBOBJECT_BASIC_ACCOUNTING_INFORMATION,
+        JOBOBJECT_BASIC_AND_IO_ACCOUNTING_INFORMATION, JOBOBJECT_EXTENDED_LIMIT_INFORMATION,
+        JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE,
     },
 };
 
@@ -27,6 +30,7 @@ use windows_sys::{
     },
 };
 
+use crate::benchmark::timing_result::IoCounters;
 use crate::util::units::Second;
 
 const HUNDRED_NS_PER_MS: i64 = 10;
@@ -46,6 +50,27 @@ static NtResumeProcess: Lazy<unsafe extern "system" fn(ProcessHandle: HANDLE) ->
         unsafe { mem::transmute(nt_resume_process.unwrap()) }
     });
 
+/// Whether all processes in the job are killed when the last handle to the job is closed,
+/// which also happens when hyperfine is terminated (e.g. with Ctrl-C)
+fn set_kill_on_job_close(job_object: HANDLE, kill_on_close: bool) -> bool {
+    // SAFETY: An all-zero limit information (without any limits) is valid
+    let mut limit_info: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = unsafe { mem::zeroed() };
+    if kill_on_close {
+        limit_info.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+    }
+
+    // SAFETY: The job object handle is valid and the limit information is valid for reads
+    let ret = unsafe {
+        SetInformationJobObject(
+            job_object,
+            JobObjectExtendedLimitInformation,
+            ptr::addr_of!(limit_info).cast(),
+            mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+        )
+    };
+    ret != 0
+}
+
 pub struct CPUTimer {
     job_object: HANDLE,
 }
@@ -58,6 +83,11 @@ impl CPUTimer {
         let job_object = unsafe { CreateJobObjectW(ptr::null_mut(), ptr::null_mut()) };
         assert!(job_object != 0, "CreateJobObjectW failed");
 
+        // If hyperfine is interrupted during the run, the whole process tree of the command
+        // is killed, including processes that are detached from the console
+        let ret = set_kill_on_job_close(job_object, true);
+        assert!(ret, "SetInformationJobObject failed");
+
         // SAFETY: The job object handle is valid
         let ret = unsafe { AssignProcessToJobObject(job_object, child_handle) };
         assert!(ret != 0, "AssignProcessToJobObject failed");
@@ -116,10 +146,127 @@ impl CPUTimer {
             (0.0, 0.0)
         }
     }
+
+    /// Number of processes that were associated with the job, i.e. the benchmarked process and
+    /// all processes that it created, including those that have exited
+    pub fn total_processes(&self) -> Option<u64> {
+        let mut job_object_info =
+            mem::MaybeUninit::<JOBOBJECT_BASIC_ACCOUNTING_INFORMATION>::uninit();
+
+        // SAFETY: A valid job object got created in `start_suspended_process`
+        let res = unsafe {
+            QueryInformationJobObject(
+                self.job_object,
+                JobObjectBasicAccountingInformation,
+                job_object_info.as_mut_ptr().cast(),
+                mem::size_of::<JOBOBJECT_BASIC_ACCOUNTING_INFORMATION>() as u32,
+                ptr::null_mut(),
+            )
+        };
+
+        if res != 0 {
+            // SAFETY: The job object info got correctly initialized
+            let job_object_info = unsafe { job_object_info.assume_init() };
+            Some(job_object_info.TotalProcesses as u64)
+        } else {
+            None
+        }
+    }
+
+    /// Wait until all processes in the job have exited, including those that are not waited
+    /// for by their parent
+    pub fn wait_for_all_processes(&self) {
+        loop {
+            let mut job_object_info =
+                mem::MaybeUninit::<JOBOBJECT_BASIC_ACCOUNTING_INFORMATION>::uninit();
+
+            // SAFETY: A valid job object got created in `start_suspended_process`
+            let res = unsafe {
+                QueryInformationJobObject(
+                    self.job_object,
+                    JobObjectBasicAccountingInformation,
+                    job_object_info.as_mut_ptr().cast(),
+                    mem::size_of::<JOBOBJECT_BASIC_ACCOUNTING_INFORMATION>() as u32,
+                    ptr::null_mut(),
+                )
+            };
+            if res == 0 {
+                return;
+            }
+
+            // SAFETY: The job object info got correctly initialized
+            let job_object_info = unsafe { job_object_info.assume_init() };
+            if job_object_info.ActiveProcesses == 0 {
+                return;
+            }
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    /// Peak memory usage (committed memory) in bytes: of any process in the job, or of all
+    /// processes in the job combined if `whole_job` is set
+    pub fn peak_memory_usage(&self, whole_job: bool) -> Option<u64> {
+        let mut limit_info = mem::MaybeUninit::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>::uninit();
+
+        // SAFETY: A valid job object got created in `start_suspended_process`
+        let res = unsafe {
+            QueryInformationJobObject(
+                self.job_object,
+                JobObjectExtendedLimitInformation,
+                limit_info.as_mut_ptr().cast(),
+                mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+                ptr::null_mut(),
+            )
+        };
+
+        if res != 0 {
+            // SAFETY: The limit info got correctly initialized
+            let limit_info = unsafe { limit_info.assume_init() };
+            Some(if whole_job {
+                limit_info.PeakJobMemoryUsed as u64
+            } else {
+                limit_info.PeakProcessMemoryUsed as u64
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Number of bytes that all processes in the job read and wrote
+    pub fn io_counters(&self) -> Option<IoCounters> {
+        let mut accounting_info =
+            mem::MaybeUninit::<JOBOBJECT_BASIC_AND_IO_ACCOUNTING_INFORMATION>::uninit();
+
+        // SAFETY: A valid job object got created in `start_suspended_process`
+        let res = unsafe {
+            QueryInformationJobObject(
+                self.job_object,
+                JobObjectBasicAndIoAccountingInformation,
+                accounting_info.as_mut_ptr().cast(),
+                mem::size_of::<JOBOBJECT_BASIC_AND_IO_ACCOUNTING_INFORMATION>() as u32,
+                ptr::null_mut(),
+            )
+        };
+
+        if res != 0 {
+            // SAFETY: The accounting info got correctly initialized
+            let accounting_info = unsafe { accounting_info.assume_init() };
+            Some(IoCounters {
+                read_bytes: accounting_info.IoInfo.ReadTransferCount,
+                written_bytes: accounting_info.IoInfo.WriteTransferCount,
+            })
+        } else {
+            None
+        }
+    }
 }
 
 impl Drop for CPUTimer {
     fn drop(&mut self) {
+        // Processes that the command left running after a completed run, like background
+        // processes that were not waited for, are not killed
+        set_kill_on_job_close(self.job_object, false);
+
         // SAFETY: A valid job object got created in `start_suspended_process`
         unsafe { CloseHandle(self.job_object) };
     }
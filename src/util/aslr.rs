@@ -0,0 +1,29 @@
+//! Disabling address space layout randomization for the benchmarked commands (`--no-aslr`)
+
+#![cfg(target_os = "linux")]
+
+use std::io;
+use std::os::unix::process::CommandExt;
+use std::process::Command;
+
+/// Value of the `persona` argument of personality(2) that only queries the current persona
+const QUERY_PERSONA: libc::c_ulong = 0xffff_ffff;
+
+/// Run the processes that are spawned by the given command without address space layout
+/// randomization. The setting is inherited by their descendants.
+pub fn disable_for_command(command: &mut Command) {
+    // SAFETY: `personality` is a plain system call, which is safe to use between `fork` and
+    // `exec`
+    unsafe {
+        command.pre_exec(|| {
+            let persona = libc::personality(QUERY_PERSONA);
+            if persona == -1
+                || libc::personality((persona | libc::ADDR_NO_RANDOMIZE) as libc::c_ulong) == -1
+            {
+                Err(io::Error::last_os_error())
+            } else {
+                Ok(())
+            }
+        });
+    }
+}
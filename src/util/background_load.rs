@@ -0,0 +1,118 @@
+//! CPU usage of other processes during a benchmark (Linux only)
+
+#![cfg(target_os = "linux")]
+
+use std::fs;
+use std::mem;
+use std::time::{Duration, Instant};
+
+/// Minimum duration of the monitoring, since the CPU times of the system are only updated
+/// every clock tick (usually 10 ms)
+const MIN_DURATION: Duration = Duration::from_secs(1);
+
+/// Busy and total CPU time of all CPUs of the system, in clock ticks
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct SystemCpuTimes {
+    busy: u64,
+    total: u64,
+}
+
+/// Parse the aggregated 'cpu' line of /proc/stat. The time that the CPUs spent idle or waiting
+/// for I/O is not counted as busy. Guest time is already included in the user time.
+fn parse_system_cpu_times(stat: &str) -> Option<SystemCpuTimes> {
+    let line = stat.lines().find(|line| line.starts_with("cpu "))?;
+    let fields: Vec<u64> = line
+        .split_whitespace()
+        .skip(1)
+        .map(|field| field.parse().ok())
+        .collect::<Option<_>>()?;
+
+    // user, nice, system, idle, iowait, irq, softirq, steal
+    let field = |index: usize| fields.get(index).copied().unwrap_or(0);
+    let idle = field(3) + field(4);
+    let busy = field(0) + field(1) + field(2) + field(5) + field(6) + field(7);
+    Some(SystemCpuTimes {
+        busy,
+        total: busy + idle,
+    })
+}
+
+fn system_cpu_times() -> Option<SystemCpuTimes> {
+    parse_system_cpu_times(&fs::read_to_string("/proc/stat").ok()?)
+}
+
+/// CPU time (user and system) of hyperfine itself and of all of its waited-for children, in
+/// seconds
+fn own_cpu_time() -> f64 {
+    let cpu_time = |who| {
+        // SAFETY: An all-zero `rusage` is a valid value
+        let mut usage: libc::rusage = unsafe { mem::zeroed() };
+        // SAFETY: The pointer is valid for writes
+        unsafe { libc::getrusage(who, &mut usage) };
+        let seconds = |time: libc::timeval| time.tv_sec as f64 + time.tv_usec as f64 * 1e-6;
+        seconds(usage.ru_utime) + seconds(usage.ru_stime)
+    };
+    cpu_time(libc::RUSAGE_SELF) + cpu_time(libc::RUSAGE_CHILDREN)
+}
+
+/// Watches how busy the system is with other processes than hyperfine and the benchmarked
+/// commands. This only compares the CPU times at the start and at the end, so it does not
+/// add any load itself.
+pub struct BackgroundLoadMonitor {
+    start_time: Instant,
+    start: SystemCpuTimes,
+    start_own: f64,
+
+    /// Length of a clock tick of /proc/stat, in seconds
+    tick: f64,
+}
+
+impl BackgroundLoadMonitor {
+    pub fn start() -> Option<Self> {
+        // SAFETY: `sysconf` is always safe to call
+        let ticks_per_second = unsafe { libc::sysconf(libc::_SC_CLK_TCK) };
+        if ticks_per_second <= 0 {
+            return None;
+        }
+
+        Some(BackgroundLoadMonitor {
+            start_time: Instant::now(),
+            start_own: own_cpu_time(),
+            start: system_cpu_times()?,
+            tick: 1.0 / ticks_per_second as f64,
+        })
+    }
+
+    /// The fraction of the capacity of all CPUs that was used by other processes since the
+    /// start. Not available if the monitoring was too short to be meaningful.
+    pub fn stop(&self) -> Option<f64> {
+        if self.start_time.elapsed() < MIN_DURATION {
+            return None;
+        }
+
+        let end = system_cpu_times()?;
+        let own = own_cpu_time() - self.start_own;
+
+        let busy = end.busy.saturating_sub(self.start.busy) as f64 * self.tick;
+        let total = end.total.saturating_sub(self.start.total) as f64 * self.tick;
+        if total <= 0.0 {
+            return None;
+        }
+        Some(((busy - own) / total).clamp(0.0, 1.0))
+    }
+}
+
+#[test]
+fn test_parse_system_cpu_times() {
+    let stat = "cpu  4705 356 584 3699 23 23 0 0 0 0\n\
+                cpu0 1393 280 234 852 6 7 0 0 0 0\n\
+                intr 114930548 113199788 3 0 5 263 0 4 [...] 0\n";
+    assert_eq!(
+        parse_system_cpu_times(stat),
+        Some(SystemCpuTimes {
+            busy: 4705 + 356 + 584 + 23,
+            total: 4705 + 356 + 584 + 3699 + 23 + 23,
+        })
+    );
+    assert_eq!(parse_system_cpu_times("intr 114930548\n"), None);
+}
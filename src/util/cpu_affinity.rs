@@ -0,0 +1,154 @@
+//! Pinning processes to a set of CPUs (`--cpu` and `--hyperfine-cpu`)
+
+#![cfg(any(target_os = "linux", windows))]
+
+use std::io;
+
+use anyhow::{bail, Result};
+
+#[cfg(target_os = "linux")]
+use std::os::unix::process::CommandExt;
+#[cfg(target_os = "linux")]
+use std::process::Command;
+
+#[cfg(windows)]
+use std::os::windows::io::AsRawHandle;
+#[cfg(windows)]
+use std::process::Child;
+#[cfg(windows)]
+use windows_sys::Win32::{
+    Foundation::HANDLE,
+    System::Threading::{GetCurrentProcess, GetProcessAffinityMask, SetProcessAffinityMask},
+};
+
+/// Highest CPU number (plus one) that can be used on this platform
+#[cfg(target_os = "linux")]
+pub const MAX_CPUS: usize = libc::CPU_SETSIZE as usize;
+#[cfg(windows)]
+pub const MAX_CPUS: usize = usize::BITS as usize;
+
+/// Make sure that all of the given CPUs can be used by the current process
+pub fn check_available(cpus: &[usize]) -> Result<()> {
+    let available = available_cpus()?;
+    if let Some(cpu) = cpus.iter().find(|cpu| !available.contains(cpu)) {
+        bail!(
+            "CPU {} is not available. Available CPUs: {}",
+            cpu,
+            available
+                .iter()
+                .map(|cpu| cpu.to_string())
+                .collect::<Vec<_>>()
+                .join(",")
+        );
+    }
+    Ok(())
+}
+
+/// The CPUs that the current process may run on
+#[cfg(target_os = "linux")]
+fn available_cpus() -> io::Result<Vec<usize>> {
+    // SAFETY: An all-zero `cpu_set_t` is a valid (empty) set
+    let mut set: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+
+    // SAFETY: The set is valid for writes. Pid 0 refers to the calling thread.
+    let ret =
+        unsafe { libc::sched_getaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &mut set) };
+    if ret != 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    // SAFETY: All CPU numbers are smaller than the size of the set
+    Ok((0..MAX_CPUS)
+        .filter(|&cpu| unsafe { libc::CPU_ISSET(cpu, &set) })
+        .collect())
+}
+
+/// The CPUs that the current process may run on
+#[cfg(windows)]
+fn available_cpus() -> io::Result<Vec<usize>> {
+    let mut process_mask = 0;
+    let mut system_mask = 0;
+
+    // SAFETY: Both pointers are valid for writes
+    let ret =
+        unsafe { GetProcessAffinityMask(GetCurrentProcess(), &mut process_mask, &mut system_mask) };
+    if ret == 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    Ok((0..MAX_CPUS)
+        .filter(|&cpu| process_mask & 1 << cpu != 0)
+        .collect())
+}
+
+#[cfg(target_os = "linux")]
+fn cpu_set(cpus: &[usize]) -> libc::cpu_set_t {
+    // SAFETY: An all-zero `cpu_set_t` is a valid (empty) set
+    let mut set: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+    for &cpu in cpus {
+        // SAFETY: The CPU number has been checked against `MAX_CPUS` in `Options`
+        unsafe { libc::CPU_SET(cpu, &mut set) };
+    }
+    set
+}
+
+#[cfg(target_os = "linux")]
+fn set_affinity(set: &libc::cpu_set_t) -> io::Result<()> {
+    // SAFETY: The set is valid for reads. Pid 0 refers to the calling thread.
+    let ret = unsafe { libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), set) };
+    if ret == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+/// Pin the processes that are spawned by the given command to the given CPUs
+#[cfg(target_os = "linux")]
+pub fn set_for_command(command: &mut Command, cpus: &[usize]) {
+    let set = cpu_set(cpus);
+
+    // SAFETY: `sched_setaffinity` is a plain system call, which is safe to use between `fork`
+    // and `exec`
+    unsafe {
+        command.pre_exec(move || set_affinity(&set));
+    }
+}
+
+/// Pin hyperfine itself to the given CPUs. This has to be called before any other threads
+/// are started, since it only applies to the calling thread (and to threads and processes
+/// that are created by it afterwards).
+#[cfg(target_os = "linux")]
+pub fn set_for_current_process(cpus: &[usize]) -> io::Result<()> {
+    set_affinity(&cpu_set(cpus))
+}
+
+#[cfg(windows)]
+fn affinity_mask(cpus: &[usize]) -> usize {
+    cpus.iter().fold(0, |mask, &cpu| mask | 1 << cpu)
+}
+
+#[cfg(windows)]
+fn set_affinity(process: HANDLE, cpus: &[usize]) -> io::Result<()> {
+    // SAFETY: The process handle is valid
+    let ret = unsafe { SetProcessAffinityMask(process, affinity_mask(cpus)) };
+    if ret != 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+/// Pin the given (suspended) child process to the given CPUs. Processes that it creates
+/// inherit the affinity.
+#[cfg(windows)]
+pub fn set_for_process(child: &Child, cpus: &[usize]) -> io::Result<()> {
+    set_affinity(child.as_raw_handle() as HANDLE, cpus)
+}
+
+/// Pin hyperfine itself to the given CPUs
+#[cfg(windows)]
+pub fn set_for_current_process(cpus: &[usize]) -> io::Result<()> {
+    // SAFETY: Getting the pseudo handle of the current process is always safe
+    set_affinity(unsafe { GetCurrentProcess() }, cpus)
+}
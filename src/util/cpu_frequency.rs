@@ -0,0 +1,125 @@
+//! Frequency scaling settings of the CPUs (Linux only)
+
+#![cfg(target_os = "linux")]
+
+use std::fs;
+use std::path::Path;
+
+const CPU_DIR: &str = "/sys/devices/system/cpu";
+
+/// The only governor that keeps the CPUs at a fixed (maximum) frequency
+pub const PERFORMANCE_GOVERNOR: &str = "performance";
+
+fn read_trimmed(path: &Path) -> Option<String> {
+    fs::read_to_string(path)
+        .ok()
+        .map(|content| content.trim().to_string())
+}
+
+/// Numbers of all CPUs in the given directory (from the 'cpuN' subdirectories)
+fn all_cpus(cpu_dir: &Path) -> Vec<usize> {
+    let mut cpus: Vec<usize> = fs::read_dir(cpu_dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|entry| {
+                    entry
+                        .file_name()
+                        .to_str()?
+                        .strip_prefix("cpu")?
+                        .parse()
+                        .ok()
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    cpus.sort_unstable();
+    cpus
+}
+
+fn scaling_governors_in(cpu_dir: &Path, cpus: &[usize]) -> Vec<String> {
+    let cpus = if cpus.is_empty() {
+        all_cpus(cpu_dir)
+    } else {
+        cpus.to_vec()
+    };
+
+    let mut governors = vec![];
+    for cpu in cpus {
+        let path = cpu_dir.join(format!("cpu{}/cpufreq/scaling_governor", cpu));
+        if let Some(governor) = read_trimmed(&path) {
+            if !governors.contains(&governor) {
+                governors.push(governor);
+            }
+        }
+    }
+    governors
+}
+
+/// The distinct frequency scaling governors of the given CPUs, or of all CPUs if the list is
+/// empty. This is empty if frequency scaling is not available, e.g. in virtual machines.
+pub fn scaling_governors(cpus: &[usize]) -> Vec<String> {
+    scaling_governors_in(Path::new(CPU_DIR), cpus)
+}
+
+fn turbo_boost_in(cpu_dir: &Path) -> Option<String> {
+    // The 'intel_pstate' driver has its own setting, other drivers share a generic one
+    let no_turbo = cpu_dir.join("intel_pstate/no_turbo");
+    let boost = cpu_dir.join("cpufreq/boost");
+
+    if read_trimmed(&no_turbo).as_deref() == Some("0") {
+        Some(format!("echo 1 | sudo tee {}", no_turbo.display()))
+    } else if read_trimmed(&boost).as_deref() == Some("1") {
+        Some(format!("echo 0 | sudo tee {}", boost.display()))
+    } else {
+        None
+    }
+}
+
+/// If turbo boost is enabled, the command that disables it
+pub fn turbo_boost() -> Option<String> {
+    turbo_boost_in(Path::new(CPU_DIR))
+}
+
+/// Current frequency of the given CPU in MHz, if frequency scaling is available
+pub fn current_frequency_mhz(cpu: usize) -> Option<f64> {
+    let path = Path::new(CPU_DIR).join(format!("cpu{}/cpufreq/scaling_cur_freq", cpu));
+    let frequency_khz: f64 = read_trimmed(&path)?.parse().ok()?;
+    Some(frequency_khz / 1000.0)
+}
+
+#[test]
+fn test_scaling_governors_and_turbo_boost() {
+    let dir = tempfile::tempdir().unwrap();
+    let write = |path: &str, content: &str| {
+        let path = dir.path().join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    };
+
+    assert!(scaling_governors_in(dir.path(), &[]).is_empty());
+    assert_eq!(turbo_boost_in(dir.path()), None);
+
+    write("cpu0/cpufreq/scaling_governor", "performance\n");
+    write("cpu1/cpufreq/scaling_governor", "powersave\n");
+    write("cpu2/cpufreq/scaling_governor", "performance\n");
+    write("cpufreq/boost", "0\n");
+    assert_eq!(
+        scaling_governors_in(dir.path(), &[]),
+        vec!["performance", "powersave"]
+    );
+    assert_eq!(
+        scaling_governors_in(dir.path(), &[0, 2]),
+        vec!["performance"]
+    );
+    assert_eq!(turbo_boost_in(dir.path()), None);
+
+    write("intel_pstate/no_turbo", "0\n");
+    assert_eq!(
+        turbo_boost_in(dir.path()),
+        Some(format!(
+            "echo 1 | sudo tee {}",
+            dir.path().join("intel_pstate/no_turbo").display()
+        ))
+    );
+}
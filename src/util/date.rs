@@ -0,0 +1,48 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The given point in time in (fractional) seconds since the Unix epoch.
+pub fn unix_timestamp(time: SystemTime) -> f64 {
+    time.duration_since(UNIX_EPOCH)
+        .map_or(0.0, |d| d.as_secs_f64())
+}
+
+/// Format the given point in time as an RFC 3339 timestamp in UTC, e.g.
+/// `2023-10-23T12:34:56+00:00`.
+pub fn format_rfc3339_utc(time: SystemTime) -> String {
+    let secs = time.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    let (days, secs_of_day) = (secs / 86400, secs % 86400);
+
+    // Convert days since the epoch to a civil date, see
+    // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days as i64 + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}+00:00",
+        year,
+        month,
+        day,
+        secs_of_day / 3600,
+        secs_of_day / 60 % 60,
+        secs_of_day % 60
+    )
+}
+
+#[test]
+fn test_format_rfc3339_utc() {
+    use std::time::Duration;
+
+    let at = |secs| format_rfc3339_utc(UNIX_EPOCH + Duration::from_secs(secs));
+
+    assert_eq!(at(0), "1970-01-01T00:00:00+00:00");
+    assert_eq!(at(951782400), "2000-02-29T00:00:00+00:00");
+    assert_eq!(at(1698064496), "2023-10-23T12:34:56+00:00");
+    assert_eq!(at(4102444799), "2099-12-31T23:59:59+00:00");
+}
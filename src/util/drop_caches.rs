@@ -0,0 +1,174 @@
+//! Dropping the file system caches of the operating system (`--drop-caches`)
+
+#![cfg(any(target_os = "linux", target_os = "macos", windows))]
+
+use anyhow::{bail, Context, Result};
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+use std::process::{Command, Stdio};
+
+#[cfg(target_os = "linux")]
+const DROP_CACHES_PATH: &str = "/proc/sys/vm/drop_caches";
+
+/// Run the given program as root, via 'sudo' if hyperfine is not running as root. If a
+/// password prompt is not allowed, 'sudo' fails instead of asking for the password.
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn run_as_root(program: &str, args: &[&str], allow_password_prompt: bool) -> Result<()> {
+    // SAFETY: `geteuid` is always safe to call
+    let mut command = if unsafe { libc::geteuid() } == 0 {
+        Command::new(program)
+    } else {
+        let mut sudo = Command::new("sudo");
+        if !allow_password_prompt {
+            sudo.arg("--non-interactive");
+        }
+        sudo.arg(program);
+        sudo
+    };
+    command.args(args).stdout(Stdio::null());
+    if !allow_password_prompt {
+        command.stdin(Stdio::null());
+    }
+
+    let status = command
+        .status()
+        .with_context(|| format!("Could not run '{}'", program))?;
+    if !status.success() {
+        bail!(
+            "'{}' failed. It has to be run as root, either by running hyperfine as root or via \
+             'sudo'.",
+            program
+        );
+    }
+    Ok(())
+}
+
+/// Drop the page cache, dentries and inodes, after writing dirty pages to the disk (which
+/// can not be dropped otherwise)
+#[cfg(target_os = "linux")]
+pub fn drop_caches(allow_password_prompt: bool) -> Result<()> {
+    // SAFETY: `sync` is always safe to call
+    unsafe { libc::sync() };
+
+    run_as_root(
+        "sh",
+        &["-c", &format!("echo 3 > {}", DROP_CACHES_PATH)],
+        allow_password_prompt,
+    )
+    .context("Could not drop the file system caches")
+}
+
+/// Drop the disk cache with 'purge', after writing dirty pages to the disk
+#[cfg(target_os = "macos")]
+pub fn drop_caches(allow_password_prompt: bool) -> Result<()> {
+    // SAFETY: `sync` is always safe to call
+    unsafe { libc::sync() };
+
+    run_as_root("purge", &[], allow_password_prompt)
+        .context("Could not drop the file system caches")
+}
+
+/// Empty the standby list, i.e. the memory pages that cache files which are not in use
+/// anymore. This requires administrator privileges.
+#[cfg(windows)]
+pub fn drop_caches(_allow_password_prompt: bool) -> Result<()> {
+    use std::ffi::c_void;
+    use std::iter;
+    use std::mem;
+    use std::ptr;
+
+    use windows_sys::Win32::Foundation::{
+        CloseHandle, GetLastError, ERROR_NOT_ALL_ASSIGNED, HANDLE, LUID,
+    };
+    use windows_sys::Win32::Security::{
+        AdjustTokenPrivileges, LookupPrivilegeValueW, LUID_AND_ATTRIBUTES, SE_PRIVILEGE_ENABLED,
+        TOKEN_ADJUST_PRIVILEGES, TOKEN_PRIVILEGES, TOKEN_QUERY,
+    };
+    use windows_sys::Win32::System::LibraryLoader::{GetModuleHandleA, GetProcAddress};
+    use windows_sys::Win32::System::Threading::{GetCurrentProcess, OpenProcessToken};
+
+    // From the undocumented `SYSTEM_INFORMATION_CLASS` and `SYSTEM_MEMORY_LIST_COMMAND`
+    const SYSTEM_MEMORY_LIST_INFORMATION: u32 = 80;
+    const MEMORY_PURGE_STANDBY_LIST: u32 = 4;
+
+    type NtSetSystemInformation = unsafe extern "system" fn(u32, *const c_void, u32) -> i32;
+
+    let not_allowed = "Dropping the file system caches requires administrator privileges";
+
+    // The privilege that allows clearing the standby list is disabled by default
+    let privilege_name: Vec<u16> = "SeProfileSingleProcessPrivilege"
+        .encode_utf16()
+        .chain(iter::once(0))
+        .collect();
+    let mut luid = LUID {
+        LowPart: 0,
+        HighPart: 0,
+    };
+    // SAFETY: The name is null-terminated and the LUID is valid for writes
+    if unsafe { LookupPrivilegeValueW(ptr::null(), privilege_name.as_ptr(), &mut luid) } == 0 {
+        return Err(std::io::Error::last_os_error()).context(not_allowed);
+    }
+
+    let mut token: HANDLE = 0;
+    // SAFETY: The token handle is valid for writes
+    if unsafe {
+        OpenProcessToken(
+            GetCurrentProcess(),
+            TOKEN_ADJUST_PRIVILEGES | TOKEN_QUERY,
+            &mut token,
+        )
+    } == 0
+    {
+        return Err(std::io::Error::last_os_error()).context(not_allowed);
+    }
+
+    let privileges = TOKEN_PRIVILEGES {
+        PrivilegeCount: 1,
+        Privileges: [LUID_AND_ATTRIBUTES {
+            Luid: luid,
+            Attributes: SE_PRIVILEGE_ENABLED,
+        }],
+    };
+    // SAFETY: The token has been opened with TOKEN_ADJUST_PRIVILEGES and the privileges are
+    // valid for reads. The previous state is not requested.
+    let (adjusted, error) = unsafe {
+        let adjusted =
+            AdjustTokenPrivileges(token, 0, &privileges, 0, ptr::null_mut(), ptr::null_mut());
+        let error = GetLastError();
+        CloseHandle(token);
+        (adjusted, error)
+    };
+    // The function also succeeds if the privilege could not be enabled
+    if adjusted == 0 || error == ERROR_NOT_ALL_ASSIGNED {
+        bail!("{}", not_allowed);
+    }
+
+    // `NtSetSystemInformation` is not part of the Windows API, but of the native API
+    // SAFETY: ntdll.dll is loaded into every process
+    let function = unsafe {
+        GetProcAddress(
+            GetModuleHandleA(b"ntdll.dll\0".as_ptr()),
+            b"NtSetSystemInformation\0".as_ptr(),
+        )
+    }
+    .context("Could not find 'NtSetSystemInformation' in ntdll.dll")?;
+    // SAFETY: The function has the given signature
+    let nt_set_system_information: NtSetSystemInformation = unsafe { mem::transmute(function) };
+
+    let command = MEMORY_PURGE_STANDBY_LIST;
+    // SAFETY: The command is valid for reads, and has the size that is passed
+    let status = unsafe {
+        nt_set_system_information(
+            SYSTEM_MEMORY_LIST_INFORMATION,
+            &command as *const u32 as *const c_void,
+            mem::size_of::<u32>() as u32,
+        )
+    };
+    if status < 0 {
+        bail!(
+            "Could not drop the file system caches (NTSTATUS {:#010x})",
+            status
+        );
+    }
+    Ok(())
+}
@@ -20,3 +20,32 @@ pub fn extract_exit_code(status: ExitStatus) -> Option<i32> {
 pub fn extract_exit_code(status: ExitStatus) -> Option<i32> {
     status.code()
 }
+
+/// The signal that terminated the process, if any.
+#[cfg(unix)]
+pub fn extract_signal(status: ExitStatus) -> Option<i32> {
+    use std::os::unix::process::ExitStatusExt;
+
+    status.signal()
+}
+
+#[cfg(not(unix))]
+pub fn extract_signal(_status: ExitStatus) -> Option<i32> {
+    None
+}
+
+#[cfg(unix)]
+#[test]
+fn test_extract_signal() {
+    use std::os::unix::process::ExitStatusExt;
+
+    // Raw wait status of a process that was killed by SIGKILL
+    let killed = ExitStatus::from_raw(9);
+    assert_eq!(extract_exit_code(killed), Some(128 + 9));
+    assert_eq!(extract_signal(killed), Some(9));
+
+    // Raw wait status of a process that exited with code 1
+    let exited = ExitStatus::from_raw(1 << 8);
+    assert_eq!(extract_exit_code(exited), Some(1));
+    assert_eq!(extract_signal(exited), None);
+}
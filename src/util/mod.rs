@@ -1,5 +1,15 @@
+pub mod aslr;
+pub mod background_load;
+pub mod cpu_affinity;
+pub mod cpu_frequency;
+pub mod date;
+pub mod drop_caches;
 pub mod exit_code;
 pub mod min_max;
+pub mod network;
 pub mod number;
+pub mod priority;
 pub mod randomized_environment_offset;
+pub mod sync;
+pub mod system_state;
 pub mod units;
@@ -0,0 +1,76 @@
+//! Isolating the benchmarks from the network (`--isolate-network`, Linux only)
+
+#![cfg(target_os = "linux")]
+
+use std::fs;
+use std::io;
+
+use anyhow::{Context, Result};
+
+/// Move hyperfine into a new network namespace, which is inherited by all commands that it
+/// runs. The namespace only contains a loopback interface, such that connections to other
+/// hosts fail immediately, while connections to localhost keep working.
+///
+/// Without the privileges to create a network namespace, a user namespace is created along
+/// with it, in which the current user and group are mapped to themselves. This has to be
+/// called before any other threads are started.
+pub fn isolate_current_process() -> Result<()> {
+    // SAFETY: `unshare` is a plain system call
+    if unsafe { libc::unshare(libc::CLONE_NEWNET) } != 0 {
+        let error = io::Error::last_os_error();
+        if error.raw_os_error() != Some(libc::EPERM) {
+            return Err(error).context("Could not create a network namespace");
+        }
+
+        // SAFETY: `geteuid`, `getegid` and `unshare` are plain system calls
+        let (uid, gid) = unsafe { (libc::geteuid(), libc::getegid()) };
+        if unsafe { libc::unshare(libc::CLONE_NEWUSER | libc::CLONE_NEWNET) } != 0 {
+            return Err(io::Error::last_os_error()).context(
+                "Could not create a network namespace. Creating it either requires root \
+                 privileges or unprivileged user namespaces.",
+            );
+        }
+
+        // The groups can only be mapped after 'setgroups' has been disabled
+        fs::write("/proc/self/setgroups", "deny")
+            .and_then(|_| fs::write("/proc/self/uid_map", format!("{} {} 1", uid, uid)))
+            .and_then(|_| fs::write("/proc/self/gid_map", format!("{} {} 1", gid, gid)))
+            .context("Could not map the user and group into the new user namespace")?;
+    }
+
+    enable_loopback().context("Could not enable the loopback interface in the network namespace")
+}
+
+/// The loopback interface of a new network namespace is down
+fn enable_loopback() -> io::Result<()> {
+    // SAFETY: `socket` is a plain system call
+    let socket = unsafe { libc::socket(libc::AF_INET, libc::SOCK_DGRAM | libc::SOCK_CLOEXEC, 0) };
+    if socket < 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    // SAFETY: An all-zero `ifreq` is valid
+    let mut request: libc::ifreq = unsafe { std::mem::zeroed() };
+    for (dst, &src) in request.ifr_name.iter_mut().zip(b"lo") {
+        *dst = src as libc::c_char;
+    }
+
+    // SAFETY: The socket is valid and the request is valid for reads and writes. The flags
+    // are initialized by SIOCGIFFLAGS.
+    let result = unsafe {
+        if libc::ioctl(socket, libc::SIOCGIFFLAGS as _, &mut request) < 0 {
+            Err(io::Error::last_os_error())
+        } else {
+            request.ifr_ifru.ifru_flags |= libc::IFF_UP as libc::c_short;
+            if libc::ioctl(socket, libc::SIOCSIFFLAGS as _, &request) < 0 {
+                Err(io::Error::last_os_error())
+            } else {
+                Ok(())
+            }
+        }
+    };
+
+    // SAFETY: The socket is valid and not used afterwards
+    unsafe { libc::close(socket) };
+    result
+}
@@ -46,3 +46,12 @@ impl TryFrom<Number> for usize {
         }
     }
 }
+
+impl From<Number> for f64 {
+    fn from(numeric: Number) -> Self {
+        match numeric {
+            Number::Int(i) => i.into(),
+            Number::Decimal(d) => d.to_f64().expect("decimal is representable as f64"),
+        }
+    }
+}
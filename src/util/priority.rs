@@ -0,0 +1,47 @@
+//! Scheduling priority of the benchmarked commands (`--priority`)
+
+#[cfg(unix)]
+use std::io;
+#[cfg(unix)]
+use std::os::unix::process::CommandExt;
+#[cfg(unix)]
+use std::process::Command;
+
+#[cfg(windows)]
+use windows_sys::Win32::System::Threading::{
+    ABOVE_NORMAL_PRIORITY_CLASS, BELOW_NORMAL_PRIORITY_CLASS, HIGH_PRIORITY_CLASS,
+    IDLE_PRIORITY_CLASS, NORMAL_PRIORITY_CLASS, PROCESS_CREATION_FLAGS,
+};
+
+/// Lowest (highest priority) and highest (lowest priority) nice level
+pub const MIN_NICE_LEVEL: i32 = -20;
+pub const MAX_NICE_LEVEL: i32 = 19;
+
+/// Run the processes that are spawned by the given command with the given nice level
+#[cfg(unix)]
+pub fn set_for_command(command: &mut Command, level: i32) {
+    // SAFETY: `setpriority` is a plain system call, which is safe to use between `fork` and
+    // `exec`
+    unsafe {
+        command.pre_exec(move || {
+            if libc::setpriority(libc::PRIO_PROCESS, 0, level) == 0 {
+                Ok(())
+            } else {
+                Err(io::Error::last_os_error())
+            }
+        });
+    }
+}
+
+/// The priority class that corresponds to the given nice level. Windows only has five
+/// priority classes for normal processes (the 'realtime' class is never used).
+#[cfg(windows)]
+pub fn priority_class(level: i32) -> PROCESS_CREATION_FLAGS {
+    match level {
+        i32::MIN..=-10 => HIGH_PRIORITY_CLASS,
+        -9..=-1 => ABOVE_NORMAL_PRIORITY_CLASS,
+        0 => NORMAL_PRIORITY_CLASS,
+        1..=9 => BELOW_NORMAL_PRIORITY_CLASS,
+        _ => IDLE_PRIORITY_CLASS,
+    }
+}
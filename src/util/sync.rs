@@ -0,0 +1,81 @@
+//! Writing dirty pages from the file system caches to the disks (`--sync-before-run`)
+
+#![cfg(any(unix, windows))]
+
+use anyhow::Result;
+
+/// Write all dirty pages to the disks and wait until this has finished, such that their
+/// writeback does not happen during the next run
+#[cfg(unix)]
+pub fn sync_file_systems() -> Result<()> {
+    // SAFETY: `sync` is always safe to call
+    unsafe { libc::sync() };
+    Ok(())
+}
+
+/// Flush the volume of the current directory, i.e. write its dirty pages to the disk. This
+/// requires administrator privileges.
+#[cfg(windows)]
+pub fn sync_file_systems() -> Result<()> {
+    use std::iter;
+    use std::os::windows::ffi::OsStrExt;
+    use std::ptr;
+
+    use anyhow::Context;
+    use windows_sys::Win32::Foundation::{CloseHandle, GENERIC_WRITE, INVALID_HANDLE_VALUE};
+    use windows_sys::Win32::Storage::FileSystem::{
+        CreateFileW, FlushFileBuffers, GetVolumePathNameW, FILE_SHARE_READ, FILE_SHARE_WRITE,
+        OPEN_EXISTING,
+    };
+
+    let dir: Vec<u16> = std::env::current_dir()?
+        .as_os_str()
+        .encode_wide()
+        .chain(iter::once(0))
+        .collect();
+    let mut volume = [0u16; 261];
+    // SAFETY: The path is null-terminated and the buffer is valid for writes of its length
+    if unsafe { GetVolumePathNameW(dir.as_ptr(), volume.as_mut_ptr(), volume.len() as u32) } == 0 {
+        return Err(std::io::Error::last_os_error())
+            .context("Could not find the volume of the current directory");
+    }
+
+    // The volume path is e.g. 'C:\', the device of the volume is '\\.\C:'
+    let volume = String::from_utf16_lossy(&volume[..volume.iter().position(|&c| c == 0).unwrap()]);
+    let device = format!(r"\\.\{}", volume.trim_end_matches('\\'));
+    let device_wide: Vec<u16> = device.encode_utf16().chain(iter::once(0)).collect();
+
+    // SAFETY: The path is null-terminated, all other arguments are plain values
+    let handle = unsafe {
+        CreateFileW(
+            device_wide.as_ptr(),
+            GENERIC_WRITE,
+            FILE_SHARE_READ | FILE_SHARE_WRITE,
+            ptr::null(),
+            OPEN_EXISTING,
+            0,
+            0,
+        )
+    };
+    if handle == INVALID_HANDLE_VALUE {
+        return Err(std::io::Error::last_os_error()).with_context(|| {
+            format!(
+                "Could not open the volume '{}'. Flushing a volume requires administrator \
+                 privileges.",
+                device
+            )
+        });
+    }
+
+    // SAFETY: The handle is valid, and closed afterwards
+    let (flushed, error) = unsafe {
+        let flushed = FlushFileBuffers(handle);
+        let error = std::io::Error::last_os_error();
+        CloseHandle(handle);
+        (flushed, error)
+    };
+    if flushed == 0 {
+        return Err(error).with_context(|| format!("Could not flush the volume '{}'", device));
+    }
+    Ok(())
+}
@@ -0,0 +1,65 @@
+//! State of the system at the start of a run, which helps to explain outliers
+
+/// Load average and available memory of the system at one point in time
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct SystemState {
+    /// Average number of runnable processes over the last minute (Linux and macOS only)
+    pub load_average: Option<f64>,
+
+    /// Memory that is available for new processes without swapping, in bytes (Linux only)
+    pub available_memory_byte: Option<u64>,
+}
+
+impl SystemState {
+    pub fn now() -> Self {
+        SystemState {
+            load_average: load_average(),
+            available_memory_byte: available_memory_byte(),
+        }
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn load_average() -> Option<f64> {
+    let mut load_average = 0.0;
+
+    // SAFETY: The pointer is valid for writes of one element
+    let ret = unsafe { libc::getloadavg(&mut load_average, 1) };
+    (ret == 1).then_some(load_average)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn load_average() -> Option<f64> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+fn available_memory_byte() -> Option<u64> {
+    parse_available_memory(&std::fs::read_to_string("/proc/meminfo").ok()?)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn available_memory_byte() -> Option<u64> {
+    None
+}
+
+/// Parse the 'MemAvailable' field of /proc/meminfo, which is given in kibibytes
+#[cfg(target_os = "linux")]
+fn parse_available_memory(meminfo: &str) -> Option<u64> {
+    let value = meminfo
+        .lines()
+        .find_map(|line| line.strip_prefix("MemAvailable:"))?;
+    let kib: u64 = value.trim().strip_suffix("kB")?.trim().parse().ok()?;
+    Some(kib * 1024)
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_parse_available_memory() {
+    let meminfo = "MemTotal:       16303412 kB\n\
+                   MemFree:         9253124 kB\n\
+                   MemAvailable:   12825996 kB\n\
+                   Buffers:          321964 kB\n";
+    assert_eq!(parse_available_memory(meminfo), Some(12825996 * 1024));
+    assert_eq!(parse_available_memory("MemTotal: 16303412 kB\n"), None);
+}
@@ -31,6 +31,15 @@ impl Unit {
             Unit::MicroSecond => format!("{:.1}", value * 1e6),
         }
     }
+
+    /// Returns the Second value converted to the Unit.
+    pub fn convert(self, value: Second) -> f64 {
+        match self {
+            Unit::Second => value,
+            Unit::MilliSecond => value * 1e3,
+            Unit::MicroSecond => value * 1e6,
+        }
+    }
 }
 
 #[test]
@@ -40,6 +49,14 @@ fn test_unit_short_name() {
     assert_eq!("µs", Unit::MicroSecond.short_name());
 }
 
+#[test]
+fn test_unit_convert() {
+    let value: Second = 1.5;
+    assert_eq!(1.5, Unit::Second.convert(value));
+    assert_eq!(1500.0, Unit::MilliSecond.convert(value));
+    assert_eq!(1500000.0, Unit::MicroSecond.convert(value));
+}
+
 // Note - the values are rounded when formatted.
 #[test]
 fn test_unit_format() {
@@ -39,6 +39,255 @@ fn can_run_commands_without_a_shell() {
         .stdout(predicate::str::contains("hello world argument2"));
 }
 
+#[test]
+fn runs_commands_in_batches() {
+    hyperfine()
+        .arg("--runs=2")
+        .arg("--batch=3")
+        .arg("--show-output")
+        .arg("echo batched")
+        .assert()
+        .success()
+        .stdout(predicate::function(|output: &str| {
+            output.lines().filter(|line| *line == "batched").count() == 6
+        }));
+}
+
+#[test]
+fn stops_a_batch_at_the_first_failing_iteration() {
+    hyperfine()
+        .arg("--runs=2")
+        .arg("--batch=3")
+        .arg("--show-output")
+        .arg("echo iteration; exit 2")
+        .assert()
+        .failure()
+        .stdout(predicate::function(|output: &str| {
+            output.lines().filter(|line| *line == "iteration").count() == 1
+        }))
+        .stderr(predicate::str::contains(
+            "Command terminated with non-zero exit code: 2",
+        ));
+}
+
+#[test]
+fn fails_with_batches_without_a_shell() {
+    hyperfine()
+        .arg("--batch=3")
+        .arg("--shell=none")
+        .arg("echo")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "'--batch' runs the command in a loop",
+        ));
+
+    hyperfine()
+        .arg("--batch=0")
+        .arg("echo")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "The argument to '--batch' has to be at least 1",
+        ));
+}
+
+#[test]
+fn reads_commands_from_a_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let command_file = dir.path().join("benchmarks.txt");
+    std::fs::write(
+        &command_file,
+        "# Suite\nfirst: sleep 0.1\n\n: sleep 0.2\nsleep 0.3\n",
+    )
+    .unwrap();
+
+    hyperfine_debug()
+        .arg("--command-file")
+        .arg(&command_file)
+        .arg("--command-name=zero")
+        .arg("sleep 0.4")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Benchmark 1: zero"))
+        .stdout(predicate::str::contains("Benchmark 2: first"))
+        .stdout(predicate::str::contains("Benchmark 3: sleep 0.2"))
+        .stdout(predicate::str::contains("Benchmark 4: sleep 0.3"));
+
+    std::fs::write(&command_file, "# Nothing to see here\n").unwrap();
+    hyperfine_debug()
+        .arg("--command-file")
+        .arg(&command_file)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("does not contain any commands"));
+}
+
+#[test]
+fn reads_commands_from_stdin() {
+    hyperfine_debug()
+        .arg("--commands-from")
+        .arg("-")
+        .write_stdin("sleep 0.1\nshort: sleep 0.2\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Benchmark 1: sleep 0.1"))
+        .stdout(predicate::str::contains("Benchmark 2: short"));
+}
+
+#[test]
+fn reads_parameter_values_from_a_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let parameter_file = dir.path().join("values.txt");
+    std::fs::write(&parameter_file, "hello, world\n\nfoo bar\n").unwrap();
+
+    hyperfine()
+        .arg("--runs=1")
+        .arg("--show-output")
+        .arg("--parameter-file")
+        .arg("message")
+        .arg(&parameter_file)
+        .arg("echo 'message: {message}'")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("message: hello, world\n"))
+        .stdout(predicate::str::contains("message: foo bar\n"));
+}
+
+#[test]
+#[cfg(unix)]
+fn runs_commands_in_their_working_directories() {
+    let dir = tempfile::tempdir().unwrap();
+    for checkout in ["old", "new"] {
+        std::fs::create_dir(dir.path().join(checkout)).unwrap();
+        std::fs::write(dir.path().join(checkout).join("version"), checkout).unwrap();
+    }
+
+    hyperfine()
+        .current_dir(dir.path())
+        .arg("--runs=1")
+        .arg("--show-output")
+        .arg("--parameter-list")
+        .arg("checkout")
+        .arg("old,new")
+        .arg("--working-directory={checkout}")
+        .arg("--prepare=cat version")
+        .arg("echo \"-$(cat version)-\"")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("old-old-\n"))
+        .stdout(predicate::str::contains("new-new-\n"));
+
+    hyperfine()
+        .current_dir(dir.path())
+        .arg("--runs=1")
+        .arg("--working-directory=old")
+        .arg("--working-directory=new")
+        .arg("true")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "The '--working-directory' option has to be provided just once or N times",
+        ));
+
+    hyperfine()
+        .current_dir(dir.path())
+        .arg("--runs=1")
+        .arg("--working-directory=missing")
+        .arg("true")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "The working directory 'missing' of the command 'true' does not exist",
+        ));
+}
+
+#[test]
+#[cfg(unix)]
+fn matches_working_directories_to_the_commands_without_the_reference() {
+    let dir = tempfile::tempdir().unwrap();
+    for checkout in ["old", "new"] {
+        std::fs::create_dir(dir.path().join(checkout)).unwrap();
+        std::fs::write(dir.path().join(checkout).join("version"), checkout).unwrap();
+    }
+    std::fs::write(dir.path().join("version"), "base").unwrap();
+
+    let output = hyperfine()
+        .current_dir(dir.path())
+        .arg("--runs=1")
+        .arg("--show-output")
+        .arg("--reference=echo \"reference-$(cat version)\"")
+        .arg("--working-directory=old")
+        .arg("--working-directory=new")
+        .arg("echo \"first-$(cat version)\"")
+        .arg("echo \"second-$(cat version)\"")
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let output = String::from_utf8(output).unwrap();
+    let lines: Vec<_> = output.lines().collect();
+
+    assert!(lines.contains(&"reference-base"));
+    assert!(lines.contains(&"first-old"));
+    assert!(lines.contains(&"second-new"));
+
+    hyperfine()
+        .current_dir(dir.path())
+        .arg("--runs=1")
+        .arg("--show-output")
+        .arg("--reference=echo \"reference-$(cat version)\"")
+        .arg("--working-directory=old")
+        .arg("echo \"first-$(cat version)\"")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("reference-old\n"))
+        .stdout(predicate::str::contains("first-old\n"));
+}
+
+#[test]
+#[cfg(unix)]
+fn sets_environment_variables() {
+    let dir = tempfile::tempdir().unwrap();
+    let env_file = dir.path().join("knobs.env");
+    std::fs::write(&env_file, "# Knobs\nFIRST=from-file\nSECOND=from-file\n").unwrap();
+
+    hyperfine()
+        .arg("--runs=1")
+        .arg("--show-output")
+        .arg("--shell=none")
+        .arg("--parameter-list")
+        .arg("level")
+        .arg("debug,trace")
+        .arg("--env-file")
+        .arg(&env_file)
+        .arg("--env=SECOND=level-{level}")
+        .arg("sh -c 'echo \"[$FIRST $SECOND]\"'")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("[from-file level-debug]"))
+        .stdout(predicate::str::contains("[from-file level-trace]"));
+}
+
+#[test]
+#[cfg(unix)]
+fn runs_commands_with_a_clear_environment() {
+    hyperfine()
+        .env("HYPERFINE_TEST_INHERITED", "1")
+        .arg("--runs=1")
+        .arg("--show-output")
+        .arg("--shell=none")
+        .arg("--env-clear")
+        .arg("--env=HYPERFINE_TEST_GIVEN=1")
+        .arg("printenv")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\nPATH="))
+        .stdout(predicate::str::contains("\nHYPERFINE_TEST_GIVEN=1\n"))
+        .stdout(predicate::str::contains("HYPERFINE_TEST_INHERITED").not());
+}
+
 #[test]
 fn fails_with_wrong_number_of_command_name_arguments() {
     hyperfine()
@@ -273,137 +522,1238 @@ fn returns_mean_time_in_correct_unit() {
 }
 
 #[test]
-fn performs_ten_runs_for_slow_commands() {
+fn shows_percentiles() {
     hyperfine_debug()
+        .arg("--percentiles=50,99.9")
+        .arg("sleep 0.123")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Percentiles:         p50: 123.0 ms, p99.9: 123.0 ms",
+        ));
+
+    hyperfine_debug()
+        .arg("--percentiles=50,101")
+        .arg("sleep 0.123")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Percentiles have to be numbers between 0 and 100, got '101'",
+        ));
+}
+
+#[test]
+fn reports_trimmed_mean() {
+    hyperfine_debug()
+        .arg("--trim=10")
         .arg("sleep 0.5")
         .assert()
         .success()
-        .stdout(predicate::str::contains("10 runs"));
+        .stdout(predicate::str::contains("runs (10% trimmed mean)"));
+
+    hyperfine_debug()
+        .arg("--trim=10")
+        .arg("--winsorize")
+        .arg("sleep 0.5")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("runs (10% winsorized mean)"));
+
+    hyperfine_debug()
+        .arg("--trim=50")
+        .arg("sleep 0.5")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "The argument to '--trim' has to be a percentage between 0 and 50, got '50'",
+        ));
 }
 
 #[test]
-fn performs_three_seconds_of_benchmarking_for_fast_commands() {
+fn shows_histogram() {
     hyperfine_debug()
-        .arg("sleep 0.01")
+        .arg("--histogram")
+        .arg("sleep 0.5")
         .assert()
         .success()
-        .stdout(predicate::str::contains("300 runs"));
+        .stdout(predicate::str::contains(
+            "Histogram:           500.0 ms █ 500.0 ms",
+        ));
 }
 
 #[test]
-fn takes_shell_spawning_time_into_account_for_computing_number_of_runs() {
+fn shows_sparkline() {
     hyperfine_debug()
-        .arg("--shell=sleep 0.02")
-        .arg("sleep 0.01")
+        .arg("--sparkline")
+        .arg("--runs=3")
+        .arg("sleep 0.5")
         .assert()
         .success()
-        .stdout(predicate::str::contains("100 runs"));
+        .stdout(predicate::str::contains("Runs:                ▁▁▁"));
 }
 
 #[test]
-fn takes_preparation_command_into_account_for_computing_number_of_runs() {
+fn shows_standard_error_of_the_mean() {
     hyperfine_debug()
-        .arg("--prepare=sleep 0.02")
-        .arg("sleep 0.01")
+        .arg("--show-sem")
+        .arg("sleep 0.1")
         .assert()
         .success()
-        .stdout(predicate::str::contains("100 runs"));
+        .stdout(predicate::str::contains("Std. error (SEM):     0.0 ms"));
+}
 
-    // Shell overhead needs to be added to both the prepare command and the actual command,
-    // leading to a total benchmark time of (prepare + shell + cmd + shell = 0.1 s)
+#[test]
+fn shows_bootstrap_confidence_intervals() {
     hyperfine_debug()
-        .arg("--shell=sleep 0.01")
-        .arg("--prepare=sleep 0.03")
-        .arg("sleep 0.05")
+        .arg("--bootstrap")
+        .arg("sleep 1")
+        .arg("sleep 2")
         .assert()
         .success()
-        .stdout(predicate::str::contains("30 runs"));
+        .stdout(predicate::str::contains(
+            "Mean (95% CI):         [1.000 s, 1.000 s]",
+        ))
+        .stdout(predicate::str::contains(
+            "2.00 ± 0.00 [2.00, 2.00] times faster",
+        ));
 }
 
 #[test]
-fn shows_benchmark_comparison_with_relative_times() {
+fn shows_relative_speed_confidence_intervals() {
     hyperfine_debug()
-        .arg("sleep 1.0")
-        .arg("sleep 2.0")
-        .arg("sleep 3.0")
+        .arg("--relative-speed-ci")
+        .arg("sleep 1")
+        .arg("sleep 2")
         .assert()
         .success()
-        .stdout(
-            predicate::str::contains("2.00 ± 0.00 times faster")
-                .and(predicate::str::contains("3.00 ± 0.00 times faster")),
-        );
+        .stdout(predicate::str::contains(
+            "2.00 ± 0.00 [2.00, 2.00] times faster",
+        ));
 }
 
 #[test]
-fn performs_all_benchmarks_in_parameter_scan() {
+fn shows_the_comparison_matrix() {
     hyperfine_debug()
-        .arg("--parameter-scan")
-        .arg("time")
-        .arg("30")
-        .arg("45")
-        .arg("--parameter-step-size")
-        .arg("5")
-        .arg("sleep {time}")
+        .arg("--comparison-matrix")
+        .arg("sleep 1")
+        .arg("sleep 2")
+        .arg("sleep 4")
         .assert()
         .success()
-        .stdout(
-            predicate::str::contains("Benchmark 1: sleep 30")
-                .and(predicate::str::contains("Benchmark 2: sleep 35"))
-                .and(predicate::str::contains("Benchmark 3: sleep 40"))
-                .and(predicate::str::contains("Benchmark 4: sleep 45"))
-                .and(predicate::str::contains("Benchmark 5: sleep 50").not()),
-        );
+        .stdout(predicate::str::contains(
+            "  #2     0.50       —    2.00   sleep 2",
+        ));
+
+    hyperfine_debug()
+        .arg("--style=none")
+        .arg("--comparison-matrix")
+        .arg("--export-json=-")
+        .arg("sleep 1")
+        .arg("sleep 2")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"comparison_matrix\": {"));
 }
 
 #[test]
-fn intermediate_results_are_not_exported_to_stdout() {
+fn shows_the_probability_of_being_faster() {
     hyperfine_debug()
-        .arg("--style=none") // To only see the Markdown export on stdout
-        .arg("--export-markdown")
-        .arg("-")
+        .arg("--probability-faster")
         .arg("sleep 1")
         .arg("sleep 2")
         .assert()
         .success()
-        .stdout(
-            (predicate::str::contains("sleep 1").count(1))
-                .and(predicate::str::contains("sleep 2").count(1)),
-        );
+        .stdout(predicate::str::contains(
+            "times faster than sleep 2\n          faster with a probability of 100.0%",
+        ));
 }
 
 #[test]
-#[cfg(unix)]
-fn exports_intermediate_results_to_file() {
+fn lists_outlier_runs() {
     use tempfile::tempdir;
 
+    // Only the first run is slow
     let tempdir = tempdir().unwrap();
-    let export_path = tempdir.path().join("results.md");
+    let marker = tempdir.path().join("marker");
+    let command = format!(
+        "test -e '{0}' || (touch '{0}'; sleep 0.5)",
+        marker.to_str().unwrap()
+    );
 
     hyperfine()
-        .arg("--runs=1")
-        .arg("--export-markdown")
-        .arg(&export_path)
-        .arg("true")
-        .arg("false")
+        .arg("--show-outliers")
+        .arg("--runs=10")
+        .arg(command)
         .assert()
-        .failure();
-
-    let contents = std::fs::read_to_string(export_path).unwrap();
-    assert!(contents.contains("true"));
+        .success()
+        .stdout(predicate::str::contains("Outliers:"))
+        .stdout(predicate::str::contains("run    1:"));
 }
 
 #[test]
-fn unused_parameters_are_shown_in_benchmark_name() {
-    hyperfine()
-        .arg("--runs=2")
-        .arg("--parameter-list")
-        .arg("branch")
-        .arg("master,feature")
-        .arg("echo test")
+fn uses_the_given_confidence_level() {
+    hyperfine_debug()
+        .arg("--confidence=0.99")
+        .arg("--bootstrap")
+        .arg("sleep 1")
         .assert()
         .success()
-        .stdout(
-            predicate::str::contains("echo test (branch = master)")
+        .stdout(predicate::str::contains("Mean (99% CI):"));
+
+    hyperfine()
+        .arg("--confidence=1.5")
+        .arg("echo a")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "The confidence level has to be a number between 0 and 1",
+        ));
+}
+
+#[test]
+fn performs_warmup_runs_until_run_times_are_stable() {
+    hyperfine_debug()
+        .arg("--warmup")
+        .arg("auto")
+        .arg("sleep 1")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("10 runs (after 6 warmup runs)"));
+}
+
+#[test]
+fn benchmarks_with_cold_and_warm_caches() {
+    hyperfine_debug()
+        .arg("--cold-prepare")
+        .arg("sleep 0.5")
+        .arg("sleep 0.1")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Benchmark 1: sleep 0.1 (cache = cold)",
+        ))
+        .stdout(predicate::str::contains("10 runs"))
+        .stdout(predicate::str::contains(
+            "Benchmark 2: sleep 0.1 (cache = warm)",
+        ))
+        .stdout(predicate::str::contains("30 runs"));
+}
+
+#[test]
+fn stops_benchmarking_after_max_time() {
+    hyperfine_debug()
+        .arg("--max-time")
+        .arg("2")
+        .arg("--min-runs")
+        .arg("3")
+        .arg("sleep 0.25")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "8 runs (stopped after --max-time)",
+        ));
+
+    // At least --min-runs runs are performed
+    hyperfine_debug()
+        .arg("--max-time")
+        .arg("2")
+        .arg("sleep 0.25")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "10 runs (stopped after --max-time)",
+        ));
+}
+
+#[test]
+fn reports_required_number_of_runs() {
+    hyperfine_debug()
+        .arg("--power-analysis")
+        .arg("2")
+        .arg("sleep 0.1")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Power analysis:      ~2 runs needed to detect a 2% difference (α = 0.05, power = 80%)",
+        ));
+}
+
+#[test]
+fn performs_ten_runs_for_slow_commands() {
+    hyperfine_debug()
+        .arg("sleep 0.5")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("10 runs"));
+}
+
+#[test]
+fn performs_three_seconds_of_benchmarking_for_fast_commands() {
+    hyperfine_debug()
+        .arg("sleep 0.01")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("300 runs"));
+}
+
+#[test]
+fn takes_shell_spawning_time_into_account_for_computing_number_of_runs() {
+    hyperfine_debug()
+        .arg("--shell=sleep 0.02")
+        .arg("sleep 0.01")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("100 runs"));
+}
+
+#[test]
+fn takes_preparation_command_into_account_for_computing_number_of_runs() {
+    hyperfine_debug()
+        .arg("--prepare=sleep 0.02")
+        .arg("sleep 0.01")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("100 runs"));
+
+    // Shell overhead needs to be added to both the prepare command and the actual command,
+    // leading to a total benchmark time of (prepare + shell + cmd + shell = 0.1 s)
+    hyperfine_debug()
+        .arg("--shell=sleep 0.01")
+        .arg("--prepare=sleep 0.03")
+        .arg("sleep 0.05")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("30 runs"));
+}
+
+#[test]
+fn shows_benchmark_comparison_with_relative_times() {
+    hyperfine_debug()
+        .arg("sleep 1.0")
+        .arg("sleep 2.0")
+        .arg("sleep 3.0")
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("2.00 ± 0.00 times faster")
+                .and(predicate::str::contains("3.00 ± 0.00 times faster")),
+        );
+}
+
+#[test]
+fn fails_for_invalid_outlier_threshold() {
+    hyperfine_debug()
+        .arg("--outlier-method=grubbs")
+        .arg("--outlier-threshold=2")
+        .arg("sleep 0.1")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "The outlier threshold has to be a positive number (and smaller than 1 for 'grubbs'), got '2'",
+        ));
+
+    hyperfine_debug()
+        .arg("--outlier-method=iqr")
+        .arg("--outlier-threshold=1.5")
+        .arg("sleep 0.1")
+        .assert()
+        .success();
+}
+
+#[test]
+fn stops_when_relative_stddev_is_reached() {
+    hyperfine_debug()
+        .arg("--max-relative-stddev=1")
+        .arg("--max-runs=20")
+        .arg("sleep 1")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("10 runs"));
+
+    hyperfine_debug()
+        .arg("--max-relative-stddev=-1")
+        .arg("--max-runs=20")
+        .arg("sleep 0.1")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "The argument to '--max-relative-stddev' has to be a positive percentage, got '-1'",
+        ));
+}
+
+#[test]
+fn stops_when_confidence_interval_is_narrow_enough() {
+    hyperfine_debug()
+        .arg("--max-ci-width=1")
+        .arg("--max-runs=20")
+        .arg("sleep 1")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("10 runs"));
+}
+
+#[test]
+fn fails_for_invalid_significance_level() {
+    hyperfine_debug()
+        .arg("--significance-level=1.5")
+        .arg("sleep 0.1")
+        .arg("sleep 0.2")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "The significance level has to be a number between 0 and 1, got '1.5'",
+        ));
+}
+
+#[test]
+fn states_the_significance_of_the_differences_in_the_summary() {
+    hyperfine()
+        .arg("--shell=none")
+        .arg("--runs=5")
+        .arg("sleep 0.01")
+        .arg("sleep 0.05")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "times faster than sleep 0.05 (p < 0.001)\n",
+        ));
+
+    // With two runs per command, the smallest p-value of the U test is 0.245
+    hyperfine()
+        .arg("--shell=none")
+        .arg("--runs=2")
+        .arg("--significance-test=mann-whitney")
+        .arg("sleep 0.01")
+        .arg("sleep 0.05")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "times faster than sleep 0.05 (p = 0.245, not significant at α = 0.05)\n",
+        ));
+
+    hyperfine()
+        .arg("--shell=none")
+        .arg("--runs=2")
+        .arg("--significance-test=mann-whitney")
+        .arg("--significance-level=0.3")
+        .arg("sleep 0.01")
+        .arg("sleep 0.05")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "times faster than sleep 0.05 (p = 0.245)\n",
+        ));
+}
+
+#[test]
+fn sorts_summary_alphabetically() {
+    hyperfine_debug()
+        .arg("--sort-by=command")
+        .arg("sleep 3")
+        .arg("sleep 1")
+        .arg("sleep 2")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Relative speed comparison
+        1.00          sleep 1
+        2.00 ±  0.00  sleep 2
+        3.00 ±  0.00  sleep 3",
+        ));
+}
+
+#[test]
+fn compares_to_reference_command() {
+    hyperfine_debug()
+        .arg("--reference=sleep 2")
+        .arg("sleep 1")
+        .arg("sleep 4")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("Benchmark 1: sleep 2"))
+        .stdout(predicate::str::contains("sleep 2 ran"))
+        .stdout(predicate::str::contains(
+            "2.00 ± 0.00 times slower than sleep 1",
+        ))
+        .stdout(predicate::str::contains(
+            "2.00 ± 0.00 times faster than sleep 4",
+        ));
+}
+
+#[test]
+fn compares_by_relative_speed_metric() {
+    hyperfine_debug()
+        .arg("--relative-speed-metric=median")
+        .arg("sleep 1")
+        .arg("sleep 3")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "sleep 1 ran (comparing median times)",
+        ))
+        .stdout(predicate::str::contains("3.00 times faster than sleep 3"));
+}
+
+#[test]
+fn shows_geometric_mean_of_relative_speeds() {
+    hyperfine_debug()
+        .arg("--geometric-mean")
+        .arg("--parameter-list")
+        .arg("time")
+        .arg("1,4")
+        .arg("sleep {time}")
+        .arg("sleep 2")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Geometric mean of relative speeds across all parameter values",
+        ))
+        .stdout(predicate::str::contains("1.00  sleep {time}"))
+        .stdout(predicate::str::contains("1.00  sleep 2"));
+}
+
+#[test]
+fn reports_throughput() {
+    hyperfine_debug()
+        .arg("--throughput")
+        .arg("bytes=2000000")
+        .arg("sleep 0.1")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Throughput:          20.0 MB/s ± 0.0 MB/s",
+        ));
+
+    hyperfine_debug()
+        .arg("--throughput")
+        .arg("items")
+        .arg("--parameter-list")
+        .arg("n")
+        .arg("300")
+        .arg("sleep 0.1")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Throughput:          3.0k items/s",
+        ));
+
+    hyperfine_debug()
+        .arg("--throughput")
+        .arg("items")
+        .arg("sleep 0.1")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "The throughput amount has to be specified",
+        ));
+}
+
+#[test]
+fn fits_complexity_of_parameter_scans() {
+    hyperfine_debug()
+        .arg("--complexity")
+        .arg("--parameter-scan")
+        .arg("n")
+        .arg("1")
+        .arg("4")
+        .arg("sleep {n}")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "O(n)  sleep {n}  (≈ 1.000 s · n, R² = 1.000)",
+        ));
+}
+
+#[test]
+fn performs_all_benchmarks_in_parameter_scan() {
+    hyperfine_debug()
+        .arg("--parameter-scan")
+        .arg("time")
+        .arg("30")
+        .arg("45")
+        .arg("--parameter-step-size")
+        .arg("5")
+        .arg("sleep {time}")
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("Benchmark 1: sleep 30")
+                .and(predicate::str::contains("Benchmark 2: sleep 35"))
+                .and(predicate::str::contains("Benchmark 3: sleep 40"))
+                .and(predicate::str::contains("Benchmark 4: sleep 45"))
+                .and(predicate::str::contains("Benchmark 5: sleep 50").not()),
+        );
+}
+
+#[test]
+fn intermediate_results_are_not_exported_to_stdout() {
+    hyperfine_debug()
+        .arg("--style=none") // To only see the Markdown export on stdout
+        .arg("--export-markdown")
+        .arg("-")
+        .arg("sleep 1")
+        .arg("sleep 2")
+        .assert()
+        .success()
+        .stdout(
+            (predicate::str::contains("sleep 1").count(1))
+                .and(predicate::str::contains("sleep 2").count(1)),
+        );
+}
+
+#[test]
+fn regular_output_goes_to_stderr_when_exporting_to_stdout() {
+    hyperfine_debug()
+        .arg("--export-json")
+        .arg("-")
+        .arg("sleep 1")
+        .arg("sleep 2")
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::starts_with("{")
+                .and(predicate::str::contains("Benchmark 1").not())
+                .and(predicate::str::contains("Summary").not()),
+        )
+        .stderr(
+            predicate::str::contains("Benchmark 1: sleep 1")
+                .and(predicate::str::contains("Summary")),
+        );
+}
+
+#[test]
+#[cfg(unix)]
+fn exports_intermediate_results_to_file() {
+    use tempfile::tempdir;
+
+    let tempdir = tempdir().unwrap();
+    let export_path = tempdir.path().join("results.md");
+
+    hyperfine()
+        .arg("--runs=1")
+        .arg("--export-markdown")
+        .arg(&export_path)
+        .arg("true")
+        .arg("false")
+        .assert()
+        .failure();
+
+    let contents = std::fs::read_to_string(export_path).unwrap();
+    assert!(contents.contains("true"));
+}
+
+#[test]
+fn exports_individual_runs_as_ndjson() {
+    use tempfile::tempdir;
+
+    let tempdir = tempdir().unwrap();
+    let export_path = tempdir.path().join("runs.ndjson");
+
+    hyperfine_debug()
+        .arg("--runs=3")
+        .arg("--export-ndjson")
+        .arg(&export_path)
+        .arg("sleep 1")
+        .arg("sleep 2")
+        .assert()
+        .success();
+
+    let contents = std::fs::read_to_string(export_path).unwrap();
+    let lines: Vec<&str> = contents.lines().collect();
+    assert_eq!(lines.len(), 6);
+    assert!(lines[0].starts_with(r#"{"command":"sleep 1","run":0,"time":1.0,"#));
+    assert!(lines[5].starts_with(r#"{"command":"sleep 2","run":2,"time":2.0,"#));
+}
+
+#[test]
+fn measures_memory_usage() {
+    hyperfine()
+        .arg("--runs=2")
+        .arg("--export-json=-")
+        .arg("echo a")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Memory (max RSS):"))
+        .stdout(predicate::str::contains("\"memory_usage_byte\": ["));
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn memory_usage_excludes_hyperfine() {
+    let dir = tempfile::tempdir().unwrap();
+    let export_file = dir.path().join("results.json");
+
+    // The parent of the shell is hyperfine
+    let output = hyperfine()
+        .arg("--runs=2")
+        .arg("--show-output")
+        .arg("--export-json")
+        .arg(&export_file)
+        .arg("grep VmHWM /proc/$PPID/status")
+        .arg("sleep 0.01")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let hyperfine_memory_usage_byte = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.strip_prefix("VmHWM:")?.trim().strip_suffix(" kB"))
+        .map(|size| size.trim().parse::<u64>().unwrap() * 1024)
+        .min()
+        .unwrap();
+
+    let json: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(&export_file).unwrap()).unwrap();
+    for memory_usage_byte in json["results"][1]["memory_usage_byte"].as_array().unwrap() {
+        assert!(
+            memory_usage_byte.as_u64().unwrap() < hyperfine_memory_usage_byte,
+            "{} >= {}",
+            memory_usage_byte,
+            hyperfine_memory_usage_byte
+        );
+    }
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn measures_io_counters() {
+    hyperfine()
+        .arg("--runs=2")
+        .arg("--show-resource-usage")
+        .arg("--export-json=-")
+        .arg("head -c 1000000 /dev/zero")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("I/O (mean):"))
+        .stdout(predicate::str::contains("\"io_read_byte\": ["))
+        .stdout(predicate::str::contains("\"io_write_byte\": ["));
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn exports_system_state_of_every_run() {
+    hyperfine()
+        .arg("--runs=2")
+        .arg("--export-json=-")
+        .arg("echo a")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"load_average\": ["))
+        .stdout(predicate::str::contains("\"available_memory_byte\": ["));
+}
+
+#[test]
+#[cfg(unix)]
+fn measures_context_switches() {
+    hyperfine()
+        .arg("--runs=2")
+        .arg("--show-resource-usage")
+        .arg("--export-json=-")
+        .arg("echo a")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Context switches:"))
+        .stdout(predicate::str::contains(
+            "\"voluntary_context_switches\": [",
+        ))
+        .stdout(predicate::str::contains(
+            "\"involuntary_context_switches\": [",
+        ));
+}
+
+#[test]
+#[cfg(unix)]
+fn measures_page_faults() {
+    hyperfine()
+        .arg("--runs=2")
+        .arg("--show-resource-usage")
+        .arg("--export-json=-")
+        .arg("echo a")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Page faults:"))
+        .stdout(predicate::str::contains("\"minor_page_faults\": ["))
+        .stdout(predicate::str::contains("\"major_page_faults\": ["));
+}
+
+#[test]
+fn fails_with_unknown_perf_counter() {
+    hyperfine_debug()
+        .arg("--perf-counters=cycles,frobnications")
+        .arg("echo a")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "invalid value 'frobnications' for '--perf-counters <LIST>'",
+        ));
+
+    hyperfine_debug()
+        .arg("--no-perf-counters-children")
+        .arg("echo a")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "the following required arguments were not provided",
+        ));
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn accepts_syscall_counter_in_debug_mode() {
+    // The tracepoint is usually only accessible for root, and not used in debug mode
+    hyperfine_debug()
+        .arg("--perf-counters=syscalls")
+        .arg("sleep 1")
+        .assert()
+        .success();
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn counts_only_the_syscalls_of_the_benchmarked_command() {
+    let min_syscalls = |extra_args: &[&str]| {
+        let output = hyperfine()
+            .arg("--shell=none")
+            .arg("--runs=3")
+            .arg("--perf-counters=syscalls")
+            .args(extra_args)
+            .arg("--export-json=-")
+            .arg("sleep 0.1")
+            .output()
+            .unwrap();
+        // The tracepoint usually requires root privileges, and the CPU frequency is not
+        // available in most virtual machines
+        if !output.status.success() {
+            return None;
+        }
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        json["results"][0]["perf_counters"]["syscalls"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|count| count.as_u64().unwrap())
+            .min()
+    };
+
+    // The threads that sample the CPU frequency are not included
+    if let (Some(without_sampler), Some(with_sampler)) =
+        (min_syscalls(&[]), min_syscalls(&["--cpu-frequency"]))
+    {
+        assert_eq!(without_sampler, with_sampler);
+    }
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn runs_commands_without_network_access() {
+    // Only the loopback interface is listed in the network namespace
+    hyperfine()
+        .arg("--runs=1")
+        .arg("--isolate-network")
+        .arg("--show-output")
+        .arg("grep -c : /proc/net/dev")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("/proc/net/dev\n1\n"));
+}
+
+#[test]
+#[cfg(unix)]
+fn writes_dirty_pages_before_each_run() {
+    hyperfine()
+        .arg("--runs=2")
+        .arg("--sync-before-run")
+        .arg("--prepare=echo prepare")
+        .arg("echo run")
+        .assert()
+        .success();
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn fails_if_profiler_is_not_available() {
+    let dir = tempfile::tempdir().unwrap();
+    hyperfine()
+        .env("PATH", "")
+        .arg("--runs=2")
+        .arg("--shell=none")
+        .arg("--profile")
+        .arg(dir.path())
+        .arg("/bin/true")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Could not run 'perf'"));
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn accepts_gpu_in_debug_mode() {
+    // Most CI machines do not have an NVIDIA GPU, and it is not sampled in debug mode
+    hyperfine_debug()
+        .arg("--gpu")
+        .arg("sleep 1")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("GPU (mean):").not());
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn accepts_measure_energy_in_debug_mode() {
+    // The energy counters are not available on most CI machines, and not used in debug mode
+    hyperfine_debug()
+        .arg("--measure-energy")
+        .arg("sleep 1")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Energy (CPU):").not());
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn accepts_cpu_frequency_in_debug_mode() {
+    // CPU frequency scaling is not available on most CI machines, and not used in debug mode
+    hyperfine_debug()
+        .arg("--cpu-frequency")
+        .arg("sleep 1")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("CPU frequency:").not());
+}
+
+#[test]
+fn fails_with_invalid_cgroup_limits() {
+    hyperfine_debug()
+        .arg("--limit-memory=1X")
+        .arg("echo a")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "The argument to '--limit-memory' has to be a positive size",
+        ));
+
+    hyperfine_debug()
+        .arg("--limit-cpus=0")
+        .arg("echo a")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "The argument to '--limit-cpus' has to be a positive number of CPUs, got '0'",
+        ));
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn counts_the_created_processes_with_cgroup() {
+    let assert = hyperfine()
+        .arg("--cgroup")
+        .arg("--shell=none")
+        .arg("--runs=2")
+        .arg("--export-json=-")
+        .arg("sh -c 'true & true & wait'")
+        .assert();
+
+    // Creating a cgroup usually requires root privileges
+    let output = assert.get_output();
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("cgroup"), "{}", stderr);
+        return;
+    }
+
+    // The shell and its two children
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        json["results"][0]["total_processes"],
+        serde_json::json!([3, 3])
+    );
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn waits_for_all_descendants_with_aggregate_children() {
+    use tempfile::tempdir;
+
+    // The background process is not waited for by the shell
+    let tempdir = tempdir().unwrap();
+    let marker = tempdir.path().join("marker");
+    let command = format!("(sleep 0.2; touch '{}') &", marker.to_str().unwrap());
+
+    hyperfine()
+        .arg("--aggregate-children")
+        .arg("--runs=1")
+        .arg(command)
+        .assert()
+        .success();
+
+    assert!(marker.exists());
+}
+
+#[test]
+fn accepts_drop_caches_in_debug_mode() {
+    // Dropping the caches requires root privileges, and is skipped in debug mode
+    hyperfine_debug()
+        .arg("--drop-caches=before-each-run")
+        .arg("sleep 1")
+        .assert()
+        .success();
+
+    hyperfine_debug()
+        .arg("--drop-caches=sometimes")
+        .arg("sleep 1")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid value 'sometimes'"));
+}
+
+#[test]
+fn fails_with_invalid_priority() {
+    hyperfine_debug()
+        .arg("--priority=20")
+        .arg("echo a")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "has to be a nice level between -20 and 19, got '20'",
+        ));
+}
+
+#[cfg(unix)]
+#[test]
+fn runs_commands_with_priority() {
+    // 'nice' without arguments prints the current nice level
+    hyperfine()
+        .arg("--priority=7")
+        .arg("--runs=1")
+        .arg("--show-output")
+        .arg("nice")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\n7\n"));
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn runs_commands_without_aslr() {
+    hyperfine()
+        .arg("--no-aslr")
+        .arg("--runs=1")
+        .arg("--show-output")
+        .arg("cat /proc/self/personality")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("00040000"));
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn measures_wall_clock_time_with_monotonic_raw_clock() {
+    hyperfine()
+        .arg("--clock=monotonic-raw")
+        .arg("--runs=2")
+        .arg("--style=basic")
+        .arg("--time-unit=millisecond")
+        .arg("sleep 0.3")
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r"Time \(mean ± σ\): +3\d\d\.\d ms").unwrap());
+}
+
+#[cfg(unix)]
+#[test]
+fn measures_cpu_time_with_process_cputime_clock() {
+    // 'sleep' does not use any notable CPU time
+    hyperfine()
+        .arg("--clock=process-cputime")
+        .arg("--runs=2")
+        .arg("--style=basic")
+        .arg("--time-unit=millisecond")
+        .arg("sleep 0.3")
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r"Time \(mean ± σ\): +\d{1,2}\.\d ms").unwrap());
+}
+
+#[test]
+fn fails_with_invalid_cpu_list() {
+    hyperfine_debug()
+        .arg("--cpu=3-1")
+        .arg("echo a")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid list of CPUs '3-1'"));
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn pins_commands_to_cpus() {
+    hyperfine()
+        .arg("--cpu=0")
+        .arg("--runs=1")
+        .arg("--show-output")
+        .arg("grep Cpus_allowed_list /proc/self/status")
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r"Cpus_allowed_list:\s+0\n").unwrap());
+}
+
+#[cfg(unix)]
+#[test]
+fn measures_time_to_first_output() {
+    hyperfine()
+        .arg("--runs=2")
+        .arg("--time-to-first-output")
+        .arg("--export-json=-")
+        .arg("echo a; sleep 0.1")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("First output:"))
+        .stdout(predicate::str::contains("\"time_to_first_output\": ["));
+
+    hyperfine()
+        .arg("--runs=2")
+        .arg("--time-to-first-output")
+        .arg("true")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "did not write anything to stdout in some of the runs",
+        ));
+}
+
+#[test]
+fn shows_cpu_utilization() {
+    // Without a shell, the mean time can not become zero by subtracting the shell spawning time
+    hyperfine()
+        .arg("--runs=2")
+        .arg("--shell=none")
+        .arg("--show-resource-usage")
+        .arg("--export-json=-")
+        .arg("echo a")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("CPU utilization:"))
+        .stdout(predicate::str::contains("\"cpu_utilization\": "));
+
+    // Only exported by default
+    hyperfine()
+        .arg("--runs=2")
+        .arg("--shell=none")
+        .arg("--export-json=-")
+        .arg("echo a")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("CPU utilization:").not())
+        .stdout(predicate::str::contains("\"cpu_utilization\": "));
+
+    // CPU times are not measured in debug mode
+    hyperfine_debug()
+        .arg("--show-resource-usage")
+        .arg("sleep 1")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("CPU utilization:").not());
+}
+
+#[test]
+fn exports_zscores_to_json() {
+    hyperfine_debug()
+        .arg("--style=none")
+        .arg("--runs=2")
+        .arg("--export-json")
+        .arg("-")
+        .arg("sleep 1")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "\"zscores\": [\n        0.0,\n        0.0\n      ]",
+        ));
+}
+
+#[test]
+fn exports_timestamps_of_every_run() {
+    let output = hyperfine()
+        .arg("--style=none")
+        .arg("--warmup=1")
+        .arg("--runs=5")
+        .arg("--export-json=-")
+        .arg("echo a")
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let json: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    let result = &json["results"][0];
+
+    let times = result["times"].as_array().unwrap();
+    let timestamps: Vec<f64> = result["timestamps"]
+        .as_array()
+        .expect("timestamps are exported")
+        .iter()
+        .map(|t| t.as_f64().unwrap())
+        .collect();
+    assert_eq!(timestamps.len(), times.len());
+    assert!(timestamps.windows(2).all(|w| w[0] <= w[1]));
+}
+
+#[test]
+fn exports_metadata_to_json() {
+    hyperfine_debug()
+        .arg("--style=none")
+        .arg("--export-json")
+        .arg("-")
+        .arg("sleep 1")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"metadata\"").not());
+
+    hyperfine_debug()
+        .arg("--style=none")
+        .arg("--export-metadata")
+        .arg("--export-json")
+        .arg("-")
+        .arg("sleep 1")
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("\"metadata\"")
+                .and(predicate::str::contains("\"hyperfine_version\""))
+                .and(predicate::str::contains("\"--export-metadata\"")),
+        );
+}
+
+#[cfg(unix)]
+#[test]
+fn exports_signals_of_killed_commands() {
+    hyperfine()
+        .arg("--runs=2")
+        .arg("--ignore-failure")
+        .arg("--style=none")
+        .arg("--export-json")
+        .arg("-")
+        .arg("kill -9 $$")
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("\"exit_codes\": [\n        137,\n        137\n      ]").and(
+                predicate::str::contains("\"signals\": [\n        9,\n        9\n      ]"),
+            ),
+        );
+}
+
+#[test]
+fn unused_parameters_are_shown_in_benchmark_name() {
+    hyperfine()
+        .arg("--runs=2")
+        .arg("--parameter-list")
+        .arg("branch")
+        .arg("master,feature")
+        .arg("echo test")
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("echo test (branch = master)")
                 .and(predicate::str::contains("echo test (branch = feature)")),
         );
 }
@@ -450,3 +1800,21 @@ fn windows_quote_before_quote_args() {
         .assert()
         .success();
 }
+
+#[test]
+fn prints_json_schema() {
+    hyperfine()
+        .arg("--export-schema")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"schema_version\""));
+
+    hyperfine_debug()
+        .arg("--style=none")
+        .arg("--export-json")
+        .arg("-")
+        .arg("sleep 1")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("{\n  \"schema_version\": 1,"));
+}